@@ -0,0 +1,113 @@
+//! Throughput benchmarks for transforms, solver and navier update.
+//!
+//! Run all: `cargo bench --bench benchmark_throughput`
+//!
+//! Run sanity check only (CI): `cargo bench --bench benchmark_throughput -- --test`
+use criterion::Criterion;
+use criterion::{criterion_group, criterion_main};
+use ndarray::Array2;
+use rustpde::bases::{Differentiate, Transform};
+use rustpde::navier::navier::Navier2D;
+use rustpde::solver::{Poisson, Solve};
+use rustpde::Integrate;
+use rustpde::{cheb_dirichlet, chebyshev, Field2, Space2};
+use std::time::{Duration, Instant};
+
+const SIZES: [usize; 3] = [128, 256, 512];
+
+/// Upper time limit for a 128 x 128 chebyshev forward + backward
+/// transform. Very generous, catches only severe regressions.
+const SANITY_LIMIT: Duration = Duration::from_millis(500);
+
+pub fn bench_chebyshev(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Chebyshev");
+    group.significance_level(0.1).sample_size(10);
+    for n in SIZES.iter() {
+        let mut ch = chebyshev::<f64>(*n);
+        let arr = Array2::from_elem((*n, *n), 1.);
+        let name = format!("Forward Size: {} x {}", *n, *n);
+        group.bench_function(&name, |b| {
+            b.iter(|| {
+                let _: Array2<f64> = ch.forward(&arr, 0);
+            })
+        });
+        let name = format!("Backward Size: {} x {}", *n, *n);
+        group.bench_function(&name, |b| {
+            b.iter(|| {
+                let _: Array2<f64> = ch.backward(&arr, 0);
+            })
+        });
+        let name = format!("Differentiate Size: {} x {}", *n, *n);
+        group.bench_function(&name, |b| {
+            b.iter(|| {
+                let _: Array2<f64> = ch.differentiate(&arr, 2, 0);
+            })
+        });
+    }
+    group.finish();
+}
+
+pub fn bench_poisson(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Poisson2D");
+    group.significance_level(0.1).sample_size(10);
+    for n in SIZES.iter() {
+        let space = Space2::new(&cheb_dirichlet(*n), &cheb_dirichlet(*n));
+        let field = Field2::new(&space);
+        let poisson = Poisson::new(&field, [1.0, 1.0]);
+        let rhs = Array2::<f64>::from_elem((*n, *n), 1.);
+        let mut out = Array2::<f64>::zeros((*n - 2, *n - 2));
+        let name = format!("Size: {} x {}", *n, *n);
+        group.bench_function(&name, |b| b.iter(|| poisson.solve(&rhs, &mut out, 0)));
+    }
+    group.finish();
+}
+
+pub fn bench_navier_periodic(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Navier2DPeriodic");
+    group.significance_level(0.1).sample_size(10);
+    let (ra, pr, dt, aspect) = (1e5, 1., 0.01, 1.);
+    for n in SIZES.iter() {
+        let mut navier = Navier2D::new_periodic(*n, *n + 1, ra, pr, dt, aspect);
+        let name = format!("Size: {} x {}", *n, *n + 1);
+        group.bench_function(&name, |b| b.iter(|| navier.update()));
+    }
+    group.finish();
+}
+
+/// Assertion style sanity check, runs before the benchmarks.
+///
+/// # Panics
+/// Panics if the transform does not roundtrip or is much
+/// slower than expected.
+pub fn sanity_throughput(_c: &mut Criterion) {
+    let n = 128;
+    let mut ch = chebyshev::<f64>(n);
+    let arr = Array2::from_shape_fn((n, n), |(i, j)| (i * j) as f64 / (n * n) as f64);
+    // Warm up
+    let mut vhat: Array2<f64> = ch.forward(&arr, 0);
+    let start = Instant::now();
+    let repeat = 10;
+    for _ in 0..repeat {
+        vhat = ch.forward(&arr, 0);
+    }
+    let back: Array2<f64> = ch.backward(&vhat, 0);
+    let elapsed = start.elapsed() / repeat;
+    for (a, b) in arr.iter().zip(back.iter()) {
+        assert!((a - b).abs() < 1e-8, "Chebyshev roundtrip failed");
+    }
+    assert!(
+        elapsed < SANITY_LIMIT,
+        "Chebyshev transform too slow: {:?} > {:?}",
+        elapsed,
+        SANITY_LIMIT
+    );
+}
+
+criterion_group!(
+    benches,
+    sanity_throughput,
+    bench_chebyshev,
+    bench_poisson,
+    bench_navier_periodic
+);
+criterion_main!(benches);
@@ -10,32 +10,91 @@ pub fn bench_transform(c: &mut Criterion) {
     group.significance_level(0.1).sample_size(10);
     for n in SIZES.iter() {
         let mut ch = cheb_dirichlet::<f64>(*n);
-        let mut arr = Array2::from_elem((*n, *n), 1.);
+        let arr = Array2::from_elem((*n, *n), 1.);
         let name = format!("Size: {} x {}", *n, *n);
         group.bench_function(&name, |b| {
             b.iter(|| {
-                let _: Array2<f64> = ch.forward(&mut arr, 0);
+                let _: Array2<f64> = ch.forward(&arr, 0);
             })
         });
         let name = format!("Size: {} x {} (Par)", *n, *n);
         group.bench_function(&name, |b| {
             b.iter(|| {
-                let _: Array2<f64> = ch.forward_par(&mut arr, 0);
+                let _: Array2<f64> = ch.forward_par(&arr, 0);
             })
         });
     }
     group.finish();
 }
 
+pub fn bench_transform_chebyshev(c: &mut Criterion) {
+    let mut group = c.benchmark_group("TransformChebyshev");
+    group.significance_level(0.1).sample_size(10);
+    for n in SIZES.iter() {
+        let mut ch = chebyshev::<f64>(*n);
+        let arr = Array2::from_elem((*n, *n), 1.);
+        let name = format!("Size: {} x {} (forward)", *n, *n);
+        group.bench_function(&name, |b| {
+            b.iter(|| {
+                let _: Array2<f64> = ch.forward(&arr, 0);
+            })
+        });
+        let name = format!("Size: {} x {} (forward_par)", *n, *n);
+        group.bench_function(&name, |b| {
+            b.iter(|| {
+                let _: Array2<f64> = ch.forward_par(&arr, 0);
+            })
+        });
+        let name = format!("Size: {} x {} (backward)", *n, *n);
+        group.bench_function(&name, |b| {
+            b.iter(|| {
+                let _: Array2<f64> = ch.backward(&arr, 0);
+            })
+        });
+        let name = format!("Size: {} x {} (backward_par)", *n, *n);
+        group.bench_function(&name, |b| {
+            b.iter(|| {
+                let _: Array2<f64> = ch.backward_par(&arr, 0);
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Timing only, allocations are counted in
+/// `chebyshev::ortho::test::test_cheby_backward_allocations`
+pub fn bench_backward(c: &mut Criterion) {
+    let mut group = c.benchmark_group("BackwardChebyshev");
+    group.significance_level(0.1).sample_size(10);
+    let n = 256;
+    let mut ch = chebyshev::<f64>(n);
+    let vhat = Array2::from_elem((n, n), 1.);
+    let mut v = Array2::from_elem((n, n), 0.);
+    let mut scratch = Array2::from_elem((n, n), 0.);
+    // Allocates output and correction buffer
+    let name = format!("Size: {} x {} (backward)", n, n);
+    group.bench_function(&name, |b| {
+        b.iter(|| {
+            let _: Array2<f64> = ch.backward(&vhat, 0);
+        })
+    });
+    // Reuses output and correction buffer
+    let name = format!("Size: {} x {} (backward_into)", n, n);
+    group.bench_function(&name, |b| {
+        b.iter(|| ch.backward_into(&vhat, &mut v, &mut scratch, 0))
+    });
+    group.finish();
+}
+
 pub fn bench_to_ortho(c: &mut Criterion) {
     let mut group = c.benchmark_group("ToOrthoChebDirichlet");
     group.significance_level(0.1).sample_size(10);
     for n in SIZES.iter() {
         let ch = cheb_dirichlet(*n);
         let ns = ch.len_spec();
-        let mut arr = Array2::from_elem((ns, ns), 1.);
+        let arr = Array2::from_elem((ns, ns), 1.);
         let name = format!("Size: {} x {}", *n, *n);
-        group.bench_function(&name, |b| b.iter(|| ch.to_ortho(&mut arr, 0)));
+        group.bench_function(&name, |b| b.iter(|| ch.to_ortho(&arr, 0)));
     }
     group.finish();
 }
@@ -46,9 +105,9 @@ pub fn bench_from_ortho(c: &mut Criterion) {
     for n in SIZES.iter() {
         let ch = cheb_dirichlet(*n);
         let ns = ch.len_phys();
-        let mut arr = Array2::from_elem((ns, ns), 1.);
+        let arr = Array2::from_elem((ns, ns), 1.);
         let name = format!("Size: {} x {}", *n, *n);
-        group.bench_function(&name, |b| b.iter(|| ch.from_ortho(&mut arr, 0)));
+        group.bench_function(&name, |b| b.iter(|| ch.from_ortho(&arr, 0)));
     }
     group.finish();
 }
@@ -58,9 +117,9 @@ pub fn bench_differentiate(c: &mut Criterion) {
     for n in SIZES.iter() {
         let ch = cheb_dirichlet(*n);
         let ns = ch.len_spec();
-        let mut arr = Array2::from_elem((ns, ns), 1.);
+        let arr = Array2::from_elem((ns, ns), 1.);
         let name = format!("Size: {} x {}", *n, *n);
-        group.bench_function(&name, |b| b.iter(|| ch.differentiate(&mut arr, 2, 0)));
+        group.bench_function(&name, |b| b.iter(|| ch.differentiate(&arr, 2, 0)));
     }
     group.finish();
 }
@@ -68,6 +127,8 @@ pub fn bench_differentiate(c: &mut Criterion) {
 criterion_group!(
     benches,
     bench_transform,
+    bench_transform_chebyshev,
+    bench_backward,
     bench_to_ortho,
     bench_from_ortho,
     bench_differentiate
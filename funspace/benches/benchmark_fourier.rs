@@ -10,17 +10,17 @@ pub fn bench_transform(c: &mut Criterion) {
     group.significance_level(0.1).sample_size(10);
     for n in SIZES.iter() {
         let mut fo = fourier_r2c::<f64>(*n);
-        let mut arr = Array2::from_elem((*n, *n), 1.);
+        let arr = Array2::from_elem((*n, *n), 1.);
         let name = format!("Size: {} x {}", *n, *n);
         group.bench_function(&name, |b| {
             b.iter(|| {
-                let _: Array2<Complex<f64>> = fo.forward(&mut arr, 0);
+                let _: Array2<Complex<f64>> = fo.forward(&arr, 0);
             })
         });
         let name = format!("Size: {} x {} (Par)", *n, *n);
         group.bench_function(&name, |b| {
             b.iter(|| {
-                let _: Array2<Complex<f64>> = fo.forward_par(&mut arr, 0);
+                let _: Array2<Complex<f64>> = fo.forward_par(&arr, 0);
             })
         });
     }
@@ -32,9 +32,9 @@ pub fn bench_differentiate(c: &mut Criterion) {
     group.significance_level(0.1).sample_size(10);
     for n in SIZES.iter() {
         let fo = fourier_r2c::<f64>(*n);
-        let mut arr = Array1::from_elem(fo.len_spec(), Complex::new(1., 1.));
+        let arr = Array1::from_elem(fo.len_spec(), Complex::new(1., 1.));
         let name = format!("Size: {}", *n);
-        group.bench_function(&name, |b| b.iter(|| fo.differentiate(&mut arr, 2, 0)));
+        group.bench_function(&name, |b| b.iter(|| fo.differentiate(&arr, 2, 0)));
     }
     group.finish();
 }
@@ -11,17 +11,17 @@ pub fn bench_sb_transform(c: &mut Criterion) {
     for n in SIZES.iter() {
         let cd = cheb_dirichlet::<f64>(*n);
         let mut space = Space2::new(&cd.clone(), &cd.clone());
-        let mut arr = Array2::from_elem((*n, *n), 1.);
+        let arr = Array2::from_elem((*n, *n), 1.);
         let name = format!("Size: {} x {} (Serial)", *n, *n);
         group.bench_function(&name, |b| {
             b.iter(|| {
-                let _: Array2<f64> = space.forward(&mut arr);
+                let _: Array2<f64> = space.forward(&arr);
             })
         });
         let name = format!("Size: {} x {} (Parallel)", *n, *n);
         group.bench_function(&name, |b| {
             b.iter(|| {
-                let _: Array2<f64> = space.forward_par(&mut arr);
+                let _: Array2<f64> = space.forward_par(&arr);
             })
         });
     }
@@ -35,11 +35,11 @@ pub fn bench_sb_to_ortho(c: &mut Criterion) {
         let cd = cheb_dirichlet::<f64>(*n);
         let space = Space2::new(&cd.clone(), &cd.clone());
         let ns = cd.len_spec();
-        let mut arr = Array2::from_elem((ns, ns), 1.);
+        let arr = Array2::from_elem((ns, ns), 1.);
         let name = format!("Size: {} x {} (Serial)", *n, *n);
-        group.bench_function(&name, |b| b.iter(|| space.to_ortho(&mut arr)));
+        group.bench_function(&name, |b| b.iter(|| space.to_ortho(&arr)));
         let name = format!("Size: {} x {} (Parallel)", *n, *n);
-        group.bench_function(&name, |b| b.iter(|| space.to_ortho_par(&mut arr)));
+        group.bench_function(&name, |b| b.iter(|| space.to_ortho_par(&arr)));
     }
     group.finish();
 }
@@ -51,11 +51,11 @@ pub fn bench_sb_from_ortho(c: &mut Criterion) {
         let cd = cheb_dirichlet::<f64>(*n);
         let space = Space2::new(&cd.clone(), &cd.clone());
         let ns = cd.len_phys();
-        let mut arr = Array2::from_elem((ns, ns), 1.);
+        let arr = Array2::from_elem((ns, ns), 1.);
         let name = format!("Size: {} x {} (Serial)", *n, *n);
-        group.bench_function(&name, |b| b.iter(|| space.from_ortho(&mut arr)));
+        group.bench_function(&name, |b| b.iter(|| space.from_ortho(&arr)));
         let name = format!("Size: {} x {} (Parallel)", *n, *n);
-        group.bench_function(&name, |b| b.iter(|| space.from_ortho_par(&mut arr)));
+        group.bench_function(&name, |b| b.iter(|| space.from_ortho_par(&arr)));
     }
     group.finish();
 }
@@ -67,13 +67,11 @@ pub fn bench_sb_differentiate(c: &mut Criterion) {
         let cd = cheb_dirichlet::<f64>(*n);
         let space = Space2::new(&cd.clone(), &cd.clone());
         let ns = cd.len_spec();
-        let mut arr = Array2::from_elem((ns, ns), 1.);
+        let arr = Array2::from_elem((ns, ns), 1.);
         let name = format!("Size: {} x {} (Serial)", *n, *n);
-        group.bench_function(&name, |b| b.iter(|| space.gradient(&mut arr, [2, 0], None)));
+        group.bench_function(&name, |b| b.iter(|| space.gradient(&arr, [2, 0], None)));
         let name = format!("Size: {} x {} (Parallel)", *n, *n);
-        group.bench_function(&name, |b| {
-            b.iter(|| space.gradient_par(&mut arr, [2, 0], None))
-        });
+        group.bench_function(&name, |b| b.iter(|| space.gradient_par(&arr, [2, 0], None)));
     }
     group.finish();
 }
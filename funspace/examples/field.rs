@@ -48,12 +48,12 @@ where
 
     /// Forward transformation
     pub fn forward(&mut self) {
-        self.space.forward_inplace_par(&mut self.v, &mut self.vhat);
+        self.space.forward_inplace_par(&self.v, &mut self.vhat);
     }
 
     /// Backward transformation
     pub fn backward(&mut self) {
-        self.space.backward_inplace_par(&mut self.vhat, &mut self.v);
+        self.space.backward_inplace_par(&self.vhat, &mut self.v);
     }
 
     /// Transform from composite to orthogonal space
@@ -119,6 +119,7 @@ where
                     (pinv.dot(&mass_sliced), peye.dot(&mass_sliced))
                 }
                 BaseR2r::CompositeChebyshev(_) => (pinv.dot(&mass), peye.dot(&mass)),
+                BaseR2r::FourierSine(_) | BaseR2r::FourierCosine(_) => (mass, lap),
             },
             BaseAll::BaseR2c(ref b) => match b {
                 BaseR2c::FourierR2c(_) => (mass, lap),
@@ -131,6 +132,7 @@ where
         let precond = match x {
             BaseAll::BaseR2r(ref b) => match b {
                 BaseR2r::Chebyshev(_) | BaseR2r::CompositeChebyshev(_) => Some(pinv),
+                BaseR2r::FourierSine(_) | BaseR2r::FourierCosine(_) => None,
             },
             BaseAll::BaseR2c(_) | BaseAll::BaseC2c(_) => None,
         };
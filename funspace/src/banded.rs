@@ -0,0 +1,167 @@
+//! Sparse banded matrices, stored by diagonals
+//!
+//! Diagonal with offset *k* holds the entries a\[i, i+k\].
+//! Only diagonals with nonzero entries are stored.
+use ndarray::prelude::*;
+use num_traits::Zero;
+use std::ops::{Add, Mul};
+
+/// Banded matrix, stored as diagonals and their offsets
+#[derive(Debug, Clone, PartialEq)]
+pub struct BandedMatrix<T> {
+    /// Shape (rows, cols) of the dense matrix
+    pub shape: (usize, usize),
+    /// Offsets of stored diagonals (0: main, >0: upper, <0: lower)
+    pub offsets: Vec<isize>,
+    /// Stored diagonals
+    pub diags: Vec<Array1<T>>,
+}
+
+impl<T: Copy + Zero + PartialEq> BandedMatrix<T> {
+    /// Construct from dense matrix. Diagonals, whose
+    /// entries are all zero, are omitted.
+    pub fn from_dense(mat: &Array2<T>) -> Self {
+        let (rows, cols) = (mat.shape()[0], mat.shape()[1]);
+        let mut offsets = Vec::new();
+        let mut diags = Vec::new();
+        for k in -(rows as isize) + 1..cols as isize {
+            let diag = Self::diag_indices(rows, cols, k)
+                .map(|(i, j)| mat[[i, j]])
+                .collect::<Array1<T>>();
+            if diag.iter().any(|x| !x.is_zero()) {
+                offsets.push(k);
+                diags.push(diag);
+            }
+        }
+        Self {
+            shape: (rows, cols),
+            offsets,
+            diags,
+        }
+    }
+
+    /// Construct diagonal matrix. A zero diagonal is omitted.
+    pub fn from_diag(diag: &Array1<T>) -> Self {
+        let n = diag.len();
+        let (offsets, diags) = if diag.iter().any(|x| !x.is_zero()) {
+            (vec![0], vec![diag.to_owned()])
+        } else {
+            (Vec::new(), Vec::new())
+        };
+        Self {
+            shape: (n, n),
+            offsets,
+            diags,
+        }
+    }
+
+    /// Return dense matrix
+    pub fn to_dense(&self) -> Array2<T> {
+        let mut mat = Array2::zeros(self.shape);
+        for (k, diag) in self.offsets.iter().zip(self.diags.iter()) {
+            for ((i, j), x) in Self::diag_indices(self.shape.0, self.shape.1, *k).zip(diag.iter()) {
+                mat[[i, j]] = *x;
+            }
+        }
+        mat
+    }
+
+    /// Number of stored entries
+    pub fn nnz(&self) -> usize {
+        self.diags.iter().map(Array1::len).sum()
+    }
+
+    /// Indices (i, i+k) of diagonal k
+    fn diag_indices(rows: usize, cols: usize, k: isize) -> impl Iterator<Item = (usize, usize)> {
+        let i0 = if k < 0 { (-k) as usize } else { 0 };
+        let j0 = if k > 0 { k as usize } else { 0 };
+        (0..(rows - i0).min(cols - j0)).map(move |i| (i0 + i, j0 + i))
+    }
+}
+
+impl<T> BandedMatrix<T>
+where
+    T: Copy + Zero + PartialEq + Add<Output = T> + Mul<Output = T>,
+{
+    /// Matrix vector product
+    ///
+    /// # Panics
+    /// Panics if length of `x` mismatches the number of columns.
+    pub fn dot<S>(&self, x: &ArrayBase<S, Ix1>) -> Array1<T>
+    where
+        S: ndarray::Data<Elem = T>,
+    {
+        assert!(
+            x.len() == self.shape.1,
+            "Size mismatch {} != {}",
+            x.len(),
+            self.shape.1
+        );
+        let mut y = Array1::<T>::zeros(self.shape.0);
+        for (k, diag) in self.offsets.iter().zip(self.diags.iter()) {
+            for ((i, j), a) in Self::diag_indices(self.shape.0, self.shape.1, *k).zip(diag.iter()) {
+                y[i] = y[i] + *a * x[j];
+            }
+        }
+        y
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::{approx_eq, approx_eq_complex};
+    use crate::{cheb_dirichlet, chebyshev, fourier_r2c, Differentiate};
+    use num_complex::Complex;
+
+    #[test]
+    fn test_banded_dense_roundtrip() {
+        let mat = array![[1., 2., 0.], [0., 3., 0.], [4., 0., 5.]];
+        let banded = BandedMatrix::from_dense(&mat);
+        assert_eq!(banded.offsets, vec![-2, 0, 1]);
+        assert_eq!(banded.to_dense(), mat);
+        let x = array![1., 2., 3.];
+        assert_eq!(banded.dot(&x), mat.dot(&x));
+    }
+
+    #[test]
+    fn test_differentiation_operator_sparse_chebyshev() {
+        for base in [chebyshev::<f64>(10), cheb_dirichlet::<f64>(10)].iter() {
+            let m = base.differentiation_operator_sparse(1).shape.1;
+            let x = Array1::from_shape_fn(m, |i| (i as f64).cos());
+            for order in 1..3 {
+                let op = base.differentiation_operator_sparse(order);
+                approx_eq(&op.dot(&x), &base.differentiate(&x, order, 0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_quasi_inverse_sparse_chebyshev() {
+        let n = 12;
+        for base in [chebyshev::<f64>(n), cheb_dirichlet::<f64>(n)].iter() {
+            for order in 1..3 {
+                let pinv = base.quasi_inverse_sparse(order);
+                // banded: at most three diagonals
+                assert!(pinv.offsets.len() <= 3);
+                // pinv * D^order = I, except for the first rows
+                let d = chebyshev::<f64>(n).differentiate(&Array2::<f64>::eye(n), order, 0);
+                let dense = pinv.to_dense().dot(&d);
+                approx_eq(
+                    &dense.slice(s![order.., ..]).to_owned(),
+                    &Array2::<f64>::eye(n).slice(s![order.., ..]).to_owned(),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_differentiation_operator_sparse_fourier() {
+        let base = fourier_r2c::<f64>(10);
+        let x = Array1::from_shape_fn(6, |i| Complex::new((i as f64).cos(), (i as f64).sin()));
+        let op = base.differentiation_operator_sparse(1);
+        // diagonal operator
+        assert_eq!(op.offsets, vec![0]);
+        approx_eq_complex(&op.dot(&x), &base.differentiate(&x, 1, 0));
+    }
+}
@@ -36,6 +36,9 @@ impl<A: FloatNum> CompositeChebyshev<A> {
     /// $$
     ///  \phi_k = T_k - T_{k+2}
     /// $$
+    ///
+    /// # Panics
+    /// Panics if *n* < 4.
     #[must_use]
     pub fn dirichlet(n: usize) -> Self {
         use super::composite_stencil::StencilChebyshev;
@@ -54,6 +57,9 @@ impl<A: FloatNum> CompositeChebyshev<A> {
     /// $$
     /// \phi_k = T_k - k^{2} \/ (k+2)^2 T_{k+2}
     /// $$
+    ///
+    /// # Panics
+    /// Panics if *n* < 4.
     #[must_use]
     pub fn neumann(n: usize) -> Self {
         use super::composite_stencil::StencilChebyshev;
@@ -67,6 +73,52 @@ impl<A: FloatNum> CompositeChebyshev<A> {
         }
     }
 
+    /// Return function space of chebyshev space
+    /// with *biharmonic* boundary conditions, i.e.
+    /// $u(\pm 1) = u'(\pm 1) = 0$
+    /// $$
+    /// \phi_k = T_k - \frac{2(k+2)}{k+3} T_{k+2} + \frac{k+1}{k+3} T_{k+4}
+    /// $$
+    ///
+    /// # Panics
+    /// Panics if *n* < 6.
+    #[must_use]
+    pub fn biharmonic(n: usize) -> Self {
+        use super::composite_stencil::StencilChebyshev;
+        let stencil = StencilChebyshev::biharmonic(n);
+        Self {
+            n,
+            m: StencilChebyshev::<A>::get_m_biharmonic(n),
+            stencil: ChebyshevStencil::StencilChebyshev(stencil),
+            ortho: Chebyshev::<A>::new(n),
+            transform_kind: TransformKind::RealToReal,
+        }
+    }
+
+    /// Return function space of chebyshev space
+    /// with *robin* boundary conditions
+    /// $$
+    /// a_l u(-1) + b_l u'(-1) = 0, \quad a_r u(1) + b_r u'(1) = 0
+    /// $$
+    /// $$
+    /// \phi_k = T_k + \alpha_k T_{k+1} + \beta_k T_{k+2}
+    /// $$
+    ///
+    /// # Panics
+    /// Panics if *n* < 4, or if the boundary conditions are degenerate.
+    #[must_use]
+    pub fn robin(n: usize, a_left: A, b_left: A, a_right: A, b_right: A) -> Self {
+        use super::composite_stencil::StencilChebyshev;
+        let stencil = StencilChebyshev::robin(n, a_left, b_left, a_right, b_right);
+        Self {
+            n,
+            m: StencilChebyshev::<A>::get_m(n),
+            stencil: ChebyshevStencil::StencilChebyshev(stencil),
+            ortho: Chebyshev::<A>::new(n),
+            transform_kind: TransformKind::RealToReal,
+        }
+    }
+
     /// Dirichlet boundary condition basis
     /// $$
     ///     \phi_0 = 0.5 T_0 - 0.5 T_1
@@ -112,6 +164,41 @@ impl<A: FloatNum> CompositeChebyshev<A> {
     pub fn coords(&self) -> &Array1<A> {
         &self.ortho.x
     }
+
+    /// Differentiation in spectral space, returning coefficients in
+    /// composite space (size *m*), such that repeated differentiation
+    /// stays in composite space.
+    ///
+    /// In contrast, [`Differentiate::differentiate`] returns
+    /// coefficients in orthogonal space (size *n*). The result is
+    /// projected back with [`FromOrtho::from_ortho`], which is exact
+    /// only if the derivative satisfies the boundary conditions of
+    /// the composite basis.
+    ///
+    /// # Example
+    /// ```
+    /// use funspace::chebyshev::CompositeChebyshev;
+    /// use funspace::utils::approx_eq;
+    /// use ndarray::prelude::*;
+    /// let cheby = CompositeChebyshev::<f64>::dirichlet(8);
+    /// let input = array![1., 0., 0., 0., 0., 0.];
+    /// let output = cheby.differentiate_into_composite(&input, 1, 0);
+    /// assert_eq!(output.len(), 6);
+    /// ```
+    pub fn differentiate_into_composite<T, S, D>(
+        &self,
+        data: &ArrayBase<S, D>,
+        n_times: usize,
+        axis: usize,
+    ) -> Array<T, D>
+    where
+        Self: Differentiate<T> + FromOrtho<T>,
+        S: ndarray::Data<Elem = T>,
+        D: Dimension,
+    {
+        let parent_coeff = self.differentiate(data, n_times, axis);
+        self.from_ortho(&parent_coeff, axis)
+    }
 }
 
 macro_rules! impl_from_ortho_composite_chebyshev {
@@ -510,6 +597,10 @@ macro_rules! impl_differentiate_composite_chebyshev {
     ($a: ty) => {
         impl<A: FloatNum> Differentiate<$a> for CompositeChebyshev<A> {
             /// Differentiation in spectral space
+            ///
+            /// Returns coefficients in orthogonal space (size *n*), see
+            /// [`CompositeChebyshev::differentiate_into_composite`] to stay
+            /// in composite space.
             /// ```
             /// use funspace::Differentiate;
             /// use funspace::chebyshev::CompositeChebyshev;
@@ -553,6 +644,8 @@ macro_rules! impl_differentiate_composite_chebyshev {
 
         impl<A: FloatNum> DifferentiatePar<$a> for CompositeChebyshev<A> {
             /// Differentiation in spectral space
+            ///
+            /// Returns coefficients in orthogonal space (size *n*)
             fn differentiate_par<S, D>(
                 &self,
                 data: &ArrayBase<S, D>,
@@ -608,6 +701,26 @@ mod test {
     use super::*;
     use crate::utils::approx_eq;
 
+    #[test]
+    fn test_differentiate_into_composite() {
+        // u = (1-x^2)^3, u' and u'' vanish at the boundaries, too
+        let n = 12;
+        let mut cheby = CompositeChebyshev::<f64>::dirichlet(n);
+        let x = cheby.coords().to_owned();
+        let u = x.mapv(|x: f64| (1. - x * x).powi(3));
+        let uhat = cheby.forward(&u, 0);
+        // Twice in composite space vs once in orthogonal space
+        let du = cheby.differentiate_into_composite(&uhat, 1, 0);
+        assert_eq!(du.len(), n - 2);
+        let ddu = cheby.differentiate_into_composite(&du, 1, 0);
+        assert_eq!(ddu.len(), n - 2);
+        approx_eq(&cheby.to_ortho(&ddu, 0), &cheby.differentiate(&uhat, 2, 0));
+        approx_eq(&cheby.differentiate_into_composite(&uhat, 2, 0), &ddu);
+        // Physical space
+        let expected = x.mapv(|x: f64| -6. * (1. - x * x).powi(2) + 24. * x * x * (1. - x * x));
+        approx_eq(&cheby.backward(&ddu, 0), &expected);
+    }
+
     #[test]
     /// Differantiate 2d array along first and second axis
     fn test_chebdirichlet_to_ortho() {
@@ -686,4 +799,102 @@ mod test {
         let diff = cheby.differentiate(&data, 2, 1);
         approx_eq(&diff, &expected);
     }
+
+    #[test]
+    fn test_chebdirichlet_single_precision() {
+        let n = 12;
+        let mut cheby_f64 = CompositeChebyshev::<f64>::dirichlet(n);
+        let mut cheby_f32 = CompositeChebyshev::<f32>::dirichlet(n);
+        let x = cheby_f64.ortho.x.to_owned();
+        let data_f64 = x.mapv(|x| (1. - x * x) * (2. * x).cos());
+        let data_f32 = data_f64.mapv(|x| x as f32);
+        let vhat_f64 = cheby_f64.forward(&data_f64, 0);
+        let vhat_f32 = cheby_f32.forward(&data_f32, 0);
+        for (a, b) in vhat_f64.iter().zip(vhat_f32.iter()) {
+            assert!((a - f64::from(*b)).abs() < 1e-4);
+        }
+        let v_f64 = cheby_f64.backward(&vhat_f64, 0);
+        let v_f32 = cheby_f32.backward(&vhat_f32, 0);
+        for (a, b) in v_f64.iter().zip(v_f32.iter()) {
+            assert!((a - f64::from(*b)).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Composite chebyshev space requires n >= 4, got n = 3.")]
+    fn test_chebdirichlet_too_small() {
+        let _ = CompositeChebyshev::<f64>::dirichlet(3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Composite chebyshev space requires n >= 4, got n = 2.")]
+    fn test_chebneumann_too_small() {
+        let _ = CompositeChebyshev::<f64>::neumann(2);
+    }
+
+    #[test]
+    fn test_chebdirichlet_smallest() {
+        // smallest valid size: phi_0 = T_0 - T_2, phi_1 = T_1 - T_3
+        let mut cheby = CompositeChebyshev::<f64>::dirichlet(4);
+        assert_eq!(cheby.m, 2);
+        let x = cheby.ortho.x.to_owned();
+        let data = x.mapv(|x| 1. - x * x);
+        let vhat = cheby.forward(&data, 0);
+        approx_eq(&cheby.backward(&vhat, 0), &data);
+    }
+
+    #[test]
+    fn test_chebrobin_dirichlet_limit() {
+        let robin = CompositeChebyshev::<f64>::robin(8, 1., 0., 1., 0.);
+        let dirichlet = CompositeChebyshev::<f64>::dirichlet(8);
+        approx_eq(&robin.stencil.to_array(), &dirichlet.stencil.to_array());
+    }
+
+    #[test]
+    fn test_chebrobin_boundary_condition() {
+        let n = 14;
+        let (a_l, b_l, a_r, b_r) = (1., -0.5, 2., 0.3);
+        let mut cheby = CompositeChebyshev::<f64>::robin(n, a_l, b_l, a_r, b_r);
+        let x = cheby.ortho.x.to_owned();
+        let data = x.mapv(|x| (1.5 * x).sin() + x * x);
+        let vhat = cheby.forward(&data, 0);
+        let parent = cheby.to_ortho(&vhat, 0);
+        // u and du/dx at x = -1 and x = 1
+        let (mut u_l, mut du_l, mut u_r, mut du_r) = (0., 0., 0., 0.);
+        for (j, c) in parent.iter().enumerate() {
+            let sign = if j % 2 == 0 { 1. } else { -1. };
+            let j2 = (j * j) as f64;
+            u_l += sign * c;
+            du_l -= sign * j2 * c;
+            u_r += c;
+            du_r += j2 * c;
+        }
+        assert!((a_l * u_l + b_l * du_l).abs() < 1e-10);
+        assert!((a_r * u_r + b_r * du_r).abs() < 1e-10);
+        // Roundtrip is exact in composite space
+        let v = cheby.backward(&vhat, 0);
+        approx_eq(&cheby.forward(&v, 0), &vhat);
+        approx_eq(&cheby.from_ortho(&parent, 0), &vhat);
+    }
+
+    #[test]
+    fn test_chebbiharmonic_boundary_condition() {
+        let n = 16;
+        let mut cheby = CompositeChebyshev::<f64>::biharmonic(n);
+        assert_eq!(cheby.m, n - 4);
+        let x = cheby.ortho.x.to_owned();
+        let data = x.mapv(|x| (2. * x).cos() + x);
+        let vhat = cheby.forward(&data, 0);
+        let v = cheby.backward(&vhat, 0);
+        // value and slope at x = -1 and x = 1
+        let mut ortho = cheby.ortho.clone();
+        let parent = ortho.forward(&v, 0);
+        let dparent = ortho.differentiate(&parent, 1, 0);
+        let dv = ortho.backward(&dparent, 0);
+        for f in [&v, &dv].iter() {
+            assert!(f[0].abs() < 1e-10);
+            assert!(f[n - 1].abs() < 1e-10);
+        }
+        approx_eq(&cheby.forward(&v, 0), &vhat);
+    }
 }
@@ -4,11 +4,25 @@
 //! $$
 //! where $S$ is a two-dimensional transform matrix.
 #![allow(clippy::used_underscore_binding)]
+use super::linalg::BandedLu;
+use crate::banded::BandedMatrix;
 use crate::{FloatNum, Scalar};
 use ndarray::prelude::*;
+use std::collections::BTreeMap;
 use std::ops::{Add, Div, Mul, Sub};
 
+/// Smallest physical size of [`StencilChebyshev`]. The stencil
+/// couples $T_k$ and $T_{k+2}$ of at least two composite coefficients.
+pub const MIN_SIZE_STENCIL: usize = 4;
+
+/// Smallest physical size of [`StencilChebyshev::biharmonic`]
+pub const MIN_SIZE_BIHARMONIC: usize = 6;
+
+/// Smallest physical size of [`StencilChebyshevBoundary`]
+pub const MIN_SIZE_BOUNDARY: usize = 2;
+
 /// Elementary methods for stencils
+#[allow(dead_code)]
 #[enum_dispatch]
 pub trait Stencil<A> {
     /// Multiply stencil with a 1d array
@@ -71,20 +85,19 @@ pub enum ChebyshevStencil<A: FloatNum> {
 }
 
 /// Container for Chebyshev Stencil (internally used)
+///
+/// The stencil matrix $S$ is stored by its diagonals, i.e.
+/// $S_{i-o, i}$ is the i-th entry of the diagonal with offset $o \le 0$.
 #[derive(Clone)]
 pub struct StencilChebyshev<A> {
     /// Number of coefficients in parent space
     n: usize,
     /// Number of coefficients in parent space
     m: usize,
-    /// Main diagonal
-    diag: Array1<A>,
-    /// Subdiagonal offset -2
-    low2: Array1<A>,
-    /// For tdma (diagonal)
-    main: Array1<A>,
-    /// For tdma (off-diagonal)
-    off: Array1<A>,
+    /// Diagonals (offset, entries) of the stencil
+    diags: Vec<(isize, Array1<A>)>,
+    /// LU decomposition of $S^T S$, used in [`StencilChebyshev::solve_vec_inplace`]
+    lu: BandedLu<A>,
 }
 
 /// Container for Boundary Condition Stencil
@@ -118,15 +131,7 @@ impl<A: FloatNum> StencilChebyshev<A> {
         let m = Self::get_m(n);
         let diag = Array::from_vec(vec![A::one(); m]);
         let low2 = Array::from_vec(vec![-A::one(); m]);
-        let (main, off) = Self::_get_main_off(&diag.view(), &low2.view());
-        Self {
-            n,
-            m,
-            diag,
-            low2,
-            main,
-            off,
-        }
+        Self::from_diags(n, vec![(0, diag), (-2, low2)])
     }
 
     /// Return stencil of chebyshev neumann space
@@ -145,44 +150,166 @@ impl<A: FloatNum> StencilChebyshev<A> {
             let k2_ = A::from_f64((k + 2).pow(2) as f64).unwrap();
             *v = -A::one() * k_ / k2_;
         }
-        let (main, off) = Self::_get_main_off(&diag.view(), &low2.view());
-        Self {
-            n,
-            m,
-            diag,
-            low2,
-            main,
-            off,
+        Self::from_diags(n, vec![(0, diag), (-2, low2)])
+    }
+
+    /// Return stencil of chebyshev space with *robin* boundary conditions
+    /// $$
+    /// a_l u(-1) + b_l u'(-1) = 0, \quad a_r u(1) + b_r u'(1) = 0
+    /// $$
+    /// with
+    /// $$
+    /// \phi_k = T_k + \alpha_k T_{k+1} + \beta_k T_{k+2}.
+    /// $$
+    /// $\alpha_k$ and $\beta_k$ follow from $T_j(\pm 1) = (\pm 1)^j$ and
+    /// $T'_j(\pm 1) = (\pm 1)^{j+1} j^2$.
+    ///
+    /// Reference:
+    /// J. Shen: Effcient Spectral-Galerkin Method II.
+    ///
+    /// # Panics
+    /// Panics if the boundary conditions admit no stencil of this form,
+    /// e.g. if $a_l = b_l = 0$.
+    pub fn robin(n: usize, a_left: A, b_left: A, a_right: A, b_right: A) -> Self {
+        let m = Self::get_m(n);
+        let diag = Array::from_vec(vec![A::one(); m]);
+        let mut low1 = Array::from_vec(vec![A::zero(); m]);
+        let mut low2 = Array::from_vec(vec![A::zero(); m]);
+        // Boundary values of a_r T_j + b_r T'_j (right) and a_l T_j + b_l T'_j (left)
+        let right = |j: usize| a_right + b_right * A::from_usize(j * j).unwrap();
+        let left = |j: usize| {
+            let sign = if j.is_multiple_of(2) {
+                A::one()
+            } else {
+                -A::one()
+            };
+            sign * (a_left - b_left * A::from_usize(j * j).unwrap())
+        };
+        for k in 0..m {
+            let det = right(k + 1) * left(k + 2) - left(k + 1) * right(k + 2);
+            assert!(
+                det.abs() > A::epsilon(),
+                "Robin boundary conditions are degenerate at k = {}.",
+                k
+            );
+            low1[k] = (left(k) * right(k + 2) - right(k) * left(k + 2)) / det;
+            low2[k] = (left(k + 1) * right(k) - right(k + 1) * left(k)) / det;
         }
+        Self::from_diags(n, vec![(0, diag), (-1, low1), (-2, low2)])
     }
 
-    /// Get main diagonal and off diagonal, used in [`StencilChebyshev::solve_vec_inplace`]
-    fn _get_main_off(diag: &ArrayView1<A>, low2: &ArrayView1<A>) -> (Array1<A>, Array1<A>) {
-        let m = diag.len();
-        let mut main = Array::from_vec(vec![A::zero(); m]);
-        let mut off = Array::from_vec(vec![A::zero(); m - 2]);
-        for (i, v) in main.iter_mut().enumerate() {
-            *v = diag[i] * diag[i] + low2[i] * low2[i];
+    /// Return stencil of chebyshev biharmonic space, i.e.
+    /// $u(\pm 1) = u'(\pm 1) = 0$
+    /// $$
+    /// \phi_k = T_k - \frac{2(k+2)}{k+3} T_{k+2} + \frac{k+1}{k+3} T_{k+4}
+    /// $$
+    ///
+    /// Reference:
+    /// J. Shen: Effcient Spectral-Galerkin Method II.
+    pub fn biharmonic(n: usize) -> Self {
+        let m = Self::get_m_biharmonic(n);
+        let diag = Array::from_vec(vec![A::one(); m]);
+        let mut low2 = Array::from_vec(vec![A::zero(); m]);
+        let mut low4 = Array::from_vec(vec![A::zero(); m]);
+        for (k, (v2, v4)) in low2.iter_mut().zip(low4.iter_mut()).enumerate() {
+            let k_ = A::from_usize(k).unwrap();
+            let _1 = A::one();
+            let _2 = A::from_f64(2.).unwrap();
+            let _3 = A::from_f64(3.).unwrap();
+            *v2 = -_2 * (k_ + _2) / (k_ + _3);
+            *v4 = (k_ + _1) / (k_ + _3);
+        }
+        Self::from_diags(n, vec![(0, diag), (-2, low2), (-4, low4)])
+    }
+
+    /// Construct stencil from its diagonals (offset, entries). Offsets
+    /// must be non-positive, i.e. each $\phi_k$ combines $T_k$ with
+    /// higher order polynomials. All diagonals are of length *m*, the
+    /// size of the composite space.
+    ///
+    /// # Panics
+    /// Panics if an offset is positive, or if a diagonal
+    /// reaches beyond the parent space.
+    pub fn from_diags(n: usize, diags: Vec<(isize, Array1<A>)>) -> Self {
+        let m = diags.first().map_or(0, |(_, d)| d.len());
+        for (offset, d) in &diags {
+            assert!(
+                *offset <= 0,
+                "Stencil offsets must be <= 0, got {}.",
+                offset
+            );
+            assert!(
+                d.len() == m && m + (-offset) as usize <= n,
+                "Stencil diagonal {} does not fit into parent space.",
+                offset
+            );
+        }
+        let lu = BandedLu::new(&Self::_normal_matrix(m, &diags));
+        Self { n, m, diags, lu }
+    }
+
+    /// Assemble $S^T S$ as banded matrix. The diagonals $o_1$ and
+    /// $o_2$ of $S$ contribute to its diagonal with offset $o_2 - o_1$.
+    fn _normal_matrix(m: usize, diags: &[(isize, Array1<A>)]) -> BandedMatrix<A> {
+        let mut normal: BTreeMap<isize, Array1<A>> = BTreeMap::new();
+        for (o1, d1) in diags {
+            for (o2, d2) in diags {
+                let k = o2 - o1;
+                let len = m - k.unsigned_abs();
+                let i0 = if k < 0 { (-k) as usize } else { 0 };
+                let diag = normal.entry(k).or_insert_with(|| Array1::<A>::zeros(len));
+                for (idx, v) in diag.iter_mut().enumerate() {
+                    let i = i0 + idx;
+                    *v = *v + d1[i] * d2[(i as isize + k) as usize];
+                }
+            }
         }
-        for (i, v) in off.iter_mut().enumerate() {
-            *v = diag[i + 2] * low2[i];
+        BandedMatrix {
+            shape: (m, m),
+            offsets: normal.keys().copied().collect(),
+            diags: normal.into_values().collect(),
         }
-        (main, off)
     }
 
     /// Composite spaces can be smaller than its orthonormal counterpart
+    ///
+    /// # Panics
+    /// Panics if *n* is too small to hold the stencil, see [`MIN_SIZE_STENCIL`].
     pub fn get_m(n: usize) -> usize {
+        assert!(
+            n >= MIN_SIZE_STENCIL,
+            "Composite chebyshev space requires n >= {}, got n = {}.",
+            MIN_SIZE_STENCIL,
+            n
+        );
         n - 2
     }
+
+    /// Size of biharmonic composite space, which is
+    /// four coefficients smaller than its parent space
+    ///
+    /// # Panics
+    /// Panics if *n* is too small to hold the stencil, see [`MIN_SIZE_BIHARMONIC`].
+    pub fn get_m_biharmonic(n: usize) -> usize {
+        assert!(
+            n >= MIN_SIZE_BIHARMONIC,
+            "Composite chebyshev biharmonic space requires n >= {}, got n = {}.",
+            MIN_SIZE_BIHARMONIC,
+            n
+        );
+        n - 4
+    }
 }
 
 impl<A: FloatNum> Stencil<A> for StencilChebyshev<A> {
     /// Returns transform stencil as 2d ndarray
     fn to_array(&self) -> Array2<A> {
         let mut mat = Array2::<A>::zeros((self.n, self.m).f());
-        for (i, (d, l)) in self.diag.iter().zip(self.low2.iter()).enumerate() {
-            mat[[i, i]] = *d;
-            mat[[i + 2, i]] = *l;
+        for (offset, d) in &self.diags {
+            let k = (-offset) as usize;
+            for (i, v) in d.iter().enumerate() {
+                mat[[i + k, i]] = *v;
+            }
         }
         mat
     }
@@ -218,14 +345,12 @@ impl<A: FloatNum> Stencil<A> for StencilChebyshev<A> {
             + Sub<A, Output = T>,
     {
         parent_coeff.mapv_inplace(|x| x * T::zero());
-        parent_coeff[0] = composite_coeff[0] * self.diag[0];
-        parent_coeff[1] = composite_coeff[1] * self.diag[1];
-        for i in 2..self.n - 2 {
-            parent_coeff[i] =
-                composite_coeff[i] * self.diag[i] + composite_coeff[i - 2] * self.low2[i - 2];
+        for (offset, d) in &self.diags {
+            let k = (-offset) as usize;
+            for i in 0..self.m {
+                parent_coeff[i + k] = parent_coeff[i + k] + composite_coeff[i] * d[i];
+            }
         }
-        parent_coeff[self.n - 2] = composite_coeff[self.n - 4] * self.low2[self.n - 4];
-        parent_coeff[self.n - 1] = composite_coeff[self.n - 3] * self.low2[self.n - 3];
     }
 
     /// Solve linear algebraic system $p = S c$ for $p$ with given composite
@@ -247,6 +372,8 @@ impl<A: FloatNum> Stencil<A> for StencilChebyshev<A> {
     }
 
     /// See [`StencilChebyshev::solve_vec`]
+    ///
+    /// Solves the normal equations $S^T S c = S^T p$.
     fn solve_vec_inplace<S1, S2, T>(
         &self,
         parent_coeff: &ArrayBase<S1, Ix1>,
@@ -260,19 +387,16 @@ impl<A: FloatNum> Stencil<A> for StencilChebyshev<A> {
             + Div<A, Output = T>
             + Sub<A, Output = T>,
     {
-        use super::linalg::tdma;
         // Multiply right hand side
-        for i in 0..self.m {
-            composite_coeff[i] =
-                parent_coeff[i] * self.diag[i] + parent_coeff[i + 2] * self.low2[i];
+        composite_coeff.mapv_inplace(|x| x * T::zero());
+        for (offset, d) in &self.diags {
+            let k = (-offset) as usize;
+            for i in 0..self.m {
+                composite_coeff[i] = composite_coeff[i] + parent_coeff[i + k] * d[i];
+            }
         }
-        // Solve tridiagonal system
-        tdma(
-            &self.off.view(),
-            &self.main.view(),
-            &self.off.view(),
-            composite_coeff,
-        );
+        // Solve banded system
+        self.lu.solve_inplace(composite_coeff);
     }
 }
 
@@ -309,7 +433,16 @@ impl<A: FloatNum> StencilChebyshevBoundary<A> {
     }
 
     /// Return size of spectral space (number of coefficients) from size in physical space
-    pub fn get_m(_n: usize) -> usize {
+    ///
+    /// # Panics
+    /// Panics if *n* is too small to hold the stencil, see [`MIN_SIZE_BOUNDARY`].
+    pub fn get_m(n: usize) -> usize {
+        assert!(
+            n >= MIN_SIZE_BOUNDARY,
+            "Chebyshev boundary space requires n >= {}, got n = {}.",
+            MIN_SIZE_BOUNDARY,
+            n
+        );
         2
     }
 }
@@ -407,6 +540,7 @@ impl<A: FloatNum> Stencil<A> for StencilChebyshevBoundary<A> {
 }
 
 #[cfg(test)]
+#[allow(clippy::approx_constant)]
 mod test {
     use super::*;
     use crate::utils::{approx_eq, approx_eq_complex};
@@ -452,4 +586,20 @@ mod test {
         let parent = stencil.multiply_vec(&composite);
         approx_eq(&parent, &array![1.5, 0.5, 0., 0.]);
     }
+
+    #[test]
+    fn test_stencil_four_diagonals() {
+        let (n, m) = (10, 7);
+        let diags = (0..4)
+            .map(|k| {
+                let d = Array1::from_shape_fn(m, |i| 1. / (1. + (i + 2 * k) as f64));
+                (-(k as isize), d)
+            })
+            .collect();
+        let stencil = StencilChebyshev::<f64>::from_diags(n, diags);
+        let composite = Array1::from_shape_fn(m, |i| (i as f64).sin());
+        let parent = stencil.multiply_vec(&composite);
+        approx_eq(&parent, &stencil.to_array().dot(&composite));
+        approx_eq(&stencil.solve_vec(&parent), &composite);
+    }
 }
@@ -1,57 +1,104 @@
 //! # Linalg functions for chebyshev space
-use crate::Scalar;
+use crate::banded::BandedMatrix;
+use crate::{FloatNum, Scalar};
 use ndarray::prelude::*;
 use std::ops::{Add, Div, Mul, Sub};
 
-/// Tridiagonal matrix solver
-///     Ax = d
-/// where A is banded with diagonals in offsets -2, 0, 2
+/// LU decomposition (without pivoting) of a square banded matrix,
+/// suitable for symmetric positive definite matrices.
 ///
-/// a: sub-diagonal (-2)
-/// b: main-diagonal
-/// c: sub-diagonal (+2)
-#[allow(clippy::many_single_char_names)]
-pub fn tdma<S1, S2, T1, T2>(
-    a: &ArrayBase<S1, Ix1>,
-    b: &ArrayBase<S1, Ix1>,
-    c: &ArrayBase<S1, Ix1>,
-    d: &mut ArrayBase<S2, Ix1>,
-) where
-    S1: ndarray::Data<Elem = T1>,
-    S2: ndarray::Data<Elem = T2> + ndarray::DataMut,
-    T1: Scalar,
-    T2: Scalar
-        + Add<T1, Output = T2>
-        + Mul<T1, Output = T2>
-        + Div<T1, Output = T2>
-        + Sub<T1, Output = T2>,
-{
-    let n = d.len();
-    let mut x = Array1::<T2>::zeros(n);
-    let mut w = Array1::<T1>::zeros(n - 2);
-    let mut g = Array1::<T2>::zeros(n);
+/// Entry (i, j) is stored in lu\[i, p + j - i\], where *p* is the
+/// largest offset of the banded matrix. No pivoting means no fill-in
+/// outside the band.
+#[derive(Clone)]
+pub struct BandedLu<A> {
+    /// Bandwidth
+    p: usize,
+    /// Factors L (unit diagonal, below) and U (diagonal and above)
+    lu: Array2<A>,
+}
 
-    // Forward sweep
-    w[0] = c[0] / b[0];
-    g[0] = d[0] / b[0];
-    if c.len() > 1 {
-        w[1] = c[1] / b[1];
+impl<A: FloatNum> BandedLu<A> {
+    /// Factorize square banded matrix
+    ///
+    /// # Panics
+    /// Panics if matrix is not square.
+    pub fn new(mat: &BandedMatrix<A>) -> Self {
+        let (n, cols) = mat.shape;
+        assert!(n == cols, "Banded LU requires a square matrix.");
+        let p = mat
+            .offsets
+            .iter()
+            .map(|k| k.unsigned_abs())
+            .max()
+            .unwrap_or(0);
+        let mut lu = Array2::<A>::zeros((n, 2 * p + 1));
+        for (k, diag) in mat.offsets.iter().zip(mat.diags.iter()) {
+            let i0 = if *k < 0 { (-k) as usize } else { 0 };
+            for (idx, v) in diag.iter().enumerate() {
+                lu[[i0 + idx, (p as isize + k) as usize]] = *v;
+            }
+        }
+        // Doolittle elimination within the band
+        for k in 0..n {
+            for i in k + 1..(k + p + 1).min(n) {
+                let l = lu[[i, p + k - i]] / lu[[k, p]];
+                lu[[i, p + k - i]] = l;
+                for j in k + 1..(k + p + 1).min(n) {
+                    let u = lu[[k, p + j - k]];
+                    lu[[i, p + j - i]] = lu[[i, p + j - i]] - l * u;
+                }
+            }
+        }
+        Self { p, lu }
     }
-    g[1] = d[1] / b[1];
 
-    for i in 2..n - 2 {
-        w[i] = c[i] / (b[i] - a[i - 2] * w[i - 2]);
-    }
-    for i in 2..n {
-        g[i] = (d[i] - g[i - 2] * a[i - 2]) / (b[i] - a[i - 2] * w[i - 2]);
+    /// Solve Ax = b, b is overwritten by x
+    pub fn solve_inplace<S, T>(&self, b: &mut ArrayBase<S, Ix1>)
+    where
+        S: ndarray::Data<Elem = T> + ndarray::DataMut,
+        T: Scalar
+            + Add<A, Output = T>
+            + Mul<A, Output = T>
+            + Div<A, Output = T>
+            + Sub<A, Output = T>,
+    {
+        let (n, p) = (b.len(), self.p);
+        // Forward substitution (L)
+        for i in 0..n {
+            for k in i.saturating_sub(p)..i {
+                b[i] = b[i] - b[k] * self.lu[[i, p + k - i]];
+            }
+        }
+        // Back substitution (U)
+        for i in (0..n).rev() {
+            for j in i + 1..(i + p + 1).min(n) {
+                b[i] = b[i] - b[j] * self.lu[[i, p + j - i]];
+            }
+            b[i] = b[i] / self.lu[[i, p]];
+        }
     }
+}
 
-    // Back substitution
-    x[n - 1] = g[n - 1];
-    x[n - 2] = g[n - 2];
-    for i in (1..n - 1).rev() {
-        x[i - 1] = g[i - 1] - x[i + 1] * w[i - 1];
-    }
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::approx_eq;
 
-    d.assign(&x);
+    #[test]
+    fn test_banded_lu() {
+        // Symmetric positive definite matrix with offsets -2..2
+        let n = 7;
+        let dense = Array2::from_shape_fn((n, n), |(i, j)| match (i as isize - j as isize).abs() {
+            0 => 6. + i as f64,
+            1 => -1.,
+            2 => 0.5,
+            _ => 0.,
+        });
+        let lu = BandedLu::new(&BandedMatrix::from_dense(&dense));
+        let x = Array1::from_shape_fn(n, |i| (i as f64).cos());
+        let mut b = dense.dot(&x);
+        lu.solve_inplace(&mut b);
+        approx_eq(&b, &x);
+    }
 }
@@ -14,4 +14,5 @@ mod dmsuite;
 mod linalg;
 mod ortho;
 pub use composite::CompositeChebyshev;
-pub use ortho::Chebyshev;
+pub use linalg::BandedLu;
+pub use ortho::{Chebyshev, NodeKind};
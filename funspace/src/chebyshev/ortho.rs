@@ -1,19 +1,35 @@
 //! # Orthogonal chebyshev space
+use crate::banded::BandedMatrix;
 use crate::traits::Basics;
 use crate::traits::Differentiate;
 use crate::traits::DifferentiatePar;
 use crate::traits::FromOrtho;
 use crate::traits::FromOrthoPar;
 use crate::traits::LaplacianInverse;
+use crate::traits::Normalization;
 use crate::traits::Transform;
 use crate::traits::TransformKind;
 use crate::traits::TransformPar;
 use crate::types::FloatNum;
+use crate::utils::dot_along;
 use crate::Scalar;
 use ndarray::prelude::*;
+use ndarray::Zip;
 use ndrustfft::DctHandler;
 use num_complex::Complex;
 
+/// Layout of chebyshev nodes in physical space
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    /// Gauss-Lobatto points (2nd kind), including the endpoints.
+    /// Transform via DCT-I.
+    GaussLobatto,
+    /// Gauss points (1st kind), excluding the endpoints.
+    /// Transform via dense matrices (DCT-II / DCT-III),
+    /// which costs O(n^2) per lane.
+    Gauss,
+}
+
 /// # Container for chebyshev space
 #[derive(Clone)]
 pub struct Chebyshev<A> {
@@ -23,14 +39,25 @@ pub struct Chebyshev<A> {
     pub m: usize,
     /// Grid coordinates of chebyshev nodes (2nd kind).
     pub x: Array1<A>,
-    /// Handles discrete cosine transform
-    dct_handler: DctHandler<A>,
+    /// Handles discrete cosine transform, None on gauss nodes
+    dct_handler: Option<DctHandler<A>>,
     /// Only for internal use, defines how to correct dct to obtain
     /// chebyshev transform
     correct_dct_forward: Array1<A>,
     correct_dct_backward: Array1<A>,
+    /// Transform matrices \[forward, backward\] on gauss nodes,
+    /// None on gauss-lobatto nodes
+    gauss: Option<[Array2<A>; 2]>,
+    /// Scaling convention of coefficients
+    normalization: Normalization,
+    /// Factors relative to classical coefficients, None if classical
+    scale: Option<Array1<A>>,
     /// Transform kind (real-to-real)
     transform_kind: TransformKind,
+    /// Chain-rule factor 2 / (b - a) of derivatives on intervall \[a, b\]
+    deriv_scale: A,
+    /// Physical intervall \[a, b\]
+    domain: [A; 2],
 }
 
 impl<A: FloatNum> Chebyshev<A> {
@@ -49,6 +76,64 @@ impl<A: FloatNum> Chebyshev<A> {
     /// ```
     #[must_use]
     pub fn new(n: usize) -> Self {
+        Self::new_with_nodes(n, NodeKind::GaussLobatto)
+    }
+
+    /// Creates a new Basis on the physical intervall $[a, b]$.
+    /// `new` is the same as `new_on(n, -1., 1.)`.
+    ///
+    /// The nodes `x` are mapped to $[a, b]$, and `differentiate`
+    /// applies the chain-rule factor $2 / (b - a)$ per derivative,
+    /// such that derivatives come out in physical units.
+    /// The transforms are unchanged.
+    ///
+    /// Note: Operators, which act directly on the coefficients of
+    /// the reference intervall (`laplace`, `laplace_inv`, ...),
+    /// are not rescaled.
+    ///
+    /// # Arguments
+    /// * `n` - Length of array's dimension which shall live in chebyshev space.
+    /// * `a, b` - Physical intervall
+    ///
+    /// # Panics
+    /// Panics when input type cannot be cast from f64, or if `b <= a`.
+    ///
+    /// # Examples
+    /// ```
+    /// use funspace::chebyshev::Chebyshev;
+    /// let cheby = Chebyshev::<f64>::new_on(10, 0., 2.);
+    /// assert!(cheby.x[0].abs() < 1e-14 && (cheby.x[9] - 2.).abs() < 1e-14);
+    /// ```
+    #[must_use]
+    pub fn new_on(n: usize, a: f64, b: f64) -> Self {
+        assert!(b > a, "Intervall [{}, {}] is empty.", a, b);
+        let mut cheby = Self::new(n);
+        let (a, b) = (A::from_f64(a).unwrap(), A::from_f64(b).unwrap());
+        let half = A::from_f64(0.5).unwrap();
+        cheby.x = cheby.x.mapv(|x| a + (x + A::one()) * (b - a) * half);
+        cheby.deriv_scale = A::from_f64(2.).unwrap() / (b - a);
+        cheby.domain = [a, b];
+        cheby
+    }
+
+    /// Creates a new Basis on the given node layout.
+    /// `new` is the same as `new_with_nodes(n, NodeKind::GaussLobatto)`.
+    ///
+    /// # Arguments
+    /// * `n` - Length of array's dimension which shall live in chebyshev space.
+    /// * `kind` - Node layout, see [`NodeKind`]
+    ///
+    /// # Panics
+    /// Panics when input type cannot be cast from f64.
+    ///
+    /// # Examples
+    /// ```
+    /// use funspace::chebyshev::{Chebyshev, NodeKind};
+    /// let cheby = Chebyshev::<f64>::new_with_nodes(10, NodeKind::Gauss);
+    /// assert!(cheby.x[0] > -1. && cheby.x[9] < 1.);
+    /// ```
+    #[must_use]
+    pub fn new_with_nodes(n: usize, kind: NodeKind) -> Self {
         let mut correct_dct = Array1::<A>::zeros(n);
         for (i, s) in correct_dct.iter_mut().enumerate() {
             *s = A::from_f64((-1.0_f64).powf(i as f64)).unwrap();
@@ -56,17 +141,252 @@ impl<A: FloatNum> Chebyshev<A> {
         let correct_dct_forward =
             correct_dct.mapv(|x| x * A::from_f64(1. / (n - 1) as f64).unwrap());
         let correct_dct_backward = correct_dct.mapv(|x| x / A::from_f64(2.0).unwrap());
+        let (x, dct_handler, gauss) = match kind {
+            NodeKind::GaussLobatto => (Self::_nodes_2nd_kind(n), Some(DctHandler::new(n)), None),
+            NodeKind::Gauss => {
+                let x = Self::_nodes_1st_kind(n);
+                let gauss = Self::_gauss_matrices(&x);
+                (x, None, Some(gauss))
+            }
+        };
         Self {
             n,
             m: n,
-            x: Self::_nodes_2nd_kind(n),
-            dct_handler: DctHandler::new(n),
+            x,
+            dct_handler,
             correct_dct_forward,
             correct_dct_backward,
+            gauss,
+            normalization: Normalization::Classical,
+            scale: None,
             transform_kind: TransformKind::RealToReal,
+            deriv_scale: A::one(),
+            domain: [-A::one(), A::one()],
         }
     }
 
+    /// Returns base whose coefficients follow the scaling convention
+    /// *norm*, see [`Normalization`]. The default is `Classical`.
+    ///
+    /// # Panics
+    /// Panics when input type cannot be cast from f64.
+    ///
+    /// # Examples
+    /// ```
+    /// use funspace::chebyshev::Chebyshev;
+    /// use funspace::{Normalization, Transform};
+    /// use ndarray::prelude::*;
+    /// let mut cheby = Chebyshev::<f64>::new(4).with_normalization(Normalization::FftUnnormalized);
+    /// let output = cheby.forward(&array![1., 1., 1., 1.], 0);
+    /// assert!((output[0] - 6.).abs() < 1e-12);
+    /// ```
+    #[must_use]
+    pub fn with_normalization(self, norm: Normalization) -> Self {
+        use std::f64::consts::PI;
+        let kind = if self.gauss.is_some() {
+            NodeKind::Gauss
+        } else {
+            NodeKind::GaussLobatto
+        };
+        let mut cheby = Self::new_with_nodes(self.n, kind);
+        // Keep physical intervall
+        cheby.x = self.x;
+        cheby.deriv_scale = self.deriv_scale;
+        cheby.domain = self.domain;
+        let n = self.n;
+        let m = if kind == NodeKind::Gauss { n } else { n - 1 };
+        let scale = match norm {
+            Normalization::Classical => None,
+            Normalization::Orthonormal => Some(Array1::from_shape_fn(n, |k| {
+                if k == 0 {
+                    PI.sqrt()
+                } else {
+                    (PI / 2.).sqrt()
+                }
+            })),
+            Normalization::FftUnnormalized => Some(Array1::from_shape_fn(n, |k| {
+                let w = if k == 0 || (kind == NodeKind::GaussLobatto && k == n - 1) {
+                    2.
+                } else {
+                    1.
+                };
+                (-1.0_f64).powi(k as i32) * w * m as f64
+            })),
+        };
+        if let Some(scale) = scale.map(|s| s.mapv(|x| A::from_f64(x).unwrap())) {
+            cheby.correct_dct_forward *= &scale;
+            Zip::from(&mut cheby.correct_dct_backward)
+                .and(&scale)
+                .for_each(|d, &s| *d = *d / s);
+            if let Some(mats) = &mut cheby.gauss {
+                mats[0] *= &scale.view().insert_axis(Axis(1));
+                Zip::from(&mut mats[1])
+                    .and_broadcast(&scale)
+                    .for_each(|d, &s| *d = *d / s);
+            }
+            cheby.scale = Some(scale);
+        }
+        cheby.normalization = norm;
+        cheby
+    }
+
+    /// Scaling convention of coefficients
+    #[must_use]
+    pub fn normalization(&self) -> Normalization {
+        self.normalization
+    }
+
+    /// Quadrature weights on the chebyshev nodes, such that
+    /// $\int_{a}^{b} f(x) dx \approx \sum_j w_j f(x_j)$, where
+    /// $[a, b]$ is the physical intervall (default $[-1, 1]$,
+    /// see [`Chebyshev::new_on`]).
+    ///
+    /// Clenshaw-Curtis weights on gauss-lobatto nodes and Fejer
+    /// (first rule) weights on gauss nodes. Both are exact for
+    /// polynomials up to degree *n - 1*.
+    ///
+    /// # Panics
+    /// Panics when input type cannot be cast from f64.
+    ///
+    /// # Examples
+    /// ```
+    /// use funspace::chebyshev::Chebyshev;
+    /// let cheby = Chebyshev::<f64>::new(10);
+    /// assert!((cheby.weights().sum() - 2.).abs() < 1e-12);
+    /// let cheby = Chebyshev::<f64>::new_on(10, 0., 3.);
+    /// assert!((cheby.weights().sum() - 3.).abs() < 1e-12);
+    /// ```
+    #[must_use]
+    pub fn weights(&self) -> Array1<A> {
+        use std::f64::consts::PI;
+        let n = self.n;
+        let weights = if self.gauss.is_some() {
+            Array1::from_shape_fn(n, |j| {
+                let theta = PI * (2 * j + 1) as f64 / (2 * n) as f64;
+                let sum: f64 = (1..=n / 2)
+                    .map(|k| (2. * k as f64 * theta).cos() / (4 * k * k - 1) as f64)
+                    .sum();
+                2. / n as f64 * (1. - 2. * sum)
+            })
+        } else {
+            let m = n - 1;
+            Array1::from_shape_fn(n, |j| {
+                let theta = PI * j as f64 / m as f64;
+                let c = if j == 0 || j == m { 1. } else { 2. };
+                let sum: f64 = (1..=m / 2)
+                    .map(|k| {
+                        let b = if 2 * k == m { 1. } else { 2. };
+                        b * (2. * k as f64 * theta).cos() / (4 * k * k - 1) as f64
+                    })
+                    .sum();
+                c / m as f64 * (1. - sum)
+            })
+        };
+        // Jacobian of the mapping from [-1, 1] to [a, b]
+        weights.mapv(|w| A::from_f64(w).unwrap() / self.deriv_scale)
+    }
+
+    /// Integrate function over the physical intervall $[a, b]$
+    /// (default $[-1, 1]$), given its values
+    /// on the chebyshev nodes. See [`Chebyshev::weights`].
+    ///
+    /// # Panics
+    /// Panics when *values* does not match the number of nodes.
+    ///
+    /// # Examples
+    /// ```
+    /// use funspace::chebyshev::Chebyshev;
+    /// let cheby = Chebyshev::<f64>::new(7);
+    /// let values = cheby.x.mapv(|x| x * x);
+    /// assert!((cheby.integrate(&values.view()) - 2. / 3.).abs() < 1e-12);
+    /// ```
+    pub fn integrate(&self, values: &ArrayView1<A>) -> A {
+        assert!(
+            values.len() == self.n,
+            "Size mismatch in integrate, got {} expected {}.",
+            values.len(),
+            self.n
+        );
+        self.weights().dot(values)
+    }
+
+    /// Evaluate chebyshev series $\sum_k c_k T_k(x)$ at arbitrary
+    /// points, using the clenshaw recurrence.
+    ///
+    /// The points refer to the physical intervall $[a, b]$
+    /// (default $[-1, 1]$, see [`Chebyshev::new_on`]), and are
+    /// mapped to $[-1, 1]$ before evaluation. Points slightly outside
+    /// of the intervall (round-off) are clamped to its boundary.
+    ///
+    /// # Panics
+    /// Debug builds panic if a point lies clearly outside of
+    /// the intervall. Release builds clamp it.
+    ///
+    /// # Examples
+    /// ```
+    /// use funspace::chebyshev::Chebyshev;
+    /// use ndarray::prelude::*;
+    /// let cheby = Chebyshev::<f64>::new(4);
+    /// // T_0 + 2 T_2 = 4x^2 - 1
+    /// let coeffs = array![1., 0., 2., 0.];
+    /// let values = cheby.eval(&coeffs.view(), &[0.5, -0.2]);
+    /// assert!((values[0] - 0.).abs() < 1e-12);
+    /// assert!((values[1] + 0.84).abs() < 1e-12);
+    /// ```
+    pub fn eval(&self, coeffs: &ArrayView1<A>, x: &[A]) -> Array1<A> {
+        let two = A::one() + A::one();
+        let coeffs = match &self.scale {
+            Some(scale) => &coeffs.to_owned() / &scale.slice(s![..coeffs.len()]),
+            None => coeffs.to_owned(),
+        };
+        let [a, b] = self.domain;
+        let tol = A::from_f64(1e-8).unwrap();
+        Array1::from_shape_fn(x.len(), |i| {
+            // Map from [a, b] to [-1, 1]
+            let xi = (two * x[i] - a - b) / (b - a);
+            debug_assert!(
+                xi.abs() <= A::one() + tol,
+                "Point {:?} outside of [{:?}, {:?}].",
+                x[i],
+                a,
+                b
+            );
+            let xi = xi.max(-A::one()).min(A::one());
+            let (mut b1, mut b2) = (A::zero(), A::zero());
+            for c in coeffs.iter().skip(1).rev() {
+                let b0 = *c + two * xi * b1 - b2;
+                b2 = b1;
+                b1 = b0;
+            }
+            match coeffs.len() {
+                0 => A::zero(),
+                _ => coeffs[0] + xi * b1 - b2,
+            }
+        })
+    }
+
+    /// Chebyshev nodes of the first kind on intervall $]-1, 1[$
+    fn _nodes_1st_kind(n: usize) -> Array1<A> {
+        use std::f64::consts::PI;
+        Array1::from_shape_fn(n, |k| {
+            A::from_f64(-(PI * (2 * k + 1) as f64 / (2 * n) as f64).cos()).unwrap()
+        })
+    }
+
+    /// Transform matrices \[forward, backward\] on gauss nodes, using
+    /// the discrete orthogonality of chebyshev polynomials
+    fn _gauss_matrices(x: &Array1<A>) -> [Array2<A>; 2] {
+        let n = x.len();
+        let backward = Array2::from_shape_fn((n, n), |(j, k)| {
+            (A::from_usize(k).unwrap() * x[j].acos()).cos()
+        });
+        let forward = Array2::from_shape_fn((n, n), |(k, j)| {
+            let w = if k == 0 { 1. } else { 2. };
+            backward[[j, k]] * A::from_f64(w / n as f64).unwrap()
+        });
+        [forward, backward]
+    }
+
     /// Chebyshev nodes of the second kind on intervall $[-1, 1]$
     fn _nodes_2nd_kind(n: usize) -> Array1<A> {
         use std::f64::consts::PI;
@@ -110,6 +430,11 @@ impl<A: FloatNum> Chebyshev<A> {
         S: ndarray::Data<Elem = T> + ndarray::DataMut,
     {
         let _2 = T::one() + T::one();
+        if let Some(scale) = &self.scale {
+            if n_times > 0 {
+                data.zip_mut_with(scale, |d, s| *d = *d / (*s).into());
+            }
+        }
         for _ in 0..n_times {
             data[0] = data[1];
             for i in 1..data.len() - 1 {
@@ -122,10 +447,114 @@ impl<A: FloatNum> Chebyshev<A> {
             }
             data[0] = data[0] + data[2] / _2;
         }
+        // Chain-rule factor of physical intervall
+        if n_times > 0 && self.deriv_scale != A::one() {
+            let scale: T = self.deriv_scale.powi(n_times as i32).into();
+            data.mapv_inplace(|v| v * scale);
+        }
+        if let Some(scale) = &self.scale {
+            if n_times > 0 {
+                data.zip_mut_with(scale, |d, s| *d = *d * (*s).into());
+            }
+        }
+    }
+
+    /// Similarity transform of an operator on classical coefficients
+    /// to the scaling convention of the base, $S M S^{-1}$
+    fn similarity(&self, mat: Array2<A>) -> Array2<A> {
+        match &self.scale {
+            Some(scale) => &mat * &scale.view().insert_axis(Axis(1)) / scale,
+            None => mat,
+        }
+    }
+
+    /// Pseudoinverse of the differentiation matrix of order `deriv`
+    /// (1 or 2) in banded form, built without the dense matrix.
+    ///
+    /// Same matrix as [`LaplacianInverse::laplace_inv`] (`deriv` = 2).
+    /// Differential equations on orthogonal coefficients become
+    /// banded, when preconditioned with this matrix.
+    ///
+    /// # Panics
+    /// Panics if `deriv` is not 1 or 2.
+    ///
+    /// # Example
+    /// ```
+    /// use funspace::chebyshev::Chebyshev;
+    /// use funspace::LaplacianInverse;
+    /// use funspace::utils::approx_eq;
+    /// let ch = Chebyshev::<f64>::new(8);
+    /// approx_eq(&ch.pinv_banded(2).to_dense(), &ch.laplace_inv());
+    /// ```
+    #[must_use]
+    pub fn pinv_banded(&self, deriv: usize) -> BandedMatrix<A> {
+        let n = self.n;
+        // Entry of diagonal `k` in row `i`
+        let entry = |k: isize, i: usize| -> f64 {
+            match (deriv, k) {
+                (1, -1) if i == 1 => 1.,
+                (1, -1) => 1. / (2. * i as f64),
+                (1, 1) if i >= 1 && i + 2 < n => -1. / (2. * i as f64),
+                (2, -2) if i == 2 => 0.25,
+                (2, -2) => 1. / (4 * i * (i - 1)) as f64,
+                (2, 0) if i >= 2 && i + 2 < n => -1. / (2 * (i * i - 1)) as f64,
+                (2, 2) if i >= 2 && i + 4 < n => 1. / (4 * i * (i + 1)) as f64,
+                _ => 0.,
+            }
+        };
+        let offsets: Vec<isize> = match deriv {
+            1 => vec![-1, 1],
+            2 => vec![-2, 0, 2],
+            _ => panic!("pinv does only support deriv's 1 & 2, got {}", deriv),
+        };
+        let mut banded = BandedMatrix {
+            shape: (n, n),
+            offsets: Vec::new(),
+            diags: Vec::new(),
+        };
+        for k in offsets {
+            let i0 = if k < 0 { (-k) as usize } else { 0 };
+            let j0 = if k > 0 { k as usize } else { 0 };
+            if i0 >= n || j0 >= n {
+                continue;
+            }
+            let diag = Array1::from_shape_fn(n - i0.max(j0), |t| {
+                let (i, j) = (i0 + t, j0 + t);
+                let mut x = A::from_f64(entry(k, i)).unwrap();
+                if let Some(scale) = &self.scale {
+                    x = x * scale[i] / scale[j];
+                }
+                x
+            });
+            if diag.iter().any(|x| *x != A::zero()) {
+                banded.offsets.push(k);
+                banded.diags.push(diag);
+            }
+        }
+        banded
     }
 }
 
 impl<A: FloatNum> Chebyshev<A> {
+    /// Correct coefficients before dct, to obtain the
+    /// backward chebyshev transform
+    ///
+    /// # Panics
+    /// Panics when input type cannot be cast from f64.
+    #[allow(clippy::used_underscore_binding)]
+    fn correct_backward<S, D>(&self, data: &mut ArrayBase<S, D>, axis: usize)
+    where
+        S: ndarray::Data<Elem = A> + ndarray::DataMut,
+        D: Dimension,
+    {
+        let _2 = A::from_f64(2.).unwrap();
+        for mut v in data.lanes_mut(Axis(axis)) {
+            v *= &self.correct_dct_backward;
+            v[0] *= _2;
+            v[self.n - 1] *= _2;
+        }
+    }
+
     /// Differentation Matrix see [`chebyshev::dmsuite::diffmat_chebyshev`]
     #[allow(clippy::must_use_candidate)]
     fn _dmat(n: usize, deriv: usize) -> Array2<A> {
@@ -246,8 +675,12 @@ impl<A: FloatNum> Transform for Chebyshev<A> {
         use ndrustfft::nddct1;
         check_array_axis(input, self.n, axis, Some("chebyshev forward"));
         check_array_axis(output, self.m, axis, Some("chebyshev forward"));
+        if let Some(mats) = &self.gauss {
+            dot_along(&mats[0], input, output, axis);
+            return;
+        }
         // Cosine transform (DCT)
-        nddct1(input, output, &mut self.dct_handler, axis);
+        nddct1(input, output, self.dct_handler.as_mut().unwrap(), axis);
         // Correct DCT
         let _05 = A::from_f64(1. / 2.).unwrap();
         for mut v in output.lanes_mut(Axis(axis)) {
@@ -284,7 +717,6 @@ impl<A: FloatNum> Transform for Chebyshev<A> {
     ///
     /// # Panics
     /// Panics when input type cannot be cast from f64.
-    #[allow(clippy::used_underscore_binding)]
     fn backward_inplace<S1, S2, D>(
         &mut self,
         input: &ArrayBase<S1, D>,
@@ -299,16 +731,48 @@ impl<A: FloatNum> Transform for Chebyshev<A> {
         use ndrustfft::nddct1;
         check_array_axis(input, self.m, axis, Some("chebyshev backward"));
         check_array_axis(output, self.n, axis, Some("chebyshev backward"));
+        if let Some(mats) = &self.gauss {
+            dot_along(&mats[1], input, output, axis);
+            return;
+        }
         // Correct
         let mut buffer = input.to_owned();
-        let _2 = A::from_f64(2.).unwrap();
-        for mut v in buffer.lanes_mut(Axis(axis)) {
-            v *= &self.correct_dct_backward;
-            v[0] *= _2;
-            v[self.n - 1] *= _2;
+        self.correct_backward(&mut buffer, axis);
+        // Cosine transform (DCT)
+        nddct1(&buffer, output, self.dct_handler.as_mut().unwrap(), axis);
+    }
+
+    /// See [`Chebyshev::backward`]
+    ///
+    /// The dct correction is applied in *scratch*, which
+    /// must be of the same shape as *input*. Neither output nor
+    /// correction buffer are allocated, only the lane buffers of
+    /// the dct.
+    fn backward_into<S1, S2, S3, D>(
+        &mut self,
+        input: &ArrayBase<S1, D>,
+        output: &mut ArrayBase<S2, D>,
+        scratch: &mut ArrayBase<S3, D>,
+        axis: usize,
+    ) where
+        S1: ndarray::Data<Elem = Self::Spectral>,
+        S2: ndarray::Data<Elem = Self::Physical> + ndarray::DataMut,
+        S3: ndarray::Data<Elem = Self::Spectral> + ndarray::DataMut,
+        D: Dimension,
+    {
+        use crate::utils::check_array_axis;
+        use ndrustfft::nddct1;
+        check_array_axis(input, self.m, axis, Some("chebyshev backward"));
+        check_array_axis(output, self.n, axis, Some("chebyshev backward"));
+        if let Some(mats) = &self.gauss {
+            dot_along(&mats[1], input, output, axis);
+            return;
         }
+        // Correct
+        scratch.assign(input);
+        self.correct_backward(scratch, axis);
         // Cosine transform (DCT)
-        nddct1(&buffer, output, &mut self.dct_handler, axis);
+        nddct1(&*scratch, output, self.dct_handler.as_mut().unwrap(), axis);
     }
 }
 
@@ -348,15 +812,20 @@ impl<A: FloatNum> TransformPar for Chebyshev<A> {
         use ndrustfft::nddct1_par;
         check_array_axis(input, self.n, axis, Some("chebyshev forward"));
         check_array_axis(output, self.m, axis, Some("chebyshev forward"));
+        if let Some(mats) = &self.gauss {
+            dot_along(&mats[0], input, output, axis);
+            return;
+        }
         // Cosine transform (DCT)
-        nddct1_par(input, output, &mut self.dct_handler, axis);
+        nddct1_par(input, output, self.dct_handler.as_mut().unwrap(), axis);
         // Correct DCT
         let _05 = A::from_f64(1. / 2.).unwrap();
-        for mut v in output.lanes_mut(Axis(axis)) {
-            v *= &self.correct_dct_forward;
+        let (correct, n) = (&self.correct_dct_forward, self.n);
+        ndarray::Zip::from(output.lanes_mut(Axis(axis))).par_for_each(|mut v| {
+            v *= correct;
             v[0] *= _05;
-            v[self.n - 1] *= _05;
-        }
+            v[n - 1] *= _05;
+        });
     }
 
     /// See [`Chebyshev::backward`]
@@ -394,16 +863,21 @@ impl<A: FloatNum> TransformPar for Chebyshev<A> {
         use ndrustfft::nddct1_par;
         check_array_axis(input, self.m, axis, Some("chebyshev backward"));
         check_array_axis(output, self.n, axis, Some("chebyshev backward"));
+        if let Some(mats) = &self.gauss {
+            dot_along(&mats[1], input, output, axis);
+            return;
+        }
         // Correct
         let mut buffer = input.to_owned();
         let _2 = A::from_f64(2.).unwrap();
-        for mut v in buffer.lanes_mut(Axis(axis)) {
-            v *= &self.correct_dct_backward;
+        let (correct, n) = (&self.correct_dct_backward, self.n);
+        ndarray::Zip::from(buffer.lanes_mut(Axis(axis))).par_for_each(|mut v| {
+            v *= correct;
             v[0] *= _2;
-            v[self.n - 1] *= _2;
-        }
+            v[n - 1] *= _2;
+        });
         // Cosine transform (DCT)
-        nddct1_par(&buffer, output, &mut self.dct_handler, axis);
+        nddct1_par(&buffer, output, self.dct_handler.as_mut().unwrap(), axis);
     }
 }
 
@@ -483,7 +957,7 @@ impl_differentiate_chebyshev!(Complex<A>);
 impl<A: FloatNum> LaplacianInverse<A> for Chebyshev<A> {
     /// Laplacian
     fn laplace(&self) -> Array2<A> {
-        Self::_dmat(self.n, 2)
+        self.similarity(Self::_dmat(self.n, 2))
     }
 
     /// Pseudoinverse Laplacian of chebyshev spectral
@@ -505,7 +979,7 @@ impl<A: FloatNum> LaplacianInverse<A> for Chebyshev<A> {
     /// approx_eq(&peye.slice(s![2..,..]).to_owned(), &ch.laplace_inv_eye());
     /// ```
     fn laplace_inv(&self) -> Array2<A> {
-        Self::_pinv(self.n, 2)
+        self.similarity(Self::_pinv(self.n, 2))
     }
 
     /// Pseudoidentity matrix of laplacian
@@ -622,6 +1096,95 @@ mod test {
     use super::*;
     use crate::utils::approx_eq;
     use ndarray::{Array, Dim, Ix};
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    /// Counts allocations of the current thread
+    struct CountingAlloc;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAlloc {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: CountingAlloc = CountingAlloc;
+
+    /// Number of allocations in *f*
+    fn count_allocations<F: FnOnce()>(f: F) -> usize {
+        let before = ALLOCATIONS.with(Cell::get);
+        f();
+        ALLOCATIONS.with(Cell::get) - before
+    }
+
+    #[test]
+    fn test_cheby_weights() {
+        for n in [2, 5, 8, 13].iter() {
+            let cheby = Chebyshev::<f64>::new(*n);
+            assert!((cheby.weights().sum() - 2.).abs() < 1e-12);
+            let cheby = Chebyshev::<f64>::new_with_nodes(*n, NodeKind::Gauss);
+            assert!((cheby.weights().sum() - 2.).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_cheby_eval() {
+        let mut cheby = Chebyshev::<f64>::new(9);
+        let f = |x: f64| x.powi(5) - 2. * x * x + 0.5;
+        let coeffs = cheby.forward(&cheby.x.mapv(f), 0);
+        let x = [-0.93, -0.1, 0.37, 0.999, 1.];
+        let values = cheby.eval(&coeffs.view(), &x);
+        for (v, xi) in values.iter().zip(x.iter()) {
+            assert!((v - f(*xi)).abs() < 1e-12);
+        }
+        // Clamped
+        let values = cheby.eval(&coeffs.view(), &[1. + 1e-10, -1. - 1e-10]);
+        assert!((values[0] - f(1.)).abs() < 1e-12);
+        assert!((values[1] - f(-1.)).abs() < 1e-12);
+        // Physical intervall [0, 2]
+        let mut cheby = Chebyshev::<f64>::new_on(9, 0., 2.);
+        let coeffs = cheby.forward(&cheby.x.mapv(f), 0);
+        let x = [0., 0.37, 1.25, 2.];
+        let values = cheby.eval(&coeffs.view(), &x);
+        for (v, xi) in values.iter().zip(x.iter()) {
+            assert!((v - f(*xi)).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn test_cheby_eval_outside() {
+        let cheby = Chebyshev::<f64>::new(4);
+        let coeffs = array![1., 0., 2., 0.];
+        cheby.eval(&coeffs.view(), &[-1.5]);
+    }
+
+    #[test]
+    fn test_cheby_integrate() {
+        for kind in [NodeKind::GaussLobatto, NodeKind::Gauss].iter() {
+            let cheby = Chebyshev::<f64>::new_with_nodes(9, *kind);
+            let values = cheby.x.mapv(|x| 1. - x * x);
+            assert!((cheby.integrate(&values.view()) - 4. / 3.).abs() < 1e-12);
+            let values = cheby.x.mapv(|x| x.powi(7) + x.powi(8));
+            assert!((cheby.integrate(&values.view()) - 2. / 9.).abs() < 1e-12);
+        }
+        // Physical intervall [0, 3]
+        let cheby = Chebyshev::<f64>::new_on(9, 0., 3.);
+        assert!((cheby.weights().sum() - 3.).abs() < 1e-12);
+        let values = cheby.x.mapv(|x| x * x);
+        assert!((cheby.integrate(&values.view()) - 9.).abs() < 1e-12);
+    }
 
     #[test]
     /// Differantiate 2d array along first and second axis
@@ -662,4 +1225,184 @@ mod test {
         cheby.differentiate_inplace(&mut diff, 1, 1);
         approx_eq(&diff, &expected);
     }
+
+    #[test]
+    fn test_cheby_par_matches_serial() {
+        let (nx, ny) = (9, 6);
+        let data = Array2::from_shape_fn((nx, ny), |(i, j)| ((i * ny + j) as f64).sin());
+        for (axis, n) in [nx, ny].iter().enumerate() {
+            let mut cheby = Chebyshev::<f64>::new(*n);
+            let vhat = cheby.forward(&data, axis);
+            approx_eq(&cheby.forward_par(&data, axis), &vhat);
+            approx_eq(
+                &cheby.backward_par(&vhat, axis),
+                &cheby.backward(&vhat, axis),
+            );
+        }
+        // Single lane
+        let mut cheby = Chebyshev::<f64>::new(nx);
+        let data = Array1::from_shape_fn(nx, |i| (i as f64).cos());
+        let vhat = cheby.forward(&data, 0);
+        approx_eq(&cheby.forward_par(&data, 0), &vhat);
+        approx_eq(&cheby.backward_par(&vhat, 0), &data);
+    }
+
+    #[test]
+    fn test_cheby_single_precision() {
+        let (nx, ny) = (14, 5);
+        let mut cheby_f64 = Chebyshev::<f64>::new(nx);
+        let mut cheby_f32 = Chebyshev::<f32>::new(nx);
+        // Same node layout
+        for (a, b) in cheby_f64.x.iter().zip(cheby_f32.x.iter()) {
+            assert!((a - f64::from(*b)).abs() < 1e-6);
+        }
+        let data_f64 = Array::from_shape_fn((nx, ny), |(i, j)| ((i * ny + j) as f64).sin());
+        let data_f32 = data_f64.mapv(|x| x as f32);
+        let vhat_f64 = cheby_f64.forward(&data_f64, 0);
+        let vhat_f32 = cheby_f32.forward(&data_f32, 0);
+        for (a, b) in vhat_f64.iter().zip(vhat_f32.iter()) {
+            assert!((a - f64::from(*b)).abs() < 1e-4);
+        }
+        // Round-trip
+        let v_f32 = cheby_f32.backward(&vhat_f32, 0);
+        for (a, b) in data_f64.iter().zip(v_f32.iter()) {
+            assert!((a - f64::from(*b)).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_cheby_backward_into() {
+        let (nx, ny) = (10, 6);
+        let mut cheby = Chebyshev::<f64>::new(nx);
+        let vhat = Array::from_shape_fn((nx, ny), |(i, j)| ((i * ny + j) as f64).cos());
+        let expected = cheby.backward(&vhat, 0);
+        let mut output = Array2::<f64>::zeros((nx, ny));
+        let mut scratch = Array2::<f64>::zeros((nx, ny));
+        cheby.backward_into(&vhat, &mut output, &mut scratch, 0);
+        approx_eq(&output, &expected);
+    }
+
+    #[test]
+    fn test_cheby_backward_allocations() {
+        // backward_into saves the allocation of output and correction
+        // buffer, the dct still allocates lane buffers internally
+        let n = 256;
+        let mut cheby = Chebyshev::<f64>::new(n);
+        let vhat = Array2::from_shape_fn((n, n), |(i, j)| ((i * n + j) as f64).cos());
+        let mut output = Array2::<f64>::zeros((n, n));
+        let mut scratch = Array2::<f64>::zeros((n, n));
+        let allocations = count_allocations(|| {
+            let _ = cheby.backward(&vhat, 0);
+        });
+        let allocations_into = count_allocations(|| {
+            cheby.backward_into(&vhat, &mut output, &mut scratch, 0);
+        });
+        assert_eq!(allocations - allocations_into, 2);
+    }
+
+    #[test]
+    fn test_cheby_gauss_nodes() {
+        let (nx, ny) = (9, 4);
+        let mut cheby = Chebyshev::<f64>::new_with_nodes(nx, NodeKind::Gauss);
+        assert!(cheby.x[0] > -1. && cheby.x[nx - 1] < 1.);
+        assert!(cheby.dct_handler.is_none());
+        // x^2 = (T_0 + T_2) / 2
+        let data = Array::from_shape_fn((nx, ny), |(i, _)| cheby.x[i] * cheby.x[i]);
+        let vhat = cheby.forward(&data, 0);
+        let mut expected = Array2::<f64>::zeros((nx, ny));
+        expected.row_mut(0).fill(0.5);
+        expected.row_mut(2).fill(0.5);
+        approx_eq(&vhat, &expected);
+        // Round-trip
+        let data = Array::from_shape_fn((ny, nx), |(i, j)| ((i * nx + j) as f64).sin());
+        let vhat = cheby.forward(&data, 1);
+        approx_eq(&cheby.backward(&vhat, 1), &data);
+        approx_eq(&cheby.backward_par(&vhat, 1), &data);
+        // Gauss-lobatto is default
+        let lobatto = Chebyshev::<f64>::new_with_nodes(nx, NodeKind::GaussLobatto);
+        approx_eq(&lobatto.x, &Chebyshev::<f64>::new(nx).x);
+    }
+
+    #[test]
+    fn test_cheby_new_on() {
+        let n = 9;
+        let mut cheby = Chebyshev::<f64>::new_on(n, 0., 2.);
+        assert!(cheby.x[0].abs() < 1e-14 && (cheby.x[n - 1] - 2.).abs() < 1e-14);
+        // d/dx x^2 = 2x on [0, 2]
+        let data = cheby.x.mapv(|x| x * x);
+        let vhat = cheby.forward(&data, 0);
+        let dvhat = cheby.differentiate(&vhat, 1, 0);
+        approx_eq(&cheby.backward(&dvhat, 0), &cheby.x.mapv(|x| 2. * x));
+        // d2/dx2 x^2 = 2
+        let dvhat = cheby.differentiate_par(&vhat, 2, 0);
+        approx_eq(&cheby.backward(&dvhat, 0), &Array1::from_elem(n, 2.));
+        // Normalization keeps the physical intervall
+        let mut cheby = cheby.with_normalization(Normalization::Orthonormal);
+        assert!(cheby.x[0].abs() < 1e-14 && (cheby.x[n - 1] - 2.).abs() < 1e-14);
+        let vhat = cheby.forward(&data, 0);
+        let dvhat = cheby.differentiate(&vhat, 1, 0);
+        approx_eq(&cheby.backward(&dvhat, 0), &cheby.x.mapv(|x| 2. * x));
+        assert!((cheby.weights().sum() - 2.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_cheby_normalization() {
+        use std::f64::consts::PI;
+        let n = 8;
+        for kind in [NodeKind::GaussLobatto, NodeKind::Gauss].iter() {
+            let mut classical = Chebyshev::<f64>::new_with_nodes(n, *kind);
+            let data = classical.x.mapv(|x| (2. * x).sin() + x);
+            let c = classical.forward(&data, 0);
+            let dc = classical.differentiate(&c, 2, 0);
+            let ddata = classical.backward(&dc, 0);
+            for norm in [
+                Normalization::Classical,
+                Normalization::Orthonormal,
+                Normalization::FftUnnormalized,
+            ]
+            .iter()
+            {
+                let mut cheby =
+                    Chebyshev::<f64>::new_with_nodes(n, *kind).with_normalization(*norm);
+                assert_eq!(cheby.normalization(), *norm);
+                // Round-trip
+                let vhat = cheby.forward(&data, 0);
+                approx_eq(&cheby.backward(&vhat, 0), &data);
+                let vhat_par = cheby.forward_par(&data, 0);
+                approx_eq(&vhat_par, &vhat);
+                approx_eq(&cheby.backward_par(&vhat_par, 0), &data);
+                // Differentiation and laplacian act on coefficients of the convention
+                let dvhat = cheby.differentiate(&vhat, 2, 0);
+                approx_eq(&cheby.backward(&dvhat, 0), &ddata);
+                approx_eq(&cheby.laplace().dot(&vhat), &dvhat);
+                // Documented factors
+                let x = &data;
+                let expected = match norm {
+                    Normalization::Classical => c.clone(),
+                    Normalization::Orthonormal => Array1::from_shape_fn(n, |k| {
+                        let s = if k == 0 { PI.sqrt() } else { (PI / 2.).sqrt() };
+                        s * c[k]
+                    }),
+                    Normalization::FftUnnormalized => match kind {
+                        NodeKind::GaussLobatto => Array1::from_shape_fn(n, |k| {
+                            let m = (n - 1) as f64;
+                            let sum: f64 = (1..n - 1)
+                                .map(|j| x[j] * (PI * (j * k) as f64 / m).cos())
+                                .sum();
+                            x[0] + (-1.0_f64).powi(k as i32) * x[n - 1] + 2. * sum
+                        }),
+                        NodeKind::Gauss => Array1::from_shape_fn(n, |k| {
+                            let sum: f64 = (0..n)
+                                .map(|j| {
+                                    x[j] * (PI * (k * (2 * j + 1)) as f64 / (2 * n) as f64).cos()
+                                })
+                                .sum();
+                            2. * sum
+                        }),
+                    },
+                };
+                approx_eq(&vhat, &expected);
+            }
+        }
+    }
 }
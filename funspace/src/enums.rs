@@ -5,10 +5,14 @@
 //!
 //! Real-to-complex transforms implement the differentiate and
 //! from ortho trait only for complex numbers
+use crate::banded::BandedMatrix;
 use crate::chebyshev::Chebyshev;
 use crate::chebyshev::CompositeChebyshev;
 use crate::fourier::FourierC2c;
+use crate::fourier::FourierCosine;
 use crate::fourier::FourierR2c;
+use crate::fourier::FourierSine;
+use crate::traits::Basics;
 use crate::traits::Differentiate;
 use crate::traits::DifferentiatePar;
 use crate::traits::FromOrtho;
@@ -19,7 +23,7 @@ use crate::types::FloatNum;
 use ndarray::prelude::*;
 use num_complex::Complex;
 
-#[allow(clippy::pub_enum_variant_names)]
+#[allow(clippy::large_enum_variant)]
 #[enum_dispatch(Basics<T>, LaplacianInverse<T>)]
 #[derive(Clone)]
 /// Enum of enums which binds all bases
@@ -41,6 +45,10 @@ pub enum BaseR2r<T: FloatNum> {
     Chebyshev(Chebyshev<T>),
     /// Chebyshev polynomials (composite)
     CompositeChebyshev(CompositeChebyshev<T>),
+    /// Sine series
+    FourierSine(FourierSine<T>),
+    /// Cosine series
+    FourierCosine(FourierCosine<T>),
 }
 
 #[enum_dispatch(Basics<T>, LaplacianInverse<T>)]
@@ -60,11 +68,47 @@ pub enum BaseC2c<T: FloatNum> {
 }
 
 // Implement traits on real-to-real
-impl_transform_trait_for_base!(BaseR2r, A, A, Chebyshev, CompositeChebyshev);
-impl_differentiate_trait_for_base!(BaseR2r, A, Chebyshev, CompositeChebyshev);
-impl_differentiate_trait_for_base!(BaseR2r, Complex<A>, Chebyshev, CompositeChebyshev);
-impl_from_ortho_trait_for_base!(BaseR2r, A, Chebyshev, CompositeChebyshev);
-impl_from_ortho_trait_for_base!(BaseR2r, Complex<A>, Chebyshev, CompositeChebyshev);
+impl_transform_trait_for_base!(
+    BaseR2r,
+    A,
+    A,
+    Chebyshev,
+    CompositeChebyshev,
+    FourierSine,
+    FourierCosine
+);
+impl_differentiate_trait_for_base!(
+    BaseR2r,
+    A,
+    Chebyshev,
+    CompositeChebyshev,
+    FourierSine,
+    FourierCosine
+);
+impl_differentiate_trait_for_base!(
+    BaseR2r,
+    Complex<A>,
+    Chebyshev,
+    CompositeChebyshev,
+    FourierSine,
+    FourierCosine
+);
+impl_from_ortho_trait_for_base!(
+    BaseR2r,
+    A,
+    Chebyshev,
+    CompositeChebyshev,
+    FourierSine,
+    FourierCosine
+);
+impl_from_ortho_trait_for_base!(
+    BaseR2r,
+    Complex<A>,
+    Chebyshev,
+    CompositeChebyshev,
+    FourierSine,
+    FourierCosine
+);
 
 // Implement traits on real-to-complex
 impl_transform_trait_for_base!(BaseR2c, A, Complex<A>, FourierR2c);
@@ -75,3 +119,64 @@ impl_from_ortho_trait_for_base!(BaseR2c, Complex<A>, FourierR2c);
 impl_transform_trait_for_base!(BaseC2c, Complex<A>, Complex<A>, FourierC2c);
 impl_differentiate_trait_for_base!(BaseC2c, Complex<A>, FourierC2c);
 impl_from_ortho_trait_for_base!(BaseC2c, Complex<A>, FourierC2c);
+
+impl<A: FloatNum> BaseR2r<A> {
+    /// Returns differentiation operator in spectral space, mapping
+    /// coefficients of this base to orthogonal coefficients of the
+    /// *order*-th derivative.
+    ///
+    /// Sine and cosine operators are diagonal. The chebyshev operator
+    /// is dense upper triangular, i.e. only every second diagonal is
+    /// nonzero, which halves the storage, but it remains O(n²). For
+    /// banded systems, use [`BaseR2r::quasi_inverse_sparse`].
+    #[must_use]
+    pub fn differentiation_operator_sparse(&self, order: usize) -> BandedMatrix<A> {
+        match self {
+            Self::Chebyshev(_) | Self::CompositeChebyshev(_) => {
+                let eye = Array2::<A>::eye(self.len_spec());
+                BandedMatrix::from_dense(&self.differentiate(&eye, order, 0))
+            }
+            Self::FourierSine(_) | Self::FourierCosine(_) => BandedMatrix::from_diag(
+                &self.differentiate(&Array1::<A>::ones(self.len_spec()), order, 0),
+            ),
+        }
+    }
+
+    /// Returns quasi-inverse (integration) operator of the chebyshev
+    /// differentiation operator of *order* (1 or 2) in banded form,
+    /// acting on orthogonal coefficients, see [`Chebyshev::pinv_banded`].
+    ///
+    /// # Panics
+    /// Panics for sine and cosine bases, whose differentiation
+    /// operator is already diagonal, or if *order* is not 1 or 2.
+    #[must_use]
+    pub fn quasi_inverse_sparse(&self, order: usize) -> BandedMatrix<A> {
+        match self {
+            Self::Chebyshev(b) => b.pinv_banded(order),
+            Self::CompositeChebyshev(b) => b.ortho.pinv_banded(order),
+            Self::FourierSine(_) | Self::FourierCosine(_) => {
+                panic!("Quasi-inverse is only defined for chebyshev bases.")
+            }
+        }
+    }
+}
+
+impl<A: FloatNum> BaseR2c<A> {
+    /// Returns differentiation operator in spectral space
+    /// (diagonal, with entries (ik)^order).
+    #[must_use]
+    pub fn differentiation_operator_sparse(&self, order: usize) -> BandedMatrix<Complex<A>> {
+        let ones = Array1::<Complex<A>>::ones(self.len_spec());
+        BandedMatrix::from_diag(&self.differentiate(&ones, order, 0))
+    }
+}
+
+impl<A: FloatNum> BaseC2c<A> {
+    /// Returns differentiation operator in spectral space
+    /// (diagonal, with entries (ik)^order).
+    #[must_use]
+    pub fn differentiation_operator_sparse(&self, order: usize) -> BandedMatrix<Complex<A>> {
+        let ones = Array1::<Complex<A>>::ones(self.len_spec());
+        BandedMatrix::from_diag(&self.differentiate(&ones, order, 0))
+    }
+}
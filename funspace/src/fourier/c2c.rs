@@ -14,6 +14,7 @@ use crate::traits::DifferentiatePar;
 use crate::traits::FromOrtho;
 use crate::traits::FromOrthoPar;
 use crate::traits::LaplacianInverse;
+use crate::traits::Normalization;
 use crate::traits::Transform;
 use crate::traits::TransformKind;
 use crate::traits::TransformPar;
@@ -37,6 +38,10 @@ pub struct FourierC2c<A> {
     pub k: Array1<Complex<A>>,
     /// Handles discrete cosine transform
     pub fft_handler: FftHandler<A>,
+    /// Scaling convention of coefficients
+    normalization: Normalization,
+    /// Factor relative to unnormalized fft, None if unnormalized
+    scale: Option<A>,
     /// Transform kind (complex-to-complex)
     transform_kind: TransformKind,
 }
@@ -51,10 +56,36 @@ impl<A: FloatNum> FourierC2c<A> {
             x: Self::nodes(n),
             k: Self::wavenumber(n),
             fft_handler: FftHandler::new(n),
+            normalization: Normalization::FftUnnormalized,
+            scale: None,
             transform_kind: TransformKind::ComplexToComplex,
         }
     }
 
+    /// Returns base whose coefficients follow the scaling convention
+    /// *norm*, see [`Normalization`]. The default is `FftUnnormalized`.
+    ///
+    /// # Panics
+    /// Panics when input type cannot be cast from f64.
+    #[must_use]
+    pub fn with_normalization(mut self, norm: Normalization) -> Self {
+        let n = self.n as f64;
+        let scale = match norm {
+            Normalization::FftUnnormalized => None,
+            Normalization::Classical => Some(1. / n),
+            Normalization::Orthonormal => Some((2. * std::f64::consts::PI).sqrt() / n),
+        };
+        self.normalization = norm;
+        self.scale = scale.map(|s| A::from_f64(s).unwrap());
+        self
+    }
+
+    /// Scaling convention of coefficients
+    #[must_use]
+    pub fn normalization(&self) -> Normalization {
+        self.normalization
+    }
+
     /// Return equispaced points on intervall [0, 2pi[
     ///
     /// ## Panics
@@ -74,13 +105,13 @@ impl<A: FloatNum> FourierC2c<A> {
             .iter()
             .zip(k.slice_mut(s![..n2]))
         {
-            *ki = A::from_f64(*i as f64).unwrap();
+            *ki = A::from_f64(*i).unwrap();
         }
-        for (i, ki) in Array1::range(-1. * (n2 / 2 + 1) as f64, 0., 1.)
+        for (i, ki) in Array1::range(-((n2 / 2 + 1) as f64), 0., 1.)
             .iter()
             .zip(k.slice_mut(s![n2..]))
         {
-            *ki = A::from_f64(*i as f64).unwrap();
+            *ki = A::from_f64(*i).unwrap();
         }
         k.mapv(|x| Complex::new(A::zero(), x))
     }
@@ -257,6 +288,9 @@ impl<A: FloatNum> Transform for FourierC2c<A> {
         check_array_axis(input, self.n, axis, Some("fourier forward"));
         check_array_axis(output, self.m, axis, Some("fourier forward"));
         ndfft(input, output, &mut self.fft_handler, axis);
+        if let Some(scale) = self.scale {
+            output.mapv_inplace(|v| v * scale);
+        }
     }
 
     /// # Example
@@ -313,7 +347,12 @@ impl<A: FloatNum> Transform for FourierC2c<A> {
         use ndrustfft::ndifft;
         check_array_axis(input, self.m, axis, Some("fourier backward"));
         check_array_axis(output, self.n, axis, Some("fourier backward"));
-        ndifft(input, output, &mut self.fft_handler, axis);
+        if let Some(scale) = self.scale {
+            let buffer = input.mapv(|v| v / scale);
+            ndifft(&buffer, output, &mut self.fft_handler, axis);
+        } else {
+            ndifft(input, output, &mut self.fft_handler, axis);
+        }
     }
 }
 
@@ -353,6 +392,9 @@ impl<A: FloatNum> TransformPar for FourierC2c<A> {
         check_array_axis(input, self.n, axis, Some("fourier forward"));
         check_array_axis(output, self.m, axis, Some("fourier forward"));
         ndfft_par(input, output, &mut self.fft_handler, axis);
+        if let Some(scale) = self.scale {
+            output.mapv_inplace(|v| v * scale);
+        }
     }
 
     /// Parallel version. See [`FourierC2c::backward`]
@@ -390,7 +432,12 @@ impl<A: FloatNum> TransformPar for FourierC2c<A> {
         use ndrustfft::ndifft_par;
         check_array_axis(input, self.m, axis, Some("fourier backward"));
         check_array_axis(output, self.n, axis, Some("fourier backward"));
-        ndifft_par(input, output, &mut self.fft_handler, axis);
+        if let Some(scale) = self.scale {
+            let buffer = input.mapv(|v| v / scale);
+            ndifft_par(&buffer, output, &mut self.fft_handler, axis);
+        } else {
+            ndifft_par(input, output, &mut self.fft_handler, axis);
+        }
     }
 }
 
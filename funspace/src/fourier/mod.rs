@@ -7,8 +7,13 @@
 //!
 //! Complex-to-complex: [`c2c::FourierC2c`]
 //! Real-to-complex: [`r2c::FourierR2c`]
+//!
+//! Real-to-real (sine and cosine series on $[0, pi]$):
+//! [`r2r::FourierSine`], [`r2r::FourierCosine`]
 #![allow(clippy::module_name_repetitions)]
 mod c2c;
 mod r2c;
+mod r2r;
 pub use c2c::FourierC2c;
 pub use r2c::FourierR2c;
+pub use r2r::{FourierCosine, FourierSine};
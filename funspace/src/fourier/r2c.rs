@@ -13,6 +13,7 @@ use crate::traits::DifferentiatePar;
 use crate::traits::FromOrtho;
 use crate::traits::FromOrthoPar;
 use crate::traits::LaplacianInverse;
+use crate::traits::Normalization;
 use crate::traits::Transform;
 use crate::traits::TransformKind;
 use crate::traits::TransformPar;
@@ -35,6 +36,10 @@ pub struct FourierR2c<A> {
     pub k: Array1<Complex<A>>,
     /// Handles discrete cosine transform
     pub fft_handler: R2cFftHandler<A>,
+    /// Scaling convention of coefficients
+    normalization: Normalization,
+    /// Factor relative to unnormalized fft, None if unnormalized
+    scale: Option<A>,
     /// Transform kind (real-to-complex)
     transform_kind: TransformKind,
 }
@@ -49,10 +54,36 @@ impl<A: FloatNum> FourierR2c<A> {
             x: FourierC2c::nodes(n),
             k: Self::wavenumber(n),
             fft_handler: R2cFftHandler::new(n),
+            normalization: Normalization::FftUnnormalized,
+            scale: None,
             transform_kind: TransformKind::RealToComplex,
         }
     }
 
+    /// Returns base whose coefficients follow the scaling convention
+    /// *norm*, see [`Normalization`]. The default is `FftUnnormalized`.
+    ///
+    /// # Panics
+    /// Panics when input type cannot be cast from f64.
+    #[must_use]
+    pub fn with_normalization(mut self, norm: Normalization) -> Self {
+        let n = self.n as f64;
+        let scale = match norm {
+            Normalization::FftUnnormalized => None,
+            Normalization::Classical => Some(1. / n),
+            Normalization::Orthonormal => Some((2. * std::f64::consts::PI).sqrt() / n),
+        };
+        self.normalization = norm;
+        self.scale = scale.map(|s| A::from_f64(s).unwrap());
+        self
+    }
+
+    /// Scaling convention of coefficients
+    #[must_use]
+    pub fn normalization(&self) -> Normalization {
+        self.normalization
+    }
+
     /// Return complex wavenumber vector for r2c transform (0, 1, 2, 3)
     #[allow(clippy::missing_panics_doc)]
     fn wavenumber(n: usize) -> Array1<Complex<A>> {
@@ -63,7 +94,7 @@ impl<A: FloatNum> FourierR2c<A> {
             .iter()
             .zip(k.slice_mut(s![..n2]))
         {
-            *ki = A::from_f64(*i as f64).unwrap();
+            *ki = A::from_f64(*i).unwrap();
         }
 
         k.mapv(|x| Complex::new(A::zero(), x))
@@ -172,6 +203,9 @@ impl<A: FloatNum> Transform for FourierR2c<A> {
         check_array_axis(input, self.n, axis, Some("fourier forward"));
         check_array_axis(output, self.m, axis, Some("fourier forward"));
         ndfft_r2c(input, output, &mut self.fft_handler, axis);
+        if let Some(scale) = self.scale {
+            output.mapv_inplace(|v| v * scale);
+        }
     }
 
     /// # Example
@@ -222,7 +256,12 @@ impl<A: FloatNum> Transform for FourierR2c<A> {
         use ndrustfft::ndifft_r2c;
         check_array_axis(input, self.m, axis, Some("fourier backward"));
         check_array_axis(output, self.n, axis, Some("fourier backward"));
-        ndifft_r2c(input, output, &mut self.fft_handler, axis);
+        if let Some(scale) = self.scale {
+            let buffer = input.mapv(|v| v / scale);
+            ndifft_r2c(&buffer, output, &mut self.fft_handler, axis);
+        } else {
+            ndifft_r2c(input, output, &mut self.fft_handler, axis);
+        }
     }
 }
 
@@ -262,6 +301,9 @@ impl<A: FloatNum> TransformPar for FourierR2c<A> {
         check_array_axis(input, self.n, axis, Some("fourier forward"));
         check_array_axis(output, self.m, axis, Some("fourier forward"));
         ndfft_r2c_par(input, output, &mut self.fft_handler, axis);
+        if let Some(scale) = self.scale {
+            output.mapv_inplace(|v| v * scale);
+        }
     }
 
     /// Parallel version. See [`FourierR2c::backward`]
@@ -299,7 +341,12 @@ impl<A: FloatNum> TransformPar for FourierR2c<A> {
         use ndrustfft::ndifft_r2c_par;
         check_array_axis(input, self.m, axis, Some("fourier backward"));
         check_array_axis(output, self.n, axis, Some("fourier backward"));
-        ndifft_r2c_par(input, output, &mut self.fft_handler, axis);
+        if let Some(scale) = self.scale {
+            let buffer = input.mapv(|v| v / scale);
+            ndifft_r2c_par(&buffer, output, &mut self.fft_handler, axis);
+        } else {
+            ndifft_r2c_par(input, output, &mut self.fft_handler, axis);
+        }
     }
 }
 
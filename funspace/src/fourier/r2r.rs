@@ -0,0 +1,920 @@
+//! # Real-to-real fourier spaces (sine and cosine series)
+//!
+//! Defined on the intervall $[0, \pi]$, on the equispaced grid
+//! $x_j = \pi j / (n - 1)$, $j = 0, .., n - 1$ (endpoints included).
+//!
+//! Differentiation is a multiplication with the wavenumbers. Even
+//! derivatives stay in the same series, odd derivatives map
+//! sine coefficients to cosine coefficients of the same
+//! wavenumber, and vice versa.
+use crate::traits::Basics;
+use crate::traits::Differentiate;
+use crate::traits::DifferentiatePar;
+use crate::traits::FromOrtho;
+use crate::traits::FromOrthoPar;
+use crate::traits::LaplacianInverse;
+use crate::traits::Transform;
+use crate::traits::TransformKind;
+use crate::traits::TransformPar;
+use crate::types::FloatNum;
+use crate::Scalar;
+use ndarray::prelude::*;
+use ndarray::Slice;
+use ndrustfft::{DctHandler, R2cFftHandler};
+use num_complex::Complex;
+
+/// # Container for cosine series
+///
+/// $$
+/// f(x) = \sum_{k=0}^{n-1} c_k \cos(k x)
+/// $$
+///
+/// The basis functions satisfy homogeneous neumann
+/// boundary conditions. Transforms via DCT-I.
+#[derive(Clone)]
+pub struct FourierCosine<A> {
+    /// Number of coefficients in physical space
+    pub n: usize,
+    /// Number of coefficients in spectral space ( equal to *n* in this case )
+    pub m: usize,
+    /// Grid coordinates
+    pub x: Array1<A>,
+    /// Wavenumber vector
+    pub k: Array1<A>,
+    /// Handles discrete cosine transform
+    dct_handler: DctHandler<A>,
+    /// Transform kind (real-to-real)
+    transform_kind: TransformKind,
+}
+
+/// # Container for sine series
+///
+/// $$
+/// f(x) = \sum_{k=1}^{n-2} c_k \sin(k x)
+/// $$
+///
+/// The basis functions satisfy homogeneous dirichlet
+/// boundary conditions, hence the spectral space is two
+/// coefficients smaller than the physical space. Transforms
+/// via DST-I, computed as real fft of the odd extension.
+#[derive(Clone)]
+pub struct FourierSine<A> {
+    /// Number of coefficients in physical space
+    pub n: usize,
+    /// Number of coefficients in spectral space ( *n* - 2 )
+    pub m: usize,
+    /// Grid coordinates
+    pub x: Array1<A>,
+    /// Wavenumber vector
+    pub k: Array1<A>,
+    /// Handles fft of odd extension (size 2 * (n - 1))
+    fft_handler: R2cFftHandler<A>,
+    /// Transform kind (real-to-real)
+    transform_kind: TransformKind,
+}
+
+/// Equispaced grid on $[0, \pi]$, including the endpoints
+fn nodes<A: FloatNum>(n: usize) -> Array1<A> {
+    let dx = A::PI() / A::from_usize(n - 1).unwrap();
+    Array1::from_shape_fn(n, |j| A::from_usize(j).unwrap() * dx)
+}
+
+/// Factor $\pm k^{order}$ of derivative *order*, where the sign
+/// is $(-1)^{\lceil order / 2 \rceil}$ (cosine) or
+/// $(-1)^{\lfloor order / 2 \rfloor}$ (sine)
+fn deriv_factor<A: FloatNum>(k: A, order: usize, cosine: bool) -> A {
+    let flips = if cosine { order.div_ceil(2) } else { order / 2 };
+    let sign = if flips % 2 == 0 { A::one() } else { -A::one() };
+    sign * k.powi(order as i32)
+}
+
+impl<A: FloatNum> FourierCosine<A> {
+    /// Creates a new Basis.
+    ///
+    /// # Arguments
+    /// * `n` - Length of array's dimension which shall live in cosine space.
+    ///
+    /// # Panics
+    /// Panics when n < 2.
+    ///
+    /// # Examples
+    /// ```
+    /// use funspace::fourier::FourierCosine;
+    /// let cosine = FourierCosine::<f64>::new(10);
+    /// ```
+    #[must_use]
+    pub fn new(n: usize) -> Self {
+        assert!(n > 1, "Cosine basis requires at least 2 points.");
+        Self {
+            n,
+            m: n,
+            x: nodes(n),
+            k: Array1::from_shape_fn(n, |i| A::from_usize(i).unwrap()),
+            dct_handler: DctHandler::new(n),
+            transform_kind: TransformKind::RealToReal,
+        }
+    }
+
+    /// Differentiate 1d Array *n_times*
+    ///
+    /// # Example
+    /// ```
+    /// use funspace::fourier::FourierCosine;
+    /// use funspace::utils::approx_eq;
+    /// use ndarray::prelude::*;
+    /// let cosine = FourierCosine::<f64>::new(4);
+    /// // d2/dx2 cos(2x) = -4 cos(2x)
+    /// let mut input = array![0., 0., 1., 0.];
+    /// cosine.differentiate_lane(&mut input, 2);
+    /// approx_eq(&input, &array![0., 0., -4., 0.]);
+    /// ```
+    pub fn differentiate_lane<T, S>(&self, data: &mut ArrayBase<S, Ix1>, n_times: usize)
+    where
+        T: Scalar + From<A>,
+        S: ndarray::Data<Elem = T> + ndarray::DataMut,
+    {
+        for (v, k) in data.iter_mut().zip(self.k.iter()) {
+            *v = *v * deriv_factor(*k, n_times, true).into();
+        }
+    }
+
+    /// Normalize DCT-I to cosine coefficients
+    fn correct_forward<S, D>(&self, data: &mut ArrayBase<S, D>, axis: usize)
+    where
+        S: ndarray::Data<Elem = A> + ndarray::DataMut,
+        D: Dimension,
+    {
+        let scale = A::one() / A::from_usize(self.n - 1).unwrap();
+        let half = A::from_f64(0.5).unwrap();
+        data.mapv_inplace(|v| v * scale);
+        for i in [0, self.n - 1].iter() {
+            data.slice_axis_mut(Axis(axis), Slice::from(*i..*i + 1))
+                .mapv_inplace(|v| v * half);
+        }
+    }
+
+    /// Cosine coefficients to input of DCT-I
+    fn correct_backward<S, D>(&self, input: &ArrayBase<S, D>, axis: usize) -> Array<A, D>
+    where
+        S: ndarray::Data<Elem = A>,
+        D: Dimension,
+    {
+        let half = A::from_f64(0.5).unwrap();
+        let mut buffer = input.mapv(|v| v * half);
+        for i in [0, self.n - 1].iter() {
+            let slice = Slice::from(*i..*i + 1);
+            buffer
+                .slice_axis_mut(Axis(axis), slice)
+                .assign(&input.slice_axis(Axis(axis), slice));
+        }
+        buffer
+    }
+}
+
+impl<A: FloatNum> FourierSine<A> {
+    /// Creates a new Basis.
+    ///
+    /// # Arguments
+    /// * `n` - Length of array's dimension which shall live in sine space.
+    ///
+    /// # Panics
+    /// Panics when n < 3.
+    ///
+    /// # Examples
+    /// ```
+    /// use funspace::fourier::FourierSine;
+    /// let sine = FourierSine::<f64>::new(10);
+    /// assert!(sine.m == 8);
+    /// ```
+    #[must_use]
+    pub fn new(n: usize) -> Self {
+        assert!(n > 2, "Sine basis requires at least 3 points.");
+        Self {
+            n,
+            m: n - 2,
+            x: nodes(n),
+            k: Array1::from_shape_fn(n - 2, |i| A::from_usize(i + 1).unwrap()),
+            fft_handler: R2cFftHandler::new(2 * (n - 1)),
+            transform_kind: TransformKind::RealToReal,
+        }
+    }
+
+    /// Differentiate 1d Array *n_times*
+    ///
+    /// # Example
+    /// ```
+    /// use funspace::fourier::FourierSine;
+    /// use funspace::utils::approx_eq;
+    /// use ndarray::prelude::*;
+    /// let sine = FourierSine::<f64>::new(5);
+    /// // d/dx sin(2x) = 2 cos(2x)
+    /// let mut input = array![0., 1., 0.];
+    /// sine.differentiate_lane(&mut input, 1);
+    /// approx_eq(&input, &array![0., 2., 0.]);
+    /// ```
+    pub fn differentiate_lane<T, S>(&self, data: &mut ArrayBase<S, Ix1>, n_times: usize)
+    where
+        T: Scalar + From<A>,
+        S: ndarray::Data<Elem = T> + ndarray::DataMut,
+    {
+        for (v, k) in data.iter_mut().zip(self.k.iter()) {
+            *v = *v * deriv_factor(*k, n_times, false).into();
+        }
+    }
+
+    /// Discrete sine transform (type I) along *axis*
+    /// $$
+    /// y_k = \sum_{j=1}^{n-2} x_j \sin(\pi j k / (n - 1)),
+    /// $$
+    /// for input and output of size *n* (the first and last
+    /// input values are ignored).
+    fn dst1<S, D>(&mut self, input: &ArrayBase<S, D>, axis: usize, par: bool) -> Array<A, D>
+    where
+        S: ndarray::Data<Elem = A>,
+        D: Dimension,
+    {
+        use ndrustfft::{ndfft_r2c, ndfft_r2c_par};
+        let m = self.n - 1;
+        // Odd extension [0, x_1, .., x_{m-1}, 0, -x_{m-1}, .., -x_1]
+        let mut dim = input.raw_dim();
+        dim[axis] = 2 * m;
+        let mut ext = Array::<A, D>::zeros(dim);
+        let inner = input.slice_axis(Axis(axis), Slice::from(1..m));
+        ext.slice_axis_mut(Axis(axis), Slice::from(1..m))
+            .assign(&inner);
+        ext.slice_axis_mut(Axis(axis), Slice::from(m + 1..)).assign(
+            &inner
+                .slice_axis(Axis(axis), Slice::new(0, None, -1))
+                .mapv(|v| -v),
+        );
+        // Real fft, the sine transform is -Im / 2
+        let mut dim = input.raw_dim();
+        dim[axis] = m + 1;
+        let mut output = Array::<Complex<A>, D>::zeros(dim);
+        if par {
+            ndfft_r2c_par(&ext, &mut output, &mut self.fft_handler, axis);
+        } else {
+            ndfft_r2c(&ext, &mut output, &mut self.fft_handler, axis);
+        }
+        let half = A::from_f64(0.5).unwrap();
+        output.mapv(|v| -v.im * half)
+    }
+
+    /// Forward transform, see [`FourierSine::forward`]
+    fn forward_dst<S1, S2, D>(
+        &mut self,
+        input: &ArrayBase<S1, D>,
+        output: &mut ArrayBase<S2, D>,
+        axis: usize,
+        par: bool,
+    ) where
+        S1: ndarray::Data<Elem = A>,
+        S2: ndarray::Data<Elem = A> + ndarray::DataMut,
+        D: Dimension,
+    {
+        use crate::utils::check_array_axis;
+        check_array_axis(input, self.n, axis, Some("sine forward"));
+        check_array_axis(output, self.m, axis, Some("sine forward"));
+        let scale = A::from_f64(2.).unwrap() / A::from_usize(self.n - 1).unwrap();
+        let dst = self.dst1(input, axis, par);
+        output.assign(
+            &dst.slice_axis(Axis(axis), Slice::from(1..self.n - 1))
+                .mapv(|v| v * scale),
+        );
+    }
+
+    /// Backward transform, see [`FourierSine::backward`]
+    fn backward_dst<S1, S2, D>(
+        &mut self,
+        input: &ArrayBase<S1, D>,
+        output: &mut ArrayBase<S2, D>,
+        axis: usize,
+        par: bool,
+    ) where
+        S1: ndarray::Data<Elem = A>,
+        S2: ndarray::Data<Elem = A> + ndarray::DataMut,
+        D: Dimension,
+    {
+        use crate::utils::check_array_axis;
+        check_array_axis(input, self.m, axis, Some("sine backward"));
+        check_array_axis(output, self.n, axis, Some("sine backward"));
+        let mut dim = input.raw_dim();
+        dim[axis] = self.n;
+        let mut padded = Array::<A, D>::zeros(dim);
+        padded
+            .slice_axis_mut(Axis(axis), Slice::from(1..self.n - 1))
+            .assign(input);
+        output.assign(&self.dst1(&padded, axis, par));
+    }
+}
+
+macro_rules! impl_basics_r2r {
+    ($base: ident) => {
+        impl<A: FloatNum> Basics<A> for $base<A> {
+            /// Size in physical space
+            fn len_phys(&self) -> usize {
+                self.n
+            }
+            /// Size in spectral space
+            fn len_spec(&self) -> usize {
+                self.m
+            }
+            /// Coordinates in physical space
+            fn coords(&self) -> &Array1<A> {
+                &self.x
+            }
+            /// Return mass matrix (= eye)
+            fn mass(&self) -> Array2<A> {
+                Array2::<A>::eye(self.m)
+            }
+            /// Return transform kind
+            fn get_transform_kind(&self) -> &TransformKind {
+                &self.transform_kind
+            }
+        }
+    };
+}
+
+impl_basics_r2r!(FourierCosine);
+impl_basics_r2r!(FourierSine);
+
+impl<A: FloatNum> Transform for FourierCosine<A> {
+    type Physical = A;
+    type Spectral = A;
+
+    /// # Example
+    /// Forward transform along first axis
+    /// ```
+    /// use funspace::Transform;
+    /// use funspace::fourier::FourierCosine;
+    /// use funspace::utils::approx_eq;
+    /// use ndarray::prelude::*;
+    /// let mut cosine = FourierCosine::new(5);
+    /// let input = cosine.x.mapv(|x: f64| 1. + (3. * x).cos());
+    /// let output = cosine.forward(&input, 0);
+    /// approx_eq(&output, &array![1., 0., 0., 1., 0.]);
+    /// ```
+    fn forward<S, D>(&mut self, input: &ArrayBase<S, D>, axis: usize) -> Array<Self::Spectral, D>
+    where
+        S: ndarray::Data<Elem = Self::Physical>,
+        D: Dimension,
+    {
+        use crate::utils::array_resized_axis;
+        let mut output = array_resized_axis(input, self.m, axis);
+        self.forward_inplace(input, &mut output, axis);
+        output
+    }
+
+    /// See [`FourierCosine::forward`]
+    fn forward_inplace<S1, S2, D>(
+        &mut self,
+        input: &ArrayBase<S1, D>,
+        output: &mut ArrayBase<S2, D>,
+        axis: usize,
+    ) where
+        S1: ndarray::Data<Elem = Self::Physical>,
+        S2: ndarray::Data<Elem = Self::Spectral> + ndarray::DataMut,
+        D: Dimension,
+    {
+        use crate::utils::check_array_axis;
+        use ndrustfft::nddct1;
+        check_array_axis(input, self.n, axis, Some("cosine forward"));
+        check_array_axis(output, self.m, axis, Some("cosine forward"));
+        nddct1(input, output, &mut self.dct_handler, axis);
+        self.correct_forward(output, axis);
+    }
+
+    /// # Example
+    /// Backward transform along first axis
+    /// ```
+    /// use funspace::Transform;
+    /// use funspace::fourier::FourierCosine;
+    /// use funspace::utils::approx_eq;
+    /// use ndarray::prelude::*;
+    /// let mut cosine = FourierCosine::new(5);
+    /// let output = cosine.backward(&array![0., 1., 0., 0., 0.], 0);
+    /// approx_eq(&output, &cosine.x.mapv(|x: f64| x.cos()));
+    /// ```
+    fn backward<S, D>(&mut self, input: &ArrayBase<S, D>, axis: usize) -> Array<Self::Physical, D>
+    where
+        S: ndarray::Data<Elem = Self::Spectral>,
+        D: Dimension,
+    {
+        use crate::utils::array_resized_axis;
+        let mut output = array_resized_axis(input, self.n, axis);
+        self.backward_inplace(input, &mut output, axis);
+        output
+    }
+
+    /// See [`FourierCosine::backward`]
+    fn backward_inplace<S1, S2, D>(
+        &mut self,
+        input: &ArrayBase<S1, D>,
+        output: &mut ArrayBase<S2, D>,
+        axis: usize,
+    ) where
+        S1: ndarray::Data<Elem = Self::Spectral>,
+        S2: ndarray::Data<Elem = Self::Physical> + ndarray::DataMut,
+        D: Dimension,
+    {
+        use crate::utils::check_array_axis;
+        use ndrustfft::nddct1;
+        check_array_axis(input, self.m, axis, Some("cosine backward"));
+        check_array_axis(output, self.n, axis, Some("cosine backward"));
+        let buffer = self.correct_backward(input, axis);
+        nddct1(&buffer, output, &mut self.dct_handler, axis);
+    }
+}
+
+impl<A: FloatNum> TransformPar for FourierCosine<A> {
+    type Physical = A;
+    type Spectral = A;
+
+    /// See [`FourierCosine::forward`]
+    fn forward_par<S, D>(
+        &mut self,
+        input: &ArrayBase<S, D>,
+        axis: usize,
+    ) -> Array<Self::Spectral, D>
+    where
+        S: ndarray::Data<Elem = Self::Physical>,
+        D: Dimension,
+    {
+        use crate::utils::array_resized_axis;
+        let mut output = array_resized_axis(input, self.m, axis);
+        self.forward_inplace_par(input, &mut output, axis);
+        output
+    }
+
+    /// See [`FourierCosine::forward`]
+    fn forward_inplace_par<S1, S2, D>(
+        &mut self,
+        input: &ArrayBase<S1, D>,
+        output: &mut ArrayBase<S2, D>,
+        axis: usize,
+    ) where
+        S1: ndarray::Data<Elem = Self::Physical>,
+        S2: ndarray::Data<Elem = Self::Spectral> + ndarray::DataMut,
+        D: Dimension,
+    {
+        use crate::utils::check_array_axis;
+        use ndrustfft::nddct1_par;
+        check_array_axis(input, self.n, axis, Some("cosine forward"));
+        check_array_axis(output, self.m, axis, Some("cosine forward"));
+        nddct1_par(input, output, &mut self.dct_handler, axis);
+        self.correct_forward(output, axis);
+    }
+
+    /// See [`FourierCosine::backward`]
+    fn backward_par<S, D>(
+        &mut self,
+        input: &ArrayBase<S, D>,
+        axis: usize,
+    ) -> Array<Self::Physical, D>
+    where
+        S: ndarray::Data<Elem = Self::Spectral>,
+        D: Dimension,
+    {
+        use crate::utils::array_resized_axis;
+        let mut output = array_resized_axis(input, self.n, axis);
+        self.backward_inplace_par(input, &mut output, axis);
+        output
+    }
+
+    /// See [`FourierCosine::backward`]
+    fn backward_inplace_par<S1, S2, D>(
+        &mut self,
+        input: &ArrayBase<S1, D>,
+        output: &mut ArrayBase<S2, D>,
+        axis: usize,
+    ) where
+        S1: ndarray::Data<Elem = Self::Spectral>,
+        S2: ndarray::Data<Elem = Self::Physical> + ndarray::DataMut,
+        D: Dimension,
+    {
+        use crate::utils::check_array_axis;
+        use ndrustfft::nddct1_par;
+        check_array_axis(input, self.m, axis, Some("cosine backward"));
+        check_array_axis(output, self.n, axis, Some("cosine backward"));
+        let buffer = self.correct_backward(input, axis);
+        nddct1_par(&buffer, output, &mut self.dct_handler, axis);
+    }
+}
+
+impl<A: FloatNum> Transform for FourierSine<A> {
+    type Physical = A;
+    type Spectral = A;
+
+    /// # Example
+    /// Forward transform along first axis
+    /// ```
+    /// use funspace::Transform;
+    /// use funspace::fourier::FourierSine;
+    /// use funspace::utils::approx_eq;
+    /// use ndarray::prelude::*;
+    /// let mut sine = FourierSine::new(6);
+    /// let input = sine.x.mapv(|x: f64| (2. * x).sin());
+    /// let output = sine.forward(&input, 0);
+    /// approx_eq(&output, &array![0., 1., 0., 0.]);
+    /// ```
+    fn forward<S, D>(&mut self, input: &ArrayBase<S, D>, axis: usize) -> Array<Self::Spectral, D>
+    where
+        S: ndarray::Data<Elem = Self::Physical>,
+        D: Dimension,
+    {
+        use crate::utils::array_resized_axis;
+        let mut output = array_resized_axis(input, self.m, axis);
+        self.forward_inplace(input, &mut output, axis);
+        output
+    }
+
+    /// See [`FourierSine::forward`]
+    fn forward_inplace<S1, S2, D>(
+        &mut self,
+        input: &ArrayBase<S1, D>,
+        output: &mut ArrayBase<S2, D>,
+        axis: usize,
+    ) where
+        S1: ndarray::Data<Elem = Self::Physical>,
+        S2: ndarray::Data<Elem = Self::Spectral> + ndarray::DataMut,
+        D: Dimension,
+    {
+        self.forward_dst(input, output, axis, false);
+    }
+
+    /// # Example
+    /// Backward transform along first axis
+    /// ```
+    /// use funspace::Transform;
+    /// use funspace::fourier::FourierSine;
+    /// use funspace::utils::approx_eq;
+    /// use ndarray::prelude::*;
+    /// let mut sine = FourierSine::new(6);
+    /// let output = sine.backward(&array![1., 0., 0., 0.], 0);
+    /// approx_eq(&output, &sine.x.mapv(|x: f64| x.sin()));
+    /// ```
+    fn backward<S, D>(&mut self, input: &ArrayBase<S, D>, axis: usize) -> Array<Self::Physical, D>
+    where
+        S: ndarray::Data<Elem = Self::Spectral>,
+        D: Dimension,
+    {
+        use crate::utils::array_resized_axis;
+        let mut output = array_resized_axis(input, self.n, axis);
+        self.backward_inplace(input, &mut output, axis);
+        output
+    }
+
+    /// See [`FourierSine::backward`]
+    fn backward_inplace<S1, S2, D>(
+        &mut self,
+        input: &ArrayBase<S1, D>,
+        output: &mut ArrayBase<S2, D>,
+        axis: usize,
+    ) where
+        S1: ndarray::Data<Elem = Self::Spectral>,
+        S2: ndarray::Data<Elem = Self::Physical> + ndarray::DataMut,
+        D: Dimension,
+    {
+        self.backward_dst(input, output, axis, false);
+    }
+}
+
+impl<A: FloatNum> TransformPar for FourierSine<A> {
+    type Physical = A;
+    type Spectral = A;
+
+    /// See [`FourierSine::forward`]
+    fn forward_par<S, D>(
+        &mut self,
+        input: &ArrayBase<S, D>,
+        axis: usize,
+    ) -> Array<Self::Spectral, D>
+    where
+        S: ndarray::Data<Elem = Self::Physical>,
+        D: Dimension,
+    {
+        use crate::utils::array_resized_axis;
+        let mut output = array_resized_axis(input, self.m, axis);
+        self.forward_inplace_par(input, &mut output, axis);
+        output
+    }
+
+    /// See [`FourierSine::forward`]
+    fn forward_inplace_par<S1, S2, D>(
+        &mut self,
+        input: &ArrayBase<S1, D>,
+        output: &mut ArrayBase<S2, D>,
+        axis: usize,
+    ) where
+        S1: ndarray::Data<Elem = Self::Physical>,
+        S2: ndarray::Data<Elem = Self::Spectral> + ndarray::DataMut,
+        D: Dimension,
+    {
+        self.forward_dst(input, output, axis, true);
+    }
+
+    /// See [`FourierSine::backward`]
+    fn backward_par<S, D>(
+        &mut self,
+        input: &ArrayBase<S, D>,
+        axis: usize,
+    ) -> Array<Self::Physical, D>
+    where
+        S: ndarray::Data<Elem = Self::Spectral>,
+        D: Dimension,
+    {
+        use crate::utils::array_resized_axis;
+        let mut output = array_resized_axis(input, self.n, axis);
+        self.backward_inplace_par(input, &mut output, axis);
+        output
+    }
+
+    /// See [`FourierSine::backward`]
+    fn backward_inplace_par<S1, S2, D>(
+        &mut self,
+        input: &ArrayBase<S1, D>,
+        output: &mut ArrayBase<S2, D>,
+        axis: usize,
+    ) where
+        S1: ndarray::Data<Elem = Self::Spectral>,
+        S2: ndarray::Data<Elem = Self::Physical> + ndarray::DataMut,
+        D: Dimension,
+    {
+        self.backward_dst(input, output, axis, true);
+    }
+}
+
+macro_rules! impl_differentiate_r2r {
+    ($base: ident, $a: ty, $name: expr) => {
+        impl<A: FloatNum> Differentiate<$a> for $base<A> {
+            fn differentiate<S, D>(
+                &self,
+                data: &ArrayBase<S, D>,
+                n_times: usize,
+                axis: usize,
+            ) -> Array<$a, D>
+            where
+                S: ndarray::Data<Elem = $a>,
+                D: Dimension,
+            {
+                let mut output = data.to_owned();
+                self.differentiate_inplace(&mut output, n_times, axis);
+                output
+            }
+
+            fn differentiate_inplace<S, D>(
+                &self,
+                data: &mut ArrayBase<S, D>,
+                n_times: usize,
+                axis: usize,
+            ) where
+                S: ndarray::Data<Elem = $a> + ndarray::DataMut,
+                D: Dimension,
+            {
+                use crate::utils::check_array_axis;
+                check_array_axis(data, self.m, axis, Some($name));
+                ndarray::Zip::from(data.lanes_mut(Axis(axis))).for_each(|mut lane| {
+                    self.differentiate_lane(&mut lane, n_times);
+                });
+            }
+        }
+
+        impl<A: FloatNum> DifferentiatePar<$a> for $base<A> {
+            fn differentiate_par<S, D>(
+                &self,
+                data: &ArrayBase<S, D>,
+                n_times: usize,
+                axis: usize,
+            ) -> Array<$a, D>
+            where
+                S: ndarray::Data<Elem = $a>,
+                D: Dimension,
+            {
+                let mut output = data.to_owned();
+                self.differentiate_inplace_par(&mut output, n_times, axis);
+                output
+            }
+
+            fn differentiate_inplace_par<S, D>(
+                &self,
+                data: &mut ArrayBase<S, D>,
+                n_times: usize,
+                axis: usize,
+            ) where
+                S: ndarray::Data<Elem = $a> + ndarray::DataMut,
+                D: Dimension,
+            {
+                use crate::utils::check_array_axis;
+                check_array_axis(data, self.m, axis, Some($name));
+                ndarray::Zip::from(data.lanes_mut(Axis(axis))).par_for_each(|mut lane| {
+                    self.differentiate_lane(&mut lane, n_times);
+                });
+            }
+        }
+    };
+}
+
+impl_differentiate_r2r!(FourierCosine, A, "cosine differentiate");
+impl_differentiate_r2r!(FourierCosine, Complex<A>, "cosine differentiate");
+impl_differentiate_r2r!(FourierSine, A, "sine differentiate");
+impl_differentiate_r2r!(FourierSine, Complex<A>, "sine differentiate");
+
+impl<A: FloatNum> LaplacianInverse<A> for FourierCosine<A> {
+    /// Laplacian ( = -k^2 ) diagonal matrix
+    fn laplace(&self) -> Array2<A> {
+        Array2::from_diag(&self.k.mapv(|k| -k * k))
+    }
+
+    /// Pseudoinverse Laplacian (the constant mode is singular)
+    fn laplace_inv(&self) -> Array2<A> {
+        let mut pinv = self.laplace();
+        for p in pinv.slice_mut(s![1.., 1..]).diag_mut().iter_mut() {
+            *p = A::one() / *p;
+        }
+        pinv
+    }
+
+    /// Pseudoidentity matrix (= eye matrix with removed first row)
+    fn laplace_inv_eye(&self) -> Array2<A> {
+        let eye = Array2::<A>::eye(self.m);
+        eye.slice(s![1.., ..]).to_owned()
+    }
+}
+
+impl<A: FloatNum> LaplacianInverse<A> for FourierSine<A> {
+    /// Laplacian ( = -k^2 ) diagonal matrix
+    fn laplace(&self) -> Array2<A> {
+        Array2::from_diag(&self.k.mapv(|k| -k * k))
+    }
+
+    /// Inverse Laplacian ( = -1/k^2 ), all wavenumbers are nonzero
+    fn laplace_inv(&self) -> Array2<A> {
+        Array2::from_diag(&self.k.mapv(|k| -A::one() / (k * k)))
+    }
+
+    /// Identity matrix
+    fn laplace_inv_eye(&self) -> Array2<A> {
+        Array2::<A>::eye(self.m)
+    }
+}
+
+macro_rules! impl_from_ortho_r2r {
+    ($base: ident, $a: ty) => {
+        impl<A: FloatNum> FromOrtho<$a> for $base<A> {
+            /// Return itself
+            fn to_ortho<S, D>(&self, input: &ArrayBase<S, D>, _axis: usize) -> Array<$a, D>
+            where
+                S: ndarray::Data<Elem = $a>,
+                D: Dimension,
+            {
+                input.to_owned()
+            }
+
+            /// Return itself
+            fn to_ortho_inplace<S1, S2, D>(
+                &self,
+                input: &ArrayBase<S1, D>,
+                output: &mut ArrayBase<S2, D>,
+                _axis: usize,
+            ) where
+                S1: ndarray::Data<Elem = $a>,
+                S2: ndarray::Data<Elem = $a> + ndarray::DataMut,
+                D: Dimension,
+            {
+                output.assign(input);
+            }
+
+            /// Return itself
+            fn from_ortho<S, D>(&self, input: &ArrayBase<S, D>, _axis: usize) -> Array<$a, D>
+            where
+                S: ndarray::Data<Elem = $a>,
+                D: Dimension,
+            {
+                input.to_owned()
+            }
+
+            /// Return itself
+            fn from_ortho_inplace<S1, S2, D>(
+                &self,
+                input: &ArrayBase<S1, D>,
+                output: &mut ArrayBase<S2, D>,
+                _axis: usize,
+            ) where
+                S1: ndarray::Data<Elem = $a>,
+                S2: ndarray::Data<Elem = $a> + ndarray::DataMut,
+                D: Dimension,
+            {
+                output.assign(input);
+            }
+        }
+
+        impl<A: FloatNum> FromOrthoPar<$a> for $base<A> {
+            /// Return itself
+            fn to_ortho_par<S, D>(&self, input: &ArrayBase<S, D>, _axis: usize) -> Array<$a, D>
+            where
+                S: ndarray::Data<Elem = $a>,
+                D: Dimension,
+            {
+                input.to_owned()
+            }
+
+            /// Return itself
+            fn to_ortho_inplace_par<S1, S2, D>(
+                &self,
+                input: &ArrayBase<S1, D>,
+                output: &mut ArrayBase<S2, D>,
+                _axis: usize,
+            ) where
+                S1: ndarray::Data<Elem = $a>,
+                S2: ndarray::Data<Elem = $a> + ndarray::DataMut,
+                D: Dimension,
+            {
+                output.assign(input);
+            }
+
+            /// Return itself
+            fn from_ortho_par<S, D>(&self, input: &ArrayBase<S, D>, _axis: usize) -> Array<$a, D>
+            where
+                S: ndarray::Data<Elem = $a>,
+                D: Dimension,
+            {
+                input.to_owned()
+            }
+
+            /// Return itself
+            fn from_ortho_inplace_par<S1, S2, D>(
+                &self,
+                input: &ArrayBase<S1, D>,
+                output: &mut ArrayBase<S2, D>,
+                _axis: usize,
+            ) where
+                S1: ndarray::Data<Elem = $a>,
+                S2: ndarray::Data<Elem = $a> + ndarray::DataMut,
+                D: Dimension,
+            {
+                output.assign(input);
+            }
+        }
+    };
+}
+
+impl_from_ortho_r2r!(FourierCosine, A);
+impl_from_ortho_r2r!(FourierCosine, Complex<A>);
+impl_from_ortho_r2r!(FourierSine, A);
+impl_from_ortho_r2r!(FourierSine, Complex<A>);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::approx_eq;
+
+    #[test]
+    fn test_cosine_roundtrip() {
+        let (nx, ny) = (7, 5);
+        let data = Array2::from_shape_fn((nx, ny), |(i, j)| ((i * ny + j) as f64).sin());
+        for (axis, n) in [nx, ny].iter().enumerate() {
+            let mut cosine = FourierCosine::<f64>::new(*n);
+            let vhat = cosine.forward(&data, axis);
+            approx_eq(&cosine.backward(&vhat, axis), &data);
+        }
+    }
+
+    #[test]
+    fn test_sine_roundtrip() {
+        // Values vanish at the boundaries
+        let (nx, ny) = (9, 6);
+        let data = Array2::from_shape_fn((nx, ny), |(i, j)| {
+            if i == 0 || i == nx - 1 {
+                0.
+            } else {
+                ((i * ny + j) as f64).sin()
+            }
+        });
+        let mut sine = FourierSine::<f64>::new(nx);
+        let vhat = sine.forward(&data, 0);
+        approx_eq(&sine.backward(&vhat, 0), &data);
+        let vhat_par = sine.forward_par(&data, 0);
+        approx_eq(&vhat_par, &vhat);
+    }
+
+    #[test]
+    fn test_sine_differentiate() {
+        // f = sin(x) + sin(3x), f' = cos(x) + 3cos(3x)
+        let n = 10;
+        let mut sine = FourierSine::<f64>::new(n);
+        let mut cosine = FourierCosine::<f64>::new(n);
+        let x = sine.x.to_owned();
+        let data = x.mapv(|x| x.sin() + (3. * x).sin());
+        let vhat = sine.forward(&data, 0);
+        // Odd derivative: cosine coefficients of k = 1, .., n - 2
+        let dvhat = sine.differentiate(&vhat, 1, 0);
+        let mut chat = Array1::<f64>::zeros(n);
+        chat.slice_mut(s![1..n - 1]).assign(&dvhat);
+        let expected = x.mapv(|x| x.cos() + 3. * (3. * x).cos());
+        approx_eq(&cosine.backward(&chat, 0), &expected);
+        // Even derivative
+        let d2vhat = sine.differentiate(&vhat, 2, 0);
+        let expected = x.mapv(|x| -x.sin() - 9. * (3. * x).sin());
+        approx_eq(&sine.backward(&d2vhat, 0), &expected);
+    }
+}
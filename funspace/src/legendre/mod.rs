@@ -0,0 +1,12 @@
+//! # Function spaces of type Legendre
+//!
+//! Defined on the intervall $[-1, 1]$, the coefficients of the legendre
+//! polynomials are of real (float) type.
+//!
+//! Legendre polynomials are orthogonal with respect to the
+//! unweighted inner product, i.e. their mass matrix is diagonal.
+//!
+//! See [`ortho::Legendre`]
+#![allow(clippy::module_name_repetitions)]
+mod ortho;
+pub use ortho::Legendre;
@@ -0,0 +1,333 @@
+//! # Orthogonal legendre space
+use crate::traits::Basics;
+use crate::traits::Differentiate;
+use crate::traits::Transform;
+use crate::traits::TransformKind;
+use crate::types::FloatNum;
+use crate::utils::dot_along;
+use crate::Scalar;
+use ndarray::prelude::*;
+use num_complex::Complex;
+
+/// Tolerance of newton iteration for quadrature nodes
+const NEWTON_TOL: f64 = 1e-15;
+
+/// # Container for legendre space
+///
+/// Transforms use gauss-legendre quadrature: The field is
+/// sampled at the *n* roots of $L_n$ (*nodes*), and
+/// $$
+/// c_k = \frac{2k+1}{2} \sum_j w_j f(x_j) L_k(x_j),
+/// $$
+/// which is exact for polynomials up to degree *n - 1*.
+#[derive(Clone)]
+pub struct Legendre<A> {
+    /// Number of coefficients in physical space
+    pub n: usize,
+    /// Number of coefficients in spectral space ( equal to *n* in this case )
+    pub m: usize,
+    /// Gauss-legendre quadrature nodes
+    pub nodes: Array1<A>,
+    /// Gauss-legendre quadrature weights
+    pub weights: Array1<A>,
+    /// Forward transform matrix
+    forward_mat: Array2<A>,
+    /// Backward transform matrix, i.e. $L_k(x_j)$
+    backward_mat: Array2<A>,
+    /// Transform kind (real-to-real)
+    transform_kind: TransformKind,
+}
+
+impl<A: FloatNum> Legendre<A> {
+    /// Creates a new Basis.
+    ///
+    /// # Arguments
+    /// * `n` - Length of array's dimension which shall live in legendre space.
+    ///
+    /// # Panics
+    /// Panics when input type cannot be cast from f64.
+    ///
+    /// # Examples
+    /// ```
+    /// use funspace::legendre::Legendre;
+    /// let legendre = Legendre::<f64>::new(10);
+    /// assert!((legendre.weights.sum() - 2.).abs() < 1e-12);
+    /// ```
+    #[must_use]
+    pub fn new(n: usize) -> Self {
+        let (nodes, weights) = Self::_gauss_legendre(n);
+        let backward = Array2::from_shape_fn((n, n), |(j, k)| Self::_eval(k, nodes[j]).0);
+        let forward = Array2::from_shape_fn((n, n), |(k, j)| {
+            (2 * k + 1) as f64 / 2. * weights[j] * backward[[j, k]]
+        });
+        let cast = |x: f64| A::from_f64(x).unwrap();
+        Self {
+            n,
+            m: n,
+            nodes: nodes.mapv(cast),
+            weights: weights.mapv(cast),
+            forward_mat: forward.mapv(cast),
+            backward_mat: backward.mapv(cast),
+            transform_kind: TransformKind::RealToReal,
+        }
+    }
+
+    /// Returns legendre polynomial $L_n(x)$ and its derivative
+    fn _eval(n: usize, x: f64) -> (f64, f64) {
+        if n == 0 {
+            return (1., 0.);
+        }
+        let (mut p0, mut p1) = (1., x);
+        for k in 1..n {
+            let p2 = ((2 * k + 1) as f64 * x * p1 - k as f64 * p0) / (k + 1) as f64;
+            p0 = p1;
+            p1 = p2;
+        }
+        let dp = n as f64 * (x * p1 - p0) / (x * x - 1.);
+        (p1, dp)
+    }
+
+    /// Gauss-legendre nodes (ascending) and weights, from
+    /// newton iteration on the roots of $L_n$
+    fn _gauss_legendre(n: usize) -> (Array1<f64>, Array1<f64>) {
+        use std::f64::consts::PI;
+        let mut nodes = Array1::<f64>::zeros(n);
+        let mut weights = Array1::<f64>::zeros(n);
+        for (i, (xi, wi)) in nodes.iter_mut().zip(weights.iter_mut()).enumerate() {
+            let mut x = -(PI * (i as f64 + 0.75) / (n as f64 + 0.5)).cos();
+            for _ in 0..100 {
+                let (p, dp) = Self::_eval(n, x);
+                let dx = p / dp;
+                x -= dx;
+                if dx.abs() < NEWTON_TOL {
+                    break;
+                }
+            }
+            let dp = Self::_eval(n, x).1;
+            *xi = x;
+            *wi = 2. / ((1. - x * x) * dp * dp);
+        }
+        (nodes, weights)
+    }
+
+    /// Differentiate 1d Array *n_times* using the recurrence relation
+    /// of legendre polynomials
+    /// $$
+    /// c'\_k = (2k+1) \left( c\_{k+1} + \frac{c'\_{k+2}}{2k+5} \right)
+    /// $$
+    ///
+    /// Differentiation is performed on input array directly.
+    ///
+    /// # Panics
+    /// Panics when input type cannot be cast from usize.
+    ///
+    /// # Example
+    /// ```
+    /// use funspace::legendre::Legendre;
+    /// use funspace::utils::approx_eq;
+    /// use ndarray::prelude::*;
+    /// let legendre = Legendre::<f64>::new(3);
+    /// // x^2 = (L_0 + 2 L_2) / 3
+    /// let mut input = array![1. / 3., 0., 2. / 3.];
+    /// legendre.differentiate_lane(&mut input, 1);
+    /// approx_eq(&input, &array![0., 2., 0.]);
+    /// ```
+    pub fn differentiate_lane<T, S>(&self, data: &mut ArrayBase<S, Ix1>, n_times: usize)
+    where
+        T: Scalar + From<A>,
+        S: ndarray::Data<Elem = T> + ndarray::DataMut,
+    {
+        let int = |i: usize| -> T { A::from_usize(i).unwrap().into() };
+        for _ in 0..n_times {
+            let (mut c_k1, mut d_k1, mut d_k2) = (T::zero(), T::zero(), T::zero());
+            for k in (0..data.len()).rev() {
+                let c_k = data[k];
+                let d_k = int(2 * k + 1) * (c_k1 + d_k2 / int(2 * k + 5));
+                data[k] = d_k;
+                c_k1 = c_k;
+                d_k2 = d_k1;
+                d_k1 = d_k;
+            }
+        }
+    }
+}
+
+impl<A: FloatNum> Basics<A> for Legendre<A> {
+    /// Size in physical space
+    fn len_phys(&self) -> usize {
+        self.n
+    }
+    /// Size in spectral space
+    fn len_spec(&self) -> usize {
+        self.m
+    }
+    /// Coordinates in physical space
+    fn coords(&self) -> &Array1<A> {
+        &self.nodes
+    }
+    /// Return mass matrix
+    fn mass(&self) -> Array2<A> {
+        Array2::<A>::eye(self.n)
+    }
+    /// Return transform kind
+    fn get_transform_kind(&self) -> &TransformKind {
+        &self.transform_kind
+    }
+}
+
+impl<A: FloatNum> Transform for Legendre<A> {
+    type Physical = A;
+    type Spectral = A;
+
+    /// # Example
+    /// Forward transform along first axis
+    /// ```
+    /// use funspace::Transform;
+    /// use funspace::legendre::Legendre;
+    /// use funspace::utils::approx_eq;
+    /// use ndarray::prelude::*;
+    /// let mut legendre = Legendre::new(4);
+    /// let input = legendre.nodes.mapv(|x: f64| x * x);
+    /// let output = legendre.forward(&input, 0);
+    /// approx_eq(&output, &array![1. / 3., 0., 2. / 3., 0.]);
+    /// ```
+    fn forward<S, D>(&mut self, input: &ArrayBase<S, D>, axis: usize) -> Array<Self::Spectral, D>
+    where
+        S: ndarray::Data<Elem = Self::Physical>,
+        D: Dimension,
+    {
+        use crate::utils::array_resized_axis;
+        let mut output = array_resized_axis(input, self.m, axis);
+        self.forward_inplace(input, &mut output, axis);
+        output
+    }
+
+    /// See [`Legendre::forward`]
+    fn forward_inplace<S1, S2, D>(
+        &mut self,
+        input: &ArrayBase<S1, D>,
+        output: &mut ArrayBase<S2, D>,
+        axis: usize,
+    ) where
+        S1: ndarray::Data<Elem = Self::Physical>,
+        S2: ndarray::Data<Elem = Self::Spectral> + ndarray::DataMut,
+        D: Dimension,
+    {
+        use crate::utils::check_array_axis;
+        check_array_axis(input, self.n, axis, Some("legendre forward"));
+        check_array_axis(output, self.m, axis, Some("legendre forward"));
+        dot_along(&self.forward_mat, input, output, axis);
+    }
+
+    /// See [`Legendre::forward`]
+    fn backward<S, D>(&mut self, input: &ArrayBase<S, D>, axis: usize) -> Array<Self::Physical, D>
+    where
+        S: ndarray::Data<Elem = Self::Spectral>,
+        D: Dimension,
+    {
+        use crate::utils::array_resized_axis;
+        let mut output = array_resized_axis(input, self.n, axis);
+        self.backward_inplace(input, &mut output, axis);
+        output
+    }
+
+    /// See [`Legendre::backward`]
+    fn backward_inplace<S1, S2, D>(
+        &mut self,
+        input: &ArrayBase<S1, D>,
+        output: &mut ArrayBase<S2, D>,
+        axis: usize,
+    ) where
+        S1: ndarray::Data<Elem = Self::Spectral>,
+        S2: ndarray::Data<Elem = Self::Physical> + ndarray::DataMut,
+        D: Dimension,
+    {
+        use crate::utils::check_array_axis;
+        check_array_axis(input, self.m, axis, Some("legendre backward"));
+        check_array_axis(output, self.n, axis, Some("legendre backward"));
+        dot_along(&self.backward_mat, input, output, axis);
+    }
+}
+
+macro_rules! impl_differentiate_legendre {
+    ($a: ty) => {
+        impl<A: FloatNum> Differentiate<$a> for Legendre<A> {
+            fn differentiate<S, D>(
+                &self,
+                data: &ArrayBase<S, D>,
+                n_times: usize,
+                axis: usize,
+            ) -> Array<$a, D>
+            where
+                S: ndarray::Data<Elem = $a>,
+                D: Dimension,
+            {
+                let mut output = data.to_owned();
+                self.differentiate_inplace(&mut output, n_times, axis);
+                output
+            }
+
+            fn differentiate_inplace<S, D>(
+                &self,
+                data: &mut ArrayBase<S, D>,
+                n_times: usize,
+                axis: usize,
+            ) where
+                S: ndarray::Data<Elem = $a> + ndarray::DataMut,
+                D: Dimension,
+            {
+                use crate::utils::check_array_axis;
+                check_array_axis(data, self.m, axis, Some("legendre differentiate"));
+                ndarray::Zip::from(data.lanes_mut(Axis(axis))).for_each(|mut lane| {
+                    self.differentiate_lane(&mut lane, n_times);
+                });
+            }
+        }
+    };
+}
+
+impl_differentiate_legendre!(A);
+impl_differentiate_legendre!(Complex<A>);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::approx_eq;
+
+    #[test]
+    fn test_legendre_quadrature() {
+        let legendre = Legendre::<f64>::new(6);
+        // exact for polynomials up to degree 2n - 1
+        let integral: f64 = legendre
+            .nodes
+            .iter()
+            .zip(legendre.weights.iter())
+            .map(|(x, w)| w * x.powi(10))
+            .sum();
+        assert!((integral - 2. / 11.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_legendre_roundtrip() {
+        let (nx, ny) = (7, 5);
+        let data = Array2::from_shape_fn((nx, ny), |(i, j)| ((i * ny + j) as f64).sin());
+        for (axis, n) in [nx, ny].iter().enumerate() {
+            let mut legendre = Legendre::<f64>::new(*n);
+            let vhat = legendre.forward(&data, axis);
+            approx_eq(&legendre.backward(&vhat, axis), &data);
+        }
+    }
+
+    #[test]
+    fn test_legendre_differentiate() {
+        // f = x^3 - x, f' = 3x^2 - 1
+        let n = 6;
+        let mut legendre = Legendre::<f64>::new(n);
+        let x = legendre.nodes.to_owned();
+        let data = Array2::from_shape_fn((n, 2), |(i, _)| x[i].powi(3) - x[i]);
+        let vhat = legendre.forward(&data, 0);
+        let dvhat = legendre.differentiate(&vhat, 1, 0);
+        let expected = Array2::from_shape_fn((n, 2), |(i, _)| 3. * x[i] * x[i] - 1.);
+        approx_eq(&legendre.backward(&dvhat, 0), &expected);
+    }
+}
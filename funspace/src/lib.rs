@@ -12,8 +12,13 @@
 //! - `Chebyshev` (Orthogonal), see [`chebyshev()`]
 //! - `ChebDirichlet` (Composite), see [`cheb_dirichlet()`]
 //! - `ChebNeumann` (Composite), see [`cheb_neumann()`]
+//! - `ChebRobin` (Composite), see [`cheb_robin()`]
+//! - `ChebBiharmonic` (Composite), see [`cheb_biharmonic()`]
 //! - `FourierC2c` (Orthogonal), see [`fourier_c2c()`]
 //! - `FourierR2c` (Orthogonal), see [`fourier_r2c()`]
+//! - `FourierSine` (Orthogonal), see [`fourier_sine()`]
+//! - `FourierCosine` (Orthogonal), see [`fourier_cosine()`]
+//! - `Legendre` (Orthogonal), see [`legendre::Legendre`]
 //!
 //! ## Transform
 //! A transform describes a change from the physical space to the function
@@ -150,6 +155,8 @@
 //! the backward transform.
 //!
 //! **Note**: Currently `funspace` supports 1- 2- and 3 - dimensional spaces.
+//! Spaces whose number of dimensions is only known at runtime are
+//! supported by [`DynSpace`], if all bases are of the same kind.
 //!
 //! ### Example
 //! Apply transform from physical to spectral in a two-dimensional space
@@ -177,20 +184,25 @@
 #![allow(clippy::just_underscores_and_digits)]
 #![allow(clippy::doc_markdown)]
 #![allow(clippy::cast_precision_loss)]
+#![allow(clippy::wrong_self_convention)]
 #[macro_use]
 extern crate enum_dispatch;
 mod macros;
 
+pub mod banded;
 pub mod chebyshev;
 pub mod enums;
 pub mod fourier;
+pub mod legendre;
 pub mod space1;
 pub mod space2;
 pub mod space3;
+pub mod space_dyn;
 pub mod space_traits;
 pub mod traits;
 pub mod types;
 pub mod utils;
+pub use crate::banded::BandedMatrix;
 pub use crate::enums::{BaseAll, BaseC2c, BaseR2c, BaseR2r};
 pub use crate::traits::Basics;
 pub use crate::traits::Differentiate;
@@ -201,12 +213,14 @@ pub use crate::traits::LaplacianInverse;
 pub use crate::traits::Transform;
 pub use crate::traits::TransformKind;
 pub use crate::traits::TransformPar;
+pub use crate::traits::{transform_along, DealiasRule, Direction, Normalization};
 use chebyshev::Chebyshev;
 use chebyshev::CompositeChebyshev;
-use fourier::{FourierC2c, FourierR2c};
+use fourier::{FourierC2c, FourierCosine, FourierR2c, FourierSine};
 pub use space1::Space1;
 pub use space2::Space2;
 pub use space3::Space3;
+pub use space_dyn::DynSpace;
 pub use space_traits::BaseSpace;
 pub use types::{FloatNum, Scalar};
 
@@ -271,6 +285,59 @@ pub fn cheb_neumann<A: FloatNum>(n: usize) -> BaseR2r<A> {
     BaseR2r::CompositeChebyshev(CompositeChebyshev::<A>::neumann(n))
 }
 
+/// Function space with biharmonic boundary conditions,
+/// i.e. $u(\pm 1) = u'(\pm 1) = 0$
+///
+/// $$
+/// \phi_k = T_k - \frac{2(k+2)}{k+3} T_{k+2} + \frac{k+1}{k+3} T_{k+4}
+/// $$
+/// ## Example
+/// Transform array to function space.
+/// ```
+/// use funspace::cheb_biharmonic;
+/// use funspace::Transform;
+/// use ndarray::Array1;
+/// let mut cb = cheb_biharmonic::<f64>(10);
+/// let mut y = ndarray::Array::linspace(0., 9., 10);
+/// let yhat: Array1<f64> = cb.forward(&mut y, 0);
+/// assert_eq!(yhat.len(), 6);
+/// ```
+#[must_use]
+pub fn cheb_biharmonic<A: FloatNum>(n: usize) -> BaseR2r<A> {
+    BaseR2r::CompositeChebyshev(CompositeChebyshev::<A>::biharmonic(n))
+}
+
+/// Function space with Robin boundary conditions
+///
+/// $$
+/// a_l u(-1) + b_l u'(-1) = 0, \quad a_r u(1) + b_r u'(1) = 0
+/// $$
+/// $$
+/// \phi_k = T_k + \alpha_k T_{k+1} + \beta_k T_{k+2}
+/// $$
+/// ## Example
+/// Transform array to function space.
+/// ```
+/// use funspace::cheb_robin;
+/// use funspace::Transform;
+/// use ndarray::Array1;
+/// let mut cr = cheb_robin::<f64>(10, 1., -0.5, 1., 0.5);
+/// let mut y = ndarray::Array::linspace(0., 9., 10);
+/// let yhat: Array1<f64> = cr.forward(&mut y, 0);
+/// ```
+#[must_use]
+pub fn cheb_robin<A: FloatNum>(
+    n: usize,
+    a_left: A,
+    b_left: A,
+    a_right: A,
+    b_right: A,
+) -> BaseR2r<A> {
+    BaseR2r::CompositeChebyshev(CompositeChebyshev::<A>::robin(
+        n, a_left, b_left, a_right, b_right,
+    ))
+}
+
 /// Functions space for inhomogeneous Dirichlet
 /// boundary conditions
 ///
@@ -343,3 +410,49 @@ pub fn fourier_c2c<A: FloatNum>(n: usize) -> BaseC2c<A> {
 pub fn fourier_r2c<A: FloatNum>(n: usize) -> BaseR2c<A> {
     BaseR2c::FourierR2c(FourierR2c::<A>::new(n))
 }
+
+/// Function space for sine series on $[0, \pi]$
+/// (Real-to-real, homogeneous dirichlet)
+///
+/// $$
+/// \sin(k x), \quad k = 1, .., n - 2
+/// $$
+///
+/// ## Example
+/// Transform array to function space.
+/// ```
+/// use funspace::fourier_sine;
+/// use funspace::{Basics, Transform};
+/// use ndarray::Array1;
+/// let mut si = fourier_sine::<f64>(10);
+/// let y = si.coords().mapv(|x| x.sin());
+/// let yhat: Array1<f64> = si.forward(&y, 0);
+/// assert!((yhat[0] - 1.).abs() < 1e-12);
+/// ```
+#[must_use]
+pub fn fourier_sine<A: FloatNum>(n: usize) -> BaseR2r<A> {
+    BaseR2r::FourierSine(FourierSine::<A>::new(n))
+}
+
+/// Function space for cosine series on $[0, \pi]$
+/// (Real-to-real, homogeneous neumann)
+///
+/// $$
+/// \cos(k x), \quad k = 0, .., n - 1
+/// $$
+///
+/// ## Example
+/// Transform array to function space.
+/// ```
+/// use funspace::fourier_cosine;
+/// use funspace::{Basics, Transform};
+/// use ndarray::Array1;
+/// let mut co = fourier_cosine::<f64>(10);
+/// let y = co.coords().mapv(|x| x.cos());
+/// let yhat: Array1<f64> = co.forward(&y, 0);
+/// assert!((yhat[1] - 1.).abs() < 1e-12);
+/// ```
+#[must_use]
+pub fn fourier_cosine<A: FloatNum>(n: usize) -> BaseR2r<A> {
+    BaseR2r::FourierCosine(FourierCosine::<A>::new(n))
+}
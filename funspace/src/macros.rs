@@ -66,6 +66,23 @@ macro_rules! impl_transform_trait_for_base {
                     $(Self::$var(ref mut b) => b.backward_inplace(input, output, axis),)*
                 }
             }
+
+            fn backward_into<S1, S2, S3, D>(
+                &mut self,
+                input: &ArrayBase<S1, D>,
+                output: &mut ArrayBase<S2, D>,
+                scratch: &mut ArrayBase<S3, D>,
+                axis: usize,
+            ) where
+                S1: ndarray::Data<Elem = Self::Spectral>,
+                S2: ndarray::Data<Elem = Self::Physical> + ndarray::DataMut,
+                S3: ndarray::Data<Elem = Self::Spectral> + ndarray::DataMut,
+                D: Dimension,
+            {
+                match self {
+                    $(Self::$var(ref mut b) => b.backward_into(input, output, scratch, axis),)*
+                }
+            }
         }
 
         impl<A: FloatNum> TransformPar for $base<A> {
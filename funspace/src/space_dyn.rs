@@ -0,0 +1,257 @@
+//! # Space with runtime number of dimensions
+//!
+//! [`DynSpace`] holds its bases in a vector, so that the number of
+//! dimensions is only known at runtime. Transforms act on arrays of
+//! dynamic dimensionality (`ArrayD`).
+//!
+//! Since physical and spectral types must agree for each step of the
+//! transform chain, all bases must be of the same kind, i.e. either
+//! all real-to-real ([`BaseR2r`]) or all complex-to-complex ([`BaseC2c`]).
+//!
+//! # Example
+//! ```
+//! use funspace::{cheb_dirichlet, chebyshev, DynSpace};
+//! use ndarray::prelude::*;
+//! let mut space = DynSpace::new(&[chebyshev::<f64>(6), cheb_dirichlet(6), chebyshev(5)]);
+//! assert_eq!(space.ndim(), 3);
+//! assert_eq!(space.shape_spectral(), vec![6, 4, 5]);
+//! let mut v = space.ndarray_physical();
+//! v += 1.;
+//! let vhat = space.forward(&v);
+//! let v = space.backward(&vhat);
+//! ```
+#![allow(clippy::module_name_repetitions)]
+use crate::traits::Basics;
+use crate::traits::Differentiate;
+use crate::traits::FromOrtho;
+use crate::traits::LaplacianInverse;
+use crate::traits::Transform;
+use crate::BaseAll;
+use crate::{BaseC2c, BaseR2r, FloatNum};
+use ndarray::{prelude::*, Data, ScalarOperand};
+use num_complex::Complex;
+
+/// Create space of arbitrary dimension
+#[derive(Clone)]
+pub struct DynSpace<B> {
+    /// Bases, one per axis
+    pub bases: Vec<B>,
+}
+
+impl<B: Clone> DynSpace<B> {
+    /// Create a new space
+    pub fn new(bases: &[B]) -> Self {
+        Self {
+            bases: bases.to_vec(),
+        }
+    }
+
+    /// Number of dimensions
+    pub fn ndim(&self) -> usize {
+        self.bases.len()
+    }
+}
+
+macro_rules! impl_dyn_space {
+    ($base: ident, $t: ty) => {
+        impl<A> DynSpace<$base<A>>
+        where
+            A: FloatNum,
+            Complex<A>: ScalarOperand,
+        {
+            /// Shape of array in physical space
+            pub fn shape_physical(&self) -> Vec<usize> {
+                self.bases.iter().map(|b| b.len_phys()).collect()
+            }
+
+            /// Shape of array in spectral space
+            pub fn shape_spectral(&self) -> Vec<usize> {
+                self.bases.iter().map(|b| b.len_spec()).collect()
+            }
+
+            /// Return array where size and type matches physical field
+            pub fn ndarray_physical(&self) -> ArrayD<$t> {
+                ArrayD::zeros(self.shape_physical())
+            }
+
+            /// Return array where size and type matches spectral field
+            pub fn ndarray_spectral(&self) -> ArrayD<$t> {
+                ArrayD::zeros(self.shape_spectral())
+            }
+
+            /// Laplacian along axis
+            pub fn laplace(&self, axis: usize) -> Array2<A> {
+                self.bases[axis].laplace()
+            }
+
+            /// Pseudoinverse of laplacian along axis
+            pub fn laplace_inv(&self, axis: usize) -> Array2<A> {
+                self.bases[axis].laplace_inv()
+            }
+
+            /// Pseudoidentity matrix of laplacian along axis
+            pub fn laplace_inv_eye(&self, axis: usize) -> Array2<A> {
+                self.bases[axis].laplace_inv_eye()
+            }
+
+            /// Mass matrix along axis
+            pub fn mass(&self, axis: usize) -> Array2<A> {
+                self.bases[axis].mass()
+            }
+
+            /// Coordinates of grid points (in physical space)
+            pub fn coords(&self) -> Vec<Array1<A>> {
+                self.bases.iter().map(|b| b.coords().clone()).collect()
+            }
+
+            /// Coordinates of grid points along axis
+            pub fn coords_axis(&self, axis: usize) -> Array1<A> {
+                self.bases[axis].coords().clone()
+            }
+
+            /// Return bases as enum
+            pub fn base_all(&self) -> Vec<BaseAll<A>> {
+                self.bases
+                    .iter()
+                    .map(|b| BaseAll::<A>::from(b.clone()))
+                    .collect()
+            }
+
+            /// Transform from composite to orthogonal space
+            pub fn to_ortho<S>(&self, input: &ArrayBase<S, IxDyn>) -> ArrayD<$t>
+            where
+                S: Data<Elem = $t>,
+            {
+                let mut output = input.to_owned();
+                for (axis, base) in self.bases.iter().enumerate() {
+                    output = base.to_ortho(&output, axis);
+                }
+                output
+            }
+
+            /// Transform from orthogonal to composite space
+            pub fn from_ortho<S>(&self, input: &ArrayBase<S, IxDyn>) -> ArrayD<$t>
+            where
+                S: Data<Elem = $t>,
+            {
+                let mut output = input.to_owned();
+                for (axis, base) in self.bases.iter().enumerate() {
+                    output = base.from_ortho(&output, axis);
+                }
+                output
+            }
+
+            /// Take gradient. Optional: Rescale result by a constant.
+            ///
+            /// # Arguments
+            ///
+            /// * `input` - *ndarray* with num type of spectral space
+            /// * `deriv` - Order of derivative along each axis
+            /// * `scale` - Optional: Rescale result by constant along each axis
+            ///
+            /// # Panics
+            /// Panics if *deriv* or *scale* do not match the number of dimensions.
+            pub fn gradient<S>(
+                &self,
+                input: &ArrayBase<S, IxDyn>,
+                deriv: &[usize],
+                scale: Option<&[A]>,
+            ) -> ArrayD<$t>
+            where
+                S: Data<Elem = $t>,
+            {
+                assert!(
+                    deriv.len() == self.ndim(),
+                    "Dimension mismatch in gradient."
+                );
+                let mut output = input.to_owned();
+                for (axis, base) in self.bases.iter().enumerate() {
+                    output = base.differentiate(&output, deriv[axis], axis);
+                }
+                if let Some(s) = scale {
+                    assert!(s.len() == self.ndim(), "Dimension mismatch in gradient.");
+                    let sc: $t = s
+                        .iter()
+                        .zip(deriv.iter())
+                        .fold(A::one(), |acc, (si, d)| acc * si.powi(*d as i32))
+                        .into();
+                    output = output / sc;
+                }
+                output
+            }
+
+            /// Transform physical -> spectral space
+            ///
+            /// The transforms are applied from the last to the first axis.
+            ///
+            /// # Panics
+            /// Panics if *input* does not match the number of dimensions.
+            pub fn forward<S>(&mut self, input: &ArrayBase<S, IxDyn>) -> ArrayD<$t>
+            where
+                S: Data<Elem = $t>,
+            {
+                assert!(
+                    input.ndim() == self.ndim(),
+                    "Dimension mismatch in forward."
+                );
+                let mut output = input.to_owned();
+                for (axis, base) in self.bases.iter_mut().enumerate().rev() {
+                    output = base.forward(&output, axis);
+                }
+                output
+            }
+
+            /// Transform spectral -> physical space
+            ///
+            /// The transforms are applied from the first to the last axis.
+            ///
+            /// # Panics
+            /// Panics if *input* does not match the number of dimensions.
+            pub fn backward<S>(&mut self, input: &ArrayBase<S, IxDyn>) -> ArrayD<$t>
+            where
+                S: Data<Elem = $t>,
+            {
+                assert!(
+                    input.ndim() == self.ndim(),
+                    "Dimension mismatch in backward."
+                );
+                let mut output = input.to_owned();
+                for (axis, base) in self.bases.iter_mut().enumerate() {
+                    output = base.backward(&output, axis);
+                }
+                output
+            }
+        }
+    };
+}
+
+impl_dyn_space!(BaseR2r, A);
+impl_dyn_space!(BaseC2c, Complex<A>);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::approx_eq;
+    use crate::{cheb_dirichlet, chebyshev, BaseSpace, Space2};
+
+    #[test]
+    fn test_dyn_space_matches_space2() {
+        let (nx, ny) = (8, 7);
+        let mut space = Space2::new(&cheb_dirichlet::<f64>(nx), &chebyshev::<f64>(ny));
+        let mut dyn_space = DynSpace::new(&[cheb_dirichlet::<f64>(nx), chebyshev::<f64>(ny)]);
+        assert_eq!(dyn_space.shape_spectral(), space.shape_spectral().to_vec());
+
+        let v = Array2::from_shape_fn((nx, ny), |(i, j)| ((i * ny + j) as f64).cos());
+        let vhat = space.forward(&v);
+        let vhat_dyn = dyn_space.forward(&v.clone().into_dyn());
+        approx_eq(&vhat_dyn, &vhat.clone().into_dyn());
+
+        let v = space.backward(&vhat);
+        let v_dyn = dyn_space.backward(&vhat_dyn);
+        approx_eq(&v_dyn, &v.into_dyn());
+
+        let dvhat = space.gradient(&vhat, [1, 2], Some([2., 0.5]));
+        let dvhat_dyn = dyn_space.gradient(&vhat_dyn, &[1, 2], Some(&[2., 0.5]));
+        approx_eq(&dvhat_dyn, &dvhat.into_dyn());
+    }
+}
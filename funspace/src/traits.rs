@@ -4,7 +4,9 @@ use crate::Chebyshev;
 use crate::CompositeChebyshev;
 use crate::FloatNum;
 use crate::FourierC2c;
+use crate::FourierCosine;
 use crate::FourierR2c;
+use crate::FourierSine;
 use ndarray::prelude::*;
 
 /// Some basic  traits
@@ -20,6 +22,35 @@ pub trait Basics<T> {
     fn mass(&self) -> Array2<T>;
     /// Return kind of transform
     fn get_transform_kind(&self) -> &TransformKind;
+    /// Return mask of spectral coefficients, which are retained
+    /// (true) or zeroed (false) when dealiasing.
+    ///
+    /// The cutoff refers to the largest wavenumber (fourier), or
+    /// to the highest orthogonal polynomial (chebyshev). Composite
+    /// coefficient *i* contains polynomials up to degree
+    /// *i + len_phys - len_spec*.
+    fn dealias_mask(&self, rule: DealiasRule) -> Array1<bool> {
+        let n = self.len_phys();
+        let offset = n - self.len_spec();
+        let fraction = rule.fraction();
+        let kmax = n as f64 / 2.;
+        match self.get_transform_kind() {
+            TransformKind::RealToReal => Array1::from_shape_fn(self.len_spec(), |i| {
+                ((i + offset) as f64) < fraction * n as f64
+            }),
+            TransformKind::RealToComplex => {
+                Array1::from_shape_fn(self.len_spec(), |k| (k as f64) < fraction * kmax)
+            }
+            TransformKind::ComplexToComplex => Array1::from_shape_fn(self.len_spec(), |i| {
+                let k = if i <= n / 2 {
+                    i as f64
+                } else {
+                    i as f64 - n as f64
+                };
+                k.abs() < fraction * kmax
+            }),
+        }
+    }
 }
 
 /// Transform from physical to spectral space and vice versa.
@@ -111,6 +142,79 @@ pub trait Transform {
         S1: ndarray::Data<Elem = Self::Spectral>,
         S2: ndarray::Data<Elem = Self::Physical> + ndarray::DataMut,
         D: Dimension;
+
+    /// Transform from spectral to physical space
+    ///
+    /// Same as *backward_inplace*, but intermediate results are
+    /// written into a caller-supplied *scratch* array of the same
+    /// shape as *input*, instead of an internal buffer.
+    ///
+    /// The default implementation ignores *scratch* and calls
+    /// *backward_inplace*, which may allocate.
+    fn backward_into<S1, S2, S3, D>(
+        &mut self,
+        input: &ArrayBase<S1, D>,
+        output: &mut ArrayBase<S2, D>,
+        scratch: &mut ArrayBase<S3, D>,
+        axis: usize,
+    ) where
+        S1: ndarray::Data<Elem = Self::Spectral>,
+        S2: ndarray::Data<Elem = Self::Physical> + ndarray::DataMut,
+        S3: ndarray::Data<Elem = Self::Spectral> + ndarray::DataMut,
+        D: Dimension,
+    {
+        let _ = scratch;
+        self.backward_inplace(input, output, axis);
+    }
+}
+
+/// Direction of transform
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Physical -> spectral space
+    Forward,
+    /// Spectral -> physical space
+    Backward,
+}
+
+/// Transform *input* along *axis* in the given *direction*
+/// and write the result into *output*.
+///
+/// Dispatch primitive for *n*-dimensional transforms, which can
+/// be written as loop over (base, axis) pairs. Restricted to bases
+/// whose scalar type is the same in physical and spectral space,
+/// i.e. real-to-real ([`BaseR2r`]) and complex-to-complex ([`BaseC2c`]).
+///
+/// # Example
+/// ```
+/// use funspace::{chebyshev, transform_along, Direction, Transform};
+/// use ndarray::prelude::*;
+/// let mut bases = [chebyshev::<f64>(5), chebyshev::<f64>(4)];
+/// let input = Array2::<f64>::from_elem((5, 4), 1.);
+/// let mut buffer = input.clone();
+/// let mut output = input.clone();
+/// for (axis, base) in bases.iter_mut().enumerate() {
+///     transform_along(base, &buffer, &mut output, axis, Direction::Forward);
+///     buffer.assign(&output);
+/// }
+/// assert!((output[[0, 0]] - 1.).abs() < 1e-10);
+/// ```
+pub fn transform_along<B, T, S1, S2, D>(
+    base: &mut B,
+    input: &ArrayBase<S1, D>,
+    output: &mut ArrayBase<S2, D>,
+    axis: usize,
+    direction: Direction,
+) where
+    B: Transform<Physical = T, Spectral = T>,
+    S1: ndarray::Data<Elem = T>,
+    S2: ndarray::Data<Elem = T> + ndarray::DataMut,
+    D: Dimension,
+{
+    match direction {
+        Direction::Forward => base.forward_inplace(input, output, axis),
+        Direction::Backward => base.backward_inplace(input, output, axis),
+    }
 }
 
 /// Transform from physical to spectral space and vice versa.
@@ -422,3 +526,168 @@ impl TransformKind {
         }
     }
 }
+
+/// Rule which defines the retained modes when dealiasing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DealiasRule {
+    /// Orszag's 2/3 rule
+    TwoThirds,
+    /// Retain modes below a fraction of the maximum mode
+    Cutoff(f64),
+}
+
+impl DealiasRule {
+    /// Fraction of retained modes
+    #[must_use]
+    pub fn fraction(&self) -> f64 {
+        match *self {
+            Self::TwoThirds => 2. / 3.,
+            Self::Cutoff(x) => x,
+        }
+    }
+}
+
+/// Scaling convention of the spectral coefficients, which
+/// are returned by the forward transform of an orthogonal base.
+///
+/// Chebyshev (nodes $x_j$ in ascending order, *n* points):
+/// - `Classical` (default): $f(x) = \sum_k c_k T_k(x)$
+/// - `Orthonormal`: coefficients $a_k$ of the basis $T_0 / \sqrt{\pi}$,
+///   $T_k \sqrt{2 / \pi}$, which is orthonormal with respect to the
+///   weight $(1-x^2)^{-1/2}$, i.e. $a_0 = \sqrt{\pi} c_0$ and
+///   $a_k = \sqrt{\pi / 2} c_k$
+/// - `FftUnnormalized`: unnormalized discrete cosine transform of the
+///   nodal values, DCT-I on gauss-lobatto nodes,
+///   $X_k = f_0 + (-1)^k f_{n-1} + 2 \sum_{j=1}^{n-2} f_j \cos(\pi j k / (n-1))$,
+///   and DCT-II on gauss nodes, $X_k = 2 \sum_j f_j \cos(\pi k (2j+1) / 2n)$.
+///   Then $X_k = (-1)^k w_k m c_k$, with $m = n - 1$ (gauss-lobatto) or
+///   $m = n$ (gauss), and $w_k = 2$ for $k = 0$ (and $k = n - 1$ on
+///   gauss-lobatto nodes), otherwise $w_k = 1$.
+///
+/// Fourier (nodes $x_j = 2 \pi j / n$):
+/// - `FftUnnormalized` (default): $X_k = \sum_j f_j e^{-ikx_j}$
+/// - `Classical`: $f(x) = \sum_k c_k e^{ikx}$, i.e. $c_k = X_k / n$
+/// - `Orthonormal`: coefficients of the basis $e^{ikx} / \sqrt{2 \pi}$,
+///   which is orthonormal on $[0, 2\pi]$, i.e. $a_k = \sqrt{2 \pi} X_k / n$
+///
+/// Differentiation and the laplacian matrices of a base act on
+/// coefficients of its convention. Composite bases always use the
+/// default convention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    /// Coefficients of the orthonormal basis functions
+    Orthonormal,
+    /// Coefficients of the classical series expansion
+    Classical,
+    /// Output of the unnormalized fast transform
+    FftUnnormalized,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::{approx_eq, approx_eq_complex};
+    use crate::{cheb_dirichlet, chebyshev, fourier_c2c, fourier_r2c};
+    use num_complex::Complex;
+
+    #[test]
+    fn test_dealias_mask_fourier() {
+        // r2c: n = 16, keep k < 16/3
+        let base = fourier_r2c::<f64>(16);
+        let mask = base.dealias_mask(DealiasRule::TwoThirds);
+        assert_eq!(mask.len(), 9);
+        let expected: Vec<bool> = (0..9).map(|k| k <= 5).collect();
+        assert_eq!(mask.to_vec(), expected);
+        // c2c: negative wavenumbers at the end
+        let base = fourier_c2c::<f64>(16);
+        let mask = base.dealias_mask(DealiasRule::TwoThirds);
+        assert_eq!(mask.len(), 16);
+        let expected: Vec<bool> = (0..16).map(|i| i <= 5 || i >= 11).collect();
+        assert_eq!(mask.to_vec(), expected);
+        // custom cutoff
+        let base = fourier_r2c::<f64>(16);
+        let mask = base.dealias_mask(DealiasRule::Cutoff(0.5));
+        assert_eq!(mask.iter().filter(|m| **m).count(), 4);
+    }
+
+    #[test]
+    fn test_dealias_mask_chebyshev() {
+        // orthogonal: keep i < 2/3 * 18 = 12
+        let base = chebyshev::<f64>(18);
+        let mask = base.dealias_mask(DealiasRule::TwoThirds);
+        assert_eq!(mask.len(), 18);
+        let expected: Vec<bool> = (0..18).map(|i| i < 12).collect();
+        assert_eq!(mask.to_vec(), expected);
+        // composite: coefficient i contains T_{i+2}
+        let base = cheb_dirichlet::<f64>(18);
+        let mask = base.dealias_mask(DealiasRule::TwoThirds);
+        assert_eq!(mask.len(), 16);
+        let expected: Vec<bool> = (0..16).map(|i| i < 10).collect();
+        assert_eq!(mask.to_vec(), expected);
+    }
+
+    #[test]
+    /// Transform 2d array along each axis, compare with direct calls
+    fn test_transform_along() {
+        let (nx, ny) = (6, 5);
+        let mut bases = [chebyshev::<f64>(nx), chebyshev::<f64>(ny)];
+        let input = Array2::from_shape_fn((nx, ny), |(i, j)| (i + 2 * j) as f64);
+        for (axis, base) in bases.iter_mut().enumerate() {
+            let mut output = Array2::<f64>::zeros((nx, ny));
+            transform_along(base, &input, &mut output, axis, Direction::Forward);
+            let expected: Array2<f64> = base.forward(&input, axis);
+            approx_eq(&output, &expected);
+            transform_along(base, &input, &mut output, axis, Direction::Backward);
+            let expected: Array2<f64> = base.backward(&input, axis);
+            approx_eq(&output, &expected);
+        }
+    }
+
+    #[test]
+    /// Transform 2d complex array along each axis
+    fn test_transform_along_c2c() {
+        let n = 6;
+        let mut base = fourier_c2c::<f64>(n);
+        let input = Array2::from_shape_fn((n, n), |(i, j)| Complex::new(i as f64, j as f64));
+        for axis in 0..2 {
+            let mut output = Array2::<Complex<f64>>::zeros((n, n));
+            transform_along(&mut base, &input, &mut output, axis, Direction::Forward);
+            let expected: Array2<Complex<f64>> = base.forward(&input, axis);
+            approx_eq_complex(&output, &expected);
+        }
+    }
+
+    #[test]
+    fn test_fourier_normalization() {
+        use crate::fourier::{FourierC2c, FourierR2c};
+        use std::f64::consts::PI;
+        let n = 8;
+        let mut r2c = FourierR2c::<f64>::new(n);
+        let data = r2c.x.mapv(|x| (2. * x).sin() + x.cos() + 1.);
+        let unnormalized = r2c.forward(&data, 0);
+        let dunnormalized = r2c.differentiate(&unnormalized, 1, 0);
+        let norms = [
+            (Normalization::FftUnnormalized, 1.),
+            (Normalization::Classical, 1. / n as f64),
+            (Normalization::Orthonormal, (2. * PI).sqrt() / n as f64),
+        ];
+        for (norm, factor) in norms.iter() {
+            // Real-to-complex
+            let mut r2c = FourierR2c::<f64>::new(n).with_normalization(*norm);
+            let vhat = r2c.forward(&data, 0);
+            approx_eq_complex(&vhat, &unnormalized.mapv(|v| v * *factor));
+            approx_eq(&r2c.backward(&vhat, 0), &data);
+            let vhat_par = r2c.forward_par(&data, 0);
+            approx_eq(&r2c.backward_par(&vhat_par, 0), &data);
+            let dvhat = r2c.differentiate(&vhat, 1, 0);
+            approx_eq_complex(&dvhat, &dunnormalized.mapv(|v| v * *factor));
+            // Complex-to-complex
+            let mut c2c = FourierC2c::<f64>::new(n).with_normalization(*norm);
+            let cdata = data.mapv(|v| Complex::new(v, 0.));
+            let expected = FourierC2c::<f64>::new(n).forward(&cdata, 0);
+            let vhat = c2c.forward(&cdata, 0);
+            approx_eq_complex(&vhat, &expected.mapv(|v| v * *factor));
+            approx_eq_complex(&c2c.backward(&vhat, 0), &cdata);
+        }
+    }
+}
@@ -75,6 +75,38 @@ pub fn check_array_axis<A, S, D>(
     }
 }
 
+/// Multiply all lanes of *input* along *axis* by matrix
+/// and write the result into *output*.
+///
+/// # Example
+/// ```
+/// use funspace::utils::dot_along;
+/// let mat = ndarray::array![[0., 1.], [1., 0.]];
+/// let input = ndarray::array![[1., 2., 3.], [4., 5., 6.]];
+/// let mut output = ndarray::Array2::<f64>::zeros((2, 3));
+/// dot_along(&mat, &input, &mut output, 0);
+/// assert!(output == ndarray::array![[4., 5., 6.], [1., 2., 3.]]);
+/// ```
+pub fn dot_along<A, S1, S2, D>(
+    mat: &Array2<A>,
+    input: &ArrayBase<S1, D>,
+    output: &mut ArrayBase<S2, D>,
+    axis: usize,
+) where
+    A: FloatNum,
+    S1: ndarray::Data<Elem = A>,
+    S2: ndarray::Data<Elem = A> + ndarray::DataMut,
+    D: Dimension,
+{
+    for (x, mut y) in input
+        .lanes(Axis(axis))
+        .into_iter()
+        .zip(output.lanes_mut(Axis(axis)))
+    {
+        y.assign(&mat.dot(&x));
+    }
+}
+
 /// Test approx equality of two arrays element-wise
 ///
 /// # Panics
@@ -2,7 +2,7 @@
 //! easier reading/writing of scalars and multidimensional arrays.
 pub use hdf5::H5Type;
 pub use hdf5::Result;
-use ndarray::{Array, Array1, ArrayBase, ArrayD, Dimension, Zip};
+use ndarray::{s, Array, Array1, ArrayBase, ArrayD, Dimension, Ix1, Zip};
 use num_complex::Complex;
 use num_traits::Num;
 use std::path::Path;
@@ -148,6 +148,43 @@ pub fn write_to_hdf5<T, S, D>(
     group: Option<&str>,
     array: &ArrayBase<S, D>,
 ) -> hdf5::Result<()>
+where
+    T: H5Type + Copy,
+    S: ndarray::Data<Elem = T>,
+    D: ndarray::Dimension,
+{
+    write_to_hdf5_compressed(filename, name, group, array, None)
+}
+
+/// Write dataset to hdf5 file, optionally compressed.
+///
+/// If `compression` is `Some(level)`, a new dataset is created
+/// chunked and gzip-compressed with `level` (0-9). Existing
+/// datasets keep their layout, i.e. they are overwritten
+/// in-place.
+///
+/// # Errors
+/// When file does not exist or when file and
+/// variable exists, but variable has different
+/// shape than input array (assign new value will fail).
+///
+/// # Panics
+/// When compression level is larger than 9.
+///
+/// # Example
+/// ```
+/// use hdf5_interface::write_to_hdf5_compressed;
+/// use ndarray::prelude::*;
+/// let x = Array1::<f64>::zeros(6);
+/// write_to_hdf5_compressed("test_compressed.h5", "x", None, &x, Some(4)).unwrap();
+/// ```
+pub fn write_to_hdf5_compressed<T, S, D>(
+    filename: &str,
+    name: &str,
+    group: Option<&str>,
+    array: &ArrayBase<S, D>,
+    compression: Option<u8>,
+) -> hdf5::Result<()>
 where
     T: H5Type + Copy,
     S: ndarray::Data<Elem = T>,
@@ -166,6 +203,13 @@ where
 
     let dset = if variable_exists? {
         file.dataset(&name_path)?
+    } else if let Some(level) = compression {
+        assert!(level <= 9, "Gzip level must be in 0-9, got {}", level);
+        file.new_dataset::<T>()
+            .chunk(array.shape())
+            .deflate(level)
+            .shape(array.shape())
+            .create(&name_path[..])?
     } else {
         file.new_dataset::<T>()
             .no_chunk()
@@ -198,6 +242,28 @@ pub fn write_to_hdf5_complex<T, S, D>(
     group: Option<&str>,
     array: &ArrayBase<S, D>,
 ) -> hdf5::Result<()>
+where
+    T: H5Type + Copy,
+    S: ndarray::Data<Elem = Complex<T>>,
+    D: ndarray::Dimension,
+{
+    write_to_hdf5_complex_compressed(filename, name, group, array, None)
+}
+
+/// Write complex valued dataset to hdf5 file, optionally
+/// gzip-compressed (see [`write_to_hdf5_compressed`]).
+///
+/// # Errors
+/// When file does not exist or when file and
+/// variable exists, but variable has different
+/// shape than input array (assign new value will fail).
+pub fn write_to_hdf5_complex_compressed<T, S, D>(
+    filename: &str,
+    name: &str,
+    group: Option<&str>,
+    array: &ArrayBase<S, D>,
+    compression: Option<u8>,
+) -> hdf5::Result<()>
 where
     T: H5Type + Copy,
     S: ndarray::Data<Elem = Complex<T>>,
@@ -205,10 +271,62 @@ where
 {
     // Write real part
     let name_re = format!("{}_re", name);
-    write_to_hdf5(filename, &name_re, group, &array.mapv(|x| x.re))?;
+    let re = array.mapv(|x| x.re);
+    write_to_hdf5_compressed(filename, &name_re, group, &re, compression)?;
     // Write imag part
     let name_im = format!("{}_im", name);
-    write_to_hdf5(filename, &name_im, group, &array.mapv(|x| x.im))?;
+    let im = array.mapv(|x| x.im);
+    write_to_hdf5_compressed(filename, &name_im, group, &im, compression)?;
+    Ok(())
+}
+
+/// Append column to an extendable 2-D dataset of shape
+/// (len, n), e.g. for time series. The dataset is created
+/// (chunked, resizable along the second axis) if it does not exist.
+///
+/// # Errors
+/// When file does not exist or when file and
+/// variable exists, but the column length differs.
+///
+/// # Example
+/// ```
+/// use hdf5_interface::append_to_hdf5;
+/// use ndarray::prelude::*;
+/// let x = Array1::<f64>::zeros(3);
+/// append_to_hdf5("test_append.h5", "x", None, &x).unwrap();
+/// ```
+pub fn append_to_hdf5<T, S>(
+    filename: &str,
+    name: &str,
+    group: Option<&str>,
+    column: &ArrayBase<S, Ix1>,
+) -> hdf5::Result<()>
+where
+    T: H5Type + Copy,
+    S: ndarray::Data<Elem = T>,
+{
+    // Open file
+    let file = if Path::new(filename).exists() {
+        hdf5::File::append(filename)?
+    } else {
+        hdf5::File::create(filename)?
+    };
+
+    // Create or extend dataset
+    let name_path = gen_name_path(name, group);
+    let len = column.len();
+    let dset = if variable_exists(&file, name, group)? {
+        file.dataset(&name_path)?
+    } else {
+        file.new_dataset::<T>()
+            .chunk((len.max(1), 64))
+            .shape((len, 0..))
+            .create(&name_path[..])?
+    };
+    let n = dset.shape()[1];
+    dset.resize((len, n + 1))?;
+    dset.write_slice(&column.view(), s![.., n])?;
+
     Ok(())
 }
 
@@ -268,6 +386,86 @@ where
     Ok(())
 }
 
+/// Write scalar attribute to the root of a hdf5 file, or
+/// to a group. Existing attributes are overwritten, missing
+/// groups are created.
+///
+/// # Errors
+/// When file can not be opened or created, or when the
+/// attribute exists, but has an incompatible type.
+///
+/// # Example
+/// ```
+/// use hdf5_interface::write_attr_to_hdf5;
+/// use hdf5_interface::read_attr_from_hdf5;
+/// write_attr_to_hdf5("test_attr.h5", "ra", None, 1e5).unwrap();
+/// let ra: f64 = read_attr_from_hdf5("test_attr.h5", "ra", None).unwrap();
+/// assert!((ra - 1e5).abs() < 1e-10);
+/// ```
+pub fn write_attr_to_hdf5<T>(
+    filename: &str,
+    name: &str,
+    group: Option<&str>,
+    value: T,
+) -> hdf5::Result<()>
+where
+    T: H5Type + Copy,
+{
+    // Open file
+    let file = if Path::new(filename).exists() {
+        hdf5::File::append(filename)?
+    } else {
+        hdf5::File::create(filename)?
+    };
+
+    // Open group
+    let location = match group {
+        Some(g) => {
+            if file.link_exists(g) {
+                file.group(g)?
+            } else {
+                file.create_group(g)?
+            }
+        }
+        None => file.group("/")?,
+    };
+
+    //Write attribute
+    let attr = if location.attr_names()?.iter().any(|i| i == name) {
+        location.attr(name)?
+    } else {
+        location.new_attr::<T>().create(name)?
+    };
+    attr.write_scalar(&value)?;
+
+    Ok(())
+}
+
+/// Read scalar attribute from the root of a hdf5 file,
+/// or from a group.
+///
+/// # Errors
+/// When file, group or attribute does not exist.
+pub fn read_attr_from_hdf5<T>(filename: &str, name: &str, group: Option<&str>) -> hdf5::Result<T>
+where
+    T: H5Type + Copy,
+{
+    let file = hdf5::File::open(filename)?;
+    let location = file.group(group.unwrap_or("/"))?;
+    location.attr(name)?.read_scalar()
+}
+
+/// Names of the members (datasets and groups) at the root
+/// of a hdf5 file, or in a group.
+///
+/// # Errors
+/// When file or group does not exist.
+pub fn read_member_names(filename: &str, group: Option<&str>) -> hdf5::Result<Vec<String>> {
+    let file = hdf5::File::open(filename)?;
+    let location = file.group(group.unwrap_or("/"))?;
+    location.member_names()
+}
+
 /// Retrieve size of dimension from an hdf5 file
 ///
 /// # Errors
@@ -354,4 +552,62 @@ mod test {
         let array_read: Array2<f64> = read_from_hdf5(&fname, "var", None).unwrap();
         assert_eq!(array, array_read);
     }
+
+    #[test]
+    /// Append columns to extendable dataset
+    fn test_append() {
+        use ndarray::Array2;
+        let fname = "test_append_columns.h5";
+        if Path::new(fname).exists() {
+            std::fs::remove_file(fname).unwrap();
+        }
+        for i in 0..3 {
+            let column = Array1::<f64>::from_elem(4, i as f64);
+            append_to_hdf5(&fname, "var", Some("series"), &column).unwrap();
+        }
+        let array_read: Array2<f64> = read_from_hdf5(&fname, "var", Some("series")).unwrap();
+        std::fs::remove_file(fname).unwrap();
+        assert_eq!(array_read.shape(), &[4, 3]);
+        assert_eq!(array_read.row(0).to_vec(), vec![0., 1., 2.]);
+    }
+
+    #[test]
+    /// Compressed and uncompressed datasets hold the same data
+    fn test_compressed() {
+        use ndarray::Array2;
+        let fname = "test_compressed_roundtrip.h5";
+        if Path::new(fname).exists() {
+            std::fs::remove_file(fname).unwrap();
+        }
+        let array = Array2::<f64>::from_shape_fn((20, 12), |(i, j)| (i * j) as f64);
+        write_to_hdf5_compressed(&fname, "plain", None, &array, None).unwrap();
+        write_to_hdf5_compressed(&fname, "gzip", None, &array, Some(6)).unwrap();
+        let file = hdf5::File::open(fname).unwrap();
+        assert!(file.dataset("plain").unwrap().chunk().is_none());
+        assert!(file.dataset("gzip").unwrap().chunk().is_some());
+        drop(file);
+        let plain: Array2<f64> = read_from_hdf5(&fname, "plain", None).unwrap();
+        let gzip: Array2<f64> = read_from_hdf5(&fname, "gzip", None).unwrap();
+        std::fs::remove_file(fname).unwrap();
+        assert_eq!(plain, array);
+        assert_eq!(gzip, plain);
+    }
+
+    #[test]
+    /// Write & overwrite & read attributes
+    fn test_attr() {
+        let fname = "test_attr_roundtrip.h5";
+        if Path::new(fname).exists() {
+            std::fs::remove_file(fname).unwrap();
+        }
+        write_attr_to_hdf5(&fname, "ra", None, 1e4).unwrap();
+        write_attr_to_hdf5(&fname, "ra", None, 2e4).unwrap();
+        write_attr_to_hdf5(&fname, "nx", Some("grid"), 64_u64).unwrap();
+        let ra: f64 = read_attr_from_hdf5(&fname, "ra", None).unwrap();
+        let nx: u64 = read_attr_from_hdf5(&fname, "nx", Some("grid")).unwrap();
+        assert!(read_attr_from_hdf5::<f64>(&fname, "pr", None).is_err());
+        std::fs::remove_file(fname).unwrap();
+        assert!((ra - 2e4).abs() < 1e-10);
+        assert_eq!(nx, 64);
+    }
 }
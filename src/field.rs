@@ -4,16 +4,24 @@
 //! belong the the physical (v) and spectral (vhat)
 //! space.
 pub mod average;
+pub mod boundary;
+pub mod diagnostics;
+pub mod npy;
+pub mod probe;
 pub mod read;
+pub mod spectral;
+pub mod vtk;
 pub mod write;
 use crate::bases::LaplacianInverse;
-use crate::bases::{BaseAll, BaseC2c, BaseR2c, BaseR2r, Basics};
-pub use crate::bases::{BaseSpace, Space1, Space2};
+use crate::bases::{BaseAll, BaseC2c, BaseR2c, BaseR2r, Basics, DealiasRule, TransformKind};
+pub use crate::bases::{BaseSpace, Space1, Space2, Space3};
 use crate::types::FloatNum;
 use ndarray::{prelude::*, Data};
 use ndarray::{Ix, ScalarOperand, Slice};
 use num_complex::Complex;
+use num_traits::Zero;
 pub use read::ReadField;
+use std::any::TypeId;
 use std::convert::TryInto;
 pub use write::WriteField;
 
@@ -23,6 +31,9 @@ pub type Field1<T2, S> = FieldBase<f64, f64, T2, S, 1>;
 /// Two dimensional Field (Real in Physical space, Generic in Spectral Space)
 pub type Field2<T2, S> = FieldBase<f64, f64, T2, S, 2>;
 
+/// Three dimensional Field (Real in Physical space, Generic in Spectral Space)
+pub type Field3<T2, S> = FieldBase<f64, f64, T2, S, 3>;
+
 /// Field struct is rustpdes backbone
 ///
 /// v: ndarray
@@ -81,6 +92,159 @@ pub struct FieldBase<A, T1, T2, S, const N: usize> {
     // pub solvers: HashMap<String, SolverField<T, N>>,
 }
 
+/// Errors on field construction
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldError {
+    /// Transform of base does not match the element type of the field
+    IncompatibleBase {
+        /// Axis of offending base
+        axis: usize,
+        /// Name of transform kind
+        transform: String,
+        /// "physical" or "spectral"
+        space: &'static str,
+        /// "real" or "complex"
+        elem: &'static str,
+    },
+    /// Base has too few grid points
+    TooFewPoints {
+        /// Axis of offending base
+        axis: usize,
+        /// Required number of points
+        min: usize,
+        /// Supplied number of points
+        got: usize,
+    },
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IncompatibleBase {
+                axis,
+                transform,
+                space,
+                elem,
+            } => write!(
+                f,
+                "Base along axis {} ({}) is incompatible with a {} field in {} space",
+                axis, transform, elem, space
+            ),
+            Self::TooFewPoints { axis, min, got } => write!(
+                f,
+                "Base along axis {} requires at least {} points but got {}",
+                axis, min, got
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+/// Check if transform kinds are consistent with element types
+/// of the field in physical and spectral space.
+///
+/// The spectral field is complex if any axis is of r2c or c2c type.
+/// The physical field is complex if any axis is of c2c type,
+/// unless it is preceded by a r2c transform.
+fn check_transform_kinds(
+    kinds: &[TransformKind],
+    physical_is_complex: bool,
+    spectral_is_complex: bool,
+) -> Result<(), FieldError> {
+    let r2c = kinds
+        .iter()
+        .position(|k| matches!(k, TransformKind::RealToComplex));
+    let c2c = kinds
+        .iter()
+        .position(|k| matches!(k, TransformKind::ComplexToComplex));
+    let not_r2r = kinds
+        .iter()
+        .position(|k| !matches!(k, TransformKind::RealToReal));
+    let elem = |is_complex: bool| if is_complex { "complex" } else { "real" };
+    // Offending axis, if expected and actual element type differ
+    let last = kinds.len() - 1;
+    let physical_axis = match (physical_is_complex, c2c.is_some() && r2c.is_none()) {
+        (false, true) => c2c,
+        (true, false) => Some(r2c.unwrap_or(last)),
+        _ => None,
+    };
+    let spectral_axis = match (spectral_is_complex, not_r2r.is_some()) {
+        (false, true) => not_r2r,
+        (true, false) => Some(last),
+        _ => None,
+    };
+    for (axis, space, is_complex) in [
+        (physical_axis, "physical", physical_is_complex),
+        (spectral_axis, "spectral", spectral_is_complex),
+    ]
+    .iter()
+    {
+        if let Some(axis) = axis {
+            return Err(FieldError::IncompatibleBase {
+                axis: *axis,
+                transform: kinds[*axis].name().to_string(),
+                space: *space,
+                elem: elem(*is_complex),
+            });
+        }
+    }
+    Ok(())
+}
+
+impl<A, T1, T2, S, const N: usize> FieldBase<A, T1, T2, S, N>
+where
+    A: FloatNum + 'static,
+    T1: 'static,
+    T2: 'static,
+    Complex<A>: ScalarOperand,
+    S: BaseSpace<A, N, Physical = T1, Spectral = T2>,
+{
+    /// Return a new field from a given space, or an error
+    /// if the bases of the space are not compatible with
+    /// the field.
+    ///
+    /// Checks that the transforms produce the element types
+    /// (real / complex) of the field and that each base has
+    /// enough grid points, instead of panicking at a later
+    /// transform.
+    ///
+    /// # Errors
+    /// `FieldError` if the space is not suitable for this field.
+    ///
+    /// # Example
+    ///```
+    /// use rustpde::{cheb_dirichlet, fourier_r2c, Field2, Space2};
+    /// use num_complex::Complex;
+    /// let space = Space2::new(&fourier_r2c(8), &cheb_dirichlet(6));
+    /// let field = Field2::<Complex<f64>, _>::try_new(&space);
+    /// assert!(field.is_ok());
+    ///```
+    pub fn try_new(space: &S) -> Result<Self, FieldError> {
+        let kinds: Vec<TransformKind> = space
+            .base_all()
+            .iter()
+            .map(|b| b.get_transform_kind().clone())
+            .collect();
+        let is_complex = |id: TypeId| id == TypeId::of::<Complex<A>>();
+        check_transform_kinds(
+            &kinds,
+            is_complex(TypeId::of::<T1>()),
+            is_complex(TypeId::of::<T2>()),
+        )?;
+        // Periodic grid spacing requires at least three points
+        let is_periodic = Self::is_periodic(space);
+        for axis in 0..N {
+            let min = if is_periodic[axis] { 3 } else { 2 };
+            let got = space.shape_physical()[axis];
+            if got < min {
+                return Err(FieldError::TooFewPoints { axis, min, got });
+            }
+        }
+        Ok(Self::new(space))
+    }
+}
+
 impl<A, T1, T2, S, const N: usize> FieldBase<A, T1, T2, S, N>
 where
     A: FloatNum,
@@ -109,6 +273,47 @@ where
         self.space.backward_inplace_par(&self.vhat, &mut self.v);
     }
 
+    /// Modify the field in physical space.
+    ///
+    /// Transforms to physical space, lends `v` to the closure
+    /// and transforms back, so that `vhat` stays consistent
+    /// with `v`.
+    ///
+    /// # Example
+    ///```
+    /// use rustpde::{chebyshev, Field2, Space2};
+    /// let space = Space2::new(&chebyshev(6), &chebyshev(5));
+    /// let mut field = Field2::new(&space);
+    /// field.with_physical_mut(|v| v.fill(1.));
+    /// assert!((field.vhat[[0, 0]] - 1.).abs() < 1e-10);
+    ///```
+    pub fn with_physical_mut<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut Array<T1, Dim<[usize; N]>>) -> R,
+    {
+        let mut guard = self.physical_view_mut();
+        f(&mut guard)
+    }
+
+    /// Mutable view of the field in physical space.
+    ///
+    /// Transforms to physical space. The returned guard
+    /// dereferences to `v` and transforms back to spectral
+    /// space, when it is dropped. See [`FieldBase::with_physical_mut`].
+    pub fn physical_view_mut(&mut self) -> PhysicalViewMut<'_, A, T1, T2, S, N> {
+        self.backward();
+        PhysicalViewMut { field: self }
+    }
+
+    /// Backward transformation, without altering the field.
+    ///
+    /// Returns the field in physical space, while `v`
+    /// remains untouched. Uses a copy of the space, because
+    /// the transforms require mutable access to their plans.
+    pub fn to_physical(&self) -> Array<T1, Dim<[usize; N]>> {
+        self.space.clone().backward_par(&self.vhat)
+    }
+
     /// Transform from composite to orthogonal space
     pub fn to_ortho(&self) -> Array<T2, Dim<[usize; N]>> {
         self.space.to_ortho_par(&self.vhat)
@@ -123,11 +328,32 @@ where
     }
 
     /// Gradient
+    ///
+    /// Returns the orthogonal coefficients of the derivative. Along sine
+    /// (cosine) axes, odd derivatives are cosine (sine) series of the same
+    /// wavenumbers, i.e. the coefficients refer to the complementary series.
+    /// Use `grad_physical` for the derivative in physical space.
     // #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
     pub fn gradient(&self, deriv: [usize; N], scale: Option<[A; N]>) -> Array<T2, Dim<[usize; N]>> {
         self.space.gradient_par(&self.vhat, deriv, scale)
     }
 
+    /// Zero spectral coefficients, which are outside the
+    /// dealias masks (see [`Basics::dealias_mask`]) of each axis
+    pub fn dealias(&mut self, rule: DealiasRule)
+    where
+        T2: Zero + Clone,
+    {
+        for (axis, base) in self.space.base_all().iter().enumerate() {
+            let mask = base.dealias_mask(rule);
+            for (mut lane, keep) in self.vhat.axis_iter_mut(Axis(axis)).zip(mask.iter()) {
+                if !keep {
+                    lane.fill(T2::zero());
+                }
+            }
+        }
+    }
+
     /// Generate grid deltas from coordinates
     ///
     /// ## Panics
@@ -170,7 +396,10 @@ where
             let x = &space.base_all()[axis];
             let is_periodic_axis = match x {
                 BaseAll::BaseR2r(ref b) => match b {
-                    BaseR2r::Chebyshev(_) | BaseR2r::CompositeChebyshev(_) => false,
+                    BaseR2r::Chebyshev(_)
+                    | BaseR2r::CompositeChebyshev(_)
+                    | BaseR2r::FourierSine(_)
+                    | BaseR2r::FourierCosine(_) => false,
                 },
                 BaseAll::BaseR2c(ref b) => match b {
                     BaseR2c::FourierR2c(_) => true,
@@ -206,6 +435,7 @@ where
                     (pinv.dot(&mass_sliced), peye.dot(&mass_sliced))
                 }
                 BaseR2r::CompositeChebyshev(_) => (pinv.dot(&mass), peye.dot(&mass)),
+                BaseR2r::FourierSine(_) | BaseR2r::FourierCosine(_) => (mass, lap),
             },
             BaseAll::BaseR2c(ref b) => match b {
                 BaseR2c::FourierR2c(_) => (mass, lap),
@@ -218,6 +448,7 @@ where
         let precond = match x {
             BaseAll::BaseR2r(ref b) => match b {
                 BaseR2r::Chebyshev(_) | BaseR2r::CompositeChebyshev(_) => Some(pinv),
+                BaseR2r::FourierSine(_) | BaseR2r::FourierCosine(_) => None,
             },
             BaseAll::BaseR2c(_) | BaseAll::BaseC2c(_) => None,
         };
@@ -244,10 +475,300 @@ where
         // if not, a eigendecomposition will diagonalize mat a,
         // however, this is more expense.
         let is_diag = match x {
+            BaseAll::BaseR2r(BaseR2r::FourierSine(_))
+            | BaseAll::BaseR2r(BaseR2r::FourierCosine(_))
+            | BaseAll::BaseR2c(_)
+            | BaseAll::BaseC2c(_) => true,
             BaseAll::BaseR2r(_) => false,
-            BaseAll::BaseR2c(_) | BaseAll::BaseC2c(_) => true,
         };
 
         (mat_a, mat_b, precond, is_diag)
     }
 }
+
+/// Guard of [`FieldBase::physical_view_mut`], which
+/// forward transforms the field when dropped
+pub struct PhysicalViewMut<'a, A, T1, T2, S, const N: usize>
+where
+    A: FloatNum,
+    Complex<A>: ScalarOperand,
+    S: BaseSpace<A, N, Physical = T1, Spectral = T2>,
+{
+    field: &'a mut FieldBase<A, T1, T2, S, N>,
+}
+
+impl<'a, A, T1, T2, S, const N: usize> std::ops::Deref for PhysicalViewMut<'a, A, T1, T2, S, N>
+where
+    A: FloatNum,
+    Complex<A>: ScalarOperand,
+    S: BaseSpace<A, N, Physical = T1, Spectral = T2>,
+{
+    type Target = Array<T1, Dim<[usize; N]>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.field.v
+    }
+}
+
+impl<'a, A, T1, T2, S, const N: usize> std::ops::DerefMut for PhysicalViewMut<'a, A, T1, T2, S, N>
+where
+    A: FloatNum,
+    Complex<A>: ScalarOperand,
+    S: BaseSpace<A, N, Physical = T1, Spectral = T2>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.field.v
+    }
+}
+
+impl<'a, A, T1, T2, S, const N: usize> Drop for PhysicalViewMut<'a, A, T1, T2, S, N>
+where
+    A: FloatNum,
+    Complex<A>: ScalarOperand,
+    S: BaseSpace<A, N, Physical = T1, Spectral = T2>,
+{
+    fn drop(&mut self) {
+        self.field.forward();
+    }
+}
+
+impl<T1, T2, S, const N: usize> FieldBase<f64, T1, T2, S, N>
+where
+    T1: Copy + Into<Complex<f64>>,
+    T2: Copy + Into<Complex<f64>>,
+{
+    /// Returns true if physical and spectral values of both
+    /// fields agree element-wise within absolute tolerance `tol`.
+    ///
+    /// Fields of different shape are never equal.
+    pub fn approx_eq(&self, other: &Self, tol: f64) -> bool {
+        let close = |a: Complex<f64>, b: Complex<f64>| (a - b).norm() <= tol;
+        self.v.shape() == other.v.shape()
+            && self.vhat.shape() == other.vhat.shape()
+            && self
+                .v
+                .iter()
+                .zip(other.v.iter())
+                .all(|(a, b)| close((*a).into(), (*b).into()))
+            && self
+                .vhat
+                .iter()
+                .zip(other.vhat.iter())
+                .all(|(a, b)| close((*a).into(), (*b).into()))
+    }
+
+    /// Returns L2 norm of the difference of the spectral
+    /// coefficients, relative to the norm of `other`.
+    ///
+    /// Returns the absolute difference, if `other` vanishes.
+    ///
+    /// # Panics
+    /// Panics if the fields differ in shape.
+    pub fn relative_difference(&self, other: &Self) -> f64 {
+        use crate::solver::iterative::{norm_l2, norm_l2_diff};
+        assert!(
+            self.vhat.shape() == other.vhat.shape(),
+            "Shape mismatch {:?} != {:?}",
+            self.vhat.shape(),
+            other.vhat.shape()
+        );
+        let diff = norm_l2_diff(&self.vhat, &other.vhat);
+        let norm = norm_l2(&other.vhat);
+        if norm > 0. {
+            diff / norm
+        } else {
+            diff
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{FdmaTensor, Solve};
+    use crate::{cheb_dirichlet, chebyshev, fourier_r2c};
+    use ndarray::Zip;
+
+    #[test]
+    fn test_ortho_roundtrip_r2c() {
+        // from_ortho(to_ortho(vhat)) == vhat on the half spectrum,
+        // for even and odd nx
+        for &nx in &[7, 8, 9, 16, 17] {
+            let space = Space2::new(&fourier_r2c(nx), &cheb_dirichlet(9));
+            let mut field = Field2::new(&space);
+            for ((i, j), v) in field.vhat.indexed_iter_mut() {
+                *v = Complex::new(0.3 + 0.1 * (i + 2 * j) as f64, 0.05 * (i * j) as f64 - 0.2);
+            }
+            let vhat = field.vhat.clone();
+            let ortho = field.to_ortho();
+            field.from_ortho(&ortho);
+            for (a, b) in field.vhat.iter().zip(vhat.iter()) {
+                assert!((a - b).norm() < 1e-12);
+            }
+            // Highest stored mode (nyquist mode for even nx)
+            let m = field.vhat.shape()[0];
+            field.vhat.fill(Complex::new(0., 0.));
+            field.vhat[[m - 1, 2]] = Complex::new(1., 0.);
+            let vhat = field.vhat.clone();
+            let ortho = field.to_ortho();
+            field.from_ortho(&ortho);
+            for (a, b) in field.vhat.iter().zip(vhat.iter()) {
+                assert!((a - b).norm() < 1e-12);
+            }
+            // ... and through physical space
+            field.backward();
+            field.forward();
+            for (a, b) in field.vhat.iter().zip(vhat.iter()) {
+                assert!((a - b).norm() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let space = Space2::new(&fourier_r2c(8), &cheb_dirichlet(6));
+        let mut field = Field2::new(&space);
+        for (i, v) in field.v.iter_mut().enumerate() {
+            *v = (i as f64).sin();
+        }
+        field.forward();
+        field.backward();
+        assert!(field.approx_eq(&field, 0.));
+        assert!(field.relative_difference(&field) == 0.);
+        let mut perturbed = field.clone();
+        perturbed.vhat[[1, 1]] += Complex::new(1e-6, 0.);
+        assert!(field.approx_eq(&perturbed, 1e-5));
+        assert!(!field.approx_eq(&perturbed, 1e-7));
+        assert!(field.relative_difference(&perturbed) > 0.);
+        // physical values are compared as well
+        let mut perturbed = field.clone();
+        perturbed.v[[0, 0]] += 1e-3;
+        assert!(!field.approx_eq(&perturbed, 1e-5));
+    }
+
+    #[test]
+    fn test_with_physical_mut() {
+        let space = Space2::new(&fourier_r2c(8), &cheb_dirichlet(6));
+        let mut field = Field2::new(&space);
+        field.vhat[[1, 1]] = Complex::new(1., 2.);
+        field.vhat[[2, 0]] = Complex::new(0.5, 0.);
+        let expected = field.vhat.mapv(|x| x * 2.);
+        let max = field.with_physical_mut(|v| {
+            *v *= 2.;
+            v.iter().fold(f64::MIN, |a, b| a.max(*b))
+        });
+        assert!(max > 0.);
+        for (a, b) in field.vhat.iter().zip(expected.iter()) {
+            assert!((a - b).norm() < 1e-10);
+        }
+        // guard
+        {
+            let mut v = field.physical_view_mut();
+            v.fill(0.);
+        }
+        assert!(field.vhat.iter().all(|x| x.norm() < 1e-12));
+    }
+
+    #[test]
+    fn test_try_new_valid() {
+        let space = Space2::new(&cheb_dirichlet(8), &cheb_dirichlet(6));
+        assert!(Field2::<f64, _>::try_new(&space).is_ok());
+        let space = Space2::new(&fourier_r2c(8), &cheb_dirichlet(6));
+        assert!(Field2::<Complex<f64>, _>::try_new(&space).is_ok());
+    }
+
+    #[test]
+    fn test_try_new_invalid() {
+        // real field with r2c base
+        let kinds = [TransformKind::RealToComplex, TransformKind::RealToReal];
+        let err = check_transform_kinds(&kinds, false, false).unwrap_err();
+        assert_eq!(
+            err,
+            FieldError::IncompatibleBase {
+                axis: 0,
+                transform: "RealToComplex".to_string(),
+                space: "spectral",
+                elem: "real",
+            }
+        );
+        // periodic base with too few points
+        let space = Space2::new(&fourier_r2c(2), &cheb_dirichlet(6));
+        let err = Field2::<Complex<f64>, _>::try_new(&space).err().unwrap();
+        assert_eq!(
+            err,
+            FieldError::TooFewPoints {
+                axis: 0,
+                min: 3,
+                got: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_field3_roundtrip() {
+        let space = Space3::new(&fourier_r2c(8), &chebyshev(7), &cheb_dirichlet(6));
+        let mut field = Field3::new(&space);
+        let (x, y, z) = (&field.x[0], &field.x[1], &field.x[2]);
+        let expected = Array3::from_shape_fn(field.v.raw_dim(), |(i, j, k)| {
+            x[i].cos() * y[j] * (1. - z[k] * z[k])
+        });
+        field.v.assign(&expected);
+        field.forward();
+        field.v.fill(0.);
+        field.backward();
+        assert!(field
+            .v
+            .iter()
+            .zip(expected.iter())
+            .all(|(a, b)| (a - b).abs() < 1e-10));
+    }
+
+    #[test]
+    fn test_field3_poisson() {
+        // u = (1 - x^2)(1 - y^2)(1 - z^2)(1 + xyz)
+        let space = Space3::new(&cheb_dirichlet(8), &cheb_dirichlet(7), &cheb_dirichlet(6));
+        let mut field = Field3::new(&space);
+        let (x, y, z) = (&field.x[0], &field.x[1], &field.x[2]);
+        let u = Array3::from_shape_fn(field.v.raw_dim(), |(i, j, k)| {
+            let (x, y, z) = (x[i], y[j], z[k]);
+            (1. - x * x) * (1. - y * y) * (1. - z * z) * (1. + x * y * z)
+        });
+        field.v.assign(&u);
+        field.forward();
+        // Laplacian (orthogonal space), preconditioned along each axis
+        let mut rhs = field.gradient([2, 0, 0], None)
+            + &field.gradient([0, 2, 0], None)
+            + &field.gradient([0, 0, 2], None);
+        let (mut laplacians, mut masses, mut is_diag) = (vec![], vec![], vec![]);
+        for axis in 0..3 {
+            let (mass, laplacian, precond, diag) = field.ingredients_for_poisson(axis);
+            if let Some(p) = precond {
+                let mut shape = rhs.raw_dim();
+                shape[axis] = p.nrows();
+                let mut buffer = Array3::<f64>::zeros(shape);
+                Zip::from(buffer.lanes_mut(Axis(axis)))
+                    .and(rhs.lanes(Axis(axis)))
+                    .for_each(|mut b, r| b.assign(&p.dot(&r)));
+                rhs = buffer;
+            }
+            laplacians.push(laplacian);
+            masses.push(mass);
+            is_diag.push(diag);
+        }
+        let solver = FdmaTensor::from_matrix(
+            [&laplacians[0], &laplacians[1], &laplacians[2]],
+            [&masses[0], &masses[1], &masses[2]],
+            [&is_diag[0], &is_diag[1], &is_diag[2]],
+            0.,
+        );
+        let mut result = Array3::<f64>::zeros(field.vhat.raw_dim());
+        solver.solve(&rhs, &mut result, 0);
+        field.vhat.assign(&result);
+        field.backward();
+        assert!(field
+            .v
+            .iter()
+            .zip(u.iter())
+            .all(|(a, b)| (a - b).abs() < 1e-10));
+    }
+}
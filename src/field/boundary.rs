@@ -0,0 +1,194 @@
+//! Evaluate fields at the boundaries of chebyshev axes,
+//! directly from the spectral coefficients.
+//!
+//! Uses $T_k(\pm 1) = (\pm 1)^k$ and $T'_k(\pm 1) = (\pm 1)^{k+1} k^2$.
+use super::{BaseSpace, FieldBase};
+use crate::bases::{chebyshev, BaseAll, BaseR2r, Transform};
+use ndarray::prelude::*;
+use num_complex::Complex;
+use num_traits::Zero;
+
+macro_rules! impl_boundary_values {
+    ($s: ty) => {
+        impl<S> FieldBase<f64, f64, $s, S, 2>
+        where
+            S: BaseSpace<f64, 2, Physical = f64, Spectral = $s>,
+        {
+            /// Returns field values at the two ends (x = -1, x = 1)
+            /// of chebyshev `axis`, as function of the other axis
+            /// (physical space).
+            ///
+            /// # Panics
+            /// Panics if `axis` is not of chebyshev type.
+            ///
+            /// # Example
+            ///```
+            /// use rustpde::{cheb_dirichlet, Field2, Space2};
+            /// let space = Space2::new(&cheb_dirichlet(8), &cheb_dirichlet(6));
+            /// let mut field = Field2::new(&space);
+            /// field.vhat.fill(1.);
+            /// let (left, right) = field.boundary_values(0);
+            /// assert!(left.iter().chain(right.iter()).all(|v| v.abs() < 1e-10));
+            ///```
+            pub fn boundary_values(&self, axis: usize) -> (Array1<f64>, Array1<f64>) {
+                self.boundary_eval(axis, 0)
+            }
+
+            /// Returns normal derivative at the two ends (x = -1, x = 1)
+            /// of chebyshev `axis`, as function of the other axis
+            /// (physical space). The derivative refers to the
+            /// standard interval \[-1, 1\].
+            ///
+            /// # Panics
+            /// Panics if `axis` is not of chebyshev type.
+            pub fn boundary_derivatives(&self, axis: usize) -> (Array1<f64>, Array1<f64>) {
+                self.boundary_eval(axis, 1)
+            }
+
+            fn boundary_eval(&self, axis: usize, deriv: usize) -> (Array1<f64>, Array1<f64>) {
+                match self.space.base_all()[axis] {
+                    BaseAll::BaseR2r(BaseR2r::Chebyshev(_))
+                    | BaseAll::BaseR2r(BaseR2r::CompositeChebyshev(_)) => (),
+                    _ => panic!("Boundary values require chebyshev base along axis {}", axis),
+                }
+                let ortho = self.to_ortho();
+                let n = ortho.shape()[axis];
+                let mut w_left = Array1::<f64>::zeros(n);
+                let mut w_right = Array1::<f64>::zeros(n);
+                for k in 0..n {
+                    let sign = if k % 2 == 0 { 1. } else { -1. };
+                    if deriv == 0 {
+                        w_left[k] = sign;
+                        w_right[k] = 1.;
+                    } else {
+                        let k2 = (k * k) as f64;
+                        w_left[k] = -sign * k2;
+                        w_right[k] = k2;
+                    }
+                }
+                let weighted_sum = |w: &Array1<f64>| -> Array1<$s> {
+                    ortho
+                        .lanes(Axis(axis))
+                        .into_iter()
+                        .map(|lane| {
+                            lane.iter()
+                                .zip(w.iter())
+                                .fold(<$s>::zero(), |acc, (c, wi)| acc + *c * *wi)
+                        })
+                        .collect()
+                };
+                let other = 1 - axis;
+                (
+                    self.backward_axis(&weighted_sum(&w_left), other),
+                    self.backward_axis(&weighted_sum(&w_right), other),
+                )
+            }
+        }
+    };
+}
+
+impl_boundary_values!(f64);
+impl_boundary_values!(Complex<f64>);
+
+impl<S> FieldBase<f64, f64, f64, S, 2>
+where
+    S: BaseSpace<f64, 2, Physical = f64, Spectral = f64>,
+{
+    /// Transform orthogonal coefficients along axis to physical space
+    fn backward_axis(&self, coef: &Array1<f64>, axis: usize) -> Array1<f64> {
+        match self.space.base_all()[axis] {
+            BaseAll::BaseR2r(BaseR2r::Chebyshev(_))
+            | BaseAll::BaseR2r(BaseR2r::CompositeChebyshev(_)) => {
+                chebyshev::<f64>(coef.len()).backward(coef, 0)
+            }
+            BaseAll::BaseR2r(ref b) => b.clone().backward(coef, 0),
+            _ => panic!("Unexpected base along axis {}", axis),
+        }
+    }
+}
+
+impl<S> FieldBase<f64, f64, Complex<f64>, S, 2>
+where
+    S: BaseSpace<f64, 2, Physical = f64, Spectral = Complex<f64>>,
+{
+    /// Transform orthogonal coefficients along axis to physical space
+    fn backward_axis(&self, coef: &Array1<Complex<f64>>, axis: usize) -> Array1<f64> {
+        match self.space.base_all()[axis] {
+            BaseAll::BaseR2c(ref b) => b.clone().backward(coef, 0),
+            _ => panic!("Unexpected base along axis {}", axis),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{cheb_dirichlet, cheb_neumann, chebyshev, fourier_r2c, Field2, Space2};
+    use num_complex::Complex;
+
+    fn assert_zero(a: &ndarray::Array1<f64>) {
+        for v in a.iter() {
+            assert!(v.abs() < 1e-10, "Expected zero, got {}", v);
+        }
+    }
+
+    #[test]
+    fn test_boundary_values_dirichlet() {
+        let space = Space2::new(&cheb_dirichlet(10), &cheb_dirichlet(8));
+        let mut field = Field2::new(&space);
+        for (i, v) in field.vhat.iter_mut().enumerate() {
+            *v = (i as f64).cos();
+        }
+        for axis in 0..2 {
+            let (left, right) = field.boundary_values(axis);
+            assert_zero(&left);
+            assert_zero(&right);
+        }
+    }
+
+    #[test]
+    fn test_boundary_values_dirichlet_periodic() {
+        let space = Space2::new(&fourier_r2c(10), &cheb_dirichlet(8));
+        let mut field = Field2::new(&space);
+        for (i, v) in field.vhat.iter_mut().enumerate() {
+            *v = Complex::new((i as f64).cos(), (i as f64).sin());
+        }
+        let (left, right) = field.boundary_values(1);
+        assert_zero(&left);
+        assert_zero(&right);
+    }
+
+    #[test]
+    fn test_boundary_derivatives_neumann() {
+        let space = Space2::new(&cheb_neumann(10), &cheb_neumann(8));
+        let mut field = Field2::new(&space);
+        for (i, v) in field.vhat.iter_mut().enumerate() {
+            *v = (i as f64).cos();
+        }
+        for axis in 0..2 {
+            let (left, right) = field.boundary_derivatives(axis);
+            assert_zero(&left);
+            assert_zero(&right);
+        }
+    }
+
+    #[test]
+    fn test_boundary_values_polynomial() {
+        // f(x,y) = x^2 + y
+        let space = Space2::new(&chebyshev(8), &chebyshev(6));
+        let mut field = Field2::new(&space);
+        let x = field.x[0].to_owned();
+        let y = field.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                field.v[[i, j]] = xi * xi + yi;
+            }
+        }
+        field.forward();
+        let (left, right) = field.boundary_values(0);
+        assert_zero(&(&left - &(&y + 1.)));
+        assert_zero(&(&right - &(&y + 1.)));
+        let (left, right) = field.boundary_derivatives(0);
+        assert_zero(&(&left + 2.));
+        assert_zero(&(&right - 2.));
+    }
+}
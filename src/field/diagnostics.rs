@@ -0,0 +1,308 @@
+//! Norms and energies, evaluated from the spectral coefficients.
+//!
+//! By Parseval's theorem, the squared L2 norm is a quadratic form
+//! of the coefficients,
+//! $$
+//! \int v^2 dV = \sum_{ijkl} \hat{v}\_{ij}^* G^x\_{ik} G^y\_{jl} \hat{v}\_{kl},
+//! $$
+//! with the gram matrices $G\_{jk} = \int \phi_j \phi_k dx$ of the
+//! basis functions along each axis. Fourier basis functions are
+//! orthogonal, i.e. $G$ is diagonal. For `fourier_r2c`, $G$ includes
+//! the factor two of the modes, which represent the missing half of
+//! the spectrum. The same holds for sine and cosine series.
+use super::{BaseSpace, FieldBase};
+use crate::bases::{chebyshev, BaseAll, BaseR2r, Basics, Transform};
+use ndarray::prelude::*;
+use num_complex::Complex;
+
+impl<T2, S> FieldBase<f64, f64, T2, S, 2>
+where
+    S: BaseSpace<f64, 2, Physical = f64, Spectral = T2>,
+    T2: Copy + Into<Complex<f64>>,
+{
+    /// Returns L2 norm $\sqrt{\int v^2 dV}$ over the (scaled) domain,
+    /// evaluated from the spectral coefficients.
+    ///
+    /// # Example
+    ///```
+    /// use rustpde::{chebyshev, Field2, Space2};
+    /// let space = Space2::new(&chebyshev(8), &chebyshev(6));
+    /// let mut field = Field2::new(&space);
+    /// field.v.fill(2.);
+    /// field.forward();
+    /// assert!((field.l2_norm() - 4.).abs() < 1e-10);
+    ///```
+    pub fn l2_norm(&self) -> f64 {
+        let bases = self.space.base_all();
+        let to_complex = |g: &Array2<f64>| g.mapv(|g| Complex::new(g, 0.));
+        let gx = to_complex(&self.gram_matrix(&bases[0], 0));
+        let gy = to_complex(&self.gram_matrix(&bases[1], 1));
+        let vhat = self.vhat.mapv(|v| v.into());
+        let gv = gx.dot(&vhat).dot(&gy);
+        let norm2: f64 = vhat
+            .iter()
+            .zip(gv.iter())
+            .map(|(v, g)| (v.conj() * g).re)
+            .sum();
+        norm2.max(0.).sqrt()
+    }
+
+    /// Returns mean square $\langle v^2 \rangle_V$ over the
+    /// (scaled) domain, see [`FieldBase::l2_norm`].
+    pub fn mean_square(&self) -> f64 {
+        let bases = self.space.base_all();
+        let volume = self.domain_length(&bases[0], 0) * self.domain_length(&bases[1], 1);
+        self.l2_norm().powi(2) / volume
+    }
+
+    /// Returns energy $\frac{1}{2} \langle v^2 \rangle_V$, i.e. the
+    /// contribution of a velocity component to the kinetic energy.
+    pub fn kinetic_energy(&self) -> f64 {
+        0.5 * self.mean_square()
+    }
+
+    /// Returns energy spectrum $E(k)$, indexed by shell $k$.
+    ///
+    /// The modal energies $\frac{1}{2} w |\hat{v}|^2$ of the orthogonal
+    /// coefficients are binned by the total wavenumber
+    /// $\sqrt{k_x^2 + k_y^2}$, rounded to the nearest integer. Along
+    /// fourier axes, $k$ is the wavenumber on the standard interval
+    /// $[0, 2\pi)$, along chebyshev axes the polynomial degree.
+    ///
+    /// Normalization: Along fourier axes, the weight $w$ is the
+    /// discrete Parseval weight, such that the modal energies sum up
+    /// to $\frac{1}{2} \langle v^2 \rangle$. For `fourier_r2c`, it
+    /// includes the folded negative wavenumbers. Along sine and cosine
+    /// axes, $w = 1/2$ (cosine mean: $w = 1$). Chebyshev coefficients
+    /// are not weighted.
+    pub fn energy_spectrum(&self) -> Array1<f64> {
+        let bases = self.space.base_all();
+        let ortho = self.to_ortho();
+        let (kx, wx) = self.modal_weights(&bases[0], 0, ortho.shape()[0]);
+        let (ky, wy) = self.modal_weights(&bases[1], 1, ortho.shape()[1]);
+        let shell = |i: usize, j: usize| (kx[i] * kx[i] + ky[j] * ky[j]).sqrt().round() as usize;
+        let n_shells = ortho
+            .indexed_iter()
+            .map(|((i, j), _)| shell(i, j) + 1)
+            .max()
+            .unwrap_or(0);
+        let mut spectrum = Array1::<f64>::zeros(n_shells);
+        for ((i, j), v) in ortho.indexed_iter() {
+            let v: Complex<f64> = (*v).into();
+            spectrum[shell(i, j)] += 0.5 * wx[i] * wy[j] * v.norm_sqr();
+        }
+        spectrum
+    }
+
+    /// Returns energy spectrum resolved along both axes, i.e. the
+    /// modal energies $\frac{1}{2} w |\hat{v}|^2$ of the orthogonal
+    /// coefficients, indexed by mode (row: axis 0, column: axis 1).
+    ///
+    /// For a fourier (x) x chebyshev (y) channel, this is the energy
+    /// over the horizontal wavenumber and the chebyshev degree, which
+    /// reveals anisotropy near the walls. The weights $w$ are those of
+    /// [`FieldBase::energy_spectrum`], the shell spectrum is the sum
+    /// over the entries of equal total wavenumber.
+    pub fn energy_spectrum_2d(&self) -> Array2<f64> {
+        let bases = self.space.base_all();
+        let ortho = self.to_ortho();
+        let (_, wx) = self.modal_weights(&bases[0], 0, ortho.shape()[0]);
+        let (_, wy) = self.modal_weights(&bases[1], 1, ortho.shape()[1]);
+        Array2::from_shape_fn(ortho.raw_dim(), |(i, j)| {
+            let v: Complex<f64> = ortho[[i, j]].into();
+            0.5 * wx[i] * wy[j] * v.norm_sqr()
+        })
+    }
+
+    /// Magnitude of wavenumbers and weights of the orthogonal
+    /// modes along axis, see [`FieldBase::energy_spectrum`]
+    fn modal_weights(
+        &self,
+        base: &BaseAll<f64>,
+        axis: usize,
+        m: usize,
+    ) -> (Array1<f64>, Array1<f64>) {
+        match base {
+            BaseAll::BaseR2r(BaseR2r::Chebyshev(_))
+            | BaseAll::BaseR2r(BaseR2r::CompositeChebyshev(_)) => {
+                (Array1::from_shape_fn(m, |i| i as f64), Array1::ones(m))
+            }
+            _ => (
+                self.wavenumbers(axis).mapv(f64::abs),
+                self.gram_matrix(base, axis).diag().to_owned() / self.domain_length(base, axis),
+            ),
+        }
+    }
+
+    /// Gram matrix of the basis functions along axis,
+    /// integrated over the (scaled) domain
+    fn gram_matrix(&self, base: &BaseAll<f64>, axis: usize) -> Array2<f64> {
+        let (n, m) = (base.len_phys(), base.len_spec());
+        let length = self.domain_length(base, axis);
+        match base {
+            BaseAll::BaseR2r(b @ BaseR2r::Chebyshev(_))
+            | BaseAll::BaseR2r(b @ BaseR2r::CompositeChebyshev(_)) => {
+                // Classical chebyshev coefficients of the basis functions
+                let phi = b.clone().backward(&Array2::<f64>::eye(m), 0);
+                let coef = chebyshev::<f64>(n).forward(&phi, 0);
+                // int T_i dx over [-1, 1]
+                let int = |i: usize| {
+                    if i % 2 == 0 {
+                        2. / (1. - (i * i) as f64)
+                    } else {
+                        0.
+                    }
+                };
+                let gram = Array2::from_shape_fn((n, n), |(j, k)| {
+                    0.5 * (int(j + k) + int(j.max(k) - j.min(k)))
+                });
+                coef.t().dot(&gram).dot(&coef) * (length / 2.)
+            }
+            // int sin^2(kx) dx = int cos^2(kx) dx = pi/2 over [0, pi] (k > 0)
+            BaseAll::BaseR2r(BaseR2r::FourierSine(_)) => {
+                Array2::from_diag(&Array1::from_elem(m, 0.5)) * length
+            }
+            BaseAll::BaseR2r(BaseR2r::FourierCosine(b)) => {
+                let weights = b.k.mapv(|k| if k == 0. { 1. } else { 0.5 });
+                Array2::from_diag(&weights) * length
+            }
+            BaseAll::BaseR2c(b) => {
+                let phi = b.clone().backward(&Array2::<Complex<f64>>::eye(m), 0);
+                let weights = phi.mapv(|p| p * p).sum_axis(Axis(0));
+                Array2::from_diag(&weights) * (length / n as f64)
+            }
+            BaseAll::BaseC2c(b) => {
+                let phi = b.clone().backward(&Array2::<Complex<f64>>::eye(m), 0);
+                let weights = phi.mapv(|p| p.norm_sqr()).sum_axis(Axis(0));
+                Array2::from_diag(&weights) * (length / n as f64)
+            }
+        }
+    }
+
+    /// Length of the (scaled) domain along axis
+    fn domain_length(&self, base: &BaseAll<f64>, axis: usize) -> f64 {
+        let x = &self.x[axis];
+        match base {
+            BaseAll::BaseR2r(_) => (x[x.len() - 1] - x[0]).abs(),
+            BaseAll::BaseR2c(_) | BaseAll::BaseC2c(_) => (x[1] - x[0]).abs() * x.len() as f64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bases::{fourier_cosine, fourier_sine};
+    use crate::{cheb_dirichlet, chebyshev, fourier_r2c, Field2, Space2};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_l2_norm_chebyshev() {
+        // f = (1 - x^2) y, int f^2 = 16/15 * 2/3
+        let space = Space2::new(&cheb_dirichlet(10), &chebyshev(7));
+        let mut field = Field2::new(&space);
+        let x = field.x[0].to_owned();
+        let y = field.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                field.v[[i, j]] = (1. - xi * xi) * yi;
+            }
+        }
+        field.forward();
+        let expected = 32. / 45.;
+        assert!((field.l2_norm().powi(2) - expected).abs() < 1e-10);
+        // Scaled domain
+        field.x[0] *= 2.;
+        assert!((field.l2_norm().powi(2) - 2. * expected).abs() < 1e-10);
+        assert!((field.kinetic_energy() - expected / 8.).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_l2_norm_fourier() {
+        // f = (1 + cos(2x) + sin(3x)) (1 - y^2), int f^2 = 4pi * 16/15
+        let space = Space2::new(&fourier_r2c(16), &cheb_dirichlet(9));
+        let mut field = Field2::new(&space);
+        let x = field.x[0].to_owned();
+        let y = field.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                field.v[[i, j]] = (1. + (2. * xi).cos() + (3. * xi).sin()) * (1. - yi * yi);
+            }
+        }
+        field.forward();
+        assert!((field.l2_norm().powi(2) - 64. * PI / 15.).abs() < 1e-10);
+        assert!((field.kinetic_energy() - 8. / 15.).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_l2_norm_sine_cosine() {
+        // f = sin(2x) (1 - y^2), int f^2 = pi/2 * 16/15
+        let space = Space2::new(&fourier_sine(12), &cheb_dirichlet(9));
+        let mut field = Field2::new(&space);
+        let x = field.x[0].to_owned();
+        let y = field.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                field.v[[i, j]] = (2. * xi).sin() * (1. - yi * yi);
+            }
+        }
+        field.forward();
+        assert!((field.l2_norm().powi(2) - 8. * PI / 15.).abs() < 1e-10);
+        // f = (1 + cos(3x)) y, int f^2 = 3pi/2 * 2/3
+        let space = Space2::new(&fourier_cosine(12), &chebyshev(7));
+        let mut field = Field2::new(&space);
+        let x = field.x[0].to_owned();
+        let y = field.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                field.v[[i, j]] = (1. + (3. * xi).cos()) * yi;
+            }
+        }
+        field.forward();
+        assert!((field.l2_norm().powi(2) - PI).abs() < 1e-10);
+        let spectrum = field.energy_spectrum_2d();
+        assert!((spectrum[[3, 1]] - 0.25).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_energy_spectrum_single_mode() {
+        // f = cos(3x) T_2(y), k = sqrt(3^2 + 2^2) -> shell 4
+        let space = Space2::new(&fourier_r2c(16), &chebyshev(9));
+        let mut field = Field2::new(&space);
+        let x = field.x[0].to_owned();
+        let y = field.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                field.v[[i, j]] = (3. * xi).cos() * (2. * yi * yi - 1.);
+            }
+        }
+        field.forward();
+        let spectrum = field.energy_spectrum();
+        for (k, e) in spectrum.iter().enumerate() {
+            let expected = if k == 4 { 0.25 } else { 0. };
+            assert!((e - expected).abs() < 1e-10, "shell {}: {}", k, e);
+        }
+    }
+
+    #[test]
+    fn test_energy_spectrum_2d_single_mode() {
+        // f = sin(2x) T_3(y)
+        let space = Space2::new(&fourier_r2c(16), &chebyshev(9));
+        let mut field = Field2::new(&space);
+        let x = field.x[0].to_owned();
+        let y = field.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                field.v[[i, j]] = (2. * xi).sin() * (4. * yi.powi(3) - 3. * yi);
+            }
+        }
+        field.forward();
+        let spectrum = field.energy_spectrum_2d();
+        assert_eq!(spectrum.shape(), &[9, 9]);
+        for ((i, j), e) in spectrum.indexed_iter() {
+            let expected = if (i, j) == (2, 3) { 0.25 } else { 0. };
+            assert!((e - expected).abs() < 1e-10, "mode ({}, {}): {}", i, j, e);
+        }
+        // Consistent with shell spectrum
+        assert!((spectrum.sum() - field.energy_spectrum().sum()).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,159 @@
+//! Exchange the physical field with python via numpy `.npy` files
+//! (format version 1.0, little-endian f64, C order), e.g.
+//! ```python
+//! import numpy as np
+//! v = np.load("temp.npy")
+//! ```
+use super::{BaseSpace, FieldBase};
+use ndarray::Array2;
+use std::io::{Error, ErrorKind, Read, Write};
+
+/// Magic string of npy files
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+
+impl<T2, S> FieldBase<f64, f64, T2, S, 2>
+where
+    S: BaseSpace<f64, 2, Physical = f64, Spectral = T2>,
+{
+    /// Write physical field (evaluated from the spectral
+    /// coefficients) to `filename` in numpy `.npy` format.
+    ///
+    /// # Errors
+    /// Errors if the file can not be written.
+    pub fn write_npy(&self, filename: &str) -> std::io::Result<()> {
+        let v = self.to_physical();
+        let shape = v.shape();
+        let mut header = format!(
+            "{{'descr': '<f8', 'fortran_order': False, 'shape': ({}, {}), }}",
+            shape[0], shape[1]
+        );
+        // Pad with spaces and terminate by newline, such that the
+        // data starts at a multiple of 64 bytes
+        let preamble = NPY_MAGIC.len() + 4;
+        let len = preamble + header.len() + 1;
+        header.push_str(&" ".repeat((64 - len % 64) % 64));
+        header.push('\n');
+        let mut file = std::io::BufWriter::new(std::fs::File::create(filename)?);
+        file.write_all(NPY_MAGIC)?;
+        file.write_all(&[1, 0])?;
+        file.write_all(&(header.len() as u16).to_le_bytes())?;
+        file.write_all(header.as_bytes())?;
+        for x in v.iter() {
+            file.write_all(&x.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Read physical field from numpy `.npy` file (f64, little-endian),
+    /// and transform it to spectral space.
+    ///
+    /// # Errors
+    /// Errors if the file can not be read, if it does not hold
+    /// little-endian f64 data, or if its shape differs from
+    /// the shape of the field.
+    pub fn read_npy(&mut self, filename: &str) -> std::io::Result<()> {
+        let invalid = |msg: String| Error::new(ErrorKind::InvalidData, msg);
+        let mut bytes = Vec::new();
+        std::fs::File::open(filename)?.read_to_end(&mut bytes)?;
+        if bytes.len() < 10 || &bytes[..6] != NPY_MAGIC {
+            return Err(invalid(format!("{} is not a npy file", filename)));
+        }
+        // Header length: u16 (version 1), u32 (version 2, 3)
+        let (start, header_len) = match bytes[6] {
+            1 => (10, u16::from_le_bytes([bytes[8], bytes[9]]) as usize),
+            2 | 3 if bytes.len() >= 12 => (
+                12,
+                u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize,
+            ),
+            _ => return Err(invalid(format!("unsupported npy version {}", bytes[6]))),
+        };
+        let header = bytes
+            .get(start..start + header_len)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .ok_or_else(|| invalid("invalid npy header".to_string()))?;
+        if !header.contains("'descr': '<f8'") {
+            return Err(invalid(format!("expected '<f8' data, header: {}", header)));
+        }
+        let fortran_order = header.contains("'fortran_order': True");
+        let shape =
+            parse_shape(header).ok_or_else(|| invalid(format!("no shape in {}", header)))?;
+        if shape != self.v.shape() {
+            return Err(invalid(format!(
+                "shape {:?} in {} does not match field shape {:?}",
+                shape,
+                filename,
+                self.v.shape()
+            )));
+        }
+        let data = &bytes[start + header_len..];
+        if data.len() != 8 * shape.iter().product::<usize>() {
+            return Err(invalid(format!("{} is truncated", filename)));
+        }
+        let values: Vec<f64> = data
+            .chunks_exact(8)
+            .map(|c| f64::from_le_bytes([c[0], c[1], c[2], c[3], c[4], c[5], c[6], c[7]]))
+            .collect();
+        let v = if fortran_order {
+            Array2::from_shape_vec((shape[1], shape[0]), values).map(|v| v.reversed_axes())
+        } else {
+            Array2::from_shape_vec((shape[0], shape[1]), values)
+        }
+        .map_err(|e| invalid(e.to_string()))?;
+        self.v.assign(&v);
+        self.forward();
+        Ok(())
+    }
+}
+
+/// Parse shape tuple, e.g. "'shape': (3, 4), " of npy header
+fn parse_shape(header: &str) -> Option<Vec<usize>> {
+    let start = header.find("'shape':")?;
+    let rest = &header[start..];
+    let tuple = &rest[rest.find('(')? + 1..rest.find(')')?];
+    tuple
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{cheb_dirichlet, chebyshev, Field2, Space2};
+
+    #[test]
+    fn test_npy_roundtrip() {
+        let space = Space2::new(&chebyshev(6), &cheb_dirichlet(5));
+        let mut field = Field2::new(&space);
+        let (x, y) = (field.x[0].to_owned(), field.x[1].to_owned());
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                field.v[[i, j]] = xi * xi * (1. - yi * yi);
+            }
+        }
+        field.forward();
+        let filename = std::env::temp_dir().join("rustpde_test_npy_roundtrip.npy");
+        let filename = filename.to_str().unwrap();
+        field.write_npy(filename).unwrap();
+        // Header
+        let bytes = std::fs::read(filename).unwrap();
+        let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+        let header = std::str::from_utf8(&bytes[10..10 + header_len]).unwrap();
+        assert!(header.contains("'shape': (6, 5)"));
+        assert!(header.ends_with('\n'));
+        assert_eq!((10 + header_len) % 64, 0);
+        assert_eq!(bytes.len(), 10 + header_len + 8 * 6 * 5);
+        // Read back
+        let mut other = Field2::new(&space);
+        other.read_npy(filename).unwrap();
+        for (a, b) in other.vhat.iter().zip(field.vhat.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        // Shape mismatch
+        let mut wrong = Field2::new(&Space2::new(&chebyshev(5), &cheb_dirichlet(6)));
+        let err = wrong.read_npy(filename).unwrap_err();
+        assert!(err.to_string().contains("does not match field shape"));
+        std::fs::remove_file(filename).unwrap();
+    }
+}
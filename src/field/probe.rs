@@ -0,0 +1,210 @@
+//! Evaluate fields at arbitrary points, directly from
+//! the spectral coefficients (spectral interpolation).
+//!
+//! Chebyshev: $f(x) = \sum_k c_k T_k(x)$, with $T_k(x) = \cos(k \arccos x)$.
+//!
+//! Fourier (r2c): $f(x) = \frac{1}{n} \sum_k w_k Re(c_k e^{ikx})$, with
+//! $w_k = 2$, except for the mean and the nyquist mode ($w_k = 1$).
+//!
+//! Sine / cosine: $f(x) = \sum_k c_k \sin(kx)$, resp. $\sum_k c_k \cos(kx)$.
+use super::{BaseSpace, FieldBase};
+use crate::bases::{BaseAll, BaseR2r, Basics};
+use ndarray::prelude::*;
+use num_complex::Complex;
+
+impl<T2, S> FieldBase<f64, f64, T2, S, 2>
+where
+    S: BaseSpace<f64, 2, Physical = f64, Spectral = T2>,
+    T2: Copy + Into<Complex<f64>>,
+{
+    /// Returns field value at point (x, y) (physical space).
+    ///
+    /// The coordinates refer to the same frame as `self.x`,
+    /// i.e. they include any scaling of the grid.
+    ///
+    /// # Panics
+    /// Panics for complex-to-complex bases.
+    ///
+    /// # Example
+    ///```
+    /// use rustpde::{chebyshev, Field2, Space2};
+    /// let space = Space2::new(&chebyshev(8), &chebyshev(6));
+    /// let mut field = Field2::new(&space);
+    /// field.v.fill(2.);
+    /// field.forward();
+    /// assert!((field.probe(0.3, -0.2) - 2.).abs() < 1e-10);
+    ///```
+    pub fn probe(&self, x: f64, y: f64) -> f64 {
+        self.probe_points(&[[x, y]])[0]
+    }
+
+    /// Returns field values at several points (physical space).
+    /// See [`FieldBase::probe`].
+    pub fn probe_points(&self, points: &[[f64; 2]]) -> Array1<f64> {
+        let ortho = self.to_ortho();
+        let bases = self.space.base_all();
+        Array1::from_shape_fn(points.len(), |p| {
+            let w0 = self.probe_weights(&bases[0], 0, points[p][0]);
+            let w1 = self.probe_weights(&bases[1], 1, points[p][1]);
+            let mut value = Complex::new(0., 0.);
+            for ((i, j), c) in ortho.indexed_iter() {
+                value += (*c).into() * w0[i] * w1[j];
+            }
+            value.re
+        })
+    }
+
+    /// Returns field values at several points (physical space),
+    /// like [`FieldBase::probe_points`].
+    ///
+    /// Points outside of the domain along non-periodic axes are
+    /// clamped to its boundary, and a warning is printed.
+    ///
+    /// # Example
+    ///```
+    /// use rustpde::{chebyshev, Field2, Space2};
+    /// let space = Space2::new(&chebyshev(8), &chebyshev(6));
+    /// let mut field = Field2::new(&space);
+    /// field.v.fill(2.);
+    /// field.forward();
+    /// let values = field.interpolate(&[[0.3, -0.2], [1.1, 0.]]);
+    /// assert!((values[1] - 2.).abs() < 1e-10);
+    ///```
+    pub fn interpolate(&self, points: &[[f64; 2]]) -> Vec<f64> {
+        let bases = self.space.base_all();
+        let mut clamped = points.to_vec();
+        for point in &mut clamped {
+            for (axis, base) in bases.iter().enumerate() {
+                if let BaseAll::BaseR2r(_) = base {
+                    let x_grid = &self.x[axis];
+                    let (a, b) = (x_grid[0], x_grid[x_grid.len() - 1]);
+                    let (lo, hi) = (a.min(b), a.max(b));
+                    if point[axis] < lo || point[axis] > hi {
+                        println!(
+                            "Point {:?} outside of domain along axis {}, clamp it.",
+                            point, axis
+                        );
+                        point[axis] = point[axis].max(lo).min(hi);
+                    }
+                }
+            }
+        }
+        self.probe_points(&clamped).to_vec()
+    }
+
+    /// Weights of (orthogonal) coefficients along axis, such that
+    /// the field value is the weighted sum of its coefficients
+    fn probe_weights(&self, base: &BaseAll<f64>, axis: usize, x: f64) -> Array1<Complex<f64>> {
+        // Map to coordinates of the base
+        let (x_grid, x_base) = (&self.x[axis], base.coords());
+        let (n_grid, n_base) = (x_grid.len() - 1, x_base.len() - 1);
+        let xs = x_base[0]
+            + (x - x_grid[0]) * (x_base[n_base] - x_base[0]) / (x_grid[n_grid] - x_grid[0]);
+        let n = base.len_phys();
+        match base {
+            BaseAll::BaseR2r(BaseR2r::Chebyshev(_))
+            | BaseAll::BaseR2r(BaseR2r::CompositeChebyshev(_)) => {
+                let theta = xs.max(-1.).min(1.).acos();
+                Array1::from_shape_fn(n, |k| Complex::new((k as f64 * theta).cos(), 0.))
+            }
+            BaseAll::BaseR2r(BaseR2r::FourierSine(b)) => {
+                b.k.mapv(|k| Complex::new((k * xs).sin(), 0.))
+            }
+            BaseAll::BaseR2r(BaseR2r::FourierCosine(b)) => {
+                b.k.mapv(|k| Complex::new((k * xs).cos(), 0.))
+            }
+            BaseAll::BaseR2c(_) => Array1::from_shape_fn(n / 2 + 1, |k| {
+                let w = if k == 0 || 2 * k == n { 1. } else { 2. };
+                Complex::from_polar(w / n as f64, k as f64 * xs)
+            }),
+            BaseAll::BaseC2c(_) => panic!("Probes not supported for c2c base along {}", axis),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bases::fourier_sine;
+    use crate::{cheb_dirichlet, chebyshev, fourier_r2c, Field2, Space2};
+
+    #[test]
+    fn test_probe_chebyshev() {
+        // f(x,y) = x^3 + y^2
+        let space = Space2::new(&chebyshev(10), &cheb_dirichlet(8));
+        let mut field = Field2::new(&space);
+        let x = field.x[0].to_owned();
+        let y = field.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                field.v[[i, j]] = (xi.powi(3) + 1.) * (1. - yi * yi);
+            }
+        }
+        field.forward();
+        for (xp, yp) in [(0.3, -0.7), (-0.95, 0.1), (1., 0.5)].iter() {
+            let expected = (xp.powi(3) + 1.) * (1. - yp * yp);
+            assert!((field.probe(*xp, *yp) - expected).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_probe_sine() {
+        // f(x,y) = sin(2x) (1 - y^2), x in [0, pi]
+        let space = Space2::new(&fourier_sine(12), &cheb_dirichlet(8));
+        let mut field = Field2::new(&space);
+        let x = field.x[0].to_owned();
+        let y = field.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                field.v[[i, j]] = (2. * xi).sin() * (1. - yi * yi);
+            }
+        }
+        field.forward();
+        for (xp, yp) in [(0.3, -0.7), (2.5, 0.1), (1., 0.5)].iter() {
+            let expected = (2. * xp).sin() * (1. - yp * yp);
+            assert!((field.probe(*xp, *yp) - expected).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_interpolate() {
+        // f(x,y) = x^2 y - y^3
+        let space = Space2::new(&chebyshev(7), &chebyshev(9));
+        let mut field = Field2::new(&space);
+        let x = field.x[0].to_owned();
+        let y = field.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                field.v[[i, j]] = xi * xi * yi - yi.powi(3);
+            }
+        }
+        field.forward();
+        let f = |x: f64, y: f64| x * x * y - y.powi(3);
+        let values = field.interpolate(&[[0.13, -0.61], [-0.77, 0.42], [1. + 1e-9, 0.5]]);
+        assert!((values[0] - f(0.13, -0.61)).abs() < 1e-10);
+        assert!((values[1] - f(-0.77, 0.42)).abs() < 1e-10);
+        // Clamped to boundary
+        assert!((values[2] - f(1., 0.5)).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_probe_fourier() {
+        // f(x,y) = sin(2x) + cos(x) y
+        let space = Space2::new(&fourier_r2c(16), &chebyshev(8));
+        let mut field = Field2::new(&space);
+        let x = field.x[0].to_owned();
+        let y = field.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                field.v[[i, j]] = (2. * xi).sin() + xi.cos() * yi;
+            }
+        }
+        field.forward();
+        // Scaled grid
+        field.x[0] *= 2.;
+        for (xp, yp) in [(0.7, -0.3), (5.1, 0.9), (11., 0.)].iter() {
+            let xs = xp / 2.;
+            let expected = (2. * xs).sin() + xs.cos() * yp;
+            assert!((field.probe(*xp, *yp) - expected).abs() < 1e-10);
+        }
+    }
+}
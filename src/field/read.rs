@@ -109,6 +109,62 @@ where
     }
 }
 
+impl<A, S> ReadField for FieldBase<A, A, A, S, 3>
+where
+    A: FloatNum + H5Type,
+    Complex<A>: ScalarOperand,
+    S: BaseSpace<A, 3, Physical = A, Spectral = A>,
+{
+    fn read(&mut self, filename: &str, group: Option<&str>) {
+        let result = read_from_hdf5::<A, Ix3>(filename, "vhat", group);
+        match result {
+            Ok(x) => {
+                if x.shape() == self.vhat.shape() {
+                    self.vhat.assign(&x);
+                } else {
+                    println!(
+                        "Attention! Broadcast from shape {:?} to shape {:?}.",
+                        x.shape(),
+                        self.vhat.shape()
+                    );
+                    broadcast_3d(&x, &mut self.vhat);
+                }
+                self.backward();
+                println!("Reading file {:?} was successfull.", filename);
+            }
+            Err(_) => println!("Error while reading file {:?}.", filename),
+        }
+    }
+}
+
+impl<A, S> ReadField for FieldBase<A, A, Complex<A>, S, 3>
+where
+    A: FloatNum + H5Type,
+    Complex<A>: ScalarOperand,
+    S: BaseSpace<A, 3, Physical = A, Spectral = Complex<A>>,
+{
+    fn read(&mut self, filename: &str, group: Option<&str>) {
+        let result = read_from_hdf5_complex::<A, Ix3>(filename, "vhat", group);
+        match result {
+            Ok(x) => {
+                if x.shape() == self.vhat.shape() {
+                    self.vhat.assign(&x);
+                } else {
+                    println!(
+                        "Attention! Broadcast from shape {:?} to shape {:?}.",
+                        x.shape(),
+                        self.vhat.shape()
+                    );
+                    broadcast_3d(&x, &mut self.vhat);
+                }
+                self.backward();
+                println!("Reading file {:?} was successfull.", filename);
+            }
+            Err(_) => println!("Error while reading file {:?}.", filename),
+        }
+    }
+}
+
 /// Broadcast 2d array
 fn broadcast_2d<T: Clone>(old: &Array2<T>, new: &mut Array2<T>) {
     let sh: Vec<usize> = old
@@ -120,3 +176,15 @@ fn broadcast_2d<T: Clone>(old: &Array2<T>, new: &mut Array2<T>) {
     new.slice_mut(s![..sh[0], ..sh[1]])
         .assign(&old.slice(s![..sh[0], ..sh[1]]));
 }
+
+/// Broadcast 3d array
+fn broadcast_3d<T: Clone>(old: &Array3<T>, new: &mut Array3<T>) {
+    let sh: Vec<usize> = old
+        .shape()
+        .iter()
+        .zip(new.shape().iter())
+        .map(|(i, j)| *std::cmp::min(i, j))
+        .collect();
+    new.slice_mut(s![..sh[0], ..sh[1], ..sh[2]])
+        .assign(&old.slice(s![..sh[0], ..sh[1], ..sh[2]]));
+}
@@ -0,0 +1,717 @@
+//! Operations which act directly on the spectral coefficients
+use super::{BaseSpace, FieldBase};
+use crate::bases::{chebyshev, fourier_cosine, fourier_r2c, fourier_sine, Basics, Transform};
+use crate::bases::{BaseAll, BaseC2c, BaseR2c, BaseR2r};
+use crate::types::FloatNum;
+use ndarray::prelude::*;
+use ndarray::{Slice, Zip};
+use num_complex::Complex;
+use num_traits::Zero;
+use std::ops::Mul;
+
+impl<A, T1, T2, S, const N: usize> FieldBase<A, T1, T2, S, N>
+where
+    A: FloatNum,
+    S: BaseSpace<A, N, Physical = T1, Spectral = T2>,
+{
+    /// Return mode indices along axis, i.e. the position
+    /// of each coefficient in the spectral array.
+    pub fn mode_indices(&self, axis: usize) -> Array1<usize> {
+        Array1::from_iter(0..self.vhat.shape()[axis])
+    }
+
+    /// Return wavenumber (fourier) or polynomial degree (chebyshev)
+    /// of each spectral coefficient along axis.
+    ///
+    /// Fourier wavenumbers are given for the standard
+    /// interval [0, 2pi[, i.e. without domain scaling.
+    pub fn wavenumbers(&self, axis: usize) -> Array1<A> {
+        let x = &self.space.base_all()[axis];
+        match x {
+            BaseAll::BaseR2r(ref b) => match b {
+                BaseR2r::Chebyshev(_) | BaseR2r::CompositeChebyshev(_) => {
+                    self.mode_indices(axis).mapv(|i| A::from_usize(i).unwrap())
+                }
+                BaseR2r::FourierSine(ref f) => f.k.to_owned(),
+                BaseR2r::FourierCosine(ref f) => f.k.to_owned(),
+            },
+            BaseAll::BaseR2c(ref b) => match b {
+                BaseR2c::FourierR2c(ref f) => f.k.mapv(|k| k.im),
+            },
+            BaseAll::BaseC2c(ref b) => match b {
+                BaseC2c::FourierC2c(ref f) => f.k.mapv(|k| k.im),
+            },
+        }
+    }
+}
+
+impl<A, T1, T2, S> FieldBase<A, T1, T2, S, 2>
+where
+    A: FloatNum,
+    T2: Copy + Mul<A, Output = T2>,
+    S: BaseSpace<A, 2, Physical = T1, Spectral = T2>,
+{
+    /// Multiply each spectral coefficient by a user supplied
+    /// multiplier `f(mode_index, wavenumbers)`.
+    ///
+    /// Wavenumbers are the fourier wavenumbers, respectively the
+    /// chebyshev degrees, along each axis (see `wavenumbers`).
+    ///
+    /// # Example
+    /// Low-pass filter
+    ///```
+    /// use rustpde::{chebyshev, Field2, Space2};
+    /// let space = Space2::new(&chebyshev(8), &chebyshev(8));
+    /// let mut field = Field2::new(&space);
+    /// field.vhat.fill(1.);
+    /// field.apply_spectral(|_, k| if k[0] > 3. || k[1] > 3. { 0. } else { 1. });
+    /// assert!(field.vhat[[4, 0]] == 0.);
+    /// assert!(field.vhat[[3, 3]] == 1.);
+    ///```
+    pub fn apply_spectral<F: Fn([usize; 2], &[A; 2]) -> A>(&mut self, f: F) {
+        let k0 = self.wavenumbers(0);
+        let k1 = self.wavenumbers(1);
+        for ((i, j), v) in self.vhat.indexed_iter_mut() {
+            *v = *v * f([i, j], &[k0[i], k1[j]]);
+        }
+    }
+}
+
+impl<T1, S> FieldBase<f64, T1, Complex<f64>, S, 2>
+where
+    S: BaseSpace<f64, 2, Physical = T1, Spectral = Complex<f64>>,
+{
+    /// Translate field by `delta_x` along the first (periodic) axis,
+    /// i.e. f(x) -> f(x - `delta_x`), by multiplying the spectral
+    /// coefficients with $e^{-ik\delta}$.
+    ///
+    /// The shift is exact (no interpolation error) for any `delta_x`,
+    /// which refers to the same frame as `self.x`. The nyquist mode of
+    /// real-to-complex bases cannot be shifted by a sub-grid distance.
+    /// Only `vhat` is updated.
+    ///
+    /// # Panics
+    /// Panics if the first axis is not of fourier type.
+    pub fn shift_periodic(&mut self, delta_x: f64) {
+        match self.space.base_all()[0] {
+            BaseAll::BaseR2c(_) | BaseAll::BaseC2c(_) => (),
+            BaseAll::BaseR2r(_) => panic!("shift_periodic requires fourier base along axis 0"),
+        }
+        // Map to coordinates of the base
+        let (x_grid, x_base) = (&self.x[0], self.space.base_all()[0].coords().clone());
+        let last = x_grid.len() - 1;
+        let delta = delta_x * (x_base[last] - x_base[0]) / (x_grid[last] - x_grid[0]);
+        let k = self.wavenumbers(0);
+        for (mut lane, ki) in self.vhat.outer_iter_mut().zip(k.iter()) {
+            lane *= Complex::from_polar(1., -ki * delta);
+        }
+    }
+}
+
+/// Relative amplitude below which coefficients are regarded as
+/// round-off, i.e. the field is resolved to machine precision
+const ROUNDOFF: f64 = 1e-13;
+
+macro_rules! impl_truncation_error {
+    ($s: ty, $abs: expr) => {
+        impl<S> FieldBase<f64, f64, $s, S, 2>
+        where
+            S: BaseSpace<f64, 2, Physical = f64, Spectral = $s>,
+        {
+            /// A posteriori estimate of the truncation error.
+            ///
+            /// Along each axis, the envelope of the (orthogonal) spectral
+            /// coefficients $a_m$ is fitted to an exponential decay
+            /// $a_m \approx e^{\alpha - \beta m}$ over the upper half of the
+            /// resolved modes. The neglected modes $m \geq M$ are
+            /// extrapolated and summed
+            /// $$
+            /// \epsilon = \frac{e^{\alpha - \beta M}}{1 - e^{-\beta}},
+            /// $$
+            /// which bounds the maximum pointwise error if the decay
+            /// continues. The estimates of both axes are added.
+            ///
+            /// Returns infinity if the coefficients do not decay, i.e.
+            /// the field is not resolved.
+            pub fn truncation_error_estimate(&self) -> f64 {
+                let ortho = self.to_ortho();
+                let abs = $abs;
+                let mut error = 0.;
+                for axis in 0..2 {
+                    // Envelope over the other axis, grouped by |wavenumber|
+                    // (composite bases have more orthogonal than galerkin coefficients)
+                    let k: Array1<usize> = match self.space.base_all()[axis] {
+                        BaseAll::BaseR2r(BaseR2r::Chebyshev(_))
+                        | BaseAll::BaseR2r(BaseR2r::CompositeChebyshev(_)) => {
+                            Array1::from_iter(0..ortho.shape()[axis])
+                        }
+                        _ => self.wavenumbers(axis).mapv(|k| k.abs().round() as usize),
+                    };
+                    let mut envelope = vec![0.; k.iter().max().map_or(0, |m| m + 1)];
+                    for (lane_k, lane) in k.iter().zip(ortho.axis_iter(Axis(axis))) {
+                        for v in lane.iter() {
+                            envelope[*lane_k] = f64::max(envelope[*lane_k], abs(v));
+                        }
+                    }
+                    error += tail_estimate(&envelope);
+                }
+                error
+            }
+        }
+    };
+}
+
+impl_truncation_error!(f64, |v: &f64| v.abs());
+impl_truncation_error!(Complex<f64>, |v: &Complex<f64>| v.norm());
+
+/// Extrapolate truncation error from the decay of the
+/// coefficient amplitudes (see `truncation_error_estimate`)
+fn tail_estimate(amplitudes: &[f64]) -> f64 {
+    let a_max = amplitudes.iter().cloned().fold(0., f64::max);
+    if a_max == 0. {
+        return 0.;
+    }
+    // Strip exact zeros at the end (dealiased modes)
+    let n = amplitudes.iter().rposition(|a| *a != 0.).unwrap() + 1;
+    // Decayed to round-off within resolved modes
+    let threshold = ROUNDOFF * a_max;
+    let m_end = amplitudes[..n]
+        .iter()
+        .rposition(|a| *a > threshold)
+        .unwrap()
+        + 1;
+    if m_end < n {
+        return threshold;
+    }
+    // Least squares fit of log(a_m) = alpha - beta * m on upper half
+    let start = (n / 2).min(n.saturating_sub(3));
+    let (m, loga): (Vec<f64>, Vec<f64>) = (start..n)
+        .filter(|i| amplitudes[*i] > 0.)
+        .map(|i| (i as f64, amplitudes[i].ln()))
+        .unzip();
+    if m.len() < 2 {
+        return f64::INFINITY;
+    }
+    let len = m.len() as f64;
+    let m_mean = m.iter().sum::<f64>() / len;
+    let loga_mean = loga.iter().sum::<f64>() / len;
+    let cov: f64 = m
+        .iter()
+        .zip(loga.iter())
+        .map(|(x, y)| (x - m_mean) * (y - loga_mean))
+        .sum();
+    let var: f64 = m.iter().map(|x| (x - m_mean).powi(2)).sum();
+    let beta = -cov / var;
+    if beta <= 0. {
+        return f64::INFINITY;
+    }
+    let alpha = loga_mean + beta * m_mean;
+    (alpha - beta * n as f64).exp() / (1. - (-beta).exp())
+}
+
+impl<S> FieldBase<f64, f64, f64, S, 2>
+where
+    S: BaseSpace<f64, 2, Physical = f64, Spectral = f64>,
+{
+    /// Returns coordinates and physical field on a grid, which
+    /// is `upsample` times finer than the original one (spectral
+    /// zero-padding), without altering the field.
+    ///
+    /// The refined grid contains the original grid points.
+    ///
+    /// # Panics
+    /// Panics if `upsample` is zero.
+    pub fn render(&self, upsample: usize) -> (Array1<f64>, Array1<f64>, Array2<f64>) {
+        assert!(upsample > 0, "upsample must be positive");
+        let nf = [
+            refined_len(&self.space.base_all()[0], upsample),
+            refined_len(&self.space.base_all()[1], upsample),
+        ];
+        let mut v = self.to_ortho();
+        for (axis, n) in nf.iter().enumerate() {
+            let mut base = refined_base(&self.space.base_all()[axis], *n);
+            v = zero_pad(&v, base.len_spec(), axis);
+            v = base.backward(&v, axis);
+        }
+        (
+            refined_coords(&self.space.base_all()[0], &self.x[0], nf[0]),
+            refined_coords(&self.space.base_all()[1], &self.x[1], nf[1]),
+            v,
+        )
+    }
+
+    /// Returns derivative of order `deriv` in physical space,
+    /// without altering the field.
+    ///
+    /// Optional: Rescale derivatives, see [`FieldBase::gradient`].
+    pub fn grad_physical(&self, deriv: [usize; 2], scale: Option<[f64; 2]>) -> Array2<f64> {
+        self.ortho_to_physical(&self.gradient(deriv, scale), deriv)
+    }
+
+    /// Returns gradient magnitude $|\nabla v|$ in physical space,
+    /// e.g. for synthetic schlieren visualizations.
+    ///
+    /// Optional: Rescale derivatives, see [`FieldBase::gradient`].
+    pub fn grad_magnitude(&self, scale: Option<[f64; 2]>) -> Array2<f64> {
+        let dvdx = self.grad_physical([1, 0], scale);
+        let dvdy = self.grad_physical([0, 1], scale);
+        Zip::from(&dvdx)
+            .and(&dvdy)
+            .map_collect(|dx, dy| dx.hypot(*dy))
+    }
+
+    /// Physical field of orthogonal (chebyshev, sine or cosine) coefficients
+    /// of the derivative of order `deriv`
+    fn ortho_to_physical(&self, ortho: &Array2<f64>, deriv: [usize; 2]) -> Array2<f64> {
+        let mut v = ortho.to_owned();
+        for axis in 0..2 {
+            let (mut base, coef) =
+                derivative_base(&v, &self.space.base_all()[axis], axis, deriv[axis]);
+            v = base.backward(&coef, axis);
+        }
+        v
+    }
+}
+
+impl<S> FieldBase<f64, f64, Complex<f64>, S, 2>
+where
+    S: BaseSpace<f64, 2, Physical = f64, Spectral = Complex<f64>>,
+{
+    /// Returns coordinates and physical field on a grid, which
+    /// is `upsample` times finer than the original one (spectral
+    /// zero-padding), without altering the field.
+    ///
+    /// The refined grid contains the original grid points.
+    ///
+    /// # Panics
+    /// Panics if `upsample` is zero, or if the space is not
+    /// of type fourier (r2c) x chebyshev (or sine, cosine).
+    pub fn render(&self, upsample: usize) -> (Array1<f64>, Array1<f64>, Array2<f64>) {
+        assert!(upsample > 0, "upsample must be positive");
+        match (&self.space.base_all()[0], &self.space.base_all()[1]) {
+            (BaseAll::BaseR2c(_), BaseAll::BaseR2r(_)) => (),
+            _ => panic!("render supports only fourier (r2c) x real-to-real spaces"),
+        }
+        let nf = [
+            refined_len(&self.space.base_all()[0], upsample),
+            refined_len(&self.space.base_all()[1], upsample),
+        ];
+        // Real-to-real axis (real and imaginary part separately)
+        let mut ch = refined_base(&self.space.base_all()[1], nf[1]);
+        let ortho = zero_pad(&self.to_ortho(), ch.len_spec(), 1);
+        let re = ch.backward(&ortho.mapv(|v| v.re), 1);
+        let im = ch.backward(&ortho.mapv(|v| v.im), 1);
+        let mut vhat = Array2::<Complex<f64>>::zeros(re.raw_dim());
+        Zip::from(&mut vhat)
+            .and(&re)
+            .and(&im)
+            .for_each(|v, &r, &i| *v = Complex::new(r, i));
+        // Fourier axis (transforms are not normalized). The
+        // nyquist mode of the coarse grid is split between +k and -k.
+        let n = self.x[0].len();
+        vhat *= nf[0] as f64 / n as f64;
+        if n % 2 == 0 {
+            vhat.row_mut(n / 2).mapv_inplace(|v| v * 0.5);
+        }
+        let vhat = zero_pad(&vhat, nf[0] / 2 + 1, 0);
+        let v = fourier_r2c::<f64>(nf[0]).backward(&vhat, 0);
+        (
+            refined_coords(&self.space.base_all()[0], &self.x[0], nf[0]),
+            refined_coords(&self.space.base_all()[1], &self.x[1], nf[1]),
+            v,
+        )
+    }
+
+    /// Returns derivative of order `deriv` in physical space,
+    /// without altering the field.
+    ///
+    /// Optional: Rescale derivatives, see [`FieldBase::gradient`].
+    ///
+    /// # Panics
+    /// Panics if the space is not of type fourier (r2c) x real-to-real.
+    pub fn grad_physical(&self, deriv: [usize; 2], scale: Option<[f64; 2]>) -> Array2<f64> {
+        self.ortho_to_physical(&self.gradient(deriv, scale), deriv)
+    }
+
+    /// Returns gradient magnitude $|\nabla v|$ in physical space,
+    /// e.g. for synthetic schlieren visualizations.
+    ///
+    /// Optional: Rescale derivatives, see [`FieldBase::gradient`].
+    ///
+    /// # Panics
+    /// Panics if the space is not of type fourier (r2c) x real-to-real.
+    pub fn grad_magnitude(&self, scale: Option<[f64; 2]>) -> Array2<f64> {
+        let dvdx = self.grad_physical([1, 0], scale);
+        let dvdy = self.grad_physical([0, 1], scale);
+        Zip::from(&dvdx)
+            .and(&dvdy)
+            .map_collect(|dx, dy| dx.hypot(*dy))
+    }
+
+    /// Physical field of orthogonal (fourier x real-to-real) coefficients
+    /// of the derivative of order `deriv`
+    fn ortho_to_physical(&self, ortho: &Array2<Complex<f64>>, deriv: [usize; 2]) -> Array2<f64> {
+        let bases = self.space.base_all();
+        let fourier = match (&bases[0], &bases[1]) {
+            (BaseAll::BaseR2c(b), BaseAll::BaseR2r(_)) => b,
+            _ => panic!("physical gradients support only fourier (r2c) x real-to-real spaces"),
+        };
+        // Real-to-real axis (real and imaginary part separately)
+        let (mut ch, coef) = derivative_base(ortho, &bases[1], 1, deriv[1]);
+        let re = ch.backward(&coef.mapv(|v| v.re), 1);
+        let im = ch.backward(&coef.mapv(|v| v.im), 1);
+        let vhat = Zip::from(&re)
+            .and(&im)
+            .map_collect(|&r, &i| Complex::new(r, i));
+        fourier.clone().backward(&vhat, 0)
+    }
+}
+
+/// Number of points of refined grid, which contains the
+/// original grid points
+fn refined_len(base: &BaseAll<f64>, upsample: usize) -> usize {
+    let n = base.len_phys();
+    match base {
+        // Grids of real-to-real bases include the end points
+        BaseAll::BaseR2r(_) => (n - 1) * upsample + 1,
+        BaseAll::BaseR2c(_) | BaseAll::BaseC2c(_) => n * upsample,
+    }
+}
+
+/// Coordinates of refined grid, mapped in the same way
+/// as the original coordinates `x` (e.g. scaled)
+fn refined_coords(base: &BaseAll<f64>, x: &Array1<f64>, n: usize) -> Array1<f64> {
+    let x_std = base.coords();
+    let x_fine = match base {
+        BaseAll::BaseR2r(_) => refined_base(base, n).coords().clone(),
+        BaseAll::BaseR2c(_) | BaseAll::BaseC2c(_) => fourier_r2c::<f64>(n).coords().clone(),
+    };
+    let last = x.len() - 1;
+    let slope = (x[last] - x[0]) / (x_std[last] - x_std[0]);
+    x_fine.mapv(|xi| x[0] + (xi - x_std[0]) * slope)
+}
+
+/// Orthogonal base of the same kind as `base` with `n` points,
+/// e.g. on the refined grid
+///
+/// # Panics
+/// Panics if `base` is not real-to-real.
+fn refined_base(base: &BaseAll<f64>, n: usize) -> BaseR2r<f64> {
+    match base {
+        BaseAll::BaseR2r(BaseR2r::Chebyshev(_))
+        | BaseAll::BaseR2r(BaseR2r::CompositeChebyshev(_)) => chebyshev::<f64>(n),
+        BaseAll::BaseR2r(BaseR2r::FourierSine(_)) => fourier_sine::<f64>(n),
+        BaseAll::BaseR2r(BaseR2r::FourierCosine(_)) => fourier_cosine::<f64>(n),
+        _ => panic!("expected a real-to-real base"),
+    }
+}
+
+/// Orthogonal base of the derivative of order `deriv` along a
+/// real-to-real axis, and the coefficients `ortho` in its layout.
+///
+/// Odd derivatives of sine (cosine) series are cosine (sine) series,
+/// see [`FieldBase::gradient`].
+fn derivative_base<T: Copy + Zero>(
+    ortho: &Array2<T>,
+    base: &BaseAll<f64>,
+    axis: usize,
+    deriv: usize,
+) -> (BaseR2r<f64>, Array2<T>) {
+    let n = base.len_phys();
+    match base {
+        BaseAll::BaseR2r(BaseR2r::FourierSine(_)) if deriv % 2 == 1 => {
+            // cos(kx), k = 1..n-2 -> k = 0..n-1
+            let mut shape = [ortho.shape()[0], ortho.shape()[1]];
+            shape[axis] = n;
+            let mut coef = Array2::<T>::zeros(shape);
+            coef.slice_axis_mut(Axis(axis), Slice::from(1..n - 1))
+                .assign(ortho);
+            (fourier_cosine::<f64>(n), coef)
+        }
+        BaseAll::BaseR2r(BaseR2r::FourierCosine(_)) if deriv % 2 == 1 => {
+            // sin(kx), k = 0..n-1 -> k = 1..n-2 (the omitted
+            // modes vanish on the grid points)
+            let coef = ortho.slice_axis(Axis(axis), Slice::from(1..n - 1));
+            (fourier_sine::<f64>(n), coef.to_owned())
+        }
+        _ => (refined_base(base, n), ortho.to_owned()),
+    }
+}
+
+/// Copy array into zero array of size `n` along axis
+fn zero_pad<T: Copy + Zero>(input: &Array2<T>, n: usize, axis: usize) -> Array2<T> {
+    let mut shape = [input.shape()[0], input.shape()[1]];
+    let m = shape[axis].min(n);
+    shape[axis] = n;
+    let mut output = Array2::<T>::zeros(shape);
+    output
+        .slice_axis_mut(Axis(axis), Slice::from(..m))
+        .assign(&input.slice_axis(Axis(axis), Slice::from(..m)));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bases::{fourier_cosine, fourier_sine};
+    use crate::{cheb_dirichlet, chebyshev, fourier_r2c, Field2, Space2};
+
+    #[test]
+    fn test_apply_spectral_low_pass() {
+        let (nx, ny) = (16, 12);
+        let space = Space2::new(&fourier_r2c(nx), &cheb_dirichlet(ny));
+        let mut field = Field2::new(&space);
+        for (i, v) in field.vhat.iter_mut().enumerate() {
+            v.re = 1. + i as f64;
+            v.im = -1.;
+        }
+        let before = field.vhat.clone();
+        let (kmax, dmax) = (3., 4.);
+        field.apply_spectral(|_, k| {
+            if k[0].abs() > kmax || k[1] > dmax {
+                0.
+            } else {
+                1.
+            }
+        });
+        for ((i, j), v) in field.vhat.indexed_iter() {
+            if i as f64 > kmax || j as f64 > dmax {
+                assert!(v.norm() < 1e-12, "High mode ({}, {}) not zeroed", i, j);
+            } else {
+                assert!((v - before[[i, j]]).norm() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_truncation_error_estimate() {
+        // f(x,y) = 1/(a-x) + 1/(b-y) has chebyshev coefficients
+        // c_k = 2/sqrt(a^2-1) * rho^-k, with rho = a + sqrt(a^2-1)
+        let (a, b) = (1.5f64, 1.8f64);
+        let tail = |c: f64, n: usize| {
+            let rho = c + (c * c - 1.).sqrt();
+            2. / (c * c - 1.).sqrt() * rho.powi(-(n as i32)) / (1. - 1. / rho)
+        };
+        for n in [16, 24].iter() {
+            let space = Space2::new(&chebyshev(*n), &chebyshev(*n));
+            let mut field = Field2::new(&space);
+            let x = field.x[0].to_owned();
+            let y = field.x[1].to_owned();
+            for (i, xi) in x.iter().enumerate() {
+                for (j, yj) in y.iter().enumerate() {
+                    field.v[[i, j]] = 1. / (a - xi) + 1. / (b - yj);
+                }
+            }
+            field.forward();
+            let estimate = field.truncation_error_estimate();
+            let exact = tail(a, *n) + tail(b, *n);
+            assert!(
+                estimate / exact < 3. && exact / estimate < 3.,
+                "n = {}: estimate {:e} vs exact {:e}",
+                n,
+                estimate,
+                exact
+            );
+        }
+    }
+
+    #[test]
+    fn test_truncation_error_estimate_resolved() {
+        let space = Space2::new(&fourier_r2c(16), &cheb_dirichlet(12));
+        let mut field = Field2::new(&space);
+        let x = field.x[0].to_owned();
+        let y = field.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yj) in y.iter().enumerate() {
+                field.v[[i, j]] = xi.cos() * (1. - yj * yj);
+            }
+        }
+        field.forward();
+        let v_max = field.vhat.iter().map(|v| v.norm()).fold(0., f64::max);
+        assert!(field.truncation_error_estimate() < 1e-12 * v_max);
+    }
+
+    #[test]
+    fn test_render_matches_grid_points() {
+        let space = Space2::new(&cheb_dirichlet(10), &chebyshev(9));
+        let mut field = Field2::new(&space);
+        for (i, v) in field.vhat.iter_mut().enumerate() {
+            *v = (i as f64).sin();
+        }
+        field.backward();
+        let upsample = 3;
+        let (x, y, v) = field.render(upsample);
+        assert_eq!(v.shape(), &[x.len(), y.len()]);
+        for ((i, j), vi) in field.v.indexed_iter() {
+            assert!((v[[i * upsample, j * upsample]] - vi).abs() < 1e-10);
+            assert!((x[i * upsample] - field.x[0][i]).abs() < 1e-12);
+            assert!((y[j * upsample] - field.x[1][j]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_render_sine() {
+        // f = sin(2x) (1 - y^2), resolved, x in [0, pi]
+        let space = Space2::new(&fourier_sine(10), &cheb_dirichlet(9));
+        let mut field = Field2::new(&space);
+        let x = field.x[0].to_owned();
+        let y = field.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yj) in y.iter().enumerate() {
+                field.v[[i, j]] = (2. * xi).sin() * (1. - yj * yj);
+            }
+        }
+        field.forward();
+        let (xf, yf, v) = field.render(3);
+        assert_eq!(v.shape(), &[28, 25]);
+        for ((i, j), vi) in v.indexed_iter() {
+            let expected = (2. * xf[i]).sin() * (1. - yf[j] * yf[j]);
+            assert!((vi - expected).abs() < 1e-10);
+        }
+        assert!(field.truncation_error_estimate() < 1e-10);
+    }
+
+    #[test]
+    fn test_render_matches_grid_points_periodic() {
+        let space = Space2::new(&fourier_r2c(12), &cheb_dirichlet(9));
+        let mut field = Field2::new(&space);
+        for ((i, j), v) in field.v.indexed_iter_mut() {
+            *v = ((i * j) as f64).cos();
+        }
+        // project onto representable space (incl. nyquist mode)
+        field.forward();
+        field.backward();
+        let upsample = 2;
+        let (x, y, v) = field.render(upsample);
+        assert_eq!(v.shape(), &[x.len(), y.len()]);
+        for ((i, j), vi) in field.v.indexed_iter() {
+            assert!((v[[i * upsample, j * upsample]] - vi).abs() < 1e-10);
+            assert!((x[i * upsample] - field.x[0][i]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_shift_periodic() {
+        use std::f64::consts::PI;
+        let (nx, ny) = (16, 9);
+        let space = Space2::new(&fourier_r2c(nx), &chebyshev(ny));
+        let mut field = Field2::new(&space);
+        let x = field.x[0].to_owned();
+        let y = field.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yj) in y.iter().enumerate() {
+                field.v[[i, j]] = (xi.sin() + (3. * xi).cos()) * (1. + yj);
+            }
+        }
+        field.forward();
+        // Full period: identity
+        let mut shifted = field.clone();
+        shifted.shift_periodic(2. * PI);
+        for (a, b) in shifted.vhat.iter().zip(field.vhat.iter()) {
+            assert!((a - b).norm() < 1e-10);
+        }
+        // Half grid cell: compare to spectral interpolation
+        let delta = 0.5 * (x[1] - x[0]);
+        let mut shifted = field.clone();
+        shifted.shift_periodic(delta);
+        shifted.backward();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yj) in y.iter().enumerate() {
+                let expected = field.probe(xi - delta, *yj);
+                assert!((shifted.v[[i, j]] - expected).abs() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_grad_magnitude_ramp() {
+        // f = 2x + 3y, |grad f| = sqrt(13)
+        let space = Space2::new(&chebyshev(7), &chebyshev(8));
+        let mut field = Field2::new(&space);
+        let x = field.x[0].to_owned();
+        let y = field.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yj) in y.iter().enumerate() {
+                field.v[[i, j]] = 2. * xi + 3. * yj;
+            }
+        }
+        field.forward();
+        for g in field.grad_magnitude(None).iter() {
+            assert!((g - 13_f64.sqrt()).abs() < 1e-10);
+        }
+        // Scaled grid: x -> 2x
+        for g in field.grad_magnitude(Some([2., 1.])).iter() {
+            assert!((g - 10_f64.sqrt()).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_grad_magnitude_periodic() {
+        // f = cos(x) + y, |grad f| = sqrt(sin(x)^2 + 1)
+        let space = Space2::new(&fourier_r2c(12), &chebyshev(7));
+        let mut field = Field2::new(&space);
+        let x = field.x[0].to_owned();
+        let y = field.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yj) in y.iter().enumerate() {
+                field.v[[i, j]] = xi.cos() + yj;
+            }
+        }
+        field.forward();
+        let grad = field.grad_magnitude(None);
+        for ((i, _), g) in grad.indexed_iter() {
+            assert!((g - (x[i].sin().powi(2) + 1.).sqrt()).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_grad_physical_sin() {
+        // d/dx sin(x) = cos(x), d2/dy2 y^2 = 2
+        let space = Space2::new(&fourier_r2c(16), &chebyshev(7));
+        let mut field = Field2::new(&space);
+        let x = field.x[0].to_owned();
+        let y = field.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yj) in y.iter().enumerate() {
+                field.v[[i, j]] = xi.sin() + yj * yj;
+            }
+        }
+        field.forward();
+        let vhat = field.vhat.to_owned();
+        let dvdx = field.grad_physical([1, 0], None);
+        for ((i, _), d) in dvdx.indexed_iter() {
+            assert!((d - x[i].cos()).abs() < 1e-10);
+        }
+        for d in field.grad_physical([0, 2], None).iter() {
+            assert!((d - 2.).abs() < 1e-10);
+        }
+        // Field is not altered
+        assert_eq!(field.vhat, vhat);
+    }
+
+    #[test]
+    fn test_grad_physical_sine_cosine() {
+        // d/dx sin(2x) = 2 cos(2x), d/dx cos(3x) = -3 sin(3x)
+        let space = Space2::new(&fourier_sine(12), &fourier_cosine(9));
+        let mut field = Field2::new(&space);
+        let x = field.x[0].to_owned();
+        let y = field.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yj) in y.iter().enumerate() {
+                field.v[[i, j]] = (2. * xi).sin() * (3. * yj).cos();
+            }
+        }
+        field.forward();
+        for ((i, j), d) in field.grad_physical([1, 0], None).indexed_iter() {
+            let expected = 2. * (2. * x[i]).cos() * (3. * y[j]).cos();
+            assert!((d - expected).abs() < 1e-10);
+        }
+        for ((i, j), d) in field.grad_physical([1, 1], None).indexed_iter() {
+            let expected = -6. * (2. * x[i]).cos() * (3. * y[j]).sin();
+            assert!((d - expected).abs() < 1e-10);
+        }
+        for ((i, j), d) in field.grad_physical([2, 0], None).indexed_iter() {
+            let expected = -4. * (2. * x[i]).sin() * (3. * y[j]).cos();
+            assert!((d - expected).abs() < 1e-10);
+        }
+    }
+}
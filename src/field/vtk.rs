@@ -0,0 +1,127 @@
+//! Export to VTK rectilinear grid files (`.vtr`, ascii xml),
+//! e.g. to open fields in ParaView.
+//!
+//! The coordinates are taken from `x`, i.e. they include any
+//! scaling of the grid. The values are the physical fields,
+//! evaluated from the spectral coefficients.
+use super::{BaseSpace, FieldBase};
+use ndarray::{Array1, Array2};
+use std::io::Write;
+
+impl<T2, S> FieldBase<f64, f64, T2, S, 2>
+where
+    S: BaseSpace<f64, 2, Physical = f64, Spectral = T2>,
+{
+    /// Write field as point data "v" of a rectilinear grid to
+    /// `filename` (vtk xml format, ending `.vtr`).
+    ///
+    /// # Errors
+    /// Errors if the file can not be written.
+    pub fn write_vtk(&self, filename: &str) -> std::io::Result<()> {
+        write_vtr(
+            filename,
+            &self.x[0],
+            &self.x[1],
+            &[("v", self.to_physical())],
+        )
+    }
+}
+
+/// Write rectilinear grid with coordinates `x`, `y` and
+/// point data `fields` (name, values of shape (nx, ny))
+pub(crate) fn write_vtr(
+    filename: &str,
+    x: &Array1<f64>,
+    y: &Array1<f64>,
+    fields: &[(&str, Array2<f64>)],
+) -> std::io::Result<()> {
+    let (nx, ny) = (x.len(), y.len());
+    let extent = format!("0 {} 0 {} 0 0", nx - 1, ny - 1);
+    let mut file = std::io::BufWriter::new(std::fs::File::create(filename)?);
+    writeln!(file, "<?xml version=\"1.0\"?>")?;
+    writeln!(
+        file,
+        "<VTKFile type=\"RectilinearGrid\" version=\"0.1\" byte_order=\"LittleEndian\">"
+    )?;
+    writeln!(file, "<RectilinearGrid WholeExtent=\"{}\">", extent)?;
+    writeln!(file, "<Piece Extent=\"{}\">", extent)?;
+    // Point data, x runs fastest
+    writeln!(file, "<PointData>")?;
+    for (name, values) in fields {
+        assert!(
+            values.shape() == [nx, ny],
+            "Field {} must be of shape {:?}",
+            name,
+            [nx, ny]
+        );
+        writeln!(
+            file,
+            "<DataArray type=\"Float64\" Name=\"{}\" format=\"ascii\">",
+            name
+        )?;
+        for j in 0..ny {
+            for i in 0..nx {
+                write!(file, "{:e} ", values[[i, j]])?;
+            }
+            writeln!(file)?;
+        }
+        writeln!(file, "</DataArray>")?;
+    }
+    writeln!(file, "</PointData>")?;
+    // Coordinates
+    writeln!(file, "<Coordinates>")?;
+    for (name, coords) in [("x", x.to_vec()), ("y", y.to_vec()), ("z", vec![0.])].iter() {
+        writeln!(
+            file,
+            "<DataArray type=\"Float64\" Name=\"{}\" format=\"ascii\">",
+            name
+        )?;
+        for c in coords {
+            write!(file, "{:e} ", c)?;
+        }
+        writeln!(file)?;
+        writeln!(file, "</DataArray>")?;
+    }
+    writeln!(file, "</Coordinates>")?;
+    writeln!(file, "</Piece>")?;
+    writeln!(file, "</RectilinearGrid>")?;
+    writeln!(file, "</VTKFile>")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{cheb_dirichlet, fourier_r2c, Field2, Space2};
+
+    #[test]
+    fn test_write_vtk() {
+        let (nx, ny) = (8, 7);
+        let space = Space2::new(&fourier_r2c(nx), &cheb_dirichlet(ny));
+        let mut field = Field2::new(&space);
+        let (x, y) = (field.x[0].to_owned(), field.x[1].to_owned());
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                field.v[[i, j]] = xi.cos() * (1. - yi * yi);
+            }
+        }
+        field.forward();
+        let filename = std::env::temp_dir().join("rustpde_test_write_vtk.vtr");
+        let filename = filename.to_str().unwrap();
+        field.write_vtk(filename).unwrap();
+        let content = std::fs::read_to_string(filename).unwrap();
+        std::fs::remove_file(filename).unwrap();
+        // Header
+        assert!(content.contains("<RectilinearGrid WholeExtent=\"0 7 0 6 0 0\">"));
+        // Point data
+        let start = content.find("Name=\"v\"").unwrap();
+        let data = &content[start..];
+        let data = &data[data.find('>').unwrap() + 1..data.find("</DataArray>").unwrap()];
+        let values: Vec<f64> = data
+            .split_whitespace()
+            .map(|v| v.parse().unwrap())
+            .collect();
+        assert_eq!(values.len(), nx * ny);
+        // x runs fastest
+        assert!((values[nx + 1] - field.v[[1, 1]]).abs() < 1e-12);
+    }
+}
@@ -1,7 +1,8 @@
 //! Implement writing to hdf5 file for struct Field
 use super::{BaseSpace, FieldBase};
 use crate::hdf5::write_to_hdf5;
-use crate::hdf5::write_to_hdf5_complex;
+use crate::hdf5::write_to_hdf5_complex_compressed;
+use crate::hdf5::write_to_hdf5_compressed;
 use crate::hdf5::H5Type;
 use crate::hdf5::Result;
 use crate::types::FloatNum;
@@ -17,6 +18,18 @@ pub trait WriteField {
     /// **Errors** when file with fields exists and the fields
     /// in the file mismatch with the current fields.
     fn write_return_result(&mut self, filename: &str, group: Option<&str>) -> Result<()>;
+    /// Write Field, where `v` and `vhat` are chunked and
+    /// gzip-compressed with level `compression` (0-9), if it is not None.
+    ///
+    /// ## Errors
+    /// **Errors** when file with fields exists and the fields
+    /// in the file mismatch with the current fields.
+    fn write_compressed(
+        &mut self,
+        filename: &str,
+        group: Option<&str>,
+        compression: Option<u8>,
+    ) -> Result<()>;
 }
 
 impl<A, S> WriteField for FieldBase<A, A, A, S, 1>
@@ -34,8 +47,17 @@ where
     }
 
     fn write_return_result(&mut self, filename: &str, group: Option<&str>) -> Result<()> {
-        write_to_hdf5(filename, "v", group, &self.v)?;
-        write_to_hdf5(filename, "vhat", group, &self.vhat)?;
+        self.write_compressed(filename, group, None)
+    }
+
+    fn write_compressed(
+        &mut self,
+        filename: &str,
+        group: Option<&str>,
+        compression: Option<u8>,
+    ) -> Result<()> {
+        write_to_hdf5_compressed(filename, "v", group, &self.v, compression)?;
+        write_to_hdf5_compressed(filename, "vhat", group, &self.vhat, compression)?;
         write_to_hdf5(filename, "x", None, &self.x[0])?;
         write_to_hdf5(filename, "dx", None, &self.dx[0])?;
         Ok(())
@@ -57,8 +79,17 @@ where
     }
 
     fn write_return_result(&mut self, filename: &str, group: Option<&str>) -> Result<()> {
-        write_to_hdf5(filename, "v", group, &self.v)?;
-        write_to_hdf5_complex(filename, "vhat", group, &self.vhat)?;
+        self.write_compressed(filename, group, None)
+    }
+
+    fn write_compressed(
+        &mut self,
+        filename: &str,
+        group: Option<&str>,
+        compression: Option<u8>,
+    ) -> Result<()> {
+        write_to_hdf5_compressed(filename, "v", group, &self.v, compression)?;
+        write_to_hdf5_complex_compressed(filename, "vhat", group, &self.vhat, compression)?;
         write_to_hdf5(filename, "x", None, &self.x[0])?;
         write_to_hdf5(filename, "dx", None, &self.dx[0])?;
         Ok(())
@@ -80,8 +111,17 @@ where
     }
 
     fn write_return_result(&mut self, filename: &str, group: Option<&str>) -> Result<()> {
-        write_to_hdf5(filename, "v", group, &self.v)?;
-        write_to_hdf5(filename, "vhat", group, &self.vhat)?;
+        self.write_compressed(filename, group, None)
+    }
+
+    fn write_compressed(
+        &mut self,
+        filename: &str,
+        group: Option<&str>,
+        compression: Option<u8>,
+    ) -> Result<()> {
+        write_to_hdf5_compressed(filename, "v", group, &self.v, compression)?;
+        write_to_hdf5_compressed(filename, "vhat", group, &self.vhat, compression)?;
         write_to_hdf5(filename, "x", None, &self.x[0])?;
         write_to_hdf5(filename, "dx", None, &self.dx[0])?;
         write_to_hdf5(filename, "y", None, &self.x[1])?;
@@ -105,12 +145,93 @@ where
     }
 
     fn write_return_result(&mut self, filename: &str, group: Option<&str>) -> Result<()> {
-        write_to_hdf5(filename, "v", group, &self.v)?;
-        write_to_hdf5_complex(filename, "vhat", group, &self.vhat)?;
+        self.write_compressed(filename, group, None)
+    }
+
+    fn write_compressed(
+        &mut self,
+        filename: &str,
+        group: Option<&str>,
+        compression: Option<u8>,
+    ) -> Result<()> {
+        write_to_hdf5_compressed(filename, "v", group, &self.v, compression)?;
+        write_to_hdf5_complex_compressed(filename, "vhat", group, &self.vhat, compression)?;
+        write_to_hdf5(filename, "x", None, &self.x[0])?;
+        write_to_hdf5(filename, "dx", None, &self.dx[0])?;
+        write_to_hdf5(filename, "y", None, &self.x[1])?;
+        write_to_hdf5(filename, "dy", None, &self.dx[1])?;
+        Ok(())
+    }
+}
+
+impl<A, S> WriteField for FieldBase<A, A, A, S, 3>
+where
+    A: FloatNum + H5Type,
+    S: BaseSpace<A, 3, Physical = A, Spectral = A>,
+{
+    /// Write Field data to hdf5 file
+    fn write(&mut self, filename: &str, group: Option<&str>) {
+        let result = self.write_return_result(filename, group);
+        match result {
+            Ok(_) => (),
+            Err(_) => println!("Error while writing file {:?}.", filename),
+        }
+    }
+
+    fn write_return_result(&mut self, filename: &str, group: Option<&str>) -> Result<()> {
+        self.write_compressed(filename, group, None)
+    }
+
+    fn write_compressed(
+        &mut self,
+        filename: &str,
+        group: Option<&str>,
+        compression: Option<u8>,
+    ) -> Result<()> {
+        write_to_hdf5_compressed(filename, "v", group, &self.v, compression)?;
+        write_to_hdf5_compressed(filename, "vhat", group, &self.vhat, compression)?;
+        write_to_hdf5(filename, "x", None, &self.x[0])?;
+        write_to_hdf5(filename, "dx", None, &self.dx[0])?;
+        write_to_hdf5(filename, "y", None, &self.x[1])?;
+        write_to_hdf5(filename, "dy", None, &self.dx[1])?;
+        write_to_hdf5(filename, "z", None, &self.x[2])?;
+        write_to_hdf5(filename, "dz", None, &self.dx[2])?;
+        Ok(())
+    }
+}
+
+impl<A, S> WriteField for FieldBase<A, A, Complex<A>, S, 3>
+where
+    A: FloatNum + H5Type,
+    S: BaseSpace<A, 3, Physical = A, Spectral = Complex<A>>,
+{
+    /// Write Field data to hdf5 file
+    fn write(&mut self, filename: &str, group: Option<&str>) {
+        let result = self.write_return_result(filename, group);
+        match result {
+            Ok(_) => (),
+            Err(_) => println!("Error while writing file {:?}.", filename),
+        }
+    }
+
+    fn write_return_result(&mut self, filename: &str, group: Option<&str>) -> Result<()> {
+        self.write_compressed(filename, group, None)
+    }
+
+    fn write_compressed(
+        &mut self,
+        filename: &str,
+        group: Option<&str>,
+        compression: Option<u8>,
+    ) -> Result<()> {
+        write_to_hdf5_compressed(filename, "v", group, &self.v, compression)?;
+        write_to_hdf5_complex_compressed(filename, "vhat", group, &self.vhat, compression)?;
         write_to_hdf5(filename, "x", None, &self.x[0])?;
         write_to_hdf5(filename, "dx", None, &self.dx[0])?;
         write_to_hdf5(filename, "y", None, &self.x[1])?;
         write_to_hdf5(filename, "dy", None, &self.dx[1])?;
+        write_to_hdf5(filename, "z", None, &self.x[2])?;
+        write_to_hdf5(filename, "dz", None, &self.dx[2])?;
         Ok(())
     }
 }
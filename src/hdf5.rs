@@ -1,9 +1,15 @@
 //! Read / Write with hdf5
+pub use hdf5_interface::append_to_hdf5;
+pub use hdf5_interface::read_attr_from_hdf5;
 pub use hdf5_interface::read_from_hdf5;
 pub use hdf5_interface::read_from_hdf5_complex;
+pub use hdf5_interface::read_member_names;
 pub use hdf5_interface::read_scalar_from_hdf5;
+pub use hdf5_interface::write_attr_to_hdf5;
 pub use hdf5_interface::write_scalar_to_hdf5;
 pub use hdf5_interface::write_to_hdf5;
 pub use hdf5_interface::write_to_hdf5_complex;
+pub use hdf5_interface::write_to_hdf5_complex_compressed;
+pub use hdf5_interface::write_to_hdf5_compressed;
 pub use hdf5_interface::H5Type;
 pub use hdf5_interface::Result;
@@ -123,14 +123,23 @@ pub mod navier;
 pub mod solver;
 pub mod types;
 pub use bases::{cheb_dirichlet, cheb_neumann, chebyshev, fourier_c2c, fourier_r2c};
-pub use field::{BaseSpace, Field1, Field2, FieldBase, ReadField, Space1, Space2, WriteField};
-pub use solver::{Solver, SolverField, SolverScalar};
+pub use field::{
+    BaseSpace, Field1, Field2, Field3, FieldBase, FieldError, ReadField, Space1, Space2, Space3,
+    WriteField,
+};
+pub use solver::{Solver, SolverError, SolverField, SolverScalar};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 /// Real type (not active)
 //pub type Real = f64;
 
 const MAX_TIMESTEP: usize = 10_000_000;
 
+/// Maximum growth factor of the timestep per step,
+/// see [`Integrate::suggest_dt`]
+const MAX_DT_GROWTH: f64 = 1.5;
+
 /// Integrate trait, step forward in time, and write results
 pub trait Integrate {
     /// Update solution
@@ -143,6 +152,38 @@ pub trait Integrate {
     fn callback(&mut self);
     /// Additional break criteria
     fn exit(&mut self) -> bool;
+    /// Suggested timestep for the next update, e.g. from a CFL
+    /// condition. If `Some`, [`integrate`] adopts it via
+    /// [`Integrate::set_dt`], but lets it grow by at most a factor
+    /// 1.5 per step. Defaults to a fixed timestep.
+    fn suggest_dt(&self) -> Option<f64> {
+        None
+    }
+    /// Set timestep, required if `suggest_dt` returns `Some`
+    ///
+    /// # Panics
+    /// The default implementation panics, i.e. types which
+    /// suggest a timestep must implement it.
+    fn set_dt(&mut self, _dt: f64) {
+        panic!("suggest_dt returned a timestep, but set_dt is not implemented");
+    }
+    /// Capture current state, see [`integrate_collect`].
+    /// Defaults to the time only.
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            time: self.get_time(),
+            fields: HashMap::new(),
+        }
+    }
+}
+
+/// State of a pde at a given time, kept in memory
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// Time of snapshot
+    pub time: f64,
+    /// Physical fields, by name
+    pub fields: HashMap<String, ndarray::ArrayD<f64>>,
 }
 
 /// Integrade pde, that implements the Integrate trait.
@@ -152,36 +193,223 @@ pub trait Integrate {
 /// Stop Criteria:
 /// 1. Timestep limit
 /// 2. Time limit
-pub fn integrate<T: Integrate>(pde: &mut T, max_time: f64, save_intervall: Option<f64>) {
+/// 3. `Integrate::exit`
+pub fn integrate<T: Integrate>(
+    pde: &mut T,
+    max_time: f64,
+    save_intervall: Option<f64>,
+) -> IntegrateReport {
+    integrate_with(pde, max_time, save_intervall, None, Integrate::callback)
+}
+
+/// Integrade pde like [`integrate`], but stop after the wall-clock
+/// time `max_wall`. When it is exceeded, output is written once
+/// (`Integrate::callback`), to checkpoint the latest state.
+///
+/// # Example
+/// ```
+/// use rustpde::navier::Navier2D;
+/// use rustpde::{integrate_wall_limited, StopReason};
+/// use std::time::Duration;
+/// let mut navier = Navier2D::new_periodic(8, 9, 1e4, 1., 0.01, 1.);
+/// let max_wall = Some(Duration::from_secs(3600));
+/// let report = integrate_wall_limited(&mut navier, 0.05, None, max_wall);
+/// assert_eq!(report.stop_reason, StopReason::TimeLimit);
+/// ```
+pub fn integrate_wall_limited<T: Integrate>(
+    pde: &mut T,
+    max_time: f64,
+    save_intervall: Option<f64>,
+    max_wall: Option<Duration>,
+) -> IntegrateReport {
+    integrate_with(pde, max_time, save_intervall, max_wall, Integrate::callback)
+}
+
+/// Reason why the time loop stopped, see [`IntegrateReport`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// Reached `max_time`
+    TimeLimit,
+    /// Reached maximum number of timesteps
+    StepLimit,
+    /// Exceeded wall-clock time limit
+    WallLimit,
+    /// `Integrate::exit` returned true
+    Exit,
+}
+
+/// Summary of the time loop, returned by [`integrate`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IntegrateReport {
+    /// Number of timesteps
+    pub steps: usize,
+    /// Time at the end of the loop
+    pub final_time: f64,
+    /// Criterion that stopped the loop
+    pub stop_reason: StopReason,
+}
+
+/// Integrade pde like [`integrate`], but instead of writing
+/// output, collect snapshots (see [`Integrate::snapshot`])
+/// every `save_intervall` in memory.
+pub fn integrate_collect<T: Integrate>(
+    pde: &mut T,
+    max_time: f64,
+    save_intervall: f64,
+) -> Vec<Snapshot> {
+    let mut snapshots = Vec::new();
+    integrate_with(pde, max_time, Some(save_intervall), None, |pde| {
+        snapshots.push(pde.snapshot());
+    });
+    snapshots
+}
+
+/// Time loop, call `on_save` every `save_intervall`
+///
+/// Outputs are saved at the step closest to each multiple of
+/// `save_intervall`, also if the timestep changes, and once more,
+/// if `max_wall` is exceeded.
+fn integrate_with<T, F>(
+    pde: &mut T,
+    max_time: f64,
+    save_intervall: Option<f64>,
+    max_wall: Option<Duration>,
+    mut on_save: F,
+) -> IntegrateReport
+where
+    T: Integrate,
+    F: FnMut(&mut T),
+{
+    let start = Instant::now();
     let mut timestep: usize = 0;
-    let eps_dt = pde.get_dt() * 1e-4;
+    let mut next_save = save_intervall
+        .filter(|dt_save| *dt_save > 0.)
+        .map(|dt_save| {
+            ((pde.get_time() + pde.get_dt() / 2.) / dt_save).floor() * dt_save + dt_save
+        });
     loop {
+        // Adapt timestep
+        if let Some(dt) = pde.suggest_dt() {
+            let dt = dt.min(pde.get_dt() * MAX_DT_GROWTH);
+            pde.set_dt(dt);
+        }
+
         // Update
         pde.update();
         timestep += 1;
 
         // Save
-        if let Some(dt_save) = &save_intervall {
-            if (pde.get_time() % dt_save) < pde.get_dt() / 2.
-                || (pde.get_time() % dt_save) > dt_save - pde.get_dt() / 2.
-            {
+        if let (Some(dt_save), Some(t_save)) = (save_intervall, next_save.as_mut()) {
+            let half_step = pde.get_dt() / 2.;
+            if pde.get_time() + half_step > *t_save {
                 //println!("Save at time: {:4.3}", pde.get_time());
-                pde.callback();
+                on_save(pde);
+                while *t_save < pde.get_time() + half_step {
+                    *t_save += dt_save;
+                }
             }
         }
 
         // Break
-        if pde.get_time() + eps_dt >= max_time {
+        let eps_dt = pde.get_dt() * 1e-4;
+        let stop_reason = if pde.get_time() + eps_dt >= max_time {
             println!("time limit reached: {:?}", pde.get_time());
-            break;
-        }
-        if timestep >= MAX_TIMESTEP {
+            Some(StopReason::TimeLimit)
+        } else if timestep >= MAX_TIMESTEP {
             println!("timestep limit reached: {:?}", timestep);
-            break;
-        }
-        if pde.exit() {
+            Some(StopReason::StepLimit)
+        } else if max_wall.map_or(false, |max_wall| start.elapsed() >= max_wall) {
+            println!("wall time limit reached: {:?}", start.elapsed());
+            on_save(pde);
+            Some(StopReason::WallLimit)
+        } else if pde.exit() {
             println!("break criteria triggered");
-            break;
+            Some(StopReason::Exit)
+        } else {
+            None
+        };
+        if let Some(stop_reason) = stop_reason {
+            return IntegrateReport {
+                steps: timestep,
+                final_time: pde.get_time(),
+                stop_reason,
+            };
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts steps and outputs
+    struct Counter {
+        time: f64,
+        saves: usize,
+    }
+
+    impl Integrate for Counter {
+        fn update(&mut self) {
+            self.time += 0.1;
+        }
+        fn get_time(&self) -> f64 {
+            self.time
+        }
+        fn get_dt(&self) -> f64 {
+            0.1
+        }
+        fn callback(&mut self) {
+            self.saves += 1;
+        }
+        fn exit(&mut self) -> bool {
+            false
+        }
+    }
+
+    /// Suggests a timestep, but does not implement `set_dt`
+    struct Suggesting(Counter);
+
+    impl Integrate for Suggesting {
+        fn update(&mut self) {
+            self.0.update();
+        }
+        fn get_time(&self) -> f64 {
+            self.0.get_time()
+        }
+        fn get_dt(&self) -> f64 {
+            self.0.get_dt()
+        }
+        fn callback(&mut self) {
+            self.0.callback();
+        }
+        fn exit(&mut self) -> bool {
+            self.0.exit()
+        }
+        fn suggest_dt(&self) -> Option<f64> {
+            Some(0.05)
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "set_dt is not implemented")]
+    fn test_integrate_suggest_dt_requires_set_dt() {
+        let mut pde = Suggesting(Counter { time: 0., saves: 0 });
+        integrate(&mut pde, 1., None);
+    }
+
+    #[test]
+    fn test_integrate_report() {
+        let mut pde = Counter { time: 0., saves: 0 };
+        let report = integrate(&mut pde, 1., Some(0.5));
+        assert_eq!(report.stop_reason, StopReason::TimeLimit);
+        assert_eq!(report.steps, 10);
+        assert!((report.final_time - 1.).abs() < 1e-8);
+        assert_eq!(pde.saves, 2);
+        // Wall-clock limit: checkpoint once after the first step
+        let mut pde = Counter { time: 0., saves: 0 };
+        let report = integrate_wall_limited(&mut pde, 1., None, Some(Duration::from_secs(0)));
+        assert_eq!(report.stop_reason, StopReason::WallLimit);
+        assert_eq!(report.steps, 1);
+        assert_eq!(pde.saves, 1);
+    }
+}
@@ -0,0 +1,277 @@
+//! # Builder
+//! Step-by-step construction of [`Navier2D`] (periodic), for setups
+//! which deviate from [`Navier2D::new_periodic`], e.g. custom boundary
+//! conditions or initial fields.
+//!
+//! # Bases
+//! The builder does not accept the bases of the solver fields
+//! directly, because they are not independent: x is periodic
+//! (`fourier_r2c`), and the y-bases of velocity and temperature
+//! follow from their boundary conditions, i.e. dirichlet for
+//! no-slip and fixed temperature, neumann for free-slip and fixed
+//! heat flux. The pressure, the work field and the implicit solvers
+//! are built on top of these. So the bases are selected by
+//! [`Navier2DPeriodicBuilder::velocity_bc`],
+//! [`Navier2DPeriodicBuilder::temp_bc`] and
+//! [`Navier2DPeriodicBuilder::temp_bc_flux`], which keeps every
+//! combination consistent. Initial fields may be given on any
+//! `fourier_r2c` x chebyshev space of the same size, see
+//! [`Navier2DPeriodicBuilder::temperature`].
+//!
+//! # Example
+//! Start from a linear temperature profile with fixed heat flux
+//! ```
+//! use rustpde::navier::{Navier2D, Navier2DPeriodicBuilder};
+//! let (nx, ny) = (16, 17);
+//! let navier = Navier2DPeriodicBuilder::new(nx, ny, 1e4, 1., 0.01, 1.)
+//!     .temp_bc_flux(Navier2D::bc_fixed_flux(nx, ny, 1., 1.))
+//!     .build();
+//! ```
+use super::navier::{Navier2D, Space2R2c, TimeScheme, VelocityBc};
+use crate::field::Field2;
+use ndarray::Array2;
+use num_complex::Complex;
+
+/// Field of the periodic navier solver
+type FieldR2c = Field2<Complex<f64>, Space2R2c>;
+
+/// Temperature boundary condition of the builder
+#[derive(Clone)]
+enum TempBc {
+    /// Fixed temperature, see `Navier2D::set_temp_bc`
+    Fixed(FieldR2c),
+    /// Fixed heat flux, see `Navier2D::set_temp_bc_flux`
+    Flux(FieldR2c),
+}
+
+/// Builder of the periodic navier solver
+///
+/// Options which are not set keep the defaults of
+/// [`Navier2D::new_periodic`].
+#[derive(Clone)]
+pub struct Navier2DPeriodicBuilder {
+    nx: usize,
+    ny: usize,
+    ra: f64,
+    pr: f64,
+    dt: f64,
+    aspect: f64,
+    temp_bc: Option<TempBc>,
+    velocity_bc: VelocityBc,
+    time_scheme: TimeScheme,
+    temp: Option<Array2<f64>>,
+    velocity: Option<[Array2<f64>; 2]>,
+    disturbance: f64,
+    penalty_eta: Option<f64>,
+    rotation: Option<f64>,
+    write_intervall: Option<f64>,
+}
+
+impl Navier2DPeriodicBuilder {
+    /// Start builder, see [`Navier2D::new_periodic`] for the arguments
+    pub fn new(nx: usize, ny: usize, ra: f64, pr: f64, dt: f64, aspect: f64) -> Self {
+        Self {
+            nx,
+            ny,
+            ra,
+            pr,
+            dt,
+            aspect,
+            temp_bc: None,
+            velocity_bc: VelocityBc::NoSlip,
+            time_scheme: TimeScheme::Euler,
+            temp: None,
+            velocity: None,
+            disturbance: 0.1,
+            penalty_eta: None,
+            rotation: None,
+            write_intervall: None,
+        }
+    }
+
+    /// Fixed temperature boundary condition field.
+    /// Default: `Navier2D::bc_rbc_periodic`
+    #[must_use]
+    pub fn temp_bc(mut self, fieldbc: FieldR2c) -> Self {
+        self.temp_bc = Some(TempBc::Fixed(fieldbc));
+        self
+    }
+
+    /// Fixed heat flux boundary condition field, see `Navier2D::bc_fixed_flux`
+    #[must_use]
+    pub fn temp_bc_flux(mut self, fieldbc: FieldR2c) -> Self {
+        self.temp_bc = Some(TempBc::Flux(fieldbc));
+        self
+    }
+
+    /// Velocity boundary conditions at the plates. Default: `NoSlip`
+    #[must_use]
+    pub fn velocity_bc(mut self, velocity_bc: VelocityBc) -> Self {
+        self.velocity_bc = velocity_bc;
+        self
+    }
+
+    /// Time integration scheme. Default: `Euler`
+    #[must_use]
+    pub fn time_scheme(mut self, time_scheme: TimeScheme) -> Self {
+        self.time_scheme = time_scheme;
+        self
+    }
+
+    /// Initial temperature, including the boundary condition field.
+    /// Only its physical values are used, so the field may be
+    /// defined on a different base, e.g. `chebyshev` along y.
+    #[must_use]
+    pub fn temperature(mut self, temp: &FieldR2c) -> Self {
+        self.temp = Some(temp.to_physical());
+        self
+    }
+
+    /// Initial velocities. Only their physical values are used.
+    #[must_use]
+    pub fn velocity(mut self, ux: &FieldR2c, uy: &FieldR2c) -> Self {
+        self.velocity = Some([ux.to_physical(), uy.to_physical()]);
+        self
+    }
+
+    /// Amplitude of random disturbance of fields, which are
+    /// not set explicitly. Default: 0.1
+    #[must_use]
+    pub fn disturbance(mut self, amp: f64) -> Self {
+        self.disturbance = amp;
+        self
+    }
+
+    /// Penalization parameter of solids, see `Navier2D::set_solid`
+    #[must_use]
+    pub fn penalty_eta(mut self, eta: f64) -> Self {
+        self.penalty_eta = Some(eta);
+        self
+    }
+
+    /// Rotation rate (coriolis force)
+    #[must_use]
+    pub fn rotation(mut self, rotation: f64) -> Self {
+        self.rotation = Some(rotation);
+        self
+    }
+
+    /// Time intervall for write fields
+    #[must_use]
+    pub fn write_intervall(mut self, write_intervall: f64) -> Self {
+        self.write_intervall = Some(write_intervall);
+        self
+    }
+
+    /// Construct solver
+    ///
+    /// # Panics
+    /// Panics if the shape of the initial fields does not
+    /// match the physical grid.
+    pub fn build(self) -> Navier2D<Complex<f64>, Space2R2c> {
+        let mut navier =
+            Navier2D::new_periodic(self.nx, self.ny, self.ra, self.pr, self.dt, self.aspect);
+        navier.set_velocity_bc(self.velocity_bc);
+        navier.set_time_scheme(self.time_scheme);
+        match self.temp_bc {
+            Some(TempBc::Fixed(fieldbc)) => navier.set_temp_bc(fieldbc),
+            Some(TempBc::Flux(fieldbc)) => navier.set_temp_bc_flux(fieldbc),
+            None => (),
+        }
+        // Initial fields
+        if self.disturbance > 0. {
+            navier.random_disturbance(self.disturbance);
+        } else {
+            navier.temp.v.fill(0.);
+            if let Some(x) = &navier.fieldbc {
+                navier.temp.v = &navier.temp.v - &x.v;
+            }
+            navier.temp.forward();
+            navier.ux.v.fill(0.);
+            navier.ux.forward();
+            navier.uy.v.fill(0.);
+            navier.uy.forward();
+        }
+        if let Some(temp) = self.temp {
+            assert!(
+                temp.shape() == navier.temp.v.shape(),
+                "Shape mismatch of initial temperature."
+            );
+            navier.temp.v.assign(&temp);
+            if let Some(x) = &navier.fieldbc {
+                navier.temp.v = &navier.temp.v - &x.v;
+            }
+            navier.temp.forward();
+        }
+        if let Some([ux, uy]) = self.velocity {
+            assert!(
+                ux.shape() == navier.ux.v.shape() && uy.shape() == navier.uy.v.shape(),
+                "Shape mismatch of initial velocities."
+            );
+            navier.ux.v.assign(&ux);
+            navier.ux.forward();
+            navier.uy.v.assign(&uy);
+            navier.uy.forward();
+        }
+        if let Some(eta) = self.penalty_eta {
+            navier.penalty_eta = eta;
+        }
+        navier.rotation = self.rotation;
+        navier.write_intervall = self.write_intervall;
+        navier
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bases::{chebyshev, fourier_r2c};
+    use crate::field::Space2;
+    use crate::Integrate;
+
+    #[test]
+    fn test_builder_custom_temperature() {
+        let (nx, ny) = (16, 17);
+        // Linear conductive profile with a sinusoidal perturbation
+        let mut temp: FieldR2c = Field2::new(&Space2::new(&fourier_r2c(nx), &chebyshev(ny)));
+        let x = temp.x[0].clone();
+        let y = temp.x[1].clone();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                temp.v[[i, j]] = -0.5 * yi + 0.1 * xi.sin() * (1. - yi * yi);
+            }
+        }
+        temp.forward();
+        let mut navier = Navier2DPeriodicBuilder::new(nx, ny, 1e4, 1., 0.01, 1.)
+            .temperature(&temp)
+            .disturbance(0.)
+            .rotation(0.5)
+            .penalty_eta(1e-3)
+            .write_intervall(2.)
+            .build();
+        // Total temperature is recovered
+        navier.temp.backward();
+        let total = &navier.temp.v + &navier.fieldbc.as_ref().unwrap().v;
+        for (a, b) in total.iter().zip(temp.to_physical().iter()) {
+            assert!((a - b).abs() < 1e-10);
+        }
+        assert!(navier.ux.v.iter().all(|v| v.abs() < 1e-14));
+        assert_eq!(navier.rotation, Some(0.5));
+        assert!((navier.penalty_eta - 1e-3).abs() < f64::EPSILON);
+        assert_eq!(navier.write_intervall, Some(2.));
+        navier.update();
+        assert!(navier.check_finite());
+    }
+
+    #[test]
+    fn test_builder_defaults() {
+        let navier = Navier2DPeriodicBuilder::new(16, 17, 1e4, 1., 0.01, 1.)
+            .velocity_bc(VelocityBc::FreeSlip)
+            .time_scheme(TimeScheme::Rk3)
+            .build();
+        assert_eq!(navier.velocity_bc(), VelocityBc::FreeSlip);
+        assert_eq!(navier.time_scheme(), TimeScheme::Rk3);
+        assert!(navier.fieldbc.is_some());
+        assert_eq!(navier.rotation, None);
+    }
+}
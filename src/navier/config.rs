@@ -0,0 +1,213 @@
+//! # Run configuration
+//! Serializable configuration of [`Navier2D`] (periodic), which
+//! holds the parameters of a run, but not its fields. Store it
+//! next to the field files (json, toml, ...) to reproduce or
+//! resume experiments.
+//!
+//! Sponge layers, solid masks, symmetry and statistics are not
+//! part of the configuration.
+use super::navier::{
+    AdaptiveDt, Approximation, Navier2D, PressureMode, Space2R2c, TempConvForm, TimeScheme,
+    VelocityBc,
+};
+use ndarray::Array1;
+use num_complex::Complex;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Configuration of a periodic navier stokes run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NavierConfig {
+    /// Number of modes in x
+    pub nx: usize,
+    /// Number of modes in y
+    pub ny: usize,
+    /// Rayleigh number
+    pub ra: f64,
+    /// Prandtl number
+    pub pr: f64,
+    /// Time step size
+    pub dt: f64,
+    /// Aspect ratio L/H
+    pub aspect: f64,
+    /// Dealias fields
+    pub dealias: bool,
+    /// Form of temperature convection term
+    pub temp_conv_form: TempConvForm,
+    /// Incremental or recomputed pressure
+    pub pressure_mode: PressureMode,
+    /// Time integration scheme
+    pub time_scheme: TimeScheme,
+    /// Velocity boundary conditions
+    pub velocity_bc: VelocityBc,
+    /// Record residual of kinetic energy budget
+    pub energy_budget: bool,
+    /// Velocity of reference frame
+    pub frame_velocity: [f64; 2],
+    /// Rotation rate (coriolis force)
+    pub rotation: Option<f64>,
+    /// Reference density profile (anelastic), None for boussinesq
+    pub rho_ref: Option<Vec<f64>>,
+    /// Maximum number of retries of diverged steps
+    pub safe_step: Option<usize>,
+    /// Adaptive timestep
+    pub adaptive_dt: Option<AdaptiveDt>,
+    /// Hold temperature fixed
+    pub freeze_temp: bool,
+    /// Hold velocities fixed
+    pub freeze_velocity: bool,
+    /// Tolerance of steady state exit criterion
+    pub steady_tol: Option<f64>,
+    /// Reference time scale
+    pub reference_time: Option<f64>,
+    /// Time intervall for write fields
+    pub write_intervall: Option<f64>,
+    /// Write grid coordinates
+    pub write_grid: bool,
+    /// Directory of output files
+    pub output_dir: PathBuf,
+    /// Prefix of flow field files
+    pub file_prefix: String,
+    /// Gzip level of field datasets
+    pub compression: Option<u8>,
+    /// Probe locations
+    pub probes: Vec<[f64; 2]>,
+}
+
+impl Navier2D<Complex<f64>, Space2R2c> {
+    /// Construct solver from configuration, see [`NavierConfig`]
+    ///
+    /// # Example
+    /// ```
+    /// use rustpde::navier::Navier2D;
+    /// let navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+    /// let config = navier.to_config();
+    /// let navier = Navier2D::from_config(&config);
+    /// assert_eq!(navier.to_config(), config);
+    /// ```
+    pub fn from_config(config: &NavierConfig) -> Self {
+        let mut navier = Self::new_periodic(
+            config.nx,
+            config.ny,
+            config.ra,
+            config.pr,
+            config.dt,
+            config.aspect,
+        );
+        navier.dealias = config.dealias;
+        navier.temp_conv_form = config.temp_conv_form;
+        navier.pressure_mode = config.pressure_mode;
+        navier.set_time_scheme(config.time_scheme);
+        navier.set_velocity_bc(config.velocity_bc);
+        navier.energy_budget = config.energy_budget;
+        navier.frame_velocity = config.frame_velocity;
+        navier.rotation = config.rotation;
+        navier.set_approximation(match &config.rho_ref {
+            Some(rho_ref) => Approximation::Anelastic {
+                rho_ref: Array1::from(rho_ref.clone()),
+            },
+            None => Approximation::Boussinesq,
+        });
+        navier.safe_step = config.safe_step;
+        navier.adaptive_dt = config.adaptive_dt;
+        navier.freeze_temp = config.freeze_temp;
+        navier.freeze_velocity = config.freeze_velocity;
+        navier.steady_tol = config.steady_tol;
+        navier.reference_time = config.reference_time;
+        navier.write_intervall = config.write_intervall;
+        navier.write_grid = config.write_grid;
+        navier.output_dir = config.output_dir.clone();
+        navier.file_prefix = config.file_prefix.clone();
+        navier.compression = config.compression;
+        navier.probes = config.probes.clone();
+        navier
+    }
+
+    /// Return configuration of solver, see [`NavierConfig`]
+    pub fn to_config(&self) -> NavierConfig {
+        let shape = self.temp.v.shape();
+        NavierConfig {
+            nx: shape[0],
+            ny: shape[1],
+            ra: self.ra,
+            pr: self.pr,
+            dt: self.dt,
+            aspect: self.scale[0],
+            dealias: self.dealias,
+            temp_conv_form: self.temp_conv_form,
+            pressure_mode: self.pressure_mode,
+            time_scheme: self.time_scheme(),
+            velocity_bc: self.velocity_bc(),
+            energy_budget: self.energy_budget,
+            frame_velocity: self.frame_velocity,
+            rotation: self.rotation,
+            rho_ref: match self.approximation() {
+                Approximation::Anelastic { rho_ref } => Some(rho_ref.to_vec()),
+                Approximation::Boussinesq => None,
+            },
+            safe_step: self.safe_step,
+            adaptive_dt: self.adaptive_dt,
+            freeze_temp: self.freeze_temp,
+            freeze_velocity: self.freeze_velocity,
+            steady_tol: self.steady_tol,
+            reference_time: self.reference_time,
+            write_intervall: self.write_intervall,
+            write_grid: self.write_grid,
+            output_dir: self.output_dir.clone(),
+            file_prefix: self.file_prefix.clone(),
+            compression: self.compression,
+            probes: self.probes.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_roundtrip() {
+        let config = NavierConfig {
+            nx: 16,
+            ny: 17,
+            ra: 3e4,
+            pr: 0.7,
+            dt: 0.005,
+            aspect: 2.,
+            dealias: false,
+            temp_conv_form: TempConvForm::Conservative,
+            pressure_mode: PressureMode::Recompute,
+            time_scheme: TimeScheme::Rk3,
+            velocity_bc: VelocityBc::FreeSlip,
+            energy_budget: true,
+            frame_velocity: [0.5, 0.],
+            rotation: Some(0.5),
+            rho_ref: Some(vec![1.5; 17]),
+            safe_step: Some(3),
+            adaptive_dt: Some(AdaptiveDt {
+                cfl: 0.5,
+                dt_max: 0.02,
+            }),
+            freeze_temp: false,
+            freeze_velocity: true,
+            steady_tol: Some(1e-8),
+            reference_time: Some(0.4),
+            write_intervall: Some(2.),
+            write_grid: true,
+            output_dir: PathBuf::from("out"),
+            file_prefix: "run1_".to_string(),
+            compression: Some(4),
+            probes: vec![[0.1, 0.2], [3., -0.5]],
+        };
+        let navier = Navier2D::from_config(&config);
+        assert_eq!(navier.to_config(), config);
+        // Defaults of constructor
+        let navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        let config = navier.to_config();
+        assert_eq!(Navier2D::from_config(&config).to_config(), config);
+        // Serialize
+        let json = serde_json::to_string(&config).unwrap();
+        let config_json: NavierConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(config_json, config);
+    }
+}
@@ -41,6 +41,45 @@ where
     u * &deriv_field.v
 }
 
+/// Calculate d(u*v)dx (conservation form)
+///
+/// # Input
+///
+///   *v*:  ndarray (2D)
+///        Transported variable in physical space
+///
+///   *deriv_field*: Field<Space2D, 2>
+///        Orthogonal field used for transforms and derivatives
+///
+///   *u*:  ndarray (2D)
+///        Velocity field in physical space
+///
+///   *deriv*: [usize; 2]
+///        \[1,0\] for partial x, \[0,1\] for partial y
+///
+/// # Return
+/// Array of d(u*v)dx term in physical space.
+pub fn conv_term_conservative<T2, S>(
+    v: &Array2<f64>,
+    deriv_field: &mut FieldBase<f64, f64, T2, S, 2>,
+    u: &Array2<f64>,
+    deriv: [usize; 2],
+    scale: Option<[f64; 2]>,
+) -> Array2<f64>
+where
+    S: BaseSpace<f64, 2, Physical = f64, Spectral = T2>,
+    T2: Scalar,
+{
+    //u*v
+    deriv_field.v.assign(&(u * v));
+    deriv_field.forward();
+    //d(u*v)dx
+    let grad = deriv_field.gradient(deriv, scale);
+    deriv_field.vhat.assign(&grad);
+    deriv_field.backward();
+    deriv_field.v.to_owned()
+}
+
 #[cfg(test)]
 mod navier {
     use super::*;
@@ -117,4 +156,45 @@ mod navier {
         // Assert
         approx_eq(&conv, &field.v);
     }
+
+    #[test]
+    fn test_conv_temp_conservative_with_solid() {
+        use crate::navier::navier::{NavierConvection, TempConvForm};
+        use crate::navier::solid_masks::solid_cylinder_inner;
+        use crate::navier::Navier2D;
+
+        let (nx, ny) = (32, 33);
+        let mut budget = Vec::new();
+        for form in [TempConvForm::Advective, TempConvForm::Conservative].iter() {
+            let mut navier = Navier2D::new_periodic(nx, ny, 1e4, 1., 0.01, 1.);
+            navier.dealias = false;
+            navier.temp_conv_form = *form;
+            let x = navier.temp.x[0].to_owned();
+            let y = navier.temp.x[1].to_owned();
+            navier.solid = Some(solid_cylinder_inner(&x, &y, PI, 0., 0.2));
+            // Compressible velocity, as found near penalized solids
+            let mut ux = Array2::<f64>::zeros((nx, ny));
+            let uy = Array2::<f64>::zeros((nx, ny));
+            for (i, xi) in x.iter().enumerate() {
+                for (j, yi) in y.iter().enumerate() {
+                    ux[[i, j]] = xi.sin() * (1. - yi * yi);
+                    navier.temp.v[[i, j]] = xi.cos() * (1. - yi * yi);
+                }
+            }
+            navier.temp.forward();
+            // Subtract contribution of solid penalization
+            let conv = navier.conv_temp(&ux, &uy);
+            let penalty = navier.conv_temp(&Array2::zeros((nx, ny)), &Array2::zeros((nx, ny)));
+            navier.field.vhat.assign(&(&conv - &penalty));
+            navier.field.backward();
+            budget.push(navier.field.average());
+        }
+        // Conservative form does not change total heat
+        assert!(
+            budget[1].abs() < 0.05 * budget[0].abs(),
+            "Advective: {}, Conservative: {}",
+            budget[0],
+            budget[1]
+        );
+    }
 }
@@ -0,0 +1,79 @@
+//! # Two-point correlation
+//! Spatial autocorrelation along the periodic x-direction,
+//! e.g. to estimate integral length scales.
+//!
+//! By the Wiener-Khinchin theorem, the autocorrelation is the
+//! inverse fourier transform of the power spectrum:
+//! $$
+//! R(r, y) = \frac{1}{n} \sum_m u'(x_m, y) u'(x_m + r, y),
+//! $$
+//! where $u'$ is the fluctuation about the horizontal mean.
+use super::navier::{Navier2D, Space2R2c};
+use crate::bases::{fourier_r2c, Transform};
+use ndarray::{Array2, Axis};
+use num_complex::Complex;
+
+/// Field of [`Navier2D`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Component {
+    /// Horizontal velocity
+    Ux,
+    /// Vertical velocity
+    Uy,
+    /// Temperature
+    Temp,
+}
+
+impl Navier2D<Complex<f64>, Space2R2c> {
+    /// Returns the autocorrelation $R(r, y)$ of the fluctuations of
+    /// `component` along x, of shape (nx, ny). The separation
+    /// of row *j* is $r_j = x_j - x_0$.
+    ///
+    /// Row zero holds the variance, divide by it to obtain the
+    /// correlation coefficient.
+    pub fn autocorrelation_x(&mut self, component: Component) -> Array2<f64> {
+        let field = match component {
+            Component::Ux => &mut self.ux,
+            Component::Uy => &mut self.uy,
+            Component::Temp => &mut self.temp,
+        };
+        field.backward();
+        let nx = field.v.shape()[0];
+        let mut fourier = fourier_r2c::<f64>(nx);
+        let mut vhat: Array2<Complex<f64>> = fourier.forward(&field.v, 0);
+        // Remove horizontal mean
+        vhat.index_axis_mut(Axis(0), 0).fill(Complex::new(0., 0.));
+        let power = vhat.mapv(|v| Complex::new(v.norm_sqr() / nx as f64, 0.));
+        fourier.backward(&power, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_autocorrelation_single_wavelength() {
+        // u = (1 + cos(3x)) (1 - y^2), R = cos(3r) (1 - y^2)^2 / 2
+        let (nx, ny) = (16, 9);
+        let mut navier = Navier2D::new_periodic(nx, ny, 1e4, 1., 0.01, 1.);
+        let x = navier.ux.x[0].to_owned();
+        let y = navier.ux.x[1].to_owned();
+        navier.ux.with_physical_mut(|v| {
+            for (i, xi) in x.iter().enumerate() {
+                for (j, yi) in y.iter().enumerate() {
+                    v[[i, j]] = (1. + (3. * xi).cos()) * (1. - yi * yi);
+                }
+            }
+        });
+        let corr = navier.autocorrelation_x(Component::Ux);
+        assert_eq!(corr.shape(), &[nx, ny]);
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                let r = xi - x[0];
+                let expected = 0.5 * (3. * r).cos() * (1. - yi * yi).powi(2);
+                assert!((corr[[i, j]] - expected).abs() < 1e-10);
+            }
+        }
+    }
+}
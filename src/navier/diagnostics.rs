@@ -0,0 +1,145 @@
+//! # Convection diagnostics
+//! Nusselt and Reynolds numbers of convection solvers.
+//!
+//! Any solver which exposes its temperature, velocities and
+//! parameters via the accessor methods of [`ConvectionDiagnostics`]
+//! gets `eval_nu`, `eval_nuvol` and `eval_re` for free.
+use super::functions::{eval_nu, eval_nuvol, eval_re};
+use crate::field::{BaseSpace, Field2};
+use crate::types::Scalar;
+use std::ops::{Div, Mul};
+
+/// Nusselt and Reynolds numbers, evaluated from the fields of a solver
+pub trait ConvectionDiagnostics<T, S>
+where
+    S: BaseSpace<f64, 2, Physical = f64, Spectral = T>,
+    T: Scalar + Mul<f64, Output = T> + Div<f64, Output = T>,
+{
+    /// Temperature (without boundary condition field)
+    fn temp(&self) -> &Field2<T, S>;
+
+    /// Horizontal velocity
+    fn ux(&self) -> &Field2<T, S>;
+
+    /// Vertical velocity
+    fn uy(&self) -> &Field2<T, S>;
+
+    /// Field in the orthogonal (chebyshev) base, used for derivatives
+    fn work_field(&self) -> &Field2<T, S>;
+
+    /// Temperature boundary condition field
+    fn fieldbc(&self) -> &Option<Field2<T, S>>;
+
+    /// Diffusivity
+    fn ka(&self) -> f64;
+
+    /// Viscosity
+    fn nu(&self) -> f64;
+
+    /// Geometry scales
+    fn scale(&self) -> [f64; 2];
+
+    /// Returns Nusselt number (heat flux at the plates)
+    /// $$
+    /// Nu = \langle - dTdz \rangle\\_x (0/H))
+    /// $$
+    fn eval_nu(&self) -> f64 {
+        eval_nu(
+            self.temp(),
+            self.work_field(),
+            self.fieldbc(),
+            &self.scale(),
+        )
+    }
+
+    /// Returns volumetric Nusselt number
+    /// $$
+    /// Nuvol = \langle uy*T/kappa - dTdz \rangle\\_V
+    /// $$
+    fn eval_nuvol(&self) -> f64 {
+        eval_nuvol(
+            self.temp(),
+            self.uy(),
+            self.work_field(),
+            self.fieldbc(),
+            self.ka(),
+            &self.scale(),
+        )
+    }
+
+    /// Returns Reynolds number based on kinetic energy
+    fn eval_re(&self) -> f64 {
+        eval_re(
+            self.ux(),
+            self.uy(),
+            self.work_field(),
+            self.nu(),
+            &self.scale(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navier::navier::Space2R2c;
+    use crate::navier::Navier2D;
+    use num_complex::Complex;
+
+    /// Minimal type, which only holds the fields
+    struct Fields {
+        temp: Field2<Complex<f64>, Space2R2c>,
+        ux: Field2<Complex<f64>, Space2R2c>,
+        uy: Field2<Complex<f64>, Space2R2c>,
+        field: Field2<Complex<f64>, Space2R2c>,
+        fieldbc: Option<Field2<Complex<f64>, Space2R2c>>,
+        ka: f64,
+        nu: f64,
+        scale: [f64; 2],
+    }
+
+    impl ConvectionDiagnostics<Complex<f64>, Space2R2c> for Fields {
+        fn temp(&self) -> &Field2<Complex<f64>, Space2R2c> {
+            &self.temp
+        }
+        fn ux(&self) -> &Field2<Complex<f64>, Space2R2c> {
+            &self.ux
+        }
+        fn uy(&self) -> &Field2<Complex<f64>, Space2R2c> {
+            &self.uy
+        }
+        fn work_field(&self) -> &Field2<Complex<f64>, Space2R2c> {
+            &self.field
+        }
+        fn fieldbc(&self) -> &Option<Field2<Complex<f64>, Space2R2c>> {
+            &self.fieldbc
+        }
+        fn ka(&self) -> f64 {
+            self.ka
+        }
+        fn nu(&self) -> f64 {
+            self.nu
+        }
+        fn scale(&self) -> [f64; 2] {
+            self.scale
+        }
+    }
+
+    #[test]
+    fn test_diagnostics_of_custom_type() {
+        let navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        let fields = Fields {
+            temp: navier.temp.clone(),
+            ux: navier.ux.clone(),
+            uy: navier.uy.clone(),
+            field: navier.field.clone(),
+            fieldbc: navier.fieldbc.clone(),
+            ka: navier.ka,
+            nu: navier.nu,
+            scale: navier.scale,
+        };
+        assert!((fields.eval_nu() - navier.eval_nu()).abs() < f64::EPSILON);
+        assert!((fields.eval_nuvol() - navier.eval_nuvol()).abs() < f64::EPSILON);
+        assert!((fields.eval_re() - navier.eval_re()).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,206 @@
+//! # Forcing
+//! Steady body force and heat source, see [`Forcing`].
+//!
+//! ## Stochastic forcing
+//! Band-limited, white-in-time random forcing of the momentum
+//! equations, as commonly used for forced 2-D turbulence in
+//! doubly periodic domains of size 2pi x 2pi.
+//!
+//! The forcing is solenoidal, derived from a random streamfunction
+//! $\hat{\psi}$, which is nonzero only in the shell
+//! $|k - k_{shell}| < 1/2$:
+//! $$
+//! \hat{f}_x = i k_y \hat{\psi}, \quad \hat{f}_y = -i k_x \hat{\psi}
+//! $$
+//! Each step, the velocity is incremented by $f \sqrt{dt}$, where
+//! $f$ is normalized to $\frac{1}{2} \langle f \cdot f \rangle = \epsilon$.
+//! For white-in-time forcing, the mean energy injection rate is
+//! thus $\epsilon$, independent of the velocity field.
+//!
+//! Spectral layout: complex-to-complex along x (axis 0),
+//! real-to-complex along y (axis 1), see [`Space2Fourier`].
+use crate::bases::{fourier_c2c, fourier_r2c, BaseC2c, BaseR2c};
+use crate::field::{Field2, Space2};
+use ndarray::Array2;
+use ndarray_rand::rand::rngs::StdRng;
+use ndarray_rand::rand::SeedableRng;
+use ndarray_rand::rand_distr::Normal;
+use ndarray_rand::RandomExt;
+use num_complex::Complex;
+
+/// Doubly periodic space (fourier x fourier)
+pub type Space2Fourier = Space2<BaseC2c<f64>, BaseR2c<f64>>;
+
+/// Steady body force and heat source (physical space), which are
+/// added to the right hand side of the momentum and temperature
+/// equations of [`super::Navier2D`], see `Navier2D::forcing`.
+#[derive(Clone)]
+pub struct Forcing {
+    /// Body force \[fx, fy\]
+    pub force: [Array2<f64>; 2],
+    /// Heat source
+    pub heat: Array2<f64>,
+}
+
+/// Random forcing in a wavenumber shell
+#[derive(Clone)]
+pub struct StochasticForcing {
+    /// Center of forced wavenumber shell
+    pub k_shell: f64,
+    /// Energy injection rate
+    pub injection_rate: f64,
+    /// Seed of random number generator
+    pub seed: u64,
+    /// Random number generator
+    rng: StdRng,
+    /// Field for transforms
+    field: Field2<Complex<f64>, Space2Fourier>,
+}
+
+impl StochasticForcing {
+    /// Return new forcing
+    ///
+    /// # Arguments
+    ///
+    /// * `nx,ny` - The number of grid points in x and y -direction
+    ///
+    /// * `k_shell` - Center of forced wavenumber shell
+    ///
+    /// * `injection_rate` - Energy injection rate
+    ///
+    /// * `seed` - Seed of random number generator
+    pub fn new(nx: usize, ny: usize, k_shell: f64, injection_rate: f64, seed: u64) -> Self {
+        let field = Field2::new(&Space2::new(&fourier_c2c(nx), &fourier_r2c(ny)));
+        Self {
+            k_shell,
+            injection_rate,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            field,
+        }
+    }
+
+    /// Restart random sequence from `seed`
+    pub fn reset(&mut self) {
+        self.rng = StdRng::seed_from_u64(self.seed);
+    }
+
+    /// Returns spectral velocity increments \[ux, uy\] for a timestep
+    /// of size `dt`, to be added to the velocities after the step.
+    ///
+    /// # Panics
+    /// Panics if the shell contains no modes.
+    pub fn increment(&mut self, dt: f64) -> [Array2<Complex<f64>>; 2] {
+        let shape = self.field.vhat.raw_dim();
+        let (nx, ny) = (self.field.v.shape()[0], self.field.v.shape()[1]);
+        let re: Array2<f64> =
+            Array2::random_using(shape, Normal::new(0., 1.).unwrap(), &mut self.rng);
+        let im: Array2<f64> =
+            Array2::random_using(shape, Normal::new(0., 1.).unwrap(), &mut self.rng);
+        // Random streamfunction in shell
+        let mut psi = Array2::<Complex<f64>>::zeros(re.raw_dim());
+        for ((i, j), p) in psi.indexed_iter_mut() {
+            let (kx, ky) = wavenumbers(i, j, nx);
+            let is_nyquist = 2 * i == nx || 2 * j == ny;
+            let k = (kx * kx + ky * ky).sqrt();
+            if !is_nyquist && (k - self.k_shell).abs() < 0.5 {
+                *p = Complex::new(re[[i, j]], im[[i, j]]);
+            }
+        }
+        enforce_reality(&mut psi);
+        assert!(
+            psi.iter().any(|p| p.norm() > 0.),
+            "No modes in shell k = {}",
+            self.k_shell
+        );
+        // Solenoidal forcing
+        let mut fx = psi.clone();
+        let mut fy = psi;
+        for ((i, j), (x, y)) in fx.indexed_iter_mut().zip(fy.iter_mut()) {
+            let (kx, ky) = wavenumbers(i, j, nx);
+            *x *= Complex::new(0., ky);
+            *y *= Complex::new(0., -kx);
+        }
+        // Normalize: 1/2 <f.f> = injection_rate
+        let mut energy = 0.;
+        for f in [&fx, &fy].iter() {
+            self.field.vhat.assign(*f);
+            self.field.backward();
+            energy += 0.5 * self.field.v.mapv(|v| v * v).mean().unwrap();
+        }
+        let amp = (self.injection_rate / energy).sqrt() * dt.sqrt();
+        [fx * amp, fy * amp]
+    }
+}
+
+/// Returns wavenumbers (kx, ky) of mode (i, j)
+fn wavenumbers(i: usize, j: usize, nx: usize) -> (f64, f64) {
+    let kx = if 2 * i <= nx {
+        i as f64
+    } else {
+        i as f64 - nx as f64
+    };
+    (kx, j as f64)
+}
+
+/// Enforce hermitian symmetry $\hat{v}(-k_x, 0) = \hat{v}^*(k_x, 0)$
+/// of the ky = 0 modes, such that the field is real in physical space.
+pub fn enforce_reality(vhat: &mut Array2<Complex<f64>>) {
+    let nx = vhat.shape()[0];
+    vhat[[0, 0]].im = 0.;
+    for i in 1..(nx + 1) / 2 {
+        vhat[[nx - i, 0]] = vhat[[i, 0]].conj();
+    }
+    if nx % 2 == 0 {
+        vhat[[nx / 2, 0]].im = 0.;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forcing_injection_rate() {
+        let (nx, ny, dt, eps) = (32, 32, 0.01, 0.3);
+        let mut forcing = StochasticForcing::new(nx, ny, 4., eps, 1);
+        let mut field = Field2::new(&Space2::new(&fourier_c2c(nx), &fourier_r2c(ny)));
+        let n_steps = 10;
+        let mut injected = 0.;
+        for _ in 0..n_steps {
+            // Energy gain of fluid at rest
+            for f in forcing.increment(dt).iter() {
+                field.vhat.assign(f);
+                field.backward();
+                injected += 0.5 * field.v.mapv(|v| v * v).mean().unwrap();
+            }
+        }
+        let rate = injected / (n_steps as f64 * dt);
+        assert!((rate - eps).abs() < 1e-10 * eps, "{} != {}", rate, eps);
+        // Forcing is real and restricted to shell
+        let [fx, _] = forcing.increment(dt);
+        field.vhat.assign(&fx);
+        field.backward();
+        field.forward();
+        for ((i, j), (a, b)) in fx.indexed_iter().zip(field.vhat.iter()) {
+            assert!((a - b).norm() < 1e-10);
+            let (kx, ky) = wavenumbers(i, j, nx);
+            if ((kx * kx + ky * ky).sqrt() - 4.).abs() >= 0.5 {
+                assert!(a.norm() == 0.);
+            }
+        }
+    }
+
+    #[test]
+    fn test_forcing_reproducible() {
+        let mut forcing_a = StochasticForcing::new(16, 16, 3., 1., 42);
+        let mut forcing_b = StochasticForcing::new(16, 16, 3., 1., 42);
+        let mut forcing_c = StochasticForcing::new(16, 16, 3., 1., 43);
+        let fa = forcing_a.increment(0.1);
+        assert_eq!(fa, forcing_b.increment(0.1));
+        assert!(fa != forcing_c.increment(0.1));
+        // reset restarts the sequence
+        forcing_a.reset();
+        assert_eq!(fa, forcing_a.increment(0.1));
+    }
+}
@@ -1,8 +1,11 @@
 //! Some useful post-processing functions
+//!
+//! All functions leave the supplied fields untouched,
+//! so they can be evaluated from shared references.
 use crate::field::{BaseSpace, FieldBase};
 use crate::types::FloatNum;
 use crate::types::Scalar;
-use ndarray::ScalarOperand;
+use ndarray::{Array1, Array2, Axis, ScalarOperand};
 use num_complex::Complex;
 use std::ops::{Div, Mul};
 /// Returns Nusselt number (heat flux at the plates)
@@ -10,8 +13,8 @@ use std::ops::{Div, Mul};
 /// Nu = \langle - dTdz \rangle\\_x (0/H))
 /// $$
 pub fn eval_nu<A, T2, S>(
-    temp: &mut FieldBase<A, A, T2, S, 2>,
-    field: &mut FieldBase<A, A, T2, S, 2>,
+    temp: &FieldBase<A, A, T2, S, 2>,
+    field: &FieldBase<A, A, T2, S, 2>,
     tempbc: &Option<FieldBase<A, A, T2, S, 2>>,
     scale: &[A; 2],
 ) -> A
@@ -22,7 +25,7 @@ where
     T2: Scalar + Mul<A, Output = T2>,
 {
     let two = A::one() + A::one();
-    //self.temp.backward();
+    let mut field = field.clone();
     field.vhat.assign(&temp.to_ortho());
     if let Some(x) = &tempbc {
         field.vhat = &field.vhat + &x.to_ortho();
@@ -40,9 +43,9 @@ where
 /// Nuvol = \langle uy*T/kappa - dTdz \rangle\\_V
 /// $$
 pub fn eval_nuvol<A, T2, S>(
-    temp: &mut FieldBase<A, A, T2, S, 2>,
-    uy: &mut FieldBase<A, A, T2, S, 2>,
-    field: &mut FieldBase<A, A, T2, S, 2>,
+    temp: &FieldBase<A, A, T2, S, 2>,
+    uy: &FieldBase<A, A, T2, S, 2>,
+    field: &FieldBase<A, A, T2, S, 2>,
     tempbc: &Option<FieldBase<A, A, T2, S, 2>>,
     kappa: A,
     scale: &[A; 2],
@@ -54,6 +57,7 @@ where
     T2: Scalar + Div<A, Output = T2>,
 {
     let two = A::one() + A::one();
+    let mut field = field.clone();
     // temp
     field.vhat.assign(&temp.to_ortho());
     if let Some(x) = &tempbc {
@@ -61,8 +65,7 @@ where
     }
     field.backward();
     // uy
-    uy.backward();
-    let uy_temp = &field.v * &uy.v;
+    let uy_temp = &field.v * &uy.to_physical();
     // dtdz
     let dtdz = field.gradient([0, 1], None) / (scale[1] * -A::one());
     field.vhat.assign(&dtdz);
@@ -80,9 +83,9 @@ where
 /// U = \sqrt{(ux^2 + uy^2)}
 /// $$
 pub fn eval_re<A, T2, S>(
-    ux: &mut FieldBase<A, A, T2, S, 2>,
-    uy: &mut FieldBase<A, A, T2, S, 2>,
-    field: &mut FieldBase<A, A, T2, S, 2>,
+    ux: &FieldBase<A, A, T2, S, 2>,
+    uy: &FieldBase<A, A, T2, S, 2>,
+    field: &FieldBase<A, A, T2, S, 2>,
     nu: A,
     scale: &[A; 2],
 ) -> A
@@ -91,11 +94,241 @@ where
     Complex<A>: ScalarOperand,
     S: BaseSpace<A, 2, Physical = A, Spectral = T2>,
 {
-    ux.backward();
-    uy.backward();
-    let ekin = &ux.v.mapv(|x| x.powi(2)) + &uy.v.mapv(|x| x.powi(2));
+    let mut field = field.clone();
+    let ekin = &ux.to_physical().mapv(|x| x.powi(2)) + &uy.to_physical().mapv(|x| x.powi(2));
     field.v.assign(&ekin.mapv(A::sqrt));
     let two = A::one() + A::one();
     field.v *= two * scale[1] / nu;
     field.average()
 }
+
+/// Returns average of `v` along the first (horizontal) axis,
+/// weighted by the grid spacings `dx`.
+///
+/// The average is normalized by the sum of the weights, which
+/// is correct for periodic and non-periodic axes.
+pub fn horizontal_mean<A: FloatNum>(v: &Array2<A>, dx: &Array1<A>) -> Array1<A> {
+    let length = dx.sum();
+    let mut mean = Array1::<A>::zeros(v.shape()[1]);
+    for (lane, w) in v.axis_iter(Axis(0)).zip(dx.iter()) {
+        mean.scaled_add(*w / length, &lane);
+    }
+    mean
+}
+
+/// Returns volume average of `v`, weighted by the grid
+/// spacings `dx` and `dy`. See [`horizontal_mean`].
+pub fn volume_mean<A: FloatNum>(v: &Array2<A>, dx: &Array1<A>, dy: &Array1<A>) -> A {
+    let profile = horizontal_mean(v, dx);
+    (&profile * dy).sum() / dy.sum()
+}
+
+/// Returns viscous dissipation
+/// $$
+/// \epsilon = \nu \langle |grad(ux)|^2 + |grad(uy)|^2 \rangle\_V
+/// $$
+/// which equals the dissipation rate of kinetic energy
+/// for no-slip or periodic boundaries. Evaluated from the
+/// spectral coefficients, see `FieldBase::mean_square`.
+pub fn eval_dissipation<T2, S>(
+    ux: &FieldBase<f64, f64, T2, S, 2>,
+    uy: &FieldBase<f64, f64, T2, S, 2>,
+    field: &FieldBase<f64, f64, T2, S, 2>,
+    nu: f64,
+    scale: &[f64; 2],
+) -> f64
+where
+    S: BaseSpace<f64, 2, Physical = f64, Spectral = T2>,
+    T2: Scalar + Into<Complex<f64>>,
+{
+    let mut field = field.clone();
+    let mut grad2 = 0.;
+    for u in [ux, uy].iter() {
+        for deriv in [[1, 0], [0, 1]].iter() {
+            field.vhat.assign(&u.gradient(*deriv, Some(*scale)));
+            grad2 += field.mean_square();
+        }
+    }
+    grad2 * nu
+}
+
+/// Returns horizontally averaged convective heat flux
+/// $$
+/// \langle uy*T \rangle\_x (y)
+/// $$
+pub fn eval_convective_flux_profile<A, T2, S>(
+    temp: &FieldBase<A, A, T2, S, 2>,
+    uy: &FieldBase<A, A, T2, S, 2>,
+    field: &FieldBase<A, A, T2, S, 2>,
+    tempbc: &Option<FieldBase<A, A, T2, S, 2>>,
+) -> Array1<A>
+where
+    A: FloatNum,
+    Complex<A>: ScalarOperand,
+    S: BaseSpace<A, 2, Physical = A, Spectral = T2>,
+    T2: Scalar,
+{
+    let mut field = field.clone();
+    field.vhat.assign(&temp.to_ortho());
+    if let Some(x) = &tempbc {
+        field.vhat = &field.vhat + &x.to_ortho();
+    }
+    field.backward();
+    let uy_temp = &field.v * &uy.to_physical();
+    horizontal_mean(&uy_temp, &field.dx[0])
+}
+
+/// Returns available potential energy, i.e. the potential energy
+/// in excess of the adiabatically sorted (minimum) state
+/// $$
+/// APE = \langle T (y^* - y) \rangle\_V
+/// $$
+/// The buoyancy equals the temperature in the present
+/// nondimensionalization, $y^*$ is the height of a fluid parcel
+/// in the sorted state (coldest fluid at the bottom).
+pub fn eval_ape<A, T2, S>(
+    temp: &FieldBase<A, A, T2, S, 2>,
+    field: &FieldBase<A, A, T2, S, 2>,
+    tempbc: &Option<FieldBase<A, A, T2, S, 2>>,
+) -> A
+where
+    A: FloatNum,
+    Complex<A>: ScalarOperand,
+    S: BaseSpace<A, 2, Physical = A, Spectral = T2>,
+    T2: Scalar,
+{
+    let two = A::one() + A::one();
+    let mut field = field.clone();
+    field.vhat.assign(&temp.to_ortho());
+    if let Some(x) = &tempbc {
+        field.vhat = &field.vhat + &x.to_ortho();
+    }
+    field.backward();
+    let (y, dx, dy) = (&temp.x[1], &temp.dx[0], &temp.dx[1]);
+    let y_min = y.iter().fold(y[0], |m, v| m.min(*v));
+    let length = dx.sum();
+    // Parcels (temperature, height, volume), sorted by temperature
+    let mut parcels: Vec<(A, A, A)> = field
+        .v
+        .indexed_iter()
+        .map(|((i, j), t)| (*t, y[j], dx[i] * dy[j]))
+        .collect();
+    parcels.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+    let (mut ape, mut volume) = (A::zero(), A::zero());
+    for (t, y_i, vol) in parcels {
+        let y_sorted = y_min + (volume + vol / two) / length;
+        ape = ape + t * (y_sorted - y_i) * vol;
+        volume = volume + vol;
+    }
+    ape / volume
+}
+
+/// Returns conversion rate from potential to kinetic energy,
+/// i.e. the buoyancy flux
+/// $$
+/// C = \langle uy*T \rangle\_V
+/// $$
+pub fn eval_conversion_rate<A, T2, S>(
+    temp: &FieldBase<A, A, T2, S, 2>,
+    uy: &FieldBase<A, A, T2, S, 2>,
+    field: &FieldBase<A, A, T2, S, 2>,
+    tempbc: &Option<FieldBase<A, A, T2, S, 2>>,
+) -> A
+where
+    A: FloatNum,
+    Complex<A>: ScalarOperand,
+    S: BaseSpace<A, 2, Physical = A, Spectral = T2>,
+    T2: Scalar,
+{
+    let mut field = field.clone();
+    field.vhat.assign(&temp.to_ortho());
+    if let Some(x) = &tempbc {
+        field.vhat = &field.vhat + &x.to_ortho();
+    }
+    field.backward();
+    let uy_temp = &field.v * &uy.to_physical();
+    volume_mean(&uy_temp, &field.dx[0], &field.dx[1])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::navier::{ConvectionDiagnostics, Navier2D};
+
+    #[test]
+    fn test_convective_flux_profile() {
+        // uy = sin(x)(1-y^2), T = sin(x)(1-y^2)y + Tbc(y)
+        // => <uy*T>_x = (1-y^2)^2 y / 2
+        let mut navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        let x = navier.temp.x[0].to_owned();
+        let y = navier.temp.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                navier.uy.v[[i, j]] = xi.sin() * (1. - yi * yi);
+                navier.temp.v[[i, j]] = xi.sin() * (1. - yi * yi) * yi;
+            }
+        }
+        navier.uy.forward();
+        navier.temp.forward();
+        let profile = navier.convective_flux_profile();
+        assert_eq!(profile.len(), y.len());
+        for (p, yi) in profile.iter().zip(y.iter()) {
+            let expected = 0.5 * (1. - yi * yi).powi(2) * yi;
+            assert!((p - expected).abs() < 1e-10, "{} != {}", p, expected);
+        }
+    }
+
+    #[test]
+    fn test_diagnostics_do_not_mutate() {
+        let mut navier = Navier2D::new(17, 17, 1e4, 1., 0.01, 1., true);
+        navier.set_velocity(0.2, 1., 1.);
+        navier.set_temperature(0.2, 1., 1.);
+        // Make physical fields inconsistent with spectral ones
+        for field in &mut [&mut navier.temp, &mut navier.ux, &mut navier.uy] {
+            field.v.fill(1.);
+        }
+        let (temp_v, temp_vhat) = (navier.temp.v.clone(), navier.temp.vhat.clone());
+        let (ux_v, uy_v) = (navier.ux.v.clone(), navier.uy.v.clone());
+
+        let navier_ref = &navier;
+        let nu = [navier_ref.eval_nu(), navier_ref.eval_nu()];
+        let nuvol = [navier_ref.eval_nuvol(), navier_ref.eval_nuvol()];
+        let re = [navier_ref.eval_re(), navier_ref.eval_re()];
+        assert!((nu[0] - nu[1]).abs() < 1e-12);
+        assert!((nuvol[0] - nuvol[1]).abs() < 1e-12);
+        assert!((re[0] - re[1]).abs() < 1e-12);
+        assert!(navier.temp.v == temp_v);
+        assert!(navier.temp.vhat == temp_vhat);
+        assert!(navier.ux.v == ux_v);
+        assert!(navier.uy.v == uy_v);
+    }
+
+    #[test]
+    fn test_available_potential_energy() {
+        // T = -y/2 (conduction, unstable): sorted state y* = -y
+        // => APE = <y^2> = 1/3
+        let mut navier = Navier2D::new_periodic(16, 65, 1e4, 1., 0.01, 1.);
+        let ape = navier.available_potential_energy();
+        assert!((ape - 1. / 3.).abs() < 1e-2, "{}", ape);
+        assert_eq!(navier.diagnostics["APE"], vec![ape]);
+    }
+
+    #[test]
+    fn test_conversion_rate() {
+        // uy = cos(x)(1-y^2), T = cos(x)(1-y^2) - y/2
+        // => <uy*T> = <(1-y^2)^2> / 2 = 4/15
+        let mut navier = Navier2D::new_periodic(16, 49, 1e4, 1., 0.01, 1.);
+        let x = navier.temp.x[0].to_owned();
+        let y = navier.temp.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                navier.uy.v[[i, j]] = xi.cos() * (1. - yi * yi);
+                navier.temp.v[[i, j]] = xi.cos() * (1. - yi * yi);
+            }
+        }
+        navier.uy.forward();
+        navier.temp.forward();
+        let conversion = navier.conversion_rate();
+        assert!((conversion - 4. / 15.).abs() < 5e-3, "{}", conversion);
+        assert_eq!(navier.diagnostics["Conversion"], vec![conversion]);
+    }
+}
@@ -0,0 +1,346 @@
+//! # Linear stability of the conductive state
+//! Linearized Rayleigh-Benard equations for a single
+//! horizontal wavenumber k, in thermal diffusion units and
+//! a layer of unit height:
+//! $$
+//! \sigma \mathcal{L} w = Pr \mathcal{L}^2 w - Ra Pr k^2 \theta
+//! $$
+//! $$
+//! \sigma \theta = w + \mathcal{L} \theta
+//! $$
+//! with $\mathcal{L} = D^2 - k^2$.
+//!
+//! The operators are discretized in the same chebyshev galerkin
+//! spaces as the fields of [`Navier2D`], and made banded with the
+//! same pseudoinverse preconditioners as its solvers:
+//! - Temperature: `cheb_dirichlet`, preconditioned with the
+//!   pseudoinverse of $D^2$, see [`crate::solver::Hholtz`].
+//! - Rigid plates (w = dw/dz = 0): `cheb_biharmonic`, preconditioned
+//!   with the pseudoinverse of $D^4$, see [`crate::solver::Biharmonic`].
+//! - Free-slip plates (w = d2w/dz2 = 0): the velocity equation is
+//!   split into $\mathcal{L} w = v$, with w and v in `cheb_dirichlet`,
+//!   and w is eliminated.
+//!
+//! This yields the generalized eigenvalue problem
+//! $A x = \sigma B x$, with an invertible $B$.
+//!
+//! # Example
+//! Critical Rayleigh number for rigid plates
+//! ```
+//! use rustpde::navier::linear_stability::{LinearStability, StabilityBc};
+//! let stability = LinearStability::new(24, 1., StabilityBc::Rigid);
+//! let (ra_c, k_c) = stability.critical_rayleigh([2.0, 4.5]);
+//! assert!((ra_c - 1707.76).abs() < 10.);
+//! assert!((k_c - 3.117).abs() < 0.05);
+//! ```
+use super::navier::{Navier2D, Space2R2c, VelocityBc};
+use crate::bases::{cheb_biharmonic, cheb_dirichlet, chebyshev, Basics, LaplacianInverse};
+use crate::field::{Field1, Space1};
+use crate::solver::utils::inv;
+use ndarray::{s, Array1, Array2};
+use num_complex::Complex;
+use std::f64::consts::PI;
+
+/// Velocity boundary conditions at the plates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StabilityBc {
+    /// No-slip: w = dw/dz = 0
+    Rigid,
+    /// Free-slip: w = d2w/dz2 = 0
+    FreeSlip,
+}
+
+/// Preconditioned operators of the fourth order
+/// velocity equation (rigid plates)
+#[derive(Clone)]
+struct RigidOperators {
+    /// Identity
+    mass: Array2<f64>,
+    /// Second derivative
+    d2: Array2<f64>,
+    /// Fourth derivative
+    d4: Array2<f64>,
+    /// Temperature in velocity equation
+    buoyancy: Array2<f64>,
+    /// Velocity in temperature equation
+    advection: Array2<f64>,
+}
+
+/// Container for linear stability analysis
+#[derive(Clone)]
+pub struct LinearStability {
+    /// Number of chebyshev grid points
+    pub n: usize,
+    /// Prandtl number
+    pub pr: f64,
+    /// Velocity boundary condition
+    pub bc: StabilityBc,
+    /// Identity (dirichlet, preconditioned)
+    mass: Array2<f64>,
+    /// Second derivative (dirichlet, preconditioned)
+    d2: Array2<f64>,
+    /// Velocity equation, if rigid
+    rigid: Option<RigidOperators>,
+}
+
+impl LinearStability {
+    /// Return new linear stability problem
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - Number of chebyshev grid points
+    ///
+    /// * `pr` - Prandtl number
+    ///
+    /// * `bc` - Velocity boundary condition
+    pub fn new(n: usize, pr: f64, bc: StabilityBc) -> Self {
+        // Same operators as the helmholtz solver of the temperature
+        let field: Field1<f64, _> = Field1::new(&Space1::new(&cheb_dirichlet(n)));
+        let (mass, d2, precond) = field.ingredients_for_hholtz(0);
+        // Layer of unit height: d/dz = 2 d/dx
+        let d2 = d2 * 4.;
+        let rigid = match bc {
+            StabilityBc::FreeSlip => None,
+            StabilityBc::Rigid => {
+                // Same preconditioner as the biharmonic solver
+                let base = cheb_biharmonic::<f64>(n);
+                let stencil = base.mass();
+                let pinv = base.laplace_inv();
+                let pinv4 = pinv.dot(&pinv).slice(s![4.., ..]).to_owned();
+                let lap = chebyshev::<f64>(n).laplace();
+                let w_d2 = pinv4.dot(&lap);
+                Some(RigidOperators {
+                    mass: pinv4.dot(&stencil),
+                    d2: w_d2.dot(&stencil) * 4.,
+                    d4: w_d2.dot(&lap).dot(&stencil) * 16.,
+                    buoyancy: pinv4.dot(&cheb_dirichlet::<f64>(n).mass()),
+                    advection: precond.unwrap().dot(&stencil),
+                })
+            }
+        };
+        Self {
+            n,
+            pr,
+            bc,
+            mass,
+            d2,
+            rigid,
+        }
+    }
+
+    /// Number of velocity unknowns, i.e. the size of the
+    /// first block of x
+    fn n_velocity(&self) -> usize {
+        self.rigid
+            .as_ref()
+            .map_or(self.mass.shape()[0], |r| r.mass.shape()[0])
+    }
+
+    /// Return matrices (A, B) of the generalized eigenvalue
+    /// problem A x = sigma B x, with x = \[w, theta\] (rigid),
+    /// respectively x = \[v, theta\] (free-slip), in galerkin
+    /// coefficients.
+    pub fn assemble(&self, ra: f64, k: f64) -> (Array2<f64>, Array2<f64>) {
+        let (mw, m) = (self.n_velocity(), self.mass.shape()[0]);
+        let lap = &self.d2 - &(&self.mass * (k * k));
+        let (a00, a01, a10, b00) = match &self.rigid {
+            Some(r) => (
+                (&r.d4 - &(&r.d2 * (2. * k * k)) + &r.mass * k.powi(4)) * self.pr,
+                &r.buoyancy * (-ra * self.pr * k * k),
+                r.advection.to_owned(),
+                &r.d2 - &(&r.mass * (k * k)),
+            ),
+            None => (
+                &lap * self.pr,
+                &self.mass * (-ra * self.pr * k * k),
+                self.mass.dot(&inv(&lap)).dot(&self.mass),
+                self.mass.to_owned(),
+            ),
+        };
+        let mut a = Array2::<f64>::zeros((mw + m, mw + m));
+        let mut b = Array2::<f64>::zeros((mw + m, mw + m));
+        a.slice_mut(s![..mw, ..mw]).assign(&a00);
+        a.slice_mut(s![..mw, mw..]).assign(&a01);
+        a.slice_mut(s![mw.., ..mw]).assign(&a10);
+        a.slice_mut(s![mw.., mw..]).assign(&lap);
+        b.slice_mut(s![..mw, ..mw]).assign(&b00);
+        b.slice_mut(s![mw.., mw..]).assign(&self.mass);
+        (a, b)
+    }
+
+    /// Returns the largest growth rate (real part of the
+    /// leading eigenvalue) for Rayleigh number and wavenumber.
+    pub fn growth_rate(&self, ra: f64, k: f64) -> f64 {
+        use ndarray_linalg::EigVals;
+        let (a, b) = self.assemble(ra, k);
+        let c = inv(&b).dot(&a);
+        let eval = c.eigvals().unwrap();
+        eval.iter().map(|e| e.re).fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    /// Returns the amplification factors of a time step `dt`
+    /// (in thermal diffusion units) for Rayleigh number and wavenumber.
+    ///
+    /// Mirrors the time scheme of `Navier2D`: diffusion is
+    /// implicit, buoyancy and the advection of the conductive
+    /// temperature gradient are explicit (euler), i.e.
+    /// $$
+    /// (B - dt A\_{imp}) x\_{new} = (B + dt A\_{exp}) x
+    /// $$
+    /// The step is stable if all factors lie within the unit circle.
+    pub fn amplification_factors(&self, ra: f64, k: f64, dt: f64) -> Array1<Complex<f64>> {
+        use ndarray_linalg::EigVals;
+        let mw = self.n_velocity();
+        let (a, b) = self.assemble(ra, k);
+        let mut a_imp = Array2::<f64>::zeros(a.raw_dim());
+        a_imp
+            .slice_mut(s![..mw, ..mw])
+            .assign(&a.slice(s![..mw, ..mw]));
+        a_imp
+            .slice_mut(s![mw.., mw..])
+            .assign(&a.slice(s![mw.., mw..]));
+        let a_exp = &a - &a_imp;
+        let g = inv(&(&b - &(a_imp * dt))).dot(&(&b + &(a_exp * dt)));
+        g.eigvals()
+            .unwrap()
+            .iter()
+            .map(|e| Complex::new(e.re, e.im))
+            .collect()
+    }
+    /// Returns Rayleigh number for which the growth rate
+    /// vanishes at wavenumber k (bisection).
+    pub fn neutral_rayleigh(&self, k: f64) -> f64 {
+        let (mut lo, mut hi) = (0., 1e3);
+        while self.growth_rate(hi, k) < 0. {
+            lo = hi;
+            hi *= 2.;
+        }
+        for _ in 0..60 {
+            let mid = 0.5 * (lo + hi);
+            if self.growth_rate(mid, k) < 0. {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+            if (hi - lo) < 1e-8 * hi {
+                break;
+            }
+        }
+        0.5 * (lo + hi)
+    }
+
+    /// Returns critical Rayleigh number and wavenumber, i.e. the
+    /// minimum of the neutral curve within `k_range` \[k_min, k_max\].
+    pub fn critical_rayleigh(&self, k_range: [f64; 2]) -> (f64, f64) {
+        // Golden section search
+        let gr = (5f64.sqrt() - 1.) / 2.;
+        let (mut a, mut b) = (k_range[0], k_range[1]);
+        let mut c = b - gr * (b - a);
+        let mut d = a + gr * (b - a);
+        let (mut fc, mut fd) = (self.neutral_rayleigh(c), self.neutral_rayleigh(d));
+        while (b - a).abs() > 1e-4 {
+            if fc < fd {
+                b = d;
+                d = c;
+                fd = fc;
+                c = b - gr * (b - a);
+                fc = self.neutral_rayleigh(c);
+            } else {
+                a = c;
+                c = d;
+                fc = fd;
+                d = a + gr * (b - a);
+                fd = self.neutral_rayleigh(d);
+            }
+        }
+        let k = 0.5 * (a + b);
+        (self.neutral_rayleigh(k), k)
+    }
+}
+
+impl Navier2D<Complex<f64>, Space2R2c> {
+    /// Returns the amplification factors of one time step, linearized
+    /// about the conductive state, for all resolved horizontal wavenumbers.
+    ///
+    /// Plot them against the unit circle to verify that `dt` is safe.
+    /// See [`LinearStability::amplification_factors`]; the pressure
+    /// projection and dealiasing are not part of the model.
+    pub fn operator_spectrum(&self) -> Vec<Complex<f64>> {
+        let (nx, ny) = (self.temp.vhat.shape()[0], self.temp.v.shape()[1]);
+        let bc = match self.velocity_bc() {
+            VelocityBc::NoSlip => StabilityBc::Rigid,
+            VelocityBc::FreeSlip => StabilityBc::FreeSlip,
+        };
+        let stability = LinearStability::new(ny, self.pr, bc);
+        // Navier2D: height 2, free-fall time units
+        // LinearStability: height 1, thermal diffusion time units
+        let dt = self.dt * self.ka / 4.;
+        let length = PI * self.scale[0];
+        let mut spectrum = Vec::new();
+        for j in 0..nx {
+            let k = 2. * PI * j as f64 / length;
+            spectrum.extend(stability.amplification_factors(self.ra, k, dt).iter());
+        }
+        spectrum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_critical_rayleigh_rigid() {
+        let stability = LinearStability::new(24, 1., StabilityBc::Rigid);
+        let (ra_c, k_c) = stability.critical_rayleigh([2.0, 4.5]);
+        assert!((ra_c - 1707.76).abs() < 5., "Got Ra_c = {}", ra_c);
+        assert!((k_c - 3.117).abs() < 0.05, "Got k_c = {}", k_c);
+    }
+
+    #[test]
+    fn test_critical_rayleigh_free_slip() {
+        let stability = LinearStability::new(24, 1., StabilityBc::FreeSlip);
+        let (ra_c, k_c) = stability.critical_rayleigh([1.0, 4.0]);
+        let ra_exact = 27. / 4. * PI.powi(4);
+        let k_exact = PI / 2f64.sqrt();
+        assert!((ra_c - ra_exact).abs() < 2., "Got Ra_c = {}", ra_c);
+        assert!((k_c - k_exact).abs() < 0.05, "Got k_c = {}", k_c);
+    }
+
+    #[test]
+    fn test_amplification_factors_diffusion() {
+        // Ra = 0: decoupled diffusion of w and theta, with
+        // eigenvalues -(n^2 pi^2 + k^2) (free-slip),
+        // i.e. implicit euler factors 1 / (1 + dt * (n^2 pi^2 + k^2))
+        let (pr, k, dt) = (2., 1.5, 0.01);
+        let stability = LinearStability::new(24, pr, StabilityBc::FreeSlip);
+        let factors = stability.amplification_factors(0., k, dt);
+        assert!(factors.iter().all(|g| g.im.abs() < 1e-8));
+        for n in 1..4 {
+            let lam = (n * n) as f64 * PI * PI + k * k;
+            for expected in [1. / (1. + dt * lam), 1. / (1. + dt * pr * lam)].iter() {
+                assert!(
+                    factors.iter().any(|g| (g.re - expected).abs() < 1e-8),
+                    "Missing factor {}",
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_operator_spectrum_subcritical() {
+        let navier = Navier2D::new_periodic(16, 17, 1e3, 1., 0.01, 1.);
+        let spectrum = navier.operator_spectrum();
+        // rigid: (ny - 4) velocity and (ny - 2) temperature modes
+        assert_eq!(spectrum.len(), 9 * (2 * 17 - 6));
+        assert!(spectrum.iter().all(|g| g.norm() < 1.));
+    }
+
+    #[test]
+    fn test_growth_rate_sign() {
+        let stability = LinearStability::new(24, 1., StabilityBc::FreeSlip);
+        assert!(stability.growth_rate(500., 2.2) < 0.);
+        assert!(stability.growth_rate(800., 2.2) > 0.);
+    }
+}
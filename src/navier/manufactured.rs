@@ -0,0 +1,132 @@
+//! # Manufactured solutions
+//! Verify the solver against a prescribed, steady solution
+//! $(u, v, T)$. Its residual in the governing equations is
+//! added as forcing, such that $(u, v, T)$ solves the forced
+//! equations exactly:
+//! $$
+//! f_u = (\mathbf{u} - \mathbf{U}_0) \cdot \nabla u - \nu \nabla^2 u,
+//! $$
+//! $$
+//! f_v = (\mathbf{u} - \mathbf{U}_0) \cdot \nabla v - \nu \nabla^2 v - T,
+//! $$
+//! $$
+//! q = (\mathbf{u} - \mathbf{U}_0) \cdot \nabla T - \kappa \nabla^2 T,
+//! $$
+//! where $\mathbf{U}_0$ is the frame velocity. The velocity must be
+//! divergence free and the pressure is assumed to be constant
+//! (Boussinesq approximation).
+//!
+//! The derivatives are evaluated with the spectral derivative
+//! operators, so that the error of a simulation, started from
+//! the manufactured solution, converges with the resolution.
+use super::forcing::Forcing;
+use super::navier::Navier2D;
+use crate::field::{BaseSpace, Field2};
+use crate::types::Scalar;
+use ndarray::Array2;
+
+impl<T, S> Navier2D<T, S>
+where
+    T: Scalar,
+    S: BaseSpace<f64, 2, Physical = f64, Spectral = T>,
+{
+    /// Returns forcing, such that the steady fields `ux`, `uy` and
+    /// `temp`, given as functions of the (scaled) coordinates, solve
+    /// the governing equations. Set it as `navier.forcing`.
+    ///
+    /// `temp` is the total temperature, including the boundary
+    /// field `fieldbc`.
+    pub fn manufactured_forcing<U, V, W>(&self, ux: U, uy: V, temp: W) -> Forcing
+    where
+        U: Fn(f64, f64) -> f64,
+        V: Fn(f64, f64) -> f64,
+        W: Fn(f64, f64) -> f64,
+    {
+        let mut field = self.field.clone();
+        let (x, y) = (&self.ux.x[0], &self.ux.x[1]);
+        let eval = |f: &dyn Fn(f64, f64) -> f64| {
+            Array2::from_shape_fn((x.len(), y.len()), |(i, j)| f(x[i], y[j]))
+        };
+        // Physical space derivative of the field's current coefficients
+        let deriv = |field: &Field2<T, S>, deriv: [usize; 2]| {
+            let mut dfield = field.clone();
+            dfield.vhat.assign(&field.gradient(deriv, Some(self.scale)));
+            dfield.backward();
+            dfield.v
+        };
+        let u = eval(&ux);
+        let v = eval(&uy);
+        let (u0, v0) = (
+            u.mapv(|u| u - self.frame_velocity[0]),
+            v.mapv(|v| v - self.frame_velocity[1]),
+        );
+        // Residual of advection-diffusion equation for f
+        let mut residual = |f: &Array2<f64>, diffusivity: f64| {
+            field.v.assign(f);
+            field.forward();
+            let mut res = &u0 * &deriv(&field, [1, 0]) + &v0 * &deriv(&field, [0, 1]);
+            res -= &((deriv(&field, [2, 0]) + deriv(&field, [0, 2])) * diffusivity);
+            res
+        };
+        let t = eval(&temp);
+        let fx = residual(&u, self.nu);
+        let fy = residual(&v, self.nu) - &t;
+        let heat = residual(&t, self.ka);
+        Forcing {
+            force: [fx, fy],
+            heat,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Integrate;
+
+    #[test]
+    fn test_manufactured_forcing_convergence() {
+        // psi = (1 - x^2)^2 (1 - y^2)^2 exp(y),
+        // u = dpsi/dy, v = -dpsi/dx, T = (1 - x^2)(1 - y^2) exp(x)
+        let a = |x: f64| (1. - x * x).powi(2);
+        let da = |x: f64| -4. * x * (1. - x * x);
+        let b = |y: f64| (1. - y * y).powi(2) * y.exp();
+        let db = |y: f64| (1. - y * y) * (1. - y * y - 4. * y) * y.exp();
+        let ux = |x: f64, y: f64| a(x) * db(y);
+        let uy = |x: f64, y: f64| -da(x) * b(y);
+        let temp = |x: f64, y: f64| (1. - x * x) * (1. - y * y) * x.exp();
+        let mut errors = vec![];
+        for n in [8, 12, 16].iter() {
+            let mut navier = Navier2D::new(*n, *n + 1, 1e3, 1., 1e-3, 1., false);
+            navier.fieldbc = None;
+            let (x, y) = (navier.ux.x[0].to_owned(), navier.ux.x[1].to_owned());
+            for (field, f) in [
+                (&mut navier.ux, &ux as &dyn Fn(f64, f64) -> f64),
+                (&mut navier.uy, &uy),
+                (&mut navier.temp, &temp),
+            ] {
+                field.v = Array2::from_shape_fn((x.len(), y.len()), |(i, j)| f(x[i], y[j]));
+                field.forward();
+            }
+            navier.forcing = Some(navier.manufactured_forcing(ux, uy, temp));
+            for _ in 0..10 {
+                navier.update();
+            }
+            navier.ux.backward();
+            navier.temp.backward();
+            let mut error = 0_f64;
+            for (i, xi) in x.iter().enumerate() {
+                for (j, yj) in y.iter().enumerate() {
+                    error = error
+                        .max((navier.ux.v[[i, j]] - ux(*xi, *yj)).abs())
+                        .max((navier.temp.v[[i, j]] - temp(*xi, *yj)).abs());
+                }
+            }
+            errors.push(error);
+        }
+        // spectral accuracy
+        assert!(errors[1] < errors[0] / 10., "errors: {:?}", errors);
+        assert!(errors[2] < errors[1] / 10., "errors: {:?}", errors);
+        assert!(errors[2] < 1e-6, "errors: {:?}", errors);
+    }
+}
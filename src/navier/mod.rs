@@ -1,15 +1,30 @@
 //! Collection of partial diff equations for *rustpde*
 #![allow(clippy::module_inception)]
+pub mod builder;
+pub mod config;
 pub mod conv_term;
+pub mod correlation;
+pub mod diagnostics;
 pub mod diffusion;
+pub mod forcing;
 pub mod functions;
+pub mod linear_stability;
+pub mod manufactured;
 pub mod navier;
 pub mod navier_adjoint;
+pub mod probes;
 pub mod statistics;
+pub mod topology;
 // pub mod navier_periodic;
 pub mod solid_masks;
+pub mod spacetime;
+pub mod sponge;
+pub mod symmetry;
 pub mod vorticity;
+pub use builder::Navier2DPeriodicBuilder;
+pub use config::NavierConfig;
 pub use conv_term::conv_term;
+pub use diagnostics::ConvectionDiagnostics;
 pub use navier::Navier2D;
 pub use navier_adjoint::Navier2DAdjoint;
 pub use solid_masks::solid_cylinder_inner;
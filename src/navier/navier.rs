@@ -27,20 +27,28 @@
 //! }
 //! ```
 use super::conv_term;
+use super::conv_term::conv_term_conservative;
+use super::diagnostics::ConvectionDiagnostics;
+use super::forcing::Forcing;
+use super::sponge::SpongeLayer;
 use super::statistics::Statistics;
+use super::symmetry::Symmetry;
 use crate::bases::fourier_r2c;
 use crate::bases::{cheb_dirichlet, cheb_dirichlet_bc, cheb_neumann, chebyshev};
-use crate::bases::{BaseR2c, BaseR2r};
+use crate::bases::{BaseR2c, BaseR2r, DealiasRule};
 use crate::field::{BaseSpace, Field2, ReadField, Space2, WriteField};
-use crate::hdf5::{read_scalar_from_hdf5, write_scalar_to_hdf5, Result};
-use crate::solver::{Hholtz, HholtzAdi, Poisson, Solve, SolverField};
+use crate::hdf5::{read_scalar_from_hdf5, write_attr_to_hdf5, write_scalar_to_hdf5, Result};
+use crate::solver::iterative::{norm_l2, norm_l2_diff};
+use crate::solver::{Hholtz, HholtzAdi, NullspaceMode, Poisson, Solve, SolverError, SolverField};
 use crate::types::Scalar;
-use crate::Integrate;
+use crate::{Integrate, Snapshot};
 use ndarray::{s, Array1, Array2};
 use num_complex::Complex;
 use num_traits::Zero;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::ops::{Div, Mul};
+use std::path::PathBuf;
 
 /// Return viscosity from Ra, Pr, and height of the cell
 pub fn get_nu(ra: f64, pr: f64, height: f64) -> f64 {
@@ -59,6 +67,151 @@ pub type Space2R2r = Space2<BaseR2r<f64>, BaseR2r<f64>>;
 /// Two-dimensional space with real-to-complex transform
 pub type Space2R2c = Space2<BaseR2c<f64>, BaseR2r<f64>>;
 
+/// Discretization of the temperature convection term
+///
+/// `Advective` evaluates u*grad(T), `Conservative` evaluates
+/// div(u*T). Both agree for a divergence-free velocity field.
+/// Prefer `Conservative` when the velocity is not discretely
+/// divergence-free, for example close to penalized solids,
+/// since it conserves the global heat content.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TempConvForm {
+    /// u*grad(T)
+    Advective,
+    /// div(u*T)
+    Conservative,
+}
+
+/// Velocity boundary conditions at the plates, see
+/// `Navier2D::set_velocity_bc`
+///
+/// `NoSlip` sets both velocities to zero. `FreeSlip` sets the normal
+/// velocity and the normal derivative of the tangential velocity to
+/// zero, i.e. the walls exert no shear stress.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum VelocityBc {
+    /// ux = uy = 0
+    NoSlip,
+    /// duxdy = uy = 0
+    FreeSlip,
+}
+
+/// Treatment of density variations
+///
+/// `Anelastic` replaces the continuity equation by
+/// $$
+/// \nabla \cdot (\bar{\rho} u) = 0,
+/// $$
+/// with a reference density profile $\bar{\rho}(y)$, given on the
+/// (physical) y-grid. The pressure variable is $p / \bar{\rho}$,
+/// such that the pressure gradient term keeps its form; the
+/// variable coefficient pressure equation is solved by fixed point
+/// iteration. Buoyancy and diffusion are treated as in `Boussinesq`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Approximation {
+    /// Constant density (except in buoyancy term)
+    Boussinesq,
+    /// Reference density profile
+    Anelastic {
+        /// Reference density along y
+        rho_ref: Array1<f64>,
+    },
+}
+
+/// Treatment of the pressure in the projection step
+///
+/// `Incremental` includes the pressure of the previous step in the
+/// momentum equations and accumulates the pseudo pressure increment.
+/// `Recompute` drops the pressure from the momentum equations and
+/// recovers the full pressure from the pseudo pressure of the current
+/// step only, so it can not drift. Both modes need a single poisson
+/// solve per step, `Recompute` saves the two pressure gradients in the
+/// momentum equations. However, its splitting error is of first order
+/// in the timestep, also in steady state, and it introduces a slip
+/// velocity of order `dt` at the walls. Prefer `Incremental`, unless
+/// the drift of the accumulated pressure is a concern.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PressureMode {
+    /// Accumulate pressure increments
+    Incremental,
+    /// Recompute pressure each step
+    Recompute,
+}
+
+/// Time integration scheme, see `Navier2D::set_time_scheme`
+///
+/// Both schemes treat diffusion implicitly and all other terms
+/// explicitly. `Euler` is of first order in the timestep. `Rk3` is
+/// the third order, L-stable IMEX Runge-Kutta scheme ARS(4,4,3)
+/// (Ascher, Ruuth and Spiteri, 1997). It needs four evaluations of the
+/// explicit terms and four implicit and pressure solves per step, and
+/// its implicit stages have a constant coefficient of `dt/2`, such that
+/// a single set of implicit solvers suffices.
+///
+/// The pressure is corrected by a projection after each stage, which
+/// limits the order of the velocity close to walls.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TimeScheme {
+    /// Semi-implicit euler
+    Euler,
+    /// IMEX Runge-Kutta of third order
+    Rk3,
+}
+
+/// Adaptive timestep from the CFL condition, see `Navier2D::adaptive_dt`
+///
+/// The timestep follows `cfl` times the largest stable timestep
+/// (see `max_stable_dt`), bounded by `dt_max`. Since each change
+/// of the timestep updates the implicit solvers, the timestep is
+/// only increased if the target exceeds it by more than 10 %.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AdaptiveDt {
+    /// Fraction of the largest stable timestep
+    pub cfl: f64,
+    /// Upper bound of the timestep
+    pub dt_max: f64,
+}
+
+impl AdaptiveDt {
+    /// Returns new timestep, given the current timestep `dt` and
+    /// the largest stable timestep `dt_stable`, or None if `dt`
+    /// should be kept.
+    pub fn suggest(&self, dt: f64, dt_stable: f64) -> Option<f64> {
+        let target = (self.cfl * dt_stable).min(self.dt_max);
+        if target < dt || target > (1. + DT_TOL) * dt {
+            Some(target)
+        } else {
+            None
+        }
+    }
+}
+
+/// Coefficients of the explicit terms of the stages of
+/// [`TimeScheme::Rk3`]
+const RK3_EXPL: [[f64; 4]; 4] = [
+    [1. / 2., 0., 0., 0.],
+    [11. / 18., 1. / 18., 0., 0.],
+    [5. / 6., -5. / 6., 1. / 2., 0.],
+    [1. / 4., 7. / 4., 3. / 4., -7. / 4.],
+];
+/// Coefficients of the implicit terms of the stages of
+/// [`TimeScheme::Rk3`], the diagonal is solved for
+const RK3_IMPL: [[f64; 4]; 4] = [
+    [1. / 2., 0., 0., 0.],
+    [1. / 6., 1. / 2., 0., 0.],
+    [-1. / 2., 1. / 2., 1. / 2., 0.],
+    [3. / 2., -3. / 2., 1. / 2., 1. / 2.],
+];
+/// Relative increase of the target timestep, below which
+/// the timestep is kept, see [`AdaptiveDt`]
+const DT_TOL: f64 = 0.1;
+/// Maximum number of fixed point iterations of anelastic pressure solve
+const ANELASTIC_MAX_ITER: usize = 50;
+/// Relative tolerance of anelastic pressure solve
+const ANELASTIC_TOL: f64 = 1e-10;
+/// Default penalization time scale of solid obstacles
+const SOLID_ETA: f64 = 1e-2;
+
 /// Implement the ndividual terms of the Navier-Stokes equation
 /// as a trait. This is necessary to support both real and complex
 /// valued spectral spaces
@@ -89,6 +242,13 @@ pub trait NavierConvection {
         uy: &Array2<Self::Physical>,
     ) -> Array2<Self::Spectral>;
 
+    /// Convection term for passive scalar
+    fn conv_scalar(
+        &mut self,
+        ux: &Array2<Self::Physical>,
+        uy: &Array2<Self::Physical>,
+    ) -> Array2<Self::Spectral>;
+
     /// Solve horizontal momentum equation
     /// $$
     /// (1 - \delta t  \mathcal{D}) u\\_new = -dt*C(u) - \delta t grad(p) + \delta t f + u
@@ -109,6 +269,12 @@ pub trait NavierConvection {
     /// $$
     fn solve_temp(&mut self, ux: &Array2<Self::Physical>, uy: &Array2<Self::Physical>);
 
+    /// Solve passive scalar equation (if present):
+    /// $$
+    /// (1 - dt*D) s\\_new = -dt*C(s) + s
+    /// $$
+    fn solve_scalar(&mut self, ux: &Array2<Self::Physical>, uy: &Array2<Self::Physical>);
+
     /// Correct velocity field.
     /// $$
     /// uxnew = ux - c*dpdx
@@ -179,6 +345,9 @@ pub struct Navier2D<T, S> {
     pub time: f64,
     /// Time step size
     pub dt: f64,
+    /// Reference time scale (dimensional time = time * `reference_time`).
+    /// If set, the dimensional time is written to file in addition.
+    pub reference_time: Option<f64>,
     /// Scale of phsical dimension \[scale_x, scale_y\]
     pub scale: [f64; 2],
     /// diagnostics like Nu, ...
@@ -186,12 +355,88 @@ pub struct Navier2D<T, S> {
     /// Time intervall for write fields
     /// If none, same intervall as diagnostics
     pub write_intervall: Option<f64>,
-    /// Add a solid obstacle
+    /// Set true and the (scaled) grid coordinates are written
+    /// once to grid.h5 in `output_dir`
+    pub write_grid: bool,
+    /// Directory of output files (fields, grid, probes, statistics
+    /// and info.txt). Default: "data"
+    pub output_dir: PathBuf,
+    /// Prefix of flow field files, followed by the time.
+    /// Default: "flow"
+    pub file_prefix: String,
+    /// Gzip level (0-9) of field datasets in hdf5 files,
+    /// None writes them uncompressed
+    pub compression: Option<u8>,
+    /// Probe locations, see `add_probe`
+    pub probes: Vec<[f64; 2]>,
+    /// Add a solid obstacle \[mask, temperature\], see `set_solid`
     pub solid: Option<[Array2<f64>; 2]>,
+    /// Penalization time scale of solid obstacles
+    pub penalty_eta: f64,
+    /// Permeability of a porous medium (Darcy drag), see `set_permeability`
+    pub permeability: Option<Array2<f64>>,
+    /// Add a sponge layer, which damps the velocities
+    pub sponge: Option<SpongeLayer>,
+    /// Body force and heat source, e.g. `manufactured_forcing`
+    pub forcing: Option<Forcing>,
+    /// Passive scalar, see `set_scalar`
+    pub scalar: Option<Field2<T, S>>,
+    /// Diffusivity of passive scalar
+    pub scalar_diffusivity: f64,
+    /// Solver of passive scalar
+    solver_scalar: Option<SolverField<f64, 2>>,
     /// Set true and the fields will be dealiased
     pub dealias: bool,
+    /// Form of temperature convection term
+    pub temp_conv_form: TempConvForm,
+    /// If set, diverged steps are repeated with half the timestep,
+    /// at most the given number of times
+    pub safe_step: Option<usize>,
+    /// If set, project fields onto symmetry subspace after each update
+    pub symmetry: Option<Symmetry>,
     /// If set, collect statistics
     pub statistics: Option<Statistics<T, S>>,
+    /// Boussinesq or anelastic approximation, see `set_approximation`
+    approximation: Approximation,
+    /// $\bar{\rho}' / \bar{\rho}$ along y of the anelastic approximation
+    anelastic_ratio: Option<Array1<f64>>,
+    /// Incremental or recomputed pressure, see [`PressureMode`]
+    pub pressure_mode: PressureMode,
+    /// If set, record the residual of the kinetic energy
+    /// budget after each update, see `energy_budget_residual`
+    pub energy_budget: bool,
+    /// Velocity of the reference frame, see `set_frame_velocity`
+    pub frame_velocity: [f64; 2],
+    /// Rotation rate $\Omega$ about the axis normal to the plane,
+    /// adds the coriolis force $-2 \Omega \times u$
+    ///
+    /// In two dimensions, the coriolis force of a divergence-free
+    /// velocity is the gradient $-2 \Omega \nabla \psi$ of the
+    /// streamfunction. It is balanced by the pressure and leaves the
+    /// velocities unchanged (Taylor-Proudman); inertial oscillations
+    /// with frequency $2 \Omega$ require a third velocity component.
+    /// They are only present in the forcing itself, before the
+    /// pressure projection.
+    pub rotation: Option<f64>,
+    /// If set, adapt timestep to the CFL condition during `integrate`
+    pub adaptive_dt: Option<AdaptiveDt>,
+    /// If true, temperature is held fixed, but still drives the flow
+    pub freeze_temp: bool,
+    /// If true, velocities (and pressure) are held fixed, but still
+    /// advect the temperature
+    pub freeze_velocity: bool,
+    /// If set, `exit` returns true once the relative change of
+    /// temperature and ux since the previous call of `exit` drops below
+    /// this tolerance. `integrate` calls `exit` after every step, so
+    /// there it is the change per timestep and scales with `dt`.
+    pub steady_tol: Option<f64>,
+    /// Temperature and ux (orthogonal space) after the last check
+    /// of `steady_tol`
+    previous_state: Option<[Array2<T>; 2]>,
+    /// Time integration scheme, see `set_time_scheme`
+    time_scheme: TimeScheme,
+    /// Velocity boundary conditions, see `set_velocity_bc`
+    velocity_bc: VelocityBc,
 }
 
 impl Navier2D<f64, Space2R2r>
@@ -272,6 +517,7 @@ impl Navier2D<f64, Space2R2r>
         diagnostics.insert("Nu".to_string(), Vec::<f64>::new());
         diagnostics.insert("Nuvol".to_string(), Vec::<f64>::new());
         diagnostics.insert("Re".to_string(), Vec::<f64>::new());
+        diagnostics.insert("CFL".to_string(), Vec::<f64>::new());
 
         // Initialize
         let mut navier = Navier2D::<f64, Space2R2r> {
@@ -289,12 +535,41 @@ impl Navier2D<f64, Space2R2r>
             pr,
             time: 0.0,
             dt,
+            reference_time: None,
             scale,
             diagnostics,
             write_intervall: None,
+            write_grid: false,
+            output_dir: PathBuf::from("data"),
+            file_prefix: "flow".to_string(),
+            compression: None,
+            probes: Vec::new(),
             solid: None,
+            penalty_eta: SOLID_ETA,
+            permeability: None,
+            sponge: None,
+            forcing: None,
+            scalar: None,
+            scalar_diffusivity: 0.,
+            solver_scalar: None,
             dealias: true,
+            temp_conv_form: TempConvForm::Advective,
+            safe_step: None,
+            symmetry: None,
             statistics: None,
+            approximation: Approximation::Boussinesq,
+            anelastic_ratio: None,
+            pressure_mode: PressureMode::Incremental,
+            energy_budget: false,
+            frame_velocity: [0., 0.],
+            rotation: None,
+            adaptive_dt: None,
+            freeze_temp: false,
+            freeze_velocity: false,
+            steady_tol: None,
+            previous_state: None,
+            time_scheme: TimeScheme::Euler,
+            velocity_bc: VelocityBc::NoSlip,
         };
         navier._scale();
         // Boundary condition
@@ -432,6 +707,7 @@ impl Navier2D<Complex<f64>, Space2R2c>
         diagnostics.insert("Nu".to_string(), Vec::<f64>::new());
         diagnostics.insert("Nuvol".to_string(), Vec::<f64>::new());
         diagnostics.insert("Re".to_string(), Vec::<f64>::new());
+        diagnostics.insert("CFL".to_string(), Vec::<f64>::new());
 
         // Initialize
         let mut navier = Navier2D::<Complex<f64>, Space2R2c> {
@@ -449,12 +725,41 @@ impl Navier2D<Complex<f64>, Space2R2c>
             pr,
             time: 0.0,
             dt,
+            reference_time: None,
             scale,
             diagnostics,
             write_intervall: None,
+            write_grid: false,
+            output_dir: PathBuf::from("data"),
+            file_prefix: "flow".to_string(),
+            compression: None,
+            probes: Vec::new(),
             solid: None,
+            penalty_eta: SOLID_ETA,
+            permeability: None,
+            sponge: None,
+            forcing: None,
+            scalar: None,
+            scalar_diffusivity: 0.,
+            solver_scalar: None,
             dealias: true,
+            temp_conv_form: TempConvForm::Advective,
+            safe_step: None,
+            symmetry: None,
             statistics: None,
+            approximation: Approximation::Boussinesq,
+            anelastic_ratio: None,
+            pressure_mode: PressureMode::Incremental,
+            energy_budget: false,
+            frame_velocity: [0., 0.],
+            rotation: None,
+            adaptive_dt: None,
+            freeze_temp: false,
+            freeze_velocity: false,
+            steady_tol: None,
+            previous_state: None,
+            time_scheme: TimeScheme::Euler,
+            velocity_bc: VelocityBc::NoSlip,
         };
         navier._scale();
         // Boundary condition
@@ -490,6 +795,164 @@ impl Navier2D<Complex<f64>, Space2R2c>
         fieldbc.forward();
         fieldbc
     }
+
+    /// Return field for fixed heat flux boundary conditions, i.e.
+    /// prescribed conductive flux $-\partial T / \partial y$ at the
+    /// bottom and at the top. Set it with `set_temp_bc_flux`.
+    ///
+    /// The field is the quadratic profile in y (unit scale), which
+    /// satisfies both conditions. Unless both fluxes agree, it
+    /// heats or cools the fluid at a constant rate.
+    ///
+    /// Note: The chebyshev boundary bases (`cheb_neumann_bc`) span
+    /// T_0 and T_1 only, which can not match two different slopes,
+    /// so the profile is represented in the full chebyshev base.
+    pub fn bc_fixed_flux(
+        nx: usize,
+        ny: usize,
+        flux_bottom: f64,
+        flux_top: f64,
+    ) -> Field2<Complex<f64>, Space2R2c> {
+        let space = Space2::new(&fourier_r2c(nx), &chebyshev(ny));
+        let mut fieldbc = Field2::new(&space);
+        // T = a y + b y^2, with dTdy(-1) = -flux_bottom, dTdy(1) = -flux_top
+        let a = -(flux_bottom + flux_top) / 2.;
+        let b = (flux_bottom - flux_top) / 4.;
+        let y = fieldbc.x[1].mapv(|y| a * y + b * y * y);
+        for mut row in fieldbc.v.outer_iter_mut() {
+            row.assign(&y);
+        }
+        fieldbc.forward();
+        fieldbc
+    }
+
+    /// Set boundary condition field for fixed heat flux, see
+    /// `bc_fixed_flux`.
+    ///
+    /// The temperature is switched to a base with homogeneous neumann
+    /// conditions along y, such that it does not alter the wall flux,
+    /// and its implicit solver is updated. The current temperature
+    /// is projected onto the new base.
+    pub fn set_temp_bc_flux(&mut self, fieldbc: Field2<Complex<f64>, Space2R2c>) {
+        let ny = self.temp.v.shape()[1];
+        self.temp = Self::rebase_field(&mut self.temp, &cheb_neumann(ny));
+        self.fieldbc = Some(fieldbc);
+        self.set_dt(self.dt);
+    }
+
+    /// Set velocity boundary conditions at the plates, see
+    /// [`VelocityBc`]. The default is `VelocityBc::NoSlip`.
+    ///
+    /// The horizontal velocity is switched to a base with dirichlet
+    /// (no-slip) or neumann (free-slip) conditions along y, and the
+    /// implicit solvers are updated. The current velocity is projected
+    /// onto the new base. The pressure correction has zero normal
+    /// derivative at the walls, so it preserves both conditions.
+    pub fn set_velocity_bc(&mut self, velocity_bc: VelocityBc) {
+        let ny = self.ux.v.shape()[1];
+        let base = match velocity_bc {
+            VelocityBc::NoSlip => cheb_dirichlet(ny),
+            VelocityBc::FreeSlip => cheb_neumann(ny),
+        };
+        self.ux = Self::rebase_field(&mut self.ux, &base);
+        self.velocity_bc = velocity_bc;
+        self.set_dt(self.dt);
+    }
+
+    /// Returns field on base `fourier_r2c` x `base_y`, which holds the
+    /// physical values of `field` (projected onto the new base). The
+    /// (scaled) coordinates are kept.
+    fn rebase_field(
+        field: &mut Field2<Complex<f64>, Space2R2c>,
+        base_y: &BaseR2r<f64>,
+    ) -> Field2<Complex<f64>, Space2R2c> {
+        let nx = field.v.shape()[0];
+        let mut rebased = Field2::new(&Space2::new(&fourier_r2c(nx), base_y));
+        rebased.x = field.x.clone();
+        rebased.dx = field.dx.clone();
+        field.backward();
+        rebased.v.assign(&field.v);
+        rebased.forward();
+        rebased
+    }
+
+    /// Return pure conduction temperature profile, i.e. linear in y
+    /// between the (horizontally averaged) plate temperatures of the
+    /// current boundary condition field. Zero if no boundary
+    /// condition field is set.
+    ///
+    /// Can be used to initialize perturbations about the conductive
+    /// state, or to compute deviations from it.
+    pub fn conductive_state(&self) -> Field2<Complex<f64>, Space2R2c> {
+        let mut cond = self.field.clone();
+        let (t_bot, t_top) = match &self.fieldbc {
+            Some(fieldbc) => {
+                let (bot, top) = fieldbc.boundary_values(1);
+                (bot.mean().unwrap(), top.mean().unwrap())
+            }
+            None => (0., 0.),
+        };
+        let y = &cond.x[1];
+        let y = (y - y[0]) / (y[y.len() - 1] - y[0]);
+        for mut column in cond.v.axis_iter_mut(ndarray::Axis(0)) {
+            column.assign(&(&y * (t_top - t_bot) + t_bot));
+        }
+        cond.forward();
+        cond
+    }
+
+    /// Returns vorticity $\omega = duy/dx - dux/dy$ in physical space
+    pub fn vorticity(&self) -> Array2<f64> {
+        let mut field = self.field.clone();
+        field.vhat.assign(
+            &(self.uy.gradient([1, 0], Some(self.scale))
+                - self.ux.gradient([0, 1], Some(self.scale))),
+        );
+        field.backward();
+        field.v
+    }
+
+    /// Returns enstrophy
+    /// $$
+    /// Z = \frac{1}{2} \langle \omega^2 \rangle\_V
+    /// $$
+    /// Appended to diagnostics ("Enstrophy").
+    pub fn enstrophy(&mut self) -> f64 {
+        let mut omega = self.field.clone();
+        omega.vhat.assign(
+            &(self.uy.gradient([1, 0], Some(self.scale))
+                - self.ux.gradient([0, 1], Some(self.scale))),
+        );
+        let enstrophy = omega.mean_square() / 2.;
+        self.diagnostics
+            .entry("Enstrophy".to_string())
+            .or_insert_with(Vec::new)
+            .push(enstrophy);
+        enstrophy
+    }
+
+    /// Returns palinstrophy
+    /// $$
+    /// P = \frac{1}{2} \langle |grad(\omega)|^2 \rangle\_V
+    /// $$
+    /// Appended to diagnostics ("Palinstrophy").
+    pub fn palinstrophy(&mut self) -> f64 {
+        let mut field = self.field.clone();
+        field.v.assign(&self.vorticity());
+        field.forward();
+        let omega = field.clone();
+        let mut grad2 = 0.;
+        for deriv in [[1, 0], [0, 1]].iter() {
+            field.vhat.assign(&omega.gradient(*deriv, Some(self.scale)));
+            grad2 += field.mean_square();
+        }
+        let palinstrophy = grad2 / 2.;
+        self.diagnostics
+            .entry("Palinstrophy".to_string())
+            .or_insert_with(Vec::new)
+            .push(palinstrophy);
+        palinstrophy
+    }
 }
 
 impl<T, S> Navier2D<T, S>
@@ -518,6 +981,128 @@ where
         self.fieldbc = Some(fieldbc);
     }
 
+    /// Set permeability K(x,y) (physical space) of a porous medium.
+    ///
+    /// Adds the Darcy drag
+    /// $$
+    /// -\frac{\nu}{K} u
+    /// $$
+    /// to the momentum equations (Darcy-Brinkman). The drag is treated
+    /// explicitly, so `dt * nu / K` should be smaller than unity.
+    ///
+    /// The penalization of solid obstacles is the stiff limit of
+    /// this term: it corresponds to K = nu * eta / mask, with
+    /// eta = `penalty_eta`.
+    ///
+    /// # Panics
+    /// Panics if shape of `k` does not match the physical grid.
+    pub fn set_permeability(&mut self, k: Array2<f64>) {
+        assert!(
+            k.shape() == self.temp.v.shape(),
+            "Permeability must be of shape {:?}",
+            self.temp.v.shape()
+        );
+        self.permeability = Some(k);
+    }
+
+    /// Set solid obstacle (Brinkman penalization). `mask` (physical
+    /// space) is one inside the solid and zero in the fluid, e.g.
+    /// from [`crate::navier::solid_masks`]. Inside the solid, the
+    /// velocities are damped to zero and the temperature to the solid
+    /// temperature on the time scale `eta` (see `set_solid_temperature`,
+    /// zero by default).
+    ///
+    /// The damping is treated explicitly, so `dt` must be smaller
+    /// than `2 * eta`, see `max_stable_dt`.
+    ///
+    /// # Panics
+    /// Panics if shape of `mask` does not match the physical grid,
+    /// or if `eta` is not positive.
+    pub fn set_solid(&mut self, mask: Array2<f64>, eta: f64) {
+        assert!(
+            mask.shape() == self.temp.v.shape(),
+            "Solid mask must be of shape {:?}, got {:?}",
+            self.temp.v.shape(),
+            mask.shape()
+        );
+        assert!(eta > 0., "Penalization time scale must be positive");
+        let value = match self.solid.take() {
+            Some([_, value]) => value,
+            None => Array2::zeros(mask.raw_dim()),
+        };
+        self.solid = Some([mask, value]);
+        self.penalty_eta = eta;
+    }
+
+    /// Set temperature (physical space) of the solid obstacle, see
+    /// `set_solid`. It is the total temperature, i.e. it includes the
+    /// boundary condition field `fieldbc`.
+    ///
+    /// # Panics
+    /// Panics if no solid is set, or if shape of `temp` does not
+    /// match the physical grid.
+    pub fn set_solid_temperature(&mut self, temp: Array2<f64>) {
+        assert!(
+            temp.shape() == self.temp.v.shape(),
+            "Solid temperature must be of shape {:?}, got {:?}",
+            self.temp.v.shape(),
+            temp.shape()
+        );
+        match &mut self.solid {
+            Some(solid) => solid[1] = temp,
+            None => panic!("No solid set, see set_solid"),
+        }
+    }
+
+    /// Solve in a reference frame, which moves with constant
+    /// velocity (ux0, uy0) relative to the domain walls.
+    ///
+    /// The frame velocity is subtracted from the advecting
+    /// velocities of all convection terms, i.e.
+    /// $$
+    /// \partial_t u + ((u - U_0) \cdot \nabla) u = \dots,
+    /// $$
+    /// where the fields remain velocities measured in the rest frame
+    /// of the walls, but are sampled at grid points which move with
+    /// $U_0$. A structure advected with $U_0$, like a plume in a mean
+    /// wind, thus stays fixed on the grid. The boundary conditions
+    /// are unchanged, so the frame should only move along periodic
+    /// directions, i.e. `uy0` should be zero for walls in y.
+    pub fn set_frame_velocity(&mut self, ux0: f64, uy0: f64) {
+        self.frame_velocity = [ux0, uy0];
+    }
+
+    /// Set boussinesq or anelastic approximation, see [`Approximation`].
+    /// The default is `Approximation::Boussinesq`.
+    ///
+    /// The ratio $\bar{\rho}' / \bar{\rho}$ of the anelastic pressure
+    /// equation is evaluated once here.
+    ///
+    /// # Panics
+    /// Panics if the reference density is not given on the
+    /// y-grid, or is not positive.
+    pub fn set_approximation(&mut self, approximation: Approximation) {
+        self.anelastic_ratio = match &approximation {
+            Approximation::Boussinesq => None,
+            Approximation::Anelastic { rho_ref } => {
+                use crate::bases::{Differentiate, Transform};
+                let ny = self.temp.v.shape()[1];
+                assert!(
+                    rho_ref.len() == ny,
+                    "Length of rho_ref ({}) must match ny ({}).",
+                    rho_ref.len(),
+                    ny
+                );
+                assert!(rho_ref.iter().all(|r| *r > 0.), "rho_ref must be positive.");
+                let mut cheby = chebyshev::<f64>(ny);
+                let mut drho = cheby.differentiate(&cheby.forward(rho_ref, 0), 1, 0);
+                drho /= self.scale[1];
+                Some(cheby.backward(&drho, 0) / rho_ref)
+            }
+        };
+        self.approximation = approximation;
+    }
+
     fn zero_rhs(&mut self) {
         for r in self.rhs.iter_mut() {
             *r = T::zero();
@@ -540,17 +1125,49 @@ macro_rules! impl_navier_convection {
                 ux: &Array2<Self::Physical>,
                 uy: &Array2<Self::Physical>,
             ) -> Array2<Self::Spectral> {
-                // + ux * dTdx + uy * dTdy
-                let mut conv = conv_term(&self.temp, &mut self.field, ux, [1, 0], Some(self.scale));
-                conv += &conv_term(&self.temp, &mut self.field, uy, [0, 1], Some(self.scale));
-                // + bc contribution
-                if let Some(field) = &self.fieldbc {
-                    conv += &conv_term(field, &mut self.field, ux, [1, 0], Some(self.scale));
-                    conv += &conv_term(field, &mut self.field, uy, [0, 1], Some(self.scale));
-                }
+                let mut conv = match self.temp_conv_form {
+                    TempConvForm::Advective => {
+                        // + ux * dTdx + uy * dTdy
+                        let mut conv =
+                            conv_term(&self.temp, &mut self.field, ux, [1, 0], Some(self.scale));
+                        conv +=
+                            &conv_term(&self.temp, &mut self.field, uy, [0, 1], Some(self.scale));
+                        // + bc contribution
+                        if let Some(field) = &self.fieldbc {
+                            conv +=
+                                &conv_term(field, &mut self.field, ux, [1, 0], Some(self.scale));
+                            conv +=
+                                &conv_term(field, &mut self.field, uy, [0, 1], Some(self.scale));
+                        }
+                        conv
+                    }
+                    TempConvForm::Conservative => {
+                        // + d(ux*T)dx + d(uy*T)dy
+                        self.temp.backward();
+                        let mut t = self.temp.v.to_owned();
+                        if let Some(field) = &self.fieldbc {
+                            t += &field.v;
+                        }
+                        let mut conv = conv_term_conservative(
+                            &t,
+                            &mut self.field,
+                            ux,
+                            [1, 0],
+                            Some(self.scale),
+                        );
+                        conv += &conv_term_conservative(
+                            &t,
+                            &mut self.field,
+                            uy,
+                            [0, 1],
+                            Some(self.scale),
+                        );
+                        conv
+                    }
+                };
                 // + solid interaction
                 if let Some(solid) = &self.solid {
-                    let eta = 1e-2;
+                    let eta = self.penalty_eta;
                     self.temp.backward();
                     let damp = self.fieldbc.as_ref().map_or_else(
                         || -1. / eta * &solid[0] * (&self.temp.v - &solid[1]),
@@ -558,6 +1175,10 @@ macro_rules! impl_navier_convection {
                     );
                     conv -= &damp;
                 }
+                // - heat source
+                if let Some(forcing) = &self.forcing {
+                    conv -= &forcing.heat;
+                }
                 // -> spectral space
                 self.field.v.assign(&conv);
                 self.field.forward();
@@ -578,10 +1199,26 @@ macro_rules! impl_navier_convection {
                 conv += &conv_term(&self.ux, &mut self.field, uy, [0, 1], Some(self.scale));
                 // + solid interaction
                 if let Some(solid) = &self.solid {
-                    let eta = 1e-2;
+                    let eta = self.penalty_eta;
                     let damp = -1. / eta * &solid[0] * ux;
                     conv -= &damp;
                 }
+                // + darcy drag
+                if let Some(k) = &self.permeability {
+                    conv += &(self.nu * ux / k);
+                }
+                // + sponge layer
+                if let Some(sponge) = &self.sponge {
+                    conv += &sponge.damping(ux, 0);
+                }
+                // - coriolis force
+                if let Some(omega) = self.rotation {
+                    conv -= &uy.mapv(|v| 2. * omega * (v + self.frame_velocity[1]));
+                }
+                // - body force
+                if let Some(forcing) = &self.forcing {
+                    conv -= &forcing.force[0];
+                }
                 // -> spectral space
                 self.field.v.assign(&conv);
                 self.field.forward();
@@ -602,10 +1239,47 @@ macro_rules! impl_navier_convection {
                 conv += &conv_term(&self.uy, &mut self.field, uy, [0, 1], Some(self.scale));
                 // + solid interaction
                 if let Some(solid) = &self.solid {
-                    let eta = 1e-2;
+                    let eta = self.penalty_eta;
                     let damp = -1. / eta * &solid[0] * uy;
                     conv -= &damp;
                 }
+                // + darcy drag
+                if let Some(k) = &self.permeability {
+                    conv += &(self.nu * uy / k);
+                }
+                // + sponge layer
+                if let Some(sponge) = &self.sponge {
+                    conv += &sponge.damping(uy, 1);
+                }
+                // - coriolis force
+                if let Some(omega) = self.rotation {
+                    conv += &ux.mapv(|v| 2. * omega * (v + self.frame_velocity[0]));
+                }
+                // - body force
+                if let Some(forcing) = &self.forcing {
+                    conv -= &forcing.force[1];
+                }
+                // -> spectral space
+                self.field.v.assign(&conv);
+                self.field.forward();
+                if self.dealias {
+                    dealias(&mut self.field);
+                }
+                self.field.vhat.to_owned()
+            }
+
+            /// Convection term for passive scalar, zero if absent
+            fn conv_scalar(
+                &mut self,
+                ux: &Array2<Self::Physical>,
+                uy: &Array2<Self::Physical>,
+            ) -> Array2<Self::Spectral> {
+                // + ux * dsdx + uy * dsdy
+                let mut conv = Array2::zeros(ux.raw_dim());
+                if let Some(scalar) = &self.scalar {
+                    conv += &conv_term(scalar, &mut self.field, ux, [1, 0], Some(self.scale));
+                    conv += &conv_term(scalar, &mut self.field, uy, [0, 1], Some(self.scale));
+                }
                 // -> spectral space
                 self.field.v.assign(&conv);
                 self.field.forward();
@@ -624,7 +1298,9 @@ macro_rules! impl_navier_convection {
                 // + old field
                 self.rhs += &self.ux.to_ortho();
                 // + pres
-                self.rhs -= &(self.pres[0].gradient([1, 0], Some(self.scale)) * self.dt);
+                if self.pressure_mode == PressureMode::Incremental {
+                    self.rhs -= &(self.pres[0].gradient([1, 0], Some(self.scale)) * self.dt);
+                }
                 // + convection
                 let conv = self.conv_ux(ux, uy);
                 self.rhs -= &(conv * self.dt);
@@ -643,7 +1319,9 @@ macro_rules! impl_navier_convection {
                 // + old field
                 self.rhs += &self.uy.to_ortho();
                 // + pres
-                self.rhs -= &(self.pres[0].gradient([0, 1], Some(self.scale)) * self.dt);
+                if self.pressure_mode == PressureMode::Incremental {
+                    self.rhs -= &(self.pres[0].gradient([0, 1], Some(self.scale)) * self.dt);
+                }
                 // + buoyancy
                 self.rhs += &(buoy * self.dt);
                 // + convection
@@ -673,6 +1351,28 @@ macro_rules! impl_navier_convection {
                 self.solver[2].solve(&self.rhs, &mut self.temp.vhat, 0);
             }
 
+            /// Solve passive scalar equation (if present):
+            /// $$
+            /// (1 - dt*D) s\\_new = -dt*C(s) + s
+            /// $$
+            fn solve_scalar(&mut self, ux: &Array2<Self::Physical>, uy: &Array2<Self::Physical>) {
+                if self.scalar.is_none() {
+                    return;
+                }
+                self.zero_rhs();
+                // + old field
+                if let Some(scalar) = &self.scalar {
+                    self.rhs += &scalar.to_ortho();
+                }
+                // + convection
+                let conv = self.conv_scalar(ux, uy);
+                self.rhs -= &(conv * self.dt);
+                // solve lhs
+                if let (Some(solver), Some(scalar)) = (&self.solver_scalar, &mut self.scalar) {
+                    solver.solve(&self.rhs, &mut scalar.vhat, 0);
+                }
+            }
+
             /// Correct velocity field.
             /// $$
             /// uxnew = ux - c*dpdx
@@ -695,10 +1395,18 @@ macro_rules! impl_navier_convection {
             }
 
             /// Divergence: duxdx + duydy
+            ///
+            /// Anelastic: duxdx + duydy + rho'/rho uy
             fn divergence(&mut self) -> Array2<Self::Spectral> {
                 self.zero_rhs();
                 self.rhs += &self.ux.gradient([1, 0], Some(self.scale));
                 self.rhs += &self.uy.gradient([0, 1], Some(self.scale));
+                if let Some(ratio) = &self.anelastic_ratio {
+                    self.uy.backward();
+                    self.field.v.assign(&(&self.uy.v * ratio));
+                    self.field.forward();
+                    self.rhs += &self.field.vhat;
+                }
                 self.rhs.to_owned()
             }
 
@@ -707,16 +1415,49 @@ macro_rules! impl_navier_convection {
             /// D2 pres = f
             /// $$
             /// pseu: pseudo pressure ( in code it is pres\[1\] )
+            ///
+            /// Anelastic: D2 pres + rho'/rho dpresdy = f, solved
+            /// by fixed point iteration.
             fn solve_pres(&mut self, f: &Array2<Self::Spectral>) {
                 //self.pres[1].vhat.assign(&self.solver[3].solve(&f));
                 self.solver[3].solve(&f, &mut self.pres[1].vhat, 0);
                 // Singularity
                 self.pres[1].vhat[[0, 0]] = Self::Spectral::zero();
+                if let Some(ratio) = &self.anelastic_ratio {
+                    let mut converged = false;
+                    for _ in 0..ANELASTIC_MAX_ITER {
+                        let old = self.pres[1].vhat.to_owned();
+                        // f - rho'/rho dpresdy
+                        let dpdy = self.pres[1].gradient([0, 1], Some(self.scale));
+                        self.field.vhat.assign(&dpdy);
+                        self.field.backward();
+                        let correction = &self.field.v * ratio;
+                        self.field.v.assign(&correction);
+                        self.field.forward();
+                        let rhs = f - &self.field.vhat;
+                        self.solver[3].solve(&rhs, &mut self.pres[1].vhat, 0);
+                        self.pres[1].vhat[[0, 0]] = Self::Spectral::zero();
+                        let change = norm_l2_diff(&self.pres[1].vhat, &old);
+                        if change <= ANELASTIC_TOL * norm_l2(&self.pres[1].vhat) {
+                            converged = true;
+                            break;
+                        }
+                    }
+                    if !converged {
+                        eprintln!(
+                            "Warning: anelastic pressure solve not converged after {} iterations",
+                            ANELASTIC_MAX_ITER
+                        );
+                    }
+                }
             }
 
             fn update_pres(&mut self, div: &Array2<Self::Spectral>) {
+                if self.pressure_mode == PressureMode::Recompute {
+                    self.pres[0].vhat.fill(Self::Spectral::zero());
+                }
                 self.pres[0].vhat = &self.pres[0].vhat - &(div * self.nu);
-                let inv_dt: Self::Spectral = (1. / self.dt).into();
+                let inv_dt: Self::Spectral = (1. / self.implicit_dt()).into();
                 self.pres[0].vhat = &self.pres[0].vhat + &(&self.pres[1].to_ortho() * inv_dt);
             }
         }
@@ -729,39 +1470,274 @@ impl_navier_convection!(Complex<f64>);
 macro_rules! impl_integrate_for_navier {
     ($s: ty, $norm: ident) => {
 
-        impl<S> Integrate for Navier2D<$s, S>
+        impl<S> Navier2D<$s, S>
         where
             S: BaseSpace<f64, 2, Physical = f64, Spectral = $s>,
         {
-            /// Update 1 timestep
-            fn update(&mut self) {
+            /// Returns relative change (L2 norm of orthogonal coefficients)
+            /// of the total temperature and ux since the last call, and
+            /// stores the current state. None on the first call.
+            fn relative_change(&mut self) -> Option<f64> {
+                let mut temp = self.temp.to_ortho();
+                if let Some(field) = &self.fieldbc {
+                    temp = &temp + &field.to_ortho();
+                }
+                let current = [temp, self.ux.to_ortho()];
+                let change = self.previous_state.as_ref().map(|previous| {
+                    let diff: f64 = current
+                        .iter()
+                        .zip(previous.iter())
+                        .map(|(c, p)| norm_l2_diff(c, p).powi(2))
+                        .sum();
+                    let norm: f64 = current.iter().map(|c| norm_l2(c).powi(2)).sum();
+                    (diff / norm.max(f64::MIN_POSITIVE)).sqrt()
+                });
+                self.previous_state = Some(current);
+                change
+            }
+
+            /// Returns true if all fields are finite
+            pub fn check_finite(&self) -> bool {
+                [
+                    &self.temp.vhat,
+                    &self.ux.vhat,
+                    &self.uy.vhat,
+                    &self.pres[0].vhat,
+                ]
+                .iter()
+                .all(|vhat| vhat.iter().all(|v| v.is_finite()))
+            }
+
+            /// Advance solution by 1 timestep
+            fn step(&mut self) {
+                if self.time_scheme == TimeScheme::Rk3 {
+                    self.step_rk3();
+                    return;
+                }
+
                 // Buoyancy
                 let mut that = self.temp.to_ortho();
                 if let Some(field) = &self.fieldbc {
                     that = &that + &field.to_ortho();
                 }
 
-                // Convection Veclocity
+                // Convection Veclocity (relative to moving frame)
                 self.ux.backward();
                 self.uy.backward();
-                let ux = self.ux.v.to_owned();
-                let uy = self.uy.v.to_owned();
+                let ux = self.ux.v.mapv(|v| v - self.frame_velocity[0]);
+                let uy = self.uy.v.mapv(|v| v - self.frame_velocity[1]);
+
+                if !self.freeze_velocity {
+                    // Solve Velocity
+                    self.solve_ux(&ux, &uy);
+                    self.solve_uy(&ux, &uy, &that);
+
+                    // Projection
+                    let div = self.divergence();
+                    self.solve_pres(&div);
+                    self.project_velocity(1.0);
+                    self.update_pres(&div);
+                }
 
-                // Solve Velocity
-                self.solve_ux(&ux, &uy);
-                self.solve_uy(&ux, &uy, &that);
+                // Solve Temperature
+                if !self.freeze_temp {
+                    self.solve_temp(&ux, &uy);
+                }
 
-                // Projection
-                let div = self.divergence();
-                self.solve_pres(&div);
-                self.project_velocity(1.0);
-                self.update_pres(&div);
+                // Solve passive scalar
+                self.solve_scalar(&ux, &uy);
 
-                // Solve Temperature
-                self.solve_temp(&ux, &uy);
+                // update time
+                self.time += self.dt;
+
+                // Project onto symmetry subspace
+                self.enforce_symmetry();
+            }
+
+            /// Advance solution by 1 timestep with the IMEX Runge-Kutta
+            /// scheme, see [`TimeScheme::Rk3`]
+            fn step_rk3(&mut self) {
+                let mut old = vec![self.ux.to_ortho(), self.uy.to_ortho(), self.temp.to_ortho()];
+                if let Some(scalar) = &self.scalar {
+                    old.push(scalar.to_ortho());
+                }
+                let mut explicit: Vec<Vec<Array2<$s>>> = Vec::with_capacity(4);
+                let mut implicit: Vec<Vec<Array2<$s>>> = Vec::with_capacity(3);
+                for stage in 0..4 {
+                    explicit.push(self.explicit_terms());
+                    if stage > 0 {
+                        implicit.push(self.implicit_terms());
+                    }
+                    for (i, old) in old.iter().enumerate() {
+                        let frozen = match i {
+                            0 | 1 => self.freeze_velocity,
+                            2 => self.freeze_temp,
+                            _ => false,
+                        };
+                        if frozen {
+                            continue;
+                        }
+                        self.zero_rhs();
+                        // + old field
+                        self.rhs += old;
+                        // + explicit terms of previous stages
+                        for (f, c) in explicit.iter().zip(RK3_EXPL[stage].iter()) {
+                            self.rhs += &(&f[i] * (c * self.dt));
+                        }
+                        // + implicit terms of previous stages
+                        for (l, c) in implicit.iter().zip(RK3_IMPL[stage].iter()) {
+                            self.rhs += &(&l[i] * (c * self.dt));
+                        }
+                        // solve lhs
+                        match i {
+                            0 => self.solver[0].solve(&self.rhs, &mut self.ux.vhat, 0),
+                            1 => self.solver[1].solve(&self.rhs, &mut self.uy.vhat, 0),
+                            2 => self.solver[2].solve(&self.rhs, &mut self.temp.vhat, 0),
+                            _ => {
+                                if let (Some(solver), Some(scalar)) =
+                                    (&self.solver_scalar, &mut self.scalar)
+                                {
+                                    solver.solve(&self.rhs, &mut scalar.vhat, 0);
+                                }
+                            }
+                        }
+                    }
+                    // Projection
+                    if !self.freeze_velocity {
+                        let div = self.divergence();
+                        self.solve_pres(&div);
+                        self.project_velocity(1.0);
+                        self.update_pres(&div);
+                    }
+                }
 
                 // update time
                 self.time += self.dt;
+
+                // Project onto symmetry subspace
+                self.enforce_symmetry();
+            }
+
+            /// Explicit terms of \[ux, uy, temp, (scalar)\] (orthogonal
+            /// space): convection, pressure (`Incremental`), buoyancy and
+            /// the diffusion of the temperature boundary field
+            fn explicit_terms(&mut self) -> Vec<Array2<$s>> {
+                // Buoyancy
+                let mut that = self.temp.to_ortho();
+                if let Some(field) = &self.fieldbc {
+                    that = &that + &field.to_ortho();
+                }
+                // Convection Veclocity (relative to moving frame)
+                self.ux.backward();
+                self.uy.backward();
+                let ux = self.ux.v.mapv(|v| v - self.frame_velocity[0]);
+                let uy = self.uy.v.mapv(|v| v - self.frame_velocity[1]);
+                let mut fx = -self.conv_ux(&ux, &uy);
+                let mut fy = -self.conv_uy(&ux, &uy) + &that;
+                if self.pressure_mode == PressureMode::Incremental {
+                    fx -= &self.pres[0].gradient([1, 0], Some(self.scale));
+                    fy -= &self.pres[0].gradient([0, 1], Some(self.scale));
+                }
+                let mut ft = -self.conv_temp(&ux, &uy);
+                if let Some(field) = &self.fieldbc {
+                    ft += &(field.gradient([2, 0], Some(self.scale)) * self.ka);
+                    ft += &(field.gradient([0, 2], Some(self.scale)) * self.ka);
+                }
+                let mut terms = vec![fx, fy, ft];
+                if self.scalar.is_some() {
+                    terms.push(-self.conv_scalar(&ux, &uy));
+                }
+                terms
+            }
+
+            /// Implicit (diffusion) terms of \[ux, uy, temp, (scalar)\]
+            /// (orthogonal space)
+            fn implicit_terms(&self) -> Vec<Array2<$s>> {
+                let diffusion = |field: &Field2<$s, S>, c: f64| {
+                    (field.gradient([2, 0], Some(self.scale))
+                        + &field.gradient([0, 2], Some(self.scale)))
+                        * c
+                };
+                let mut terms = vec![
+                    diffusion(&self.ux, self.nu),
+                    diffusion(&self.uy, self.nu),
+                    diffusion(&self.temp, self.ka),
+                ];
+                if let Some(scalar) = &self.scalar {
+                    terms.push(diffusion(scalar, self.scalar_diffusivity));
+                }
+                terms
+            }
+
+            /// Advance solution by 1 timestep, repeat diverged
+            /// steps with half the timestep if `safe_step` is set
+            fn safe_update(&mut self) {
+                let max_retries = match self.safe_step {
+                    Some(x) => x,
+                    None => {
+                        self.step();
+                        return;
+                    }
+                };
+                let snapshot = [
+                    self.temp.vhat.to_owned(),
+                    self.ux.vhat.to_owned(),
+                    self.uy.vhat.to_owned(),
+                    self.pres[0].vhat.to_owned(),
+                    self.pres[1].vhat.to_owned(),
+                ];
+                let scalar = self.scalar.as_ref().map(|s| s.vhat.to_owned());
+                let time = self.time;
+                for retry in 0..=max_retries {
+                    self.step();
+                    if self.check_finite() {
+                        return;
+                    }
+                    if retry == max_retries {
+                        println!("Step diverged after {} retries, giving up", max_retries);
+                        return;
+                    }
+                    // Restore and retry with smaller timestep
+                    self.temp.vhat.assign(&snapshot[0]);
+                    self.ux.vhat.assign(&snapshot[1]);
+                    self.uy.vhat.assign(&snapshot[2]);
+                    self.pres[0].vhat.assign(&snapshot[3]);
+                    self.pres[1].vhat.assign(&snapshot[4]);
+                    if let (Some(field), Some(vhat)) = (&mut self.scalar, &scalar) {
+                        field.vhat.assign(vhat);
+                    }
+                    self.time = time;
+                    self.set_dt(self.dt / 2.);
+                    println!("Step diverged, retry with dt = {:e}", self.dt);
+                }
+            }
+        }
+
+        impl<S> Integrate for Navier2D<$s, S>
+        where
+            S: BaseSpace<f64, 2, Physical = f64, Spectral = $s>,
+        {
+            /// Update 1 timestep
+            ///
+            /// If `safe_step` is set, a diverged step (non-finite
+            /// fields) is undone and repeated with half the timestep.
+            ///
+            /// If `energy_budget` is set, the residual of the kinetic
+            /// energy budget is appended to diagnostics ("KE_residual").
+            fn update(&mut self) {
+                let before = if self.energy_budget {
+                    Some(self.energy_and_power())
+                } else {
+                    None
+                };
+                self.safe_update();
+                if let Some(before) = before {
+                    let residual = self.energy_budget_residual(before);
+                    self.diagnostics
+                        .entry("KE_residual".to_string())
+                        .or_insert_with(Vec::new)
+                        .push(residual);
+                }
             }
 
             fn get_time(&self) -> f64 {
@@ -772,27 +1748,27 @@ macro_rules! impl_integrate_for_navier {
                 self.dt
             }
 
+            /// Timestep from the CFL condition, if `adaptive_dt` is set
+            fn suggest_dt(&self) -> Option<f64> {
+                self.adaptive_dt
+                    .and_then(|adaptive| adaptive.suggest(self.dt, self.max_stable_dt()))
+            }
+
+            /// Set timestep and update the implicit solvers
+            fn set_dt(&mut self, dt: f64) {
+                Navier2D::set_dt(self, dt);
+            }
+
             fn callback(&mut self) {
                 use std::io::Write;
 
-                // Write hdf5 file
-                std::fs::create_dir_all("data").unwrap();
-
-                // Write flow field
-                //let fname = format!("data/flow{:.*}.h5", 3, self.time);
-                let fname = format!("data/flow{:0>8.2}.h5", self.time);
-                if let Some(dt_save) = &self.write_intervall {
-                    if (self.time % dt_save) < self.dt / 2.
-                        || (self.time % dt_save) > dt_save - self.dt / 2.
-                    {
-                        self.write(&fname);
-                    }
-                } else {
-                    self.write(&fname);
+                // Write hdf5 files
+                if let Err(e) = self.try_write() {
+                    eprintln!("Couldn't write output: {}", e);
                 }
 
                 // Write statistics
-                let statname = "data/statistics.h5";
+                let statname = self.output_file("statistics.h5");
                 if let Some(ref mut statistics) = self.statistics {
                     // Update
                     if (self.time % &statistics.save_stat) < self.dt / 2.
@@ -818,13 +1794,15 @@ macro_rules! impl_integrate_for_navier {
                 let nu = self.eval_nu();
                 let nuvol = self.eval_nuvol();
                 let re = self.eval_re();
+                let cfl = self.cfl();
                 println!(
-                    "time = {:4.2}      |div| = {:4.2e}     Nu = {:5.3e}     Nuv = {:5.3e}    Re = {:5.3e}",
+                    "time = {:4.2}      |div| = {:4.2e}     Nu = {:5.3e}     Nuv = {:5.3e}    Re = {:5.3e}    CFL = {:4.2}",
                     self.time,
                     $norm(&div),
                     nu,
                     nuvol,
                     re,
+                    cfl,
                 );
 
                 // diagnostics
@@ -840,15 +1818,22 @@ macro_rules! impl_integrate_for_navier {
                 if let Some(d) = self.diagnostics.get_mut("Re") {
                     d.push(re);
                 }
-                let mut file = std::fs::OpenOptions::new()
+                if let Some(d) = self.diagnostics.get_mut("CFL") {
+                    d.push(cfl);
+                }
+                match std::fs::OpenOptions::new()
                     .write(true)
                     .append(true)
                     .create(true)
-                    .open("data/info.txt")
-                    .unwrap();
-                //write!(file, "{} {}", time, nu);
-                if let Err(e) = writeln!(file, "{} {} {} {}", self.time, nu, nuvol, re) {
-                    eprintln!("Couldn't write to file: {}", e);
+                    .open(self.output_file("info.txt"))
+                {
+                    Ok(mut file) => {
+                        //write!(file, "{} {}", time, nu);
+                        if let Err(e) = writeln!(file, "{} {} {} {}", self.time, nu, nuvol, re) {
+                            eprintln!("Couldn't write to file: {}", e);
+                        }
+                    }
+                    Err(e) => eprintln!("Couldn't open file: {}", e),
                 }
             }
 
@@ -858,8 +1843,36 @@ macro_rules! impl_integrate_for_navier {
                 if $norm(&div).is_nan() {
                     return true;
                 }
+                // Break if steady
+                if let Some(tol) = self.steady_tol {
+                    if let Some(change) = self.relative_change() {
+                        if change < tol {
+                            println!("steady state reached: relative change = {:4.2e}", change);
+                            return true;
+                        }
+                    }
+                }
                 false
             }
+
+            /// Physical temp (without boundary condition field), ux, uy and pres
+            fn snapshot(&self) -> Snapshot {
+                let mut fields = HashMap::new();
+                for (name, field) in [
+                    ("temp", &self.temp),
+                    ("ux", &self.ux),
+                    ("uy", &self.uy),
+                    ("pres", &self.pres[0]),
+                ]
+                .iter()
+                {
+                    fields.insert(name.to_string(), field.to_physical().into_dyn());
+                }
+                Snapshot {
+                    time: self.time,
+                    fields,
+                }
+            }
         }
     };
 }
@@ -878,46 +1891,118 @@ fn norm_l2_c64(array: &Array2<Complex<f64>>) -> f64 {
         .sqrt()
 }
 
-impl<T, S> Navier2D<T, S>
+impl<T, S> ConvectionDiagnostics<T, S> for Navier2D<T, S>
 where
     S: BaseSpace<f64, 2, Physical = f64, Spectral = T>,
     T: Scalar + Mul<f64, Output = T> + Div<f64, Output = T>,
 {
-    /// Returns Nusselt number (heat flux at the plates)
+    fn temp(&self) -> &Field2<T, S> {
+        &self.temp
+    }
+
+    fn ux(&self) -> &Field2<T, S> {
+        &self.ux
+    }
+
+    fn uy(&self) -> &Field2<T, S> {
+        &self.uy
+    }
+
+    fn work_field(&self) -> &Field2<T, S> {
+        &self.field
+    }
+
+    fn fieldbc(&self) -> &Option<Field2<T, S>> {
+        &self.fieldbc
+    }
+
+    fn ka(&self) -> f64 {
+        self.ka
+    }
+
+    fn nu(&self) -> f64 {
+        self.nu
+    }
+
+    fn scale(&self) -> [f64; 2] {
+        self.scale
+    }
+}
+
+impl<T, S> Navier2D<T, S>
+where
+    S: BaseSpace<f64, 2, Physical = f64, Spectral = T>,
+    T: Scalar + Mul<f64, Output = T> + Div<f64, Output = T> + Into<Complex<f64>>,
+{
+    /// Returns available potential energy
     /// $$
-    /// Nu = \langle - dTdz \rangle\\_x (0/H))
+    /// APE = \langle T (y^* - y) \rangle\_V,
     /// $$
-    pub fn eval_nu(&mut self) -> f64 {
-        use super::functions::eval_nu;
-        eval_nu(&mut self.temp, &mut self.field, &self.fieldbc, &self.scale)
+    /// where $y^*$ is the height of a fluid parcel in the
+    /// adiabatically sorted state. Appended to diagnostics ("APE").
+    pub fn available_potential_energy(&mut self) -> f64 {
+        use super::functions::eval_ape;
+        let ape = eval_ape(&self.temp, &self.field, &self.fieldbc);
+        self.diagnostics
+            .entry("APE".to_string())
+            .or_insert_with(Vec::new)
+            .push(ape);
+        ape
     }
 
-    /// Returns volumetric Nusselt number
+    /// Returns conversion rate from potential to kinetic energy
     /// $$
-    /// Nuvol = \langle uy*T/kappa - dTdz \rangle\\_V
+    /// C = \langle uy*T \rangle\_V
     /// $$
-    pub fn eval_nuvol(&mut self) -> f64 {
-        use super::functions::eval_nuvol;
-        eval_nuvol(
-            &mut self.temp,
-            &mut self.uy,
-            &mut self.field,
-            &self.fieldbc,
-            self.ka,
-            &self.scale,
-        )
+    /// Appended to diagnostics ("Conversion").
+    pub fn conversion_rate(&mut self) -> f64 {
+        use super::functions::eval_conversion_rate;
+        let conversion = eval_conversion_rate(&self.temp, &self.uy, &self.field, &self.fieldbc);
+        self.diagnostics
+            .entry("Conversion".to_string())
+            .or_insert_with(Vec::new)
+            .push(conversion);
+        conversion
     }
 
-    /// Returns Reynolds number based on kinetic energy
-    pub fn eval_re(&mut self) -> f64 {
-        use super::functions::eval_re;
-        eval_re(
-            &mut self.ux,
-            &mut self.uy,
-            &mut self.field,
-            self.nu,
-            &self.scale,
-        )
+    /// Returns kinetic energy and the net power
+    /// \[KE, buoyancy flux - dissipation\]
+    pub fn energy_and_power(&self) -> [f64; 2] {
+        use super::functions::{eval_conversion_rate, eval_dissipation};
+        let ke = self.ux.kinetic_energy() + self.uy.kinetic_energy();
+        let buoyancy = eval_conversion_rate(&self.temp, &self.uy, &self.field, &self.fieldbc);
+        let dissipation = eval_dissipation(&self.ux, &self.uy, &self.field, self.nu, &self.scale);
+        [ke, buoyancy - dissipation]
+    }
+
+    /// Returns residual of the kinetic energy budget over the
+    /// last timestep
+    /// $$
+    /// r = \frac{KE^{n+1} - KE^n}{\delta t} - \frac{P^{n+1} + P^n}{2},
+    /// $$
+    /// where the power $P$ is the buoyancy flux minus the viscous
+    /// dissipation; the boundary flux vanishes for no-slip and
+    /// periodic boundaries. *before* holds \[KE, P\] at the
+    /// beginning of the step.
+    ///
+    /// The residual is of order `dt` in well resolved runs, a
+    /// growing residual indicates under-resolution or a too large
+    /// timestep, typically long before the fields diverge.
+    /// Forcing by solids, porous media and sponge layers is not
+    /// part of the budget.
+    pub fn energy_budget_residual(&self, before: [f64; 2]) -> f64 {
+        let after = self.energy_and_power();
+        (after[0] - before[0]) / self.dt - (after[1] + before[1]) / 2.
+    }
+
+    /// Returns horizontally averaged convective heat flux
+    /// $\langle uy*T \rangle\_x$ as function of y.
+    ///
+    /// Together with the conductive flux $-\kappa dT/dy$, the
+    /// total heat flux is independent of y in a steady state.
+    pub fn convective_flux_profile(&self) -> Array1<f64> {
+        use super::functions::eval_convective_flux_profile;
+        eval_convective_flux_profile(&self.temp, &self.uy, &self.field, &self.fieldbc)
     }
 
     /// Initialize velocity with fourier modes
@@ -951,6 +2036,242 @@ where
     pub fn reset_time(&mut self) {
         self.time = 0.;
     }
+
+    /// Set timestep size and update the implicit solvers
+    pub fn set_dt(&mut self, dt: f64) {
+        self.dt = dt;
+        let dt = self.implicit_dt();
+        let [sx, sy] = [self.scale[0].powf(2.), self.scale[1].powf(2.)];
+        let c_nu = [dt * self.nu / sx, dt * self.nu / sy];
+        let c_ka = [dt * self.ka / sx, dt * self.ka / sy];
+        for (i, c) in [c_nu, c_nu, c_ka].iter().enumerate() {
+            let field = match i {
+                0 => &self.ux,
+                1 => &self.uy,
+                _ => &self.temp,
+            };
+            self.solver[i] = match self.solver[i] {
+                SolverField::HholtzAdi(_) => SolverField::HholtzAdi(HholtzAdi::new(field, *c)),
+                _ => SolverField::Hholtz(Hholtz::new(field, *c)),
+            };
+        }
+        if let Some(scalar) = &self.scalar {
+            let c = [
+                dt * self.scalar_diffusivity / sx,
+                dt * self.scalar_diffusivity / sy,
+            ];
+            self.solver_scalar = Some(SolverField::Hholtz(Hholtz::new(scalar, c)));
+        }
+    }
+
+    /// Add passive scalar, e.g. a dye concentration, and update its
+    /// implicit solver. It is advected by the velocity and diffused
+    /// with `diffusivity`, but does not enter the buoyancy term.
+    ///
+    /// The boundary conditions follow from the base of `scalar`, e.g.
+    /// `cheb_neumann` along y for zero flux through the plates.
+    /// Its grid coordinates are scaled like those of the other fields.
+    pub fn set_scalar(&mut self, mut scalar: Field2<T, S>, diffusivity: f64) {
+        for axis in 0..2 {
+            scalar.x[axis] *= self.scale[axis];
+            scalar.dx[axis] *= self.scale[axis];
+        }
+        self.scalar = Some(scalar);
+        self.scalar_diffusivity = diffusivity;
+        self.set_dt(self.dt);
+    }
+
+    /// Returns velocity boundary conditions, see [`VelocityBc`]
+    pub fn velocity_bc(&self) -> VelocityBc {
+        self.velocity_bc
+    }
+
+    /// Returns boussinesq or anelastic approximation, see [`Approximation`]
+    pub fn approximation(&self) -> &Approximation {
+        &self.approximation
+    }
+
+    /// Returns time integration scheme, see [`TimeScheme`]
+    pub fn time_scheme(&self) -> TimeScheme {
+        self.time_scheme
+    }
+
+    /// Set time integration scheme and update the implicit solvers.
+    /// The default is `TimeScheme::Euler`.
+    ///
+    /// # Example
+    /// ```
+    /// use rustpde::navier::navier::TimeScheme;
+    /// use rustpde::navier::Navier2D;
+    /// let mut navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+    /// navier.set_time_scheme(TimeScheme::Rk3);
+    /// ```
+    pub fn set_time_scheme(&mut self, time_scheme: TimeScheme) {
+        self.time_scheme = time_scheme;
+        self.set_dt(self.dt);
+    }
+
+    /// Coefficient of the implicit terms, which are solved for
+    /// in each step or stage
+    fn implicit_dt(&self) -> f64 {
+        match self.time_scheme {
+            TimeScheme::Euler => self.dt,
+            TimeScheme::Rk3 => RK3_IMPL[0][0] * self.dt,
+        }
+    }
+
+    /// Write temperature (including the boundary field), ux, uy and
+    /// pressure to a vtk rectilinear grid file (ending `.vtr`),
+    /// e.g. to open them in ParaView
+    ///
+    /// # Errors
+    /// Errors if the file can not be written.
+    pub fn write_vtk(&self, filename: &str) -> std::io::Result<()> {
+        use crate::field::vtk::write_vtr;
+        let mut temp = self.temp.to_physical();
+        if let Some(field) = &self.fieldbc {
+            temp += &field.to_physical();
+        }
+        let fields = [
+            ("temp", temp),
+            ("ux", self.ux.to_physical()),
+            ("uy", self.uy.to_physical()),
+            ("pres", self.pres[0].to_physical()),
+        ];
+        write_vtr(filename, &self.temp.x[0], &self.temp.x[1], &fields)
+    }
+
+    /// Returns force \[fx, fy\] of the fluid on the solid obstacle,
+    /// per unit volume of the domain, i.e. the volume average of
+    /// the penalization term `mask * u / eta`. Zero without solid.
+    pub fn solid_drag(&self) -> [f64; 2] {
+        match &self.solid {
+            Some(solid) => {
+                let mut field = self.field.clone();
+                let mut drag = [0.; 2];
+                for (d, u) in drag.iter_mut().zip([&self.ux, &self.uy].iter()) {
+                    field
+                        .v
+                        .assign(&(&solid[0] * &u.to_physical() / self.penalty_eta));
+                    *d = field.average();
+                }
+                drag
+            }
+            None => [0.; 2],
+        }
+    }
+
+    /// Returns the largest stable timestep of the explicitly
+    /// treated terms, e.g. to choose `dt` for `set_dt`.
+    ///
+    /// Convection is limited by the CFL condition
+    /// $$
+    /// \delta t \max \left( \frac{|u|}{\Delta x} + \frac{|v|}{\Delta y} \right) \leq 1,
+    /// $$
+    /// with the local grid spacing and the velocity relative to the
+    /// moving frame. Linear damping with rate $\sigma$ (solids, darcy
+    /// drag and sponge layers) is limited by $\delta t \sigma \leq 2$.
+    /// Diffusion is treated implicitly and does not limit the timestep.
+    ///
+    /// Returns the binding limit, or infinity if no limit applies.
+    pub fn max_stable_dt(&self) -> f64 {
+        let max = |a: &Array2<f64>| a.iter().fold(0_f64, |m, v| m.max(v.abs()));
+        // Convective limit
+        let cfl = self.convective_rate();
+        // Limit of explicit damping
+        let mut sigma = 0.;
+        if let Some(solid) = &self.solid {
+            sigma += max(&solid[0]) / self.penalty_eta;
+        }
+        if let Some(k) = &self.permeability {
+            let k_min = k.iter().fold(f64::INFINITY, |m, v| m.min(*v));
+            sigma += self.nu / k_min;
+        }
+        if let Some(sponge) = &self.sponge {
+            sigma += max(&sponge.mask) * sponge.rate;
+        }
+        (1. / cfl).min(2. / sigma)
+    }
+
+    /// Returns path of output file `name` in `output_dir`
+    pub fn output_file(&self, name: &str) -> String {
+        self.output_dir.join(name).to_string_lossy().into_owned()
+    }
+
+    /// Returns path of flow field file at the current time,
+    /// i.e. `{output_dir}/{file_prefix}{time}.h5`
+    pub fn flow_file(&self) -> String {
+        self.output_file(&format!("{}{:0>8.2}.h5", self.file_prefix, self.time))
+    }
+
+    /// Returns CFL number
+    /// $$
+    /// \delta t \max \left( \frac{|u|}{\Delta x} + \frac{|v|}{\Delta y} \right),
+    /// $$
+    /// with the local spacing of the (non-uniform) grid and the
+    /// velocity relative to the moving frame. Appended to
+    /// diagnostics ("CFL") on each write. Steps with a CFL number
+    /// above unity are unstable, see `max_stable_dt`.
+    pub fn cfl(&self) -> f64 {
+        self.dt * self.convective_rate()
+    }
+
+    /// Largest ratio of velocity and local grid spacing
+    fn convective_rate(&self) -> f64 {
+        let (vx, vy) = (self.ux.to_physical(), self.uy.to_physical());
+        let (dx, dy) = (&self.ux.dx[0], &self.ux.dx[1]);
+        let mut rate = 0_f64;
+        for ((i, j), u) in vx.indexed_iter() {
+            let ux = (u - self.frame_velocity[0]).abs();
+            let uy = (vy[[i, j]] - self.frame_velocity[1]).abs();
+            rate = rate.max(ux / dx[i] + uy / dy[j]);
+        }
+        rate
+    }
+
+    /// Set treatment of the singular pressure poisson
+    /// equation and update the pressure solver.
+    /// The default is `NullspaceMode::Nudge`.
+    ///
+    /// # Errors
+    /// Returns `SolverError::Singular` for `NullspaceMode::Error`
+    /// if the pressure equation is singular. The solver is unchanged.
+    pub fn set_pressure_nullspace(&mut self, nullspace: NullspaceMode) -> Result<(), SolverError> {
+        let [sx, sy] = [self.scale[0].powf(2.), self.scale[1].powf(2.)];
+        let poisson = Poisson::with_nullspace(&self.pres[1], [1. / sx, 1. / sy], nullspace)?;
+        self.solver[3] = SolverField::Poisson(poisson);
+        Ok(())
+    }
+
+    /// Return diagnostics as csv formatted string.
+    ///
+    /// Columns are ordered deterministically, "time" first,
+    /// followed by all other keys in alphabetical order.
+    /// Shorter columns are padded with empty entries.
+    pub fn diagnostics_csv_string(&self) -> String {
+        let mut keys: Vec<&String> = self.diagnostics.keys().collect();
+        keys.sort_by(|a, b| (*a != "time").cmp(&(*b != "time")).then(a.cmp(b)));
+        let nrows = self.diagnostics.values().map(Vec::len).max().unwrap_or(0);
+        let mut csv = keys
+            .iter()
+            .map(|k| k.as_str())
+            .collect::<Vec<&str>>()
+            .join(",");
+        csv.push('\n');
+        for i in 0..nrows {
+            let row: Vec<String> = keys
+                .iter()
+                .map(|k| {
+                    self.diagnostics[*k]
+                        .get(i)
+                        .map_or_else(String::new, f64::to_string)
+                })
+                .collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
 }
 
 macro_rules! impl_read_write_navier {
@@ -960,17 +2281,70 @@ macro_rules! impl_read_write_navier {
             S: BaseSpace<f64, 2, Physical = f64, Spectral = $s>,
         {
             /// Restart from file
+            ///
+            /// Restores fields, time and the history of diagnostics,
+            /// such that time averages continue seamlessly.
             pub fn read(&mut self, filename: &str) {
+                use crate::hdf5::{read_from_hdf5, read_member_names};
                 // Field
                 self.temp.read(&filename, Some("temp"));
                 self.ux.read(&filename, Some("ux"));
                 self.uy.read(&filename, Some("uy"));
                 self.pres[0].read(&filename, Some("pres"));
+                if let Some(scalar) = &mut self.scalar {
+                    scalar.read(&filename, Some("scalar"));
+                }
                 // Read scalars
                 self.time = read_scalar_from_hdf5::<f64>(&filename, "time", None).unwrap();
+                // Read diagnostics (series missing in the file, e.g. files
+                // written before diagnostics were stored, start empty)
+                for value in self.diagnostics.values_mut() {
+                    value.clear();
+                }
+                let keys = read_member_names(&filename, Some("diagnostics")).unwrap_or_default();
+                for key in keys {
+                    if let Ok(x) =
+                        read_from_hdf5::<f64, ndarray::Ix1>(&filename, &key, Some("diagnostics"))
+                    {
+                        self.diagnostics.insert(key, x.to_vec());
+                    }
+                }
                 println!(" <== {:?}", filename);
             }
 
+            /// Write output files of the callback into `output_dir`:
+            /// grid coordinates (once, if `write_grid`), flow field
+            /// (every `write_intervall`) and probes.
+            ///
+            /// # Errors
+            /// Returns an error if the directory can not be created
+            /// or a file can not be written.
+            pub fn try_write(&mut self) -> Result<()> {
+                std::fs::create_dir_all(&self.output_dir).map_err(|e| e.to_string())?;
+                // Write grid coordinates (once)
+                let gridname = self.output_file("grid.h5");
+                if self.write_grid && !std::path::Path::new(&gridname).exists() {
+                    self.write_grid_return_result(&gridname)?;
+                    println!(" ==> {:?}", gridname);
+                }
+                // Write flow field
+                let fname = self.flow_file();
+                let write_field = match self.write_intervall {
+                    Some(dt_save) => {
+                        (self.time % dt_save) < self.dt / 2.
+                            || (self.time % dt_save) > dt_save - self.dt / 2.
+                    }
+                    None => true,
+                };
+                if write_field {
+                    self.write_return_result(&fname)?;
+                    println!(" ==> {:?}", fname);
+                }
+                // Write probes
+                self.write_probes(&self.output_file("probes.h5"))?;
+                Ok(())
+            }
+
             /// Write Field data to hdf5 file
             pub fn write(&mut self, filename: &str) {
                 let result = self.write_return_result(filename);
@@ -980,7 +2354,21 @@ macro_rules! impl_read_write_navier {
                 }
             }
 
+            /// Write to temporary file, which is renamed to `filename`
+            /// once it is complete. An interrupted write never leaves
+            /// a partially written (restart) file behind.
             fn write_return_result(&mut self, filename: &str) -> Result<()> {
+                let tmp = format!("{}.tmp", filename);
+                // Remove leftovers of interrupted writes
+                if std::path::Path::new(&tmp).exists() {
+                    std::fs::remove_file(&tmp).map_err(|e| e.to_string())?;
+                }
+                self.write_to_file(&tmp)?;
+                std::fs::rename(&tmp, filename).map_err(|e| e.to_string())?;
+                Ok(())
+            }
+
+            fn write_to_file(&mut self, filename: &str) -> Result<()> {
                 use crate::hdf5::write_to_hdf5;
                 self.temp.backward();
                 self.ux.backward();
@@ -991,26 +2379,72 @@ macro_rules! impl_read_write_navier {
                     self.temp.v = &self.temp.v + &x.v;
                 }
                 // Field
-                self.temp.write(&filename, Some("temp"));
-                self.ux.write(&filename, Some("ux"));
-                self.uy.write(&filename, Some("uy"));
-                self.pres[0].write(&filename, Some("pres"));
+                let level = self.compression;
+                self.temp.write_compressed(&filename, Some("temp"), level)?;
+                self.ux.write_compressed(&filename, Some("ux"), level)?;
+                self.uy.write_compressed(&filename, Some("uy"), level)?;
+                self.pres[0].write_compressed(&filename, Some("pres"), level)?;
+                if let Some(scalar) = &mut self.scalar {
+                    scalar.backward();
+                    scalar.write_compressed(&filename, Some("scalar"), level)?;
+                }
                 // Write solid mask
                 if let Some(x) = &self.solid {
                     write_to_hdf5(&filename, "mask", Some("solid"), &x[0])?;
                 }
                 // Write scalars
                 write_scalar_to_hdf5(&filename, "time", None, self.time)?;
+                if let Some(t_ref) = self.reference_time {
+                    write_scalar_to_hdf5(&filename, "time_dimensional", None, self.time * t_ref)?;
+                }
                 write_scalar_to_hdf5(&filename, "ra", None, self.ra)?;
                 write_scalar_to_hdf5(&filename, "pr", None, self.pr)?;
                 write_scalar_to_hdf5(&filename, "nu", None, self.nu)?;
                 write_scalar_to_hdf5(&filename, "kappa", None, self.ka)?;
+                // Write run parameters as attributes
+                let shape = self.temp.v.shape();
+                write_attr_to_hdf5(&filename, "ra", None, self.ra)?;
+                write_attr_to_hdf5(&filename, "pr", None, self.pr)?;
+                write_attr_to_hdf5(&filename, "aspect", None, self.scale[0] / self.scale[1])?;
+                write_attr_to_hdf5(&filename, "dt", None, self.dt)?;
+                write_attr_to_hdf5(&filename, "nx", None, shape[0] as u64)?;
+                write_attr_to_hdf5(&filename, "ny", None, shape[1] as u64)?;
+                // Write diagnostics (for restart)
+                for (key, value) in &self.diagnostics {
+                    if !value.is_empty() {
+                        write_to_hdf5(
+                            &filename,
+                            key,
+                            Some("diagnostics"),
+                            &Array1::from(value.clone()),
+                        )?;
+                    }
+                }
                 // Undo addition of bc
                 if self.fieldbc.is_some() {
                     self.temp.backward();
                 }
                 Ok(())
             }
+
+            /// Write physical grid coordinates x, y (and grid
+            /// spacings dx, dy) to hdf5 file
+            pub fn write_grid(&self, filename: &str) {
+                let result = self.write_grid_return_result(filename);
+                match result {
+                    Ok(_) => println!(" ==> {:?}", filename),
+                    Err(_) => println!("Error while writing file {:?}.", filename),
+                }
+            }
+
+            fn write_grid_return_result(&self, filename: &str) -> Result<()> {
+                use crate::hdf5::write_to_hdf5;
+                write_to_hdf5(&filename, "x", None, &self.temp.x[0])?;
+                write_to_hdf5(&filename, "y", None, &self.temp.x[1])?;
+                write_to_hdf5(&filename, "dx", None, &self.temp.dx[0])?;
+                write_to_hdf5(&filename, "dy", None, &self.temp.dx[1])?;
+                Ok(())
+            }
         }
     };
 }
@@ -1024,11 +2458,7 @@ where
     S: BaseSpace<f64, 2, Physical = f64, Spectral = T2>,
     T2: Zero + Clone + Copy,
 {
-    let zero = T2::zero();
-    let n_x: usize = field.vhat.shape()[0] * 2 / 3;
-    let n_y: usize = field.vhat.shape()[1] * 2 / 3;
-    field.vhat.slice_mut(s![n_x.., ..]).fill(zero);
-    field.vhat.slice_mut(s![.., n_y..]).fill(zero);
+    field.dealias(DealiasRule::TwoThirds);
 }
 
 /// Construct field f(x,y) = amp \* sin(pi\*m)cos(pi\*n)
@@ -1103,3 +2533,986 @@ fn transfer_function(x: &Array1<f64>, v_l: f64, v_m: f64, v_r: f64, k: f64) -> A
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_dimensional_time() {
+        let mut navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        navier.time = 2.5;
+        navier.reference_time = Some(0.4);
+        let filename = std::env::temp_dir().join("rustpde_test_dimensional_time.h5");
+        let filename = filename.to_str().unwrap();
+        navier.write_return_result(filename).unwrap();
+        let time = read_scalar_from_hdf5::<f64>(filename, "time", None).unwrap();
+        let time_dim = read_scalar_from_hdf5::<f64>(filename, "time_dimensional", None).unwrap();
+        assert!((time - 2.5).abs() < 1e-12);
+        assert!((time_dim - time * 0.4).abs() < 1e-12);
+        std::fs::remove_file(filename).unwrap();
+    }
+
+    #[test]
+    fn test_safe_step_halves_dt() {
+        let dt = 1e40;
+        let mut navier = Navier2D::new(17, 17, 1e4, 1., dt, 1., false);
+        // huge velocities, such that the explicit convection
+        // term overflows for large timesteps
+        navier.set_velocity(1e140, 1., 1.);
+        navier.safe_step = Some(200);
+        navier.update();
+        assert!(navier.check_finite());
+        assert!(navier.dt < dt);
+        assert!((navier.time - navier.dt).abs() < 1e-12 * navier.dt);
+        // subsequent steps use the reduced timestep
+        let dt_reduced = navier.dt;
+        navier.set_velocity(0.1, 1., 1.);
+        navier.update();
+        assert!(navier.check_finite());
+        assert!((navier.dt - dt_reduced).abs() < 1e-12 * dt_reduced);
+    }
+
+    #[test]
+    #[ignore = "slow: 4000 steps, run with `cargo test -- --ignored`"]
+    fn test_pressure_modes_agree_in_steady_state() {
+        // Heated sidewall, T = -0.5x, below the onset of instability
+        let (nx, ny, dt, steps) = (17, 17, 0.005, 4000);
+        let mut velocities = Vec::new();
+        for mode in [PressureMode::Incremental, PressureMode::Recompute].iter() {
+            let mut navier = Navier2D::new(nx, ny, 1e3, 1., dt, 1., false);
+            let mut fieldbc = Field2::new(&Space2::new(&chebyshev(nx), &chebyshev(ny)));
+            let x = fieldbc.x[0].to_owned();
+            for (i, xi) in x.iter().enumerate() {
+                fieldbc.v.row_mut(i).fill(-0.5 * xi);
+            }
+            fieldbc.forward();
+            navier.set_temp_bc(fieldbc);
+            navier.temp.vhat.fill(0.);
+            navier.ux.vhat.fill(0.);
+            navier.uy.vhat.fill(0.);
+            navier.pressure_mode = *mode;
+            for _ in 0..steps {
+                navier.update();
+            }
+            velocities.push([navier.ux.to_physical(), navier.uy.to_physical()]);
+        }
+        // Agree up to the splitting error of order dt
+        for (a, b) in velocities[0].iter().zip(velocities[1].iter()) {
+            let max = a.iter().fold(0., |m: f64, v| m.max(v.abs()));
+            assert!(max > 1e-3);
+            let diff = (a - b).iter().fold(0., |m: f64, v| m.max(v.abs()));
+            assert!(diff < 5e-2 * max, "diff {} max {}", diff, max);
+        }
+    }
+
+    #[test]
+    fn test_write_vtk_result() {
+        let navier = Navier2D::new_periodic(8, 9, 1e4, 1., 0.01, 1.);
+        let filename = std::env::temp_dir().join("rustpde_test_navier_write_vtk.vtr");
+        let filename = filename.to_str().unwrap();
+        navier.write_vtk(filename).unwrap();
+        let content = std::fs::read_to_string(filename).unwrap();
+        std::fs::remove_file(filename).unwrap();
+        for name in ["temp", "ux", "uy", "pres"].iter() {
+            assert!(content.contains(&format!("Name=\"{}\"", name)));
+        }
+        // Missing directory
+        let filename = std::env::temp_dir().join("rustpde_missing_dir/navier.vtr");
+        assert!(navier.write_vtk(filename.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_energy_budget_residual_small() {
+        let mut navier = Navier2D::new_periodic(32, 33, 1e4, 1., 0.01, 1.);
+        navier.ux.vhat.fill(Complex::new(0., 0.));
+        navier.uy.vhat.fill(Complex::new(0., 0.));
+        let x = navier.temp.x[0].to_owned();
+        let y = navier.temp.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                navier.temp.v[[i, j]] = 0.1 * xi.sin() * (std::f64::consts::FRAC_PI_2 * yi).cos();
+            }
+        }
+        navier.temp.forward();
+        navier.energy_budget = true;
+        let mut max_power: f64 = 0.;
+        for _ in 0..50 {
+            navier.update();
+            max_power = max_power.max(navier.energy_and_power()[1].abs());
+        }
+        let residual = &navier.diagnostics["KE_residual"];
+        assert_eq!(residual.len(), 50);
+        assert!(max_power > 1e-6);
+        for r in residual.iter() {
+            assert!(r.abs() < 5e-2 * max_power, "{} {}", r, max_power);
+        }
+    }
+
+    #[test]
+    fn test_enstrophy_palinstrophy() {
+        // psi = sin(x)(1-y^2)^2 => omega = -sin(x) g(y),
+        // with g = 5 - 14y^2 + y^4
+        let mut navier = Navier2D::new_periodic(16, 33, 1e4, 1., 0.01, 1.);
+        let x = navier.ux.x[0].to_owned();
+        let y = navier.ux.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                let f = 1. - yi * yi;
+                navier.ux.v[[i, j]] = 4. * yi * f * xi.sin();
+                navier.uy.v[[i, j]] = f * f * xi.cos();
+            }
+        }
+        navier.ux.forward();
+        navier.uy.forward();
+        // Z = 1/8 int g^2 dy, P = 1/8 int g^2 + g'^2 dy
+        let enstrophy = navier.enstrophy();
+        assert!((enstrophy - 176. / 45.).abs() < 1e-8, "{}", enstrophy);
+        let palinstrophy = navier.palinstrophy();
+        assert!(
+            (palinstrophy - 18464. / 315.).abs() < 1e-8,
+            "{}",
+            palinstrophy
+        );
+        assert_eq!(navier.diagnostics["Enstrophy"], vec![enstrophy]);
+        assert_eq!(navier.diagnostics["Palinstrophy"], vec![palinstrophy]);
+    }
+
+    #[test]
+    fn test_pressure_nullspace_modes_agree() {
+        let mut navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        navier.set_velocity(0.2, 1., 1.);
+        navier.set_temperature(0.2, 1., 1.);
+        let mut pinned = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        pinned.set_velocity(0.2, 1., 1.);
+        pinned.set_temperature(0.2, 1., 1.);
+        assert!(pinned.set_pressure_nullspace(NullspaceMode::Error).is_err());
+        pinned
+            .set_pressure_nullspace(NullspaceMode::PinMean)
+            .unwrap();
+        for _ in 0..10 {
+            navier.update();
+            pinned.update();
+        }
+        assert!(pinned.ux.relative_difference(&navier.ux) < 1e-6);
+        assert!(pinned.temp.relative_difference(&navier.temp) < 1e-6);
+    }
+
+    #[test]
+    fn test_max_stable_dt_poiseuille() {
+        // ux = U (1 - y^2), uy = 0 => dt = dx / U (ny odd, so y = 0 is a gridpoint)
+        let u = 2.;
+        let mut navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        let y = navier.ux.x[1].to_owned();
+        let y_max = y.iter().fold(0_f64, |m, v| m.max(v.abs()));
+        navier.uy.vhat.fill(Complex::new(0., 0.));
+        navier.ux.with_physical_mut(|v| {
+            for (j, yj) in y.iter().enumerate() {
+                v.column_mut(j).fill(u * (1. - (yj / y_max).powi(2)));
+            }
+        });
+        let dx = navier.ux.x[0][1] - navier.ux.x[0][0];
+        assert!((navier.max_stable_dt() - dx / u).abs() < 1e-10);
+        assert!((navier.cfl() - navier.dt * u / dx).abs() < 1e-10);
+        // Sponge layer becomes the binding constraint
+        let mask = Array2::<f64>::ones(navier.ux.v.raw_dim());
+        navier.sponge = Some(SpongeLayer::new(mask, 1e4));
+        assert!((navier.max_stable_dt() - 2e-4).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_frame_velocity() {
+        // Fluid at rest between the walls drifts with -U0 in the
+        // moving frame, so T(x, y, t) = T0(x + U0 t, y)
+        let (dt, steps, u0) = (0.005, 10, 1.);
+        let mut navier = Navier2D::new_periodic(32, 33, 1e8, 1., dt, 1.);
+        navier.fieldbc = None;
+        navier.ux.vhat.fill(Complex::new(0., 0.));
+        navier.uy.vhat.fill(Complex::new(0., 0.));
+        let x = navier.temp.x[0].to_owned();
+        let y = navier.temp.x[1].to_owned();
+        let t0 = |x: f64, y: f64| 0.1 * x.sin() * (std::f64::consts::FRAC_PI_2 * y).cos();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                navier.temp.v[[i, j]] = t0(*xi, *yi);
+            }
+        }
+        navier.temp.forward();
+        let initial = navier.temp.to_physical();
+        navier.set_frame_velocity(u0, 0.);
+        for _ in 0..steps {
+            navier.update();
+        }
+        let temp = navier.temp.to_physical();
+        let shift = u0 * dt * steps as f64;
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                let expected = t0(xi + shift, *yi);
+                assert!((temp[[i, j]] - expected).abs() < 1e-3, "{:?}", (i, j));
+            }
+        }
+        // Moved on the grid
+        let moved = (&temp - &initial)
+            .iter()
+            .fold(0., |m: f64, v| m.max(v.abs()));
+        assert!(moved > 1e-3);
+    }
+
+    #[test]
+    fn test_write_grid() {
+        use crate::hdf5::read_from_hdf5;
+        let navier = Navier2D::new(16, 17, 1e4, 1., 0.01, 2., true);
+        let filename = std::env::temp_dir().join("rustpde_test_write_grid.h5");
+        let filename = filename.to_str().unwrap();
+        navier.write_grid_return_result(filename).unwrap();
+        let x = read_from_hdf5::<f64, ndarray::Ix1>(filename, "x", None).unwrap();
+        let y = read_from_hdf5::<f64, ndarray::Ix1>(filename, "y", None).unwrap();
+        std::fs::remove_file(filename).unwrap();
+        assert_eq!(x, navier.temp.x[0]);
+        assert_eq!(y, navier.temp.x[1]);
+        // scaled to aspect ratio
+        assert!((x[x.len() - 1] - x[0] - 2. * (y[y.len() - 1] - y[0])).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_write_parameter_attributes() {
+        use crate::hdf5::read_attr_from_hdf5;
+        let mut navier = Navier2D::new(16, 17, 1e4, 0.7, 0.01, 2., true);
+        let filename = std::env::temp_dir().join("rustpde_test_write_attributes.h5");
+        let filename = filename.to_str().unwrap();
+        navier.write_to_file(filename).unwrap();
+        let ra: f64 = read_attr_from_hdf5(filename, "ra", None).unwrap();
+        let pr: f64 = read_attr_from_hdf5(filename, "pr", None).unwrap();
+        let aspect: f64 = read_attr_from_hdf5(filename, "aspect", None).unwrap();
+        let dt: f64 = read_attr_from_hdf5(filename, "dt", None).unwrap();
+        let nx: u64 = read_attr_from_hdf5(filename, "nx", None).unwrap();
+        let ny: u64 = read_attr_from_hdf5(filename, "ny", None).unwrap();
+        // datasets are kept
+        let ra_dset: f64 = read_scalar_from_hdf5(filename, "ra", None).unwrap();
+        std::fs::remove_file(filename).unwrap();
+        assert!((ra - 1e4).abs() < 1e-10);
+        assert!((pr - 0.7).abs() < 1e-10);
+        assert!((aspect - 2.).abs() < 1e-10);
+        assert!((dt - 0.01).abs() < 1e-10);
+        assert_eq!((nx, ny), (16, 17));
+        assert!((ra_dset - ra).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_try_write() {
+        let mut navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        navier.write_grid = true;
+        let dir = std::env::temp_dir().join("rustpde_test_try_write");
+        navier.output_dir = dir.clone();
+        navier.try_write().unwrap();
+        let flow = dir.join("flow00000.00.h5");
+        let grid = dir.join("grid.h5");
+        let exist = flow.exists() && grid.exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(exist);
+        // Output directory can not be created below a file
+        let blocker = std::env::temp_dir().join("rustpde_test_try_write_blocker");
+        std::fs::write(&blocker, "").unwrap();
+        navier.output_dir = blocker.join("data");
+        let result = navier.try_write();
+        // Callback logs the error and continues
+        navier.callback();
+        std::fs::remove_file(&blocker).unwrap();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_file_prefix() {
+        let mut navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        let dir = std::env::temp_dir().join("rustpde_test_file_prefix");
+        navier.output_dir = dir.clone();
+        navier.file_prefix = "case_a_".to_string();
+        navier.time = 1.5;
+        let expected = dir.join("case_a_00001.50.h5");
+        assert_eq!(navier.flow_file(), expected.to_string_lossy());
+        navier.try_write().unwrap();
+        let exists = expected.exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(exists);
+    }
+
+    #[test]
+    fn test_compressed_write_roundtrip() {
+        let mut navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        navier.random_disturbance(0.1);
+        let dir = std::env::temp_dir();
+        let plain = dir.join("rustpde_test_write_plain.h5");
+        let gzip = dir.join("rustpde_test_write_gzip.h5");
+        let (plain, gzip) = (plain.to_str().unwrap(), gzip.to_str().unwrap());
+        navier.write_to_file(plain).unwrap();
+        navier.compression = Some(6);
+        navier.write_to_file(gzip).unwrap();
+        let mut a = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        let mut b = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        a.read(plain);
+        b.read(gzip);
+        std::fs::remove_file(plain).unwrap();
+        std::fs::remove_file(gzip).unwrap();
+        assert_eq!(a.temp.vhat, b.temp.vhat);
+        assert_eq!(a.ux.vhat, b.ux.vhat);
+        assert_eq!(a.uy.vhat, b.uy.vhat);
+        assert_eq!(a.pres[0].vhat, b.pres[0].vhat);
+    }
+
+    #[test]
+    fn test_restart_restores_diagnostics() {
+        fn step(navier: &mut Navier2D<Complex<f64>, Space2R2c>) {
+            navier.update();
+            let nu = navier.eval_nu();
+            let time = navier.time;
+            navier.diagnostics.get_mut("time").unwrap().push(time);
+            navier.diagnostics.get_mut("Nu").unwrap().push(nu);
+        }
+        fn mean(v: &[f64]) -> f64 {
+            v.iter().sum::<f64>() / v.len() as f64
+        }
+        let (nx, ny, ra, pr, dt, aspect) = (16, 17, 1e4, 1., 0.01, 1.);
+        let (n1, n2) = (5, 5);
+        // Continuous run
+        let mut navier = Navier2D::new_periodic(nx, ny, ra, pr, dt, aspect);
+        let filename = std::env::temp_dir().join("rustpde_test_restart_diagnostics.h5");
+        let filename = filename.to_str().unwrap();
+        navier.write_return_result(filename).unwrap();
+        for _ in 0..n1 + n2 {
+            step(&mut navier);
+        }
+        let nu_continuous = mean(&navier.diagnostics["Nu"]);
+        // Two segments, restart in between
+        let mut navier = Navier2D::new_periodic(nx, ny, ra, pr, dt, aspect);
+        navier.read(filename);
+        std::fs::remove_file(filename).unwrap();
+        for _ in 0..n1 {
+            step(&mut navier);
+        }
+        navier.write_return_result(filename).unwrap();
+        let nu_last = *navier.diagnostics["Nu"].last().unwrap();
+        let mut navier = Navier2D::new_periodic(nx, ny, ra, pr, dt, aspect);
+        navier.read(filename);
+        std::fs::remove_file(filename).unwrap();
+        assert_eq!(navier.diagnostics["Nu"].len(), n1);
+        assert!((navier.diagnostics["Nu"][n1 - 1] - nu_last).abs() < 1e-14);
+        for _ in 0..n2 {
+            step(&mut navier);
+        }
+        let nu_restart = mean(&navier.diagnostics["Nu"]);
+        assert!(
+            (nu_continuous - nu_restart).abs() < 1e-8 * nu_continuous.abs(),
+            "{} != {}",
+            nu_continuous,
+            nu_restart
+        );
+    }
+
+    #[test]
+    fn test_restart_restores_lazy_diagnostics() {
+        let mut navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        let filename = std::env::temp_dir().join("rustpde_test_restart_lazy_diagnostics.h5");
+        let filename = filename.to_str().unwrap();
+        navier.update();
+        let enstrophy = navier.enstrophy();
+        navier.write_return_result(filename).unwrap();
+        // Series is created on first evaluation only
+        let mut navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        assert!(!navier.diagnostics.contains_key("Enstrophy"));
+        navier.read(filename);
+        std::fs::remove_file(filename).unwrap();
+        assert_eq!(navier.diagnostics["Enstrophy"], vec![enstrophy]);
+        assert!(navier.diagnostics["Nu"].is_empty());
+    }
+
+    #[test]
+    fn test_restart_without_diagnostics() {
+        let mut navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        let filename = std::env::temp_dir().join("rustpde_test_restart_no_diagnostics.h5");
+        let filename = filename.to_str().unwrap();
+        // No diagnostics recorded, i.e. none written
+        navier.write_return_result(filename).unwrap();
+        navier.diagnostics.get_mut("Nu").unwrap().push(1.);
+        navier.read(filename);
+        std::fs::remove_file(filename).unwrap();
+        assert!(navier.diagnostics.values().all(Vec::is_empty));
+    }
+
+    #[test]
+    fn test_conductive_state() {
+        let navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        let mut cond = navier.conductive_state();
+        // Plate temperatures
+        let y = cond.x[1].to_owned();
+        cond.backward();
+        for column in cond.v.outer_iter() {
+            assert!((column[0] - 0.5).abs() < 1e-10);
+            assert!((column[y.len() - 1] + 0.5).abs() < 1e-10);
+        }
+        // Laplacian vanishes
+        let lap = cond.gradient([2, 0], None) + cond.gradient([0, 2], None);
+        assert!(lap.iter().all(|v| v.norm() < 1e-10));
+    }
+
+    #[test]
+    fn test_closed_cell_conduction() {
+        // Chebyshev in x and y: no-slip on all four walls, adiabatic
+        // sidewalls. Below the onset of convection the disturbance
+        // decays and the heat flux approaches conduction (Nu = 1).
+        let mut navier = Navier2D::new(17, 17, 1e2, 1., 0.05, 1., true);
+        for _ in 0..300 {
+            navier.update();
+        }
+        for u in [&mut navier.ux, &mut navier.uy].iter_mut() {
+            u.backward();
+            let n = u.v.shape();
+            for i in 0..n[0] {
+                assert!(u.v[[i, 0]].abs() < 1e-10 && u.v[[i, n[1] - 1]].abs() < 1e-10);
+            }
+            for j in 0..n[1] {
+                assert!(u.v[[0, j]].abs() < 1e-10 && u.v[[n[0] - 1, j]].abs() < 1e-10);
+            }
+        }
+        assert!(navier.eval_re() < 1e-2);
+        assert!((navier.eval_nu() - 1.).abs() < 1e-2);
+        assert!((navier.eval_nuvol() - 1.).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_closed_cell_convection() {
+        // Square cell with adiabatic sidewalls, Ra = 1e4 and Pr = 0.71.
+        // Reference: Nu = 2.158 (Ouertatani et al., Comptes Rendus
+        // Mecanique 336, 2008). The periodic counterpart is
+        // test_rbc_verification_nusselt.
+        use crate::integrate;
+        let nu_ref = 2.158;
+        let mut navier = Navier2D::new(17, 17, 1e4, 0.71, 0.02, 1., true);
+        navier.set_velocity(0.2, 1., 1.);
+        navier.set_temperature(0.2, 1., 1.);
+        navier.steady_tol = Some(1e-7);
+        integrate(&mut navier, 200., None);
+        assert!(navier.check_finite());
+        assert!(navier.eval_re() > 1.);
+        assert!((navier.eval_nu() - nu_ref).abs() < 2e-2 * nu_ref);
+        assert!((navier.eval_nuvol() - nu_ref).abs() < 2e-2 * nu_ref);
+    }
+
+    #[test]
+    fn test_integrate_collect() {
+        use crate::integrate_collect;
+        let mut navier = Navier2D::new_periodic(8, 9, 1e4, 1., 0.01, 1.);
+        navier.random_disturbance(1e-3);
+        let snapshots = integrate_collect(&mut navier, 0.1, 0.02);
+        assert_eq!(snapshots.len(), 5);
+        for (k, snapshot) in snapshots.iter().enumerate() {
+            assert!((snapshot.time - 0.02 * (k + 1) as f64).abs() < 1e-8);
+            assert_eq!(snapshot.fields["temp"].shape(), &[8, 9]);
+        }
+        // Snapshots are copies
+        assert!(snapshots[0].fields["temp"] != snapshots[4].fields["temp"]);
+        assert!(snapshots[4].fields["temp"] == navier.temp.to_physical().into_dyn());
+    }
+
+    #[test]
+    fn test_adaptive_dt() {
+        use crate::integrate_collect;
+        let adaptive = AdaptiveDt {
+            cfl: 0.5,
+            dt_max: 1.,
+        };
+        assert_eq!(adaptive.suggest(0.1, 0.1), Some(0.05));
+        assert_eq!(adaptive.suggest(0.1, 0.21), None);
+        assert_eq!(adaptive.suggest(0.1, 10.), Some(1.));
+        // Fluid at rest: timestep grows (by at most 1.5 per step) up to dt_max
+        let mut navier = Navier2D::new_periodic(8, 9, 1e4, 1., 1e-3, 1.);
+        navier.adaptive_dt = Some(AdaptiveDt {
+            cfl: 0.5,
+            dt_max: 4e-3,
+        });
+        let snapshots = integrate_collect(&mut navier, 0.1, 0.02);
+        assert!((navier.dt - 4e-3).abs() < 1e-12);
+        assert!(navier.time + 1e-8 >= 0.1);
+        // No save is missed, although the timestep changes
+        assert_eq!(snapshots.len(), 5);
+        for (k, snapshot) in snapshots.iter().enumerate() {
+            assert!((snapshot.time - 0.02 * (k + 1) as f64).abs() <= 2e-3 + 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_freeze_velocity() {
+        let mut navier = Navier2D::new_periodic(8, 9, 1e4, 1., 0.01, 1.);
+        navier.random_disturbance(1e-2);
+        navier.freeze_velocity = true;
+        let (ux, uy, temp) = (
+            navier.ux.vhat.to_owned(),
+            navier.uy.vhat.to_owned(),
+            navier.temp.vhat.to_owned(),
+        );
+        navier.update();
+        assert!(navier.ux.vhat == ux);
+        assert!(navier.uy.vhat == uy);
+        assert!(navier.temp.vhat != temp);
+        // Frozen temperature
+        navier.freeze_velocity = false;
+        navier.freeze_temp = true;
+        let temp = navier.temp.vhat.to_owned();
+        navier.update();
+        assert!(navier.temp.vhat == temp);
+        assert!(navier.uy.vhat != uy);
+    }
+
+    #[test]
+    fn test_rk3_temporal_order() {
+        // T = exp(-lam t) cos(x + t) cos(pi y / 2), advected by the
+        // frame velocity and diffused, lam = ka (1 + pi^2 / 4)
+        use std::f64::consts::PI;
+        let mut errors = vec![];
+        for dt in [0.2, 0.1, 0.05].iter() {
+            let mut navier = Navier2D::new_periodic(16, 17, 80., 1., *dt, 1.);
+            navier.set_time_scheme(TimeScheme::Rk3);
+            navier.fieldbc = None;
+            navier.freeze_velocity = true;
+            navier.set_frame_velocity(1., 0.);
+            navier.ux.vhat.fill(Complex::new(0., 0.));
+            navier.uy.vhat.fill(Complex::new(0., 0.));
+            let lam = navier.ka * (1. + PI * PI / 4.);
+            let exact =
+                |x: f64, y: f64, t: f64| (-lam * t).exp() * (x + t).cos() * (PI * y / 2.).cos();
+            let (x, y) = (navier.temp.x[0].to_owned(), navier.temp.x[1].to_owned());
+            navier.temp.v =
+                Array2::from_shape_fn((x.len(), y.len()), |(i, j)| exact(x[i], y[j], 0.));
+            navier.temp.forward();
+            for _ in 0..(1. / dt).round() as usize {
+                navier.update();
+            }
+            navier.temp.backward();
+            let error = navier.temp.v.indexed_iter().fold(0_f64, |e, ((i, j), v)| {
+                e.max((v - exact(x[i], y[j], navier.time)).abs())
+            });
+            errors.push(error);
+        }
+        for pair in errors.windows(2) {
+            let ratio = pair[0] / pair[1];
+            assert!(ratio > 7. && ratio < 9., "errors: {:?}", errors);
+        }
+    }
+
+    #[test]
+    #[ignore = "slow: 800 steps, run with `cargo test -- --ignored`"]
+    fn test_fixed_flux_bc() {
+        let flux = 0.8;
+        let mut navier = Navier2D::new_periodic(8, 9, 100., 1., 0.05, 1.);
+        navier.set_temp_bc_flux(Navier2D::bc_fixed_flux(8, 9, flux, flux));
+        navier.random_disturbance(1e-2);
+        for _ in 0..800 {
+            navier.update();
+        }
+        // Total temperature gradient
+        let mut dtdy = navier.field.clone();
+        dtdy.vhat
+            .assign(&navier.temp.gradient([0, 1], Some(navier.scale)));
+        dtdy.backward();
+        let mut dtdy_bc = navier.field.clone();
+        dtdy_bc.vhat.assign(
+            &navier
+                .fieldbc
+                .as_ref()
+                .unwrap()
+                .gradient([0, 1], Some(navier.scale)),
+        );
+        dtdy_bc.backward();
+        let dtdy = &dtdy.v + &dtdy_bc.v;
+        // At the walls (exact) and in the interior (steady state)
+        let ny = dtdy.shape()[1];
+        for (i, row) in dtdy.outer_iter().enumerate() {
+            for j in [0, ny - 1].iter() {
+                assert!((row[*j] + flux).abs() < 1e-8, "wall ({}, {})", i, j);
+            }
+            for v in row.iter() {
+                assert!((v + flux).abs() < 1e-4, "interior: {}", v);
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "slow: 500 steps, run with `cargo test -- --ignored`"]
+    fn test_free_slip_conserves_momentum() {
+        // Shear flow ux = (1 - y^2)^2 diffuses. Free-slip walls exert
+        // no stress, i.e. the horizontal momentum is conserved
+        // (galilean invariance), while no-slip walls remove it.
+        let momentum = |navier: &Navier2D<Complex<f64>, Space2R2c>| {
+            // int ux dy of mean mode, int T_k = 2 / (1 - k^2) (k even)
+            let ortho = navier.ux.to_ortho();
+            ortho
+                .row(0)
+                .iter()
+                .enumerate()
+                .filter(|(k, _)| k % 2 == 0)
+                .map(|(k, c)| c.re * 2. / (1. - (k * k) as f64))
+                .sum::<f64>()
+        };
+        let mut conserved = vec![];
+        for bc in [VelocityBc::FreeSlip, VelocityBc::NoSlip].iter() {
+            let mut navier = Navier2D::new_periodic(8, 33, 1e3, 1., 0.01, 1.);
+            navier.set_velocity_bc(*bc);
+            assert_eq!(navier.velocity_bc(), *bc);
+            navier.fieldbc = None;
+            navier.temp.vhat.fill(Complex::new(0., 0.));
+            navier.uy.vhat.fill(Complex::new(0., 0.));
+            let y = navier.ux.x[1].to_owned();
+            navier.ux.with_physical_mut(|v| {
+                for mut row in v.outer_iter_mut() {
+                    row.assign(&y.mapv(|y| (1. - y * y).powi(2)));
+                }
+            });
+            let m0 = momentum(&navier);
+            for _ in 0..500 {
+                navier.update();
+            }
+            conserved.push(momentum(&navier) / m0);
+        }
+        assert!(
+            (conserved[0] - 1.).abs() < 1e-6,
+            "free-slip: {}",
+            conserved[0]
+        );
+        assert!(conserved[1] < 0.9, "no-slip: {}", conserved[1]);
+    }
+
+    #[test]
+    fn test_passive_scalar_in_linear_shear() {
+        // Gaussian blob in ux = g y: stays gaussian with covariance
+        // syy = s0^2 + 2 D t, sxy = g (s0^2 t + D t^2),
+        // sxx = s0^2 + 2 D t + g^2 (s0^2 t^2 + 2/3 D t^3)
+        let (nx, ny) = (64, 33);
+        let (g, d, s0, x0) = (1., 0.01, 0.2, std::f64::consts::PI);
+        let (dt, t) = (1e-3, 0.5);
+        let mut navier = Navier2D::new_periodic(nx, ny, 1e4, 1., dt, 1.);
+        let scalar = Field2::new(&Space2::new(&fourier_r2c(nx), &cheb_neumann(ny)));
+        navier.set_scalar(scalar, d);
+        let (x, y) = (navier.temp.x[0].to_owned(), navier.temp.x[1].to_owned());
+        let exact = |t: f64| {
+            let syy = s0 * s0 + 2. * d * t;
+            let sxy = g * (s0 * s0 * t + d * t * t);
+            let sxx = s0 * s0 + 2. * d * t + g * g * (s0 * s0 * t * t + 2. / 3. * d * t.powi(3));
+            let det = sxx * syy - sxy * sxy;
+            Array2::from_shape_fn((nx, ny), |(i, j)| {
+                let (rx, ry) = (x[i] - x0, y[j]);
+                let q = (syy * rx * rx - 2. * sxy * rx * ry + sxx * ry * ry) / det;
+                s0 * s0 / det.sqrt() * (-0.5 * q).exp()
+            })
+        };
+        let scalar = navier.scalar.as_mut().unwrap();
+        scalar.v.assign(&exact(0.));
+        scalar.forward();
+        // Prescribed velocity, the fields ux and uy are not used
+        let ux = Array2::from_shape_fn((nx, ny), |(_, j)| g * y[j]);
+        let uy = Array2::zeros((nx, ny));
+        for _ in 0..(t / dt).round() as usize {
+            navier.solve_scalar(&ux, &uy);
+        }
+        let scalar = navier.scalar.as_mut().unwrap();
+        scalar.backward();
+        let error = (&scalar.v - &exact(t))
+            .iter()
+            .fold(0_f64, |e, v| e.max(v.abs()));
+        assert!(error < 5e-3, "error: {}", error);
+    }
+
+    #[test]
+    #[ignore = "slow: 2000 steps, run with `cargo test -- --ignored`"]
+    fn test_solid_cylinder_drag() {
+        // Pressure driven channel flow (body force g) past a cylinder.
+        // In steady state, g is balanced by wall friction and drag.
+        use crate::navier::solid_masks::solid_cylinder_inner;
+        use std::f64::consts::PI;
+        let g = 0.05;
+        let mut navier = Navier2D::new_periodic(32, 33, 1e4, 1., 0.02, 1.);
+        navier.fieldbc = None;
+        navier.freeze_temp = true;
+        navier.temp.vhat.fill(Complex::new(0., 0.));
+        navier.uy.vhat.fill(Complex::new(0., 0.));
+        let (x, y) = (navier.temp.x[0].to_owned(), navier.temp.x[1].to_owned());
+        let [mask, _] = solid_cylinder_inner(&x, &y, PI, 0., 0.3);
+        navier.set_solid(mask, 0.05);
+        navier.set_solid_temperature(Array2::from_elem((32, 33), 0.5));
+        assert!(navier.solid.as_ref().unwrap()[1][[0, 0]] == 0.5);
+        // Poiseuille flow as initial condition
+        let nu = navier.nu;
+        navier.ux.with_physical_mut(|v| {
+            for mut row in v.outer_iter_mut() {
+                row.assign(&y.mapv(|y| g / (2. * nu) * (1. - y * y)));
+            }
+        });
+        let zero = Array2::zeros((32, 33));
+        navier.forcing = Some(Forcing {
+            force: [Array2::from_elem((32, 33), g), zero.clone()],
+            heat: zero,
+        });
+        for _ in 0..2000 {
+            navier.update();
+        }
+        let drag = navier.solid_drag();
+        assert!(drag[0] > 0.1 * g && drag[0] < g, "drag: {:?}", drag);
+        // Symmetric about the centerline: no lift
+        assert!(drag[1].abs() < 1e-3 * drag[0], "drag: {:?}", drag);
+    }
+
+    #[test]
+    #[should_panic(expected = "Solid mask must be of shape")]
+    fn test_solid_shape_mismatch() {
+        let mut navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        navier.set_solid(Array2::zeros((16, 16)), 1e-2);
+    }
+
+    #[test]
+    fn test_rotation_balanced_by_pressure() {
+        // Without buoyancy, the coriolis force is a gradient in 2-D:
+        // no inertial oscillations (which would reverse the velocity
+        // after t = pi / (2 omega)), only the pressure changes.
+        let omega = 1.;
+        let mut reference = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        reference.fieldbc = None;
+        reference.freeze_temp = true;
+        reference.random_disturbance(1e-2);
+        reference.temp.vhat.fill(Complex::new(0., 0.));
+        let mut rotating = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        rotating.fieldbc = None;
+        rotating.freeze_temp = true;
+        rotating.temp.vhat.fill(Complex::new(0., 0.));
+        rotating.ux.vhat.assign(&reference.ux.vhat);
+        rotating.uy.vhat.assign(&reference.uy.vhat);
+        rotating.rotation = Some(omega);
+        for _ in 0..200 {
+            reference.update();
+            rotating.update();
+        }
+        let diff = norm_l2_diff(&rotating.ux.vhat, &reference.ux.vhat);
+        assert!(diff < 1e-2 * norm_l2(&reference.ux.vhat), "diff: {}", diff);
+        let diff = norm_l2_diff(&rotating.pres[0].vhat, &reference.pres[0].vhat);
+        assert!(diff > 1e-2 * norm_l2(&reference.pres[0].vhat));
+    }
+
+    #[test]
+    fn test_rotation_inertial_oscillation() {
+        // The coriolis force alone, before the pressure projection, turns
+        // a uniform velocity (u, v) with frequency 2 omega:
+        // du/dt = 2 omega v, dv/dt = -2 omega u. After half a period,
+        // t = pi / (2 omega), the velocity is reversed.
+        let omega = 0.5;
+        let mut navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        navier.ux.vhat.fill(Complex::new(0., 0.));
+        navier.uy.vhat.fill(Complex::new(0., 0.));
+        navier.rotation = Some(omega);
+        let mut rhs = |u: [f64; 2]| {
+            let shape = navier.field.v.raw_dim();
+            let ux = Array2::from_elem(shape, u[0]);
+            let uy = Array2::from_elem(shape, u[1]);
+            let mut dudt = [0.; 2];
+            for (d, conv) in dudt
+                .iter_mut()
+                .zip([navier.conv_ux(&ux, &uy), navier.conv_uy(&ux, &uy)].iter())
+            {
+                navier.field.vhat.assign(conv);
+                navier.field.backward();
+                *d = -navier.field.v[[0, 0]];
+            }
+            dudt
+        };
+        // Runge-Kutta 4
+        let (steps, t) = (100, std::f64::consts::PI / (2. * omega));
+        let dt = t / steps as f64;
+        let mut u = [1., 0.];
+        for _ in 0..steps {
+            let k1 = rhs(u);
+            let k2 = rhs([u[0] + 0.5 * dt * k1[0], u[1] + 0.5 * dt * k1[1]]);
+            let k3 = rhs([u[0] + 0.5 * dt * k2[0], u[1] + 0.5 * dt * k2[1]]);
+            let k4 = rhs([u[0] + dt * k3[0], u[1] + dt * k3[1]]);
+            for (i, ui) in u.iter_mut().enumerate() {
+                *ui += dt / 6. * (k1[i] + 2. * k2[i] + 2. * k3[i] + k4[i]);
+            }
+        }
+        assert!((u[0] + 1.).abs() < 1e-6, "u: {:?}", u);
+        assert!(u[1].abs() < 1e-6, "u: {:?}", u);
+    }
+
+    #[test]
+    fn test_rbc_verification_nusselt() {
+        // Periodic cell of length 2H, Ra = 1e4, Pr = 0.71, see
+        // examples/rbc_verification.rs. Reference: Nu = 2.66 (Clever &
+        // Busse, J. Fluid Mech. 65, 1974), expected within 3 %.
+        use crate::integrate;
+        let nu_ref = 2.66;
+        let aspect = 2. / std::f64::consts::PI;
+        let mut navier = Navier2D::new_periodic(16, 17, 1e4, 0.71, 0.05, aspect);
+        navier.set_velocity(0.2, 1., 1.);
+        navier.set_temperature(0.2, 1., 1.);
+        navier.steady_tol = Some(1e-7);
+        integrate(&mut navier, 200., None);
+        let nu = navier.eval_nu();
+        assert!((nu - nu_ref).abs() < 0.03 * nu_ref, "Nu = {}", nu);
+        assert!((navier.eval_nuvol() - nu).abs() < 0.03 * nu_ref);
+    }
+
+    #[test]
+    fn test_steady_tol_exit() {
+        use crate::{integrate, StopReason};
+        // Subcritical: disturbances decay towards the conductive state
+        let mut navier = Navier2D::new_periodic(8, 9, 1e3, 1., 0.1, 1.);
+        navier.random_disturbance(1e-2);
+        navier.steady_tol = Some(1e-6);
+        let report = integrate(&mut navier, 1000., None);
+        assert_eq!(report.stop_reason, StopReason::Exit);
+        assert!(report.final_time < 1000.);
+        assert!(navier.check_finite());
+    }
+
+    #[test]
+    fn test_diagnostics_csv_string() {
+        let mut navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        let mut nu = Vec::new();
+        for _ in 0..3 {
+            navier.update();
+            let value = navier.eval_nu();
+            nu.push(value);
+            navier
+                .diagnostics
+                .get_mut("time")
+                .unwrap()
+                .push(navier.time);
+            navier.diagnostics.get_mut("Nu").unwrap().push(value);
+        }
+        let csv = navier.diagnostics_csv_string();
+        let mut lines = csv.lines();
+        let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+        assert_eq!(header, vec!["time", "CFL", "Nu", "Nuvol", "Re"]);
+        let col = header.iter().position(|h| *h == "Nu").unwrap();
+        let parsed: Vec<f64> = lines
+            .map(|l| l.split(',').nth(col).unwrap().parse::<f64>().unwrap())
+            .collect();
+        assert_eq!(parsed, nu);
+    }
+
+    #[test]
+    fn test_darcy_drag_damps_shear_flow() {
+        // Shear flow ux(y) is not affected by convection and
+        // pressure; the drag is the only difference
+        let (nx, ny) = (16, 17);
+        let (dt, steps, k) = (0.01, 20, 0.5);
+        let mut amplitude = Vec::new();
+        for porous in [false, true].iter() {
+            let mut navier = Navier2D::new_periodic(nx, ny, 1e4, 1., dt, 1.);
+            navier.temp.vhat.fill(Complex::new(0., 0.));
+            navier.uy.vhat.fill(Complex::new(0., 0.));
+            let y = navier.ux.x[1].to_owned();
+            for mut row in navier.ux.v.outer_iter_mut() {
+                for (v, yi) in row.iter_mut().zip(y.iter()) {
+                    *v = 1. - yi * yi;
+                }
+            }
+            navier.ux.forward();
+            if *porous {
+                navier.set_permeability(Array2::from_elem(navier.temp.v.raw_dim(), k));
+            }
+            for _ in 0..steps {
+                navier.update();
+            }
+            navier.ux.backward();
+            amplitude.push(navier.ux.v.iter().map(|x| x.abs()).sum::<f64>());
+        }
+        // explicit drag reduces the velocity by (1 - dt nu / K) per step
+        let expected = (1. - dt * get_nu(1e4, 1., 2.) / k).powi(steps);
+        let ratio = amplitude[1] / amplitude[0];
+        assert!(
+            (ratio - expected).abs() < 1e-4,
+            "Expected ratio {}, got {}",
+            expected,
+            ratio
+        );
+    }
+    #[test]
+    fn test_anelastic_uniform_density() {
+        let mut boussinesq = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        let mut anelastic = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        anelastic.temp.vhat.assign(&boussinesq.temp.vhat);
+        anelastic.ux.vhat.assign(&boussinesq.ux.vhat);
+        anelastic.uy.vhat.assign(&boussinesq.uy.vhat);
+        let rho_ref = Array1::from_elem(17, 2.);
+        anelastic.set_approximation(Approximation::Anelastic { rho_ref });
+        for _ in 0..5 {
+            boussinesq.update();
+            anelastic.update();
+        }
+        for (a, b) in [
+            (&anelastic.temp, &boussinesq.temp),
+            (&anelastic.ux, &boussinesq.ux),
+            (&anelastic.uy, &boussinesq.uy),
+            (&anelastic.pres[0], &boussinesq.pres[0]),
+        ]
+        .iter()
+        {
+            assert!(norm_l2_diff(&a.vhat, &b.vhat) < 1e-10 * norm_l2(&b.vhat).max(1.));
+        }
+    }
+    #[test]
+    fn test_anelastic_stratified_density() {
+        let mut boussinesq = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        let mut anelastic = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        anelastic.temp.vhat.assign(&boussinesq.temp.vhat);
+        anelastic.ux.vhat.assign(&boussinesq.ux.vhat);
+        anelastic.uy.vhat.assign(&boussinesq.uy.vhat);
+        // Density decreases with height
+        let rho_ref = anelastic.temp.x[1].mapv(|y| (-0.5 * y).exp());
+        anelastic.set_approximation(Approximation::Anelastic { rho_ref });
+        for _ in 0..10 {
+            boussinesq.update();
+            anelastic.update();
+        }
+        assert!(anelastic.check_finite());
+        // div(rho u) = 0 is enforced as well as div(u) = 0 (boussinesq)
+        let div_anelastic = norm_l2(&anelastic.divergence());
+        let div_boussinesq = norm_l2(&boussinesq.divergence());
+        assert!(
+            div_anelastic < 10. * div_boussinesq + 1e-8,
+            "{} vs {}",
+            div_anelastic,
+            div_boussinesq
+        );
+        // Stratification alters the flow
+        assert!(norm_l2_diff(&anelastic.uy.vhat, &boussinesq.uy.vhat) > 1e-8);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_anelastic_density_length_mismatch() {
+        let mut navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        navier.set_approximation(Approximation::Anelastic {
+            rho_ref: Array1::ones(16),
+        });
+    }
+
+    #[test]
+    fn test_write_interrupted_keeps_checkpoint() {
+        let mut navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        let filename = std::env::temp_dir().join("rustpde_test_atomic_write.h5");
+        let filename = filename.to_str().unwrap();
+        let tmp = format!("{}.tmp", filename);
+        navier.time = 1.0;
+        navier.write_return_result(filename).unwrap();
+        assert!(!std::path::Path::new(&tmp).exists());
+        // Interrupted write: temporary file cannot be replaced
+        std::fs::create_dir_all(&tmp).unwrap();
+        navier.time = 2.0;
+        assert!(navier.write_return_result(filename).is_err());
+        let time = read_scalar_from_hdf5::<f64>(filename, "time", None).unwrap();
+        assert!((time - 1.0).abs() < 1e-12);
+        // Leftover of interrupted write is replaced
+        std::fs::remove_dir(&tmp).unwrap();
+        std::fs::write(&tmp, b"corrupt").unwrap();
+        navier.write_return_result(filename).unwrap();
+        let time = read_scalar_from_hdf5::<f64>(filename, "time", None).unwrap();
+        assert!((time - 2.0).abs() < 1e-12);
+        std::fs::remove_file(filename).unwrap();
+    }
+}
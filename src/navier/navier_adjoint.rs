@@ -35,6 +35,7 @@
 //! An adjoint-based approach for finding invariant solutions of Navier--Stokes equations
 //! J. Fluid Mech., 795, 278-312.
 use super::conv_term;
+use super::diagnostics::ConvectionDiagnostics;
 use super::navier::{apply_cos_sin, apply_sin_cos, dealias};
 use super::navier::{get_ka, get_nu, Navier2D};
 use crate::bases::fourier_r2c;
@@ -940,53 +941,49 @@ fn norm_l2_c64(array: &Array2<Complex<f64>>) -> f64 {
 //         .sqrt()
 // }
 
-impl<T, S> Navier2DAdjoint<T, S>
+impl<T, S> ConvectionDiagnostics<T, S> for Navier2DAdjoint<T, S>
 where
     S: BaseSpace<f64, 2, Physical = f64, Spectral = T>,
     T: crate::types::Scalar + Mul<f64, Output = T> + Div<f64, Output = T>,
 {
-    /// Returns Nusselt number (heat flux at the plates)
-    /// $$
-    /// Nu = \langle - dTdz \rangle\\_x (0/H))
-    /// $$
-    pub fn eval_nu(&mut self) -> f64 {
-        use super::functions::eval_nu;
-        eval_nu(
-            &mut self.temp[0],
-            &mut self.field,
-            &self.fieldbc,
-            &self.scale,
-        )
+    fn temp(&self) -> &Field2<T, S> {
+        &self.temp[0]
     }
 
-    /// Returns volumetric Nusselt number
-    /// $$
-    /// Nuvol = \langle uy*T/kappa - dTdz \rangle\\_V
-    /// $$
-    pub fn eval_nuvol(&mut self) -> f64 {
-        use super::functions::eval_nuvol;
-        eval_nuvol(
-            &mut self.temp[0],
-            &mut self.uy[0],
-            &mut self.field,
-            &self.fieldbc,
-            self.ka,
-            &self.scale,
-        )
+    fn ux(&self) -> &Field2<T, S> {
+        &self.ux[0]
     }
 
-    /// Returns Reynolds number based on kinetic energy
-    pub fn eval_re(&mut self) -> f64 {
-        use super::functions::eval_re;
-        eval_re(
-            &mut self.ux[0],
-            &mut self.uy[0],
-            &mut self.field,
-            self.nu,
-            &self.scale,
-        )
+    fn uy(&self) -> &Field2<T, S> {
+        &self.uy[0]
+    }
+
+    fn work_field(&self) -> &Field2<T, S> {
+        &self.field
+    }
+
+    fn fieldbc(&self) -> &Option<Field2<T, S>> {
+        &self.fieldbc
+    }
+
+    fn ka(&self) -> f64 {
+        self.ka
+    }
+
+    fn nu(&self) -> f64 {
+        self.nu
     }
 
+    fn scale(&self) -> [f64; 2] {
+        self.scale
+    }
+}
+
+impl<T, S> Navier2DAdjoint<T, S>
+where
+    S: BaseSpace<f64, 2, Physical = f64, Spectral = T>,
+    T: crate::types::Scalar + Mul<f64, Output = T> + Div<f64, Output = T>,
+{
     /// Initialize velocity with fourier modes
     ///
     /// ux = amp \* sin(mx)cos(nx)
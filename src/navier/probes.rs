@@ -0,0 +1,98 @@
+//! # Probes
+//! Record time series of ux, uy, temp and pres at fixed
+//! points, e.g. for the spectral analysis of the flow.
+//!
+//! The values are evaluated by spectral interpolation (see
+//! [`crate::field::FieldBase::probe`]) and appended to
+//! extendable datasets of shape (`n_probes`, `n_times`)
+//! in group *probes*.
+use super::navier::Navier2D;
+use crate::field::BaseSpace;
+use crate::hdf5::{append_to_hdf5, write_to_hdf5, Result};
+use ndarray::{Array1, Array2};
+use num_complex::Complex;
+
+impl<T, S> Navier2D<T, S>
+where
+    S: BaseSpace<f64, 2, Physical = f64, Spectral = T>,
+    T: Copy + Into<Complex<f64>>,
+{
+    /// Register probe at point (x, y), in the (scaled)
+    /// coordinates of the fields
+    pub fn add_probe(&mut self, x: f64, y: f64) {
+        self.probes.push([x, y]);
+    }
+
+    /// Append current values at all probes to hdf5 file
+    ///
+    /// # Errors
+    /// When the file exists, but the number of
+    /// probes differs.
+    pub fn write_probes(&self, filename: &str) -> Result<()> {
+        if self.probes.is_empty() {
+            return Ok(());
+        }
+        let mut temp = self.temp.probe_points(&self.probes);
+        if let Some(x) = &self.fieldbc {
+            // boundary field is defined on the unscaled grid
+            let points: Vec<[f64; 2]> = self
+                .probes
+                .iter()
+                .map(|p| [p[0] / self.scale[0], p[1] / self.scale[1]])
+                .collect();
+            temp = &temp + &x.probe_points(&points);
+        }
+        let group = Some("probes");
+        append_to_hdf5(filename, "ux", group, &self.ux.probe_points(&self.probes))?;
+        append_to_hdf5(filename, "uy", group, &self.uy.probe_points(&self.probes))?;
+        append_to_hdf5(filename, "temp", group, &temp)?;
+        append_to_hdf5(
+            filename,
+            "pres",
+            group,
+            &self.pres[0].probe_points(&self.probes),
+        )?;
+        append_to_hdf5(filename, "time", group, &Array1::from_elem(1, self.time))?;
+        let positions = Array2::from_shape_fn((self.probes.len(), 2), |(i, j)| self.probes[i][j]);
+        write_to_hdf5(filename, "positions", group, &positions)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hdf5::read_from_hdf5;
+    use crate::navier::navier::apply_random_disturbance;
+    use crate::Integrate;
+
+    #[test]
+    fn test_write_probes() {
+        let mut navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+        apply_random_disturbance(&mut navier.temp, 0.1);
+        navier.add_probe(1.0, 0.2);
+        navier.add_probe(3.5, -0.4);
+        let filename = std::env::temp_dir().join("rustpde_test_probes.h5");
+        let filename = filename.to_str().unwrap();
+        if std::path::Path::new(filename).exists() {
+            std::fs::remove_file(filename).unwrap();
+        }
+        let n_times = 3;
+        for _ in 0..n_times {
+            navier.update();
+            navier.write_probes(filename).unwrap();
+        }
+        let ux = read_from_hdf5::<f64, ndarray::Ix2>(filename, "ux", Some("probes")).unwrap();
+        let temp = read_from_hdf5::<f64, ndarray::Ix2>(filename, "temp", Some("probes")).unwrap();
+        let time = read_from_hdf5::<f64, ndarray::Ix2>(filename, "time", Some("probes")).unwrap();
+        std::fs::remove_file(filename).unwrap();
+        assert_eq!(ux.shape(), &[2, n_times]);
+        assert_eq!(temp.shape(), &[2, n_times]);
+        assert!((time[[0, n_times - 1]] - navier.time).abs() < 1e-12);
+        // last column matches current state
+        let expected = navier.ux.probe_points(&navier.probes);
+        for (a, b) in ux.column(n_times - 1).iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! # Space-time spectrum
+//! Record a horizontal line of a field over time and compute
+//! its wavenumber-frequency (k, omega) spectrum, e.g. to
+//! identify the dispersion relation of traveling waves.
+//!
+//! The spectrum is the squared magnitude of the 2-D fourier
+//! transform over (x, t). The sign of omega is chosen, such that
+//! a wave $\cos(k x - \omega t)$, traveling in positive
+//! x-direction, peaks at positive omega.
+//!
+//! Requires a periodic x-direction and equidistant samples in time.
+use crate::bases::{fourier_c2c, fourier_r2c, Transform};
+use crate::field::{BaseSpace, Field2};
+use ndarray::{s, Array1, Array2};
+use num_complex::Complex;
+use std::f64::consts::PI;
+
+/// Time history of a horizontal line (fixed y) of a field
+#[derive(Debug, Clone)]
+pub struct SpaceTimeSpectrum {
+    /// Index of the recorded line along y
+    pub y_index: usize,
+    /// Times of the recorded samples
+    pub time: Vec<f64>,
+    /// Recorded lines (physical space)
+    buffer: Vec<Array1<f64>>,
+    /// Length of the periodic domain
+    length: f64,
+}
+
+impl SpaceTimeSpectrum {
+    /// Record line at `y_index`
+    pub fn new(y_index: usize) -> Self {
+        Self {
+            y_index,
+            time: Vec::new(),
+            buffer: Vec::new(),
+            length: 2. * PI,
+        }
+    }
+
+    /// Append current values of `field` along the line
+    pub fn record<T2, S>(&mut self, field: &Field2<T2, S>, time: f64)
+    where
+        S: BaseSpace<f64, 2, Physical = f64, Spectral = T2>,
+    {
+        let x = &field.x[0];
+        self.length = (x[1] - x[0]) * x.len() as f64;
+        let v = field.to_physical();
+        self.buffer.push(v.slice(s![.., self.y_index]).to_owned());
+        self.time.push(time);
+    }
+
+    /// Number of recorded samples
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns true if nothing is recorded
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Returns wavenumbers k, frequencies omega (fft order) and the
+    /// power spectrum of shape (k.len(), omega.len()).
+    ///
+    /// # Panics
+    /// Panics if less than two samples are recorded.
+    pub fn spectrum(&self) -> (Array1<f64>, Array1<f64>, Array2<f64>) {
+        assert!(self.len() > 1, "Spectrum requires at least two samples");
+        let (nx, nt) = (self.buffer[0].len(), self.len());
+        let mut data = Array2::<f64>::zeros((nx, nt));
+        for (mut column, line) in data.columns_mut().into_iter().zip(self.buffer.iter()) {
+            column.assign(line);
+        }
+        // Transform space, then time
+        let vhat: Array2<Complex<f64>> = fourier_r2c::<f64>(nx).forward(&data, 0);
+        let vhat = fourier_c2c::<f64>(nt).forward(&vhat, 1);
+        let power = vhat.mapv(|v| v.norm_sqr() / (nx * nt).pow(2) as f64);
+        // Wavenumbers and frequencies
+        let dt = (self.time[nt - 1] - self.time[0]) / (nt - 1) as f64;
+        let k = Array1::from_shape_fn(nx / 2 + 1, |i| 2. * PI * i as f64 / self.length);
+        let omega = Array1::from_shape_fn(nt, |j| {
+            let f = if j <= nt / 2 {
+                j as f64
+            } else {
+                j as f64 - nt as f64
+            };
+            -2. * PI * f / (nt as f64 * dt)
+        });
+        (k, omega, power)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chebyshev, Space2};
+
+    #[test]
+    fn test_spectrum_traveling_wave() {
+        let (nx, ny, nt) = (16, 5, 32);
+        let (k0, dt) = (3., 0.1);
+        let omega0 = 2. * PI * 4. / (nt as f64 * dt);
+        let space = Space2::new(&fourier_r2c(nx), &chebyshev(ny));
+        let mut field = Field2::new(&space);
+        let mut spacetime = SpaceTimeSpectrum::new(2);
+        let x = field.x[0].to_owned();
+        for m in 0..nt {
+            let t = m as f64 * dt;
+            for (i, xi) in x.iter().enumerate() {
+                for j in 0..ny {
+                    field.v[[i, j]] = (k0 * xi - omega0 * t).cos();
+                }
+            }
+            field.forward();
+            spacetime.record(&field, t);
+        }
+        assert_eq!(spacetime.len(), nt);
+        let (k, omega, power) = spacetime.spectrum();
+        assert_eq!(power.shape(), &[nx / 2 + 1, nt]);
+        let (mut imax, mut jmax) = (0, 0);
+        for ((i, j), p) in power.indexed_iter() {
+            if *p > power[[imax, jmax]] {
+                imax = i;
+                jmax = j;
+            }
+        }
+        assert!((k[imax] - k0).abs() < 1e-10);
+        assert!((omega[jmax] - omega0).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,124 @@
+//! Sponge layers, which damp the velocity field towards
+//! a reference state in a given region.
+//!
+//! The damping is applied as an explicit forcing term
+//! $$
+//! -\sigma(x) (u - u\\_{ref})
+//! $$
+//! in the momentum equations, similar to the penalization
+//! of solid obstacles.
+//!
+//! # Example
+//! Damp velocities close to the right boundary
+//! ```ignore
+//! use rustpde::navier::Navier2D;
+//! use rustpde::navier::sponge::SpongeLayer;
+//!
+//! let mut navier = Navier2D::new_periodic(64, 65, 1e5, 1., 0.01, 1.);
+//! let x = &navier.temp.x[0];
+//! let y = &navier.temp.x[1];
+//! navier.sponge = Some(SpongeLayer::along_x(x, y, 5.0, 6.2, 1.0));
+//! ```
+use ndarray::{Array1, Array2};
+
+/// Container for sponge layer
+#[derive(Clone)]
+pub struct SpongeLayer {
+    /// Spatial mask in \[0, 1\], 1 inside sponge, 0 outside
+    pub mask: Array2<f64>,
+    /// Relaxation rate sigma
+    pub rate: f64,
+    /// Reference state \[ux, uy\], zero if none
+    pub reference: Option<[Array2<f64>; 2]>,
+}
+
+impl SpongeLayer {
+    /// Return new sponge layer from mask and relaxation rate.
+    ///
+    /// The damping is explicit, so `rate * dt` should be
+    /// smaller than unity.
+    pub fn new(mask: Array2<f64>, rate: f64) -> Self {
+        Self {
+            mask,
+            rate,
+            reference: None,
+        }
+    }
+
+    /// Return sponge layer which covers \[x0, x1\] in x-direction.
+    /// The mask rises smoothly from 0 at x0 to 1 at x1.
+    pub fn along_x(x: &Array1<f64>, y: &Array1<f64>, x0: f64, x1: f64, rate: f64) -> Self {
+        let mut mask = Array2::<f64>::zeros((x.len(), y.len()));
+        for (i, xi) in x.iter().enumerate() {
+            let s = ((xi - x0) / (x1 - x0)).max(0.).min(1.);
+            // smoothstep
+            let value = s * s * (3. - 2. * s);
+            mask.row_mut(i).fill(value);
+        }
+        Self::new(mask, rate)
+    }
+
+    /// Set reference state towards which the velocity is relaxed
+    pub fn set_reference(&mut self, ux: Array2<f64>, uy: Array2<f64>) {
+        self.reference = Some([ux, uy]);
+    }
+
+    /// Return damping term sigma(x) * (u - u_ref) for velocity
+    /// component `u` (0: ux, 1: uy), in physical space.
+    pub fn damping(&self, u: &Array2<f64>, component: usize) -> Array2<f64> {
+        let mut damp = u.to_owned();
+        if let Some(reference) = &self.reference {
+            damp -= &reference[component];
+        }
+        damp * &self.mask * self.rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navier::navier::Space2R2c;
+    use crate::navier::Navier2D;
+    use crate::Integrate;
+    use num_complex::Complex;
+
+    fn kinetic_energy(navier: &mut Navier2D<Complex<f64>, Space2R2c>) -> f64 {
+        navier.ux.backward();
+        navier.uy.backward();
+        navier.ux.v.iter().map(|x| x * x).sum::<f64>()
+            + navier.uy.v.iter().map(|x| x * x).sum::<f64>()
+    }
+
+    #[test]
+    fn test_sponge_damps_pulse() {
+        let (nx, ny) = (32, 17);
+        let mut energy = Vec::new();
+        for with_sponge in [false, true].iter() {
+            let mut navier = Navier2D::new_periodic(nx, ny, 1e4, 1., 0.01, 1.);
+            navier.temp.vhat.fill(Complex::new(0., 0.));
+            let x = navier.temp.x[0].to_owned();
+            let y = navier.temp.x[1].to_owned();
+            // Pulse in the right half of the domain
+            for (i, xi) in x.iter().enumerate() {
+                for (j, yi) in y.iter().enumerate() {
+                    navier.ux.v[[i, j]] = (-(xi - 4.5).powi(2) / 0.5).exp() * (1. - yi * yi);
+                }
+            }
+            navier.ux.forward();
+            navier.uy.vhat.fill(Complex::new(0., 0.));
+            if *with_sponge {
+                navier.sponge = Some(SpongeLayer::along_x(&x, &y, 3.0, 4.0, 10.0));
+            }
+            for _ in 0..50 {
+                navier.update();
+            }
+            energy.push(kinetic_energy(&mut navier));
+        }
+        assert!(
+            energy[1] < 0.1 * energy[0],
+            "Sponge did not damp pulse: {} vs {}",
+            energy[1],
+            energy[0]
+        );
+    }
+}
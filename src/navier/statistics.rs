@@ -3,7 +3,7 @@ use super::navier::Navier2D;
 use crate::field::Field2;
 use crate::hdf5::Result;
 use crate::BaseSpace;
-use ndarray::Array2;
+use ndarray::{Array1, Array2};
 use num_complex::Complex;
 
 /// Collection of fields for statistics
@@ -28,6 +28,11 @@ pub struct Statistics<T, S> {
     pub uy_avg: Field2<T, S>,
     /// Nusselt number
     pub nusselt: Field2<T, S>,
+    /// Horizontally and time averaged velocity moments
+    /// \[<ux>, <uy>, <ux*ux>, <ux*uy>, <uy*uy>\]
+    pub vel_moments: [Array1<f64>; 5],
+    /// Write reynolds stresses to file
+    pub write_reynolds_stresses: bool,
     /// Save stats every n-timeunits
     pub save_stat: f64,
     /// Write status every n-timeunits
@@ -63,6 +68,14 @@ where
         let ux_avg = Field2::new(&space);
         let uy_avg = Field2::new(&space);
         let nusselt = Field2::new(&space);
+        let ny = field.v.shape()[1];
+        let vel_moments = [
+            Array1::zeros(ny),
+            Array1::zeros(ny),
+            Array1::zeros(ny),
+            Array1::zeros(ny),
+            Array1::zeros(ny),
+        ];
         let avg_time = 0.;
         let tot_time = navier.time;
         let num_save = 0;
@@ -77,6 +90,8 @@ where
             ux_avg,
             uy_avg,
             nusselt,
+            vel_moments,
+            write_reynolds_stresses: false,
             save_stat,
             write_stat,
             avg_time,
@@ -146,11 +161,35 @@ where
         self.uy_avg.vhat.assign(&uyhat);
         nusselt(&mut self.field, &that, &uyhat, self.ka, &self.scale);
         self.nusselt.vhat.assign(&self.field.vhat);
+        self.update_vel_moments(uxhat, uyhat, weight);
         // Update time info
         self.num_save += 1;
         self.avg_time += time - self.tot_time;
         self.tot_time = time;
     }
+
+    /// Update running average of velocity moments
+    fn update_vel_moments(&mut self, uxhat: &Array2<T>, uyhat: &Array2<T>, weight: f64) {
+        self.field.vhat.assign(uxhat);
+        self.field.backward();
+        let ux = self.field.v.to_owned();
+        self.field.vhat.assign(uyhat);
+        self.field.backward();
+        let uy = self.field.v.to_owned();
+        let products = [&ux, &uy, &(&ux * &ux), &(&ux * &uy), &(&uy * &uy)];
+        let dx = &self.field.dx[0];
+        for (moment, product) in self.vel_moments.iter_mut().zip(products.iter()) {
+            let profile = product.t().dot(dx) / dx.sum();
+            *moment = (&*moment * weight + &profile) / (weight + 1.);
+        }
+    }
+
+    /// Returns horizontally and time averaged reynolds stresses
+    /// \[<ux'ux'>, <ux'uy'>, <uy'uy'>\] as function of y
+    pub fn reynolds_stresses(&self) -> [Array1<f64>; 3] {
+        let [ux, uy, uxux, uxuy, uyuy] = &self.vel_moments;
+        [uxux - &(ux * ux), uxuy - &(ux * uy), uyuy - &(uy * uy)]
+    }
 }
 
 macro_rules! impl_read_write {
@@ -183,6 +222,20 @@ macro_rules! impl_read_write {
                 self.uy_avg.write(&filename, Some("uy"));
                 self.nusselt.write(&filename, Some("nusselt"));
                 // Write scalars
+                if self.write_reynolds_stresses {
+                    use crate::hdf5::write_to_hdf5;
+                    let [uxux, uxuy, uyuy] = self.reynolds_stresses();
+                    write_to_hdf5(&filename, "uxux", Some("reynolds_stress"), &uxux)?;
+                    write_to_hdf5(&filename, "uxuy", Some("reynolds_stress"), &uxuy)?;
+                    write_to_hdf5(&filename, "uyuy", Some("reynolds_stress"), &uyuy)?;
+                }
+                // Accumulated velocity moments (for restart)
+                {
+                    use crate::hdf5::write_to_hdf5;
+                    for (i, m) in self.vel_moments.iter().enumerate() {
+                        write_to_hdf5(&filename, &format!("m{}", i), Some("vel_moments"), m)?;
+                    }
+                }
                 write_scalar_to_hdf5(&filename, "tot_time", None, self.tot_time)?;
                 write_scalar_to_hdf5(&filename, "avg_time", None, self.avg_time)?;
                 write_scalar_to_hdf5(&filename, "num_save", None, self.num_save)?;
@@ -196,12 +249,23 @@ macro_rules! impl_read_write {
             /// Read statistics file
             pub fn read(&mut self, filename: &str) {
                 use crate::field::read::ReadField;
-                use crate::hdf5::read_scalar_from_hdf5;
+                use crate::hdf5::{read_from_hdf5, read_scalar_from_hdf5};
                 // Field
                 self.t_avg.read(&filename, Some("temp"));
                 self.ux_avg.read(&filename, Some("ux"));
                 self.uy_avg.read(&filename, Some("uy"));
                 self.nusselt.read(&filename, Some("nusselt"));
+                // Accumulated velocity moments (missing in older files)
+                for (i, m) in self.vel_moments.iter_mut().enumerate() {
+                    let name = format!("m{}", i);
+                    if let Ok(x) =
+                        read_from_hdf5::<f64, ndarray::Ix1>(&filename, &name, Some("vel_moments"))
+                    {
+                        if x.len() == m.len() {
+                            m.assign(&x);
+                        }
+                    }
+                }
                 // Read scalars
                 self.tot_time = read_scalar_from_hdf5::<f64>(&filename, "tot_time", None).unwrap();
                 self.avg_time = read_scalar_from_hdf5::<f64>(&filename, "avg_time", None).unwrap();
@@ -245,3 +309,40 @@ fn nusselt<T, S>(
     field.v = (dtdz + uy_temp / kappa) * 2. * scale[1];
     field.forward();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reynolds_stresses() {
+        let (nx, ny) = (16, 17);
+        let mut navier = Navier2D::new_periodic(nx, ny, 1e4, 1., 0.01, 1.);
+        let mut statistics = Statistics::new(&navier, 1., 1.);
+        let x = navier.ux.x[0].to_owned();
+        let y = navier.ux.x[1].to_owned();
+        let (u0, a, b) = (0.5, 0.2, 0.3);
+        // Fluctuations change sign in time, mean profile stays
+        for (step, sign) in [1., -1.].iter().enumerate() {
+            for (i, xi) in x.iter().enumerate() {
+                for (j, yi) in y.iter().enumerate() {
+                    let f = 1. - yi * yi;
+                    navier.ux.v[[i, j]] = u0 * f + sign * a * xi.sin() * f;
+                    navier.uy.v[[i, j]] = sign * b * xi.sin() * f;
+                }
+            }
+            navier.ux.forward();
+            navier.uy.forward();
+            let that = navier.temp.to_ortho();
+            let time = 0.1 * (step + 1) as f64;
+            statistics.update(&that, &navier.ux.to_ortho(), &navier.uy.to_ortho(), time);
+        }
+        let [uxux, uxuy, uyuy] = statistics.reynolds_stresses();
+        for (j, yi) in y.iter().enumerate() {
+            let f2 = (1. - yi * yi).powi(2);
+            assert!((uxux[j] - a * a * f2 / 2.).abs() < 1e-8);
+            assert!((uxuy[j] - a * b * f2 / 2.).abs() < 1e-8);
+            assert!((uyuy[j] - b * b * f2 / 2.).abs() < 1e-8);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! # Symmetry projection
+//! Project the flow fields onto a symmetry subspace, such that
+//! symmetric states can be studied without symmetry breaking
+//! noise accumulating.
+//!
+//! Each field is either symmetric (+1) or antisymmetric (-1)
+//! under the symmetry operation:
+//!
+//! | Symmetry    | Operation         | ux | uy | temp | pres |
+//! |-------------|-------------------|----|----|------|------|
+//! | `UpDown`    | y -> -y           | +1 | -1 | -1   | +1   |
+//! | `LeftRight` | x -> -x           | -1 | +1 | +1   | +1   |
+//! | `Centro`    | (x, y) -> (-x, -y)| -1 | -1 | -1   | +1   |
+//!
+//! The temperature refers to the deviation from the boundary
+//! condition field, which is itself odd in y and even in x.
+//!
+//! # Mode parity rules
+//! *Chebyshev*: $T_m(-y) = (-1)^m T_m(y)$. The composite dirichlet
+//! and neumann bases combine $T_m$ and $T_{m+2}$ and thus share the
+//! parity of $T_m$. A field of sign s retains only modes with
+//! $(-1)^m = s$.
+//!
+//! *Fourier* (real field, reflection about x = 0):
+//! $f(-x) = s f(x)$ requires real coefficients for s = +1, and purely
+//! imaginary coefficients for s = -1.
+//!
+//! *Centro* combines both rules: in a chebyshev x chebyshev space mode
+//! (k, m) is retained if $(-1)^{k+m} = s$. In a fourier x chebyshev space
+//! the coefficients of mode m are real if $s (-1)^m = 1$, and purely
+//! imaginary otherwise.
+use super::navier::Navier2D;
+use crate::field::BaseSpace;
+use ndarray::Array2;
+use num_complex::Complex;
+
+/// Symmetry subspace of the flow
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Symmetry {
+    /// Mirror symmetry y -> -y
+    UpDown,
+    /// Mirror symmetry x -> -x
+    LeftRight,
+    /// Point symmetry (x, y) -> (-x, -y)
+    Centro,
+}
+
+impl Symmetry {
+    /// Signs of \[ux, uy, temp, pres\] under symmetry operation
+    fn signs(self) -> [f64; 4] {
+        match self {
+            Self::UpDown => [1., -1., -1., 1.],
+            Self::LeftRight => [-1., 1., 1., 1.],
+            Self::Centro => [-1., -1., -1., 1.],
+        }
+    }
+}
+
+/// Parity of mode index
+fn parity(i: usize) -> f64 {
+    if i % 2 == 0 {
+        1.
+    } else {
+        -1.
+    }
+}
+
+/// Zero modes which violate symmetry (chebyshev x chebyshev)
+fn project_r2r(vhat: &mut Array2<f64>, symmetry: Symmetry, sign: f64) {
+    for ((k, m), v) in vhat.indexed_iter_mut() {
+        let p = match symmetry {
+            Symmetry::UpDown => parity(m),
+            Symmetry::LeftRight => parity(k),
+            Symmetry::Centro => parity(k + m),
+        };
+        if (p - sign).abs() > 0.5 {
+            *v = 0.;
+        }
+    }
+}
+
+/// Zero real or imaginary parts which violate symmetry
+/// (fourier x chebyshev)
+fn project_r2c(vhat: &mut Array2<Complex<f64>>, symmetry: Symmetry, sign: f64) {
+    for ((_, m), v) in vhat.indexed_iter_mut() {
+        match symmetry {
+            Symmetry::UpDown => {
+                if (parity(m) - sign).abs() > 0.5 {
+                    *v = Complex::new(0., 0.);
+                }
+            }
+            Symmetry::LeftRight | Symmetry::Centro => {
+                let s = if symmetry == Symmetry::Centro {
+                    sign * parity(m)
+                } else {
+                    sign
+                };
+                if s > 0. {
+                    v.im = 0.;
+                } else {
+                    v.re = 0.;
+                }
+            }
+        }
+    }
+}
+
+macro_rules! impl_enforce_symmetry {
+    ($s: ty, $project: ident) => {
+        impl<S> Navier2D<$s, S>
+        where
+            S: BaseSpace<f64, 2, Physical = f64, Spectral = $s>,
+        {
+            /// Project ux, uy, temp and pres onto the subspace
+            /// of `self.symmetry`. Does nothing if unset.
+            ///
+            /// Called after each `update`.
+            pub fn enforce_symmetry(&mut self) {
+                if let Some(symmetry) = self.symmetry {
+                    let [s_ux, s_uy, s_temp, s_pres] = symmetry.signs();
+                    $project(&mut self.ux.vhat, symmetry, s_ux);
+                    $project(&mut self.uy.vhat, symmetry, s_uy);
+                    $project(&mut self.temp.vhat, symmetry, s_temp);
+                    $project(&mut self.pres[0].vhat, symmetry, s_pres);
+                }
+            }
+        }
+    };
+}
+
+impl_enforce_symmetry!(f64, project_r2r);
+impl_enforce_symmetry!(Complex<f64>, project_r2c);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navier::navier::apply_random_disturbance;
+    use crate::Integrate;
+
+    fn violation(vhat: &Array2<Complex<f64>>, symmetry: Symmetry, sign: f64) -> f64 {
+        let mut projected = vhat.clone();
+        project_r2c(&mut projected, symmetry, sign);
+        (vhat - &projected).iter().map(|v| v.norm()).sum()
+    }
+
+    #[test]
+    fn test_enforce_symmetry() {
+        for symmetry in [Symmetry::UpDown, Symmetry::LeftRight, Symmetry::Centro].iter() {
+            let mut navier = Navier2D::new_periodic(16, 17, 1e4, 1., 0.01, 1.);
+            navier.symmetry = Some(*symmetry);
+            // symmetric initial state
+            apply_random_disturbance(&mut navier.temp, 0.1);
+            navier.enforce_symmetry();
+            let signs = symmetry.signs();
+            for _ in 0..3 {
+                // tiny asymmetric perturbation
+                let mut perturbation = navier.temp.clone();
+                apply_random_disturbance(&mut perturbation, 1e-6);
+                navier.temp.vhat = &navier.temp.vhat + &perturbation.vhat;
+                assert!(violation(&navier.temp.vhat, *symmetry, signs[2]) > 0.);
+                navier.update();
+                for (vhat, sign) in [
+                    (&navier.ux.vhat, signs[0]),
+                    (&navier.uy.vhat, signs[1]),
+                    (&navier.temp.vhat, signs[2]),
+                    (&navier.pres[0].vhat, signs[3]),
+                ]
+                .iter()
+                {
+                    assert!(violation(vhat, *symmetry, *sign) == 0.);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_project_r2r() {
+        let mut vhat = Array2::<f64>::ones((4, 4));
+        project_r2r(&mut vhat, Symmetry::Centro, -1.);
+        for ((k, m), v) in vhat.indexed_iter() {
+            let expected = if (k + m) % 2 == 1 { 1. } else { 0. };
+            assert!((v - expected).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,259 @@
+//! # Flow topology
+//! Locate and classify critical points (stagnation points)
+//! of the velocity field.
+//!
+//! The velocity is interpolated bilinearly inside each grid
+//! cell and its zeros are found by a newton iteration. These
+//! are refined by a newton iteration on the spectral interpolant,
+//! with the spectral velocity gradient as jacobian. Each zero is
+//! classified by the eigenvalues of the velocity gradient tensor
+//! at this point.
+use super::navier::Navier2D;
+use crate::field::{BaseSpace, Field2};
+use ndarray::Array2;
+use num_complex::Complex;
+
+/// Classification of critical points
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CriticalKind {
+    /// Purely imaginary eigenvalues
+    Center,
+    /// Complex eigenvalues with nonzero real part
+    Focus,
+    /// Real eigenvalues of equal sign
+    Node,
+    /// Real eigenvalues of opposite sign
+    Saddle,
+}
+
+/// Tolerance of newton iteration
+const NEWTON_TOL: f64 = 1e-10;
+
+/// Maximum number of newton iterations
+const NEWTON_MAX_ITER: usize = 30;
+
+impl<T, S> Navier2D<T, S>
+where
+    T: Copy + Into<Complex<f64>>,
+    S: BaseSpace<f64, 2, Physical = f64, Spectral = T>,
+{
+    /// Returns all critical points (x, y, kind) of the velocity field
+    /// in the interior of the domain.
+    ///
+    /// Cells adjacent to the boundaries are excluded, because
+    /// the velocity vanishes on walls.
+    pub fn critical_points(&mut self) -> Vec<(f64, f64, CriticalKind)> {
+        // velocities and velocity gradients in physical space
+        self.ux.backward();
+        self.uy.backward();
+        let ux = self.ux.v.to_owned();
+        let uy = self.uy.v.to_owned();
+        // velocity gradients in orthogonal space, on the grid of ux
+        let mut grad = Vec::new();
+        for (u, deriv) in [
+            (&self.ux, [1, 0]),
+            (&self.ux, [0, 1]),
+            (&self.uy, [1, 0]),
+            (&self.uy, [0, 1]),
+        ]
+        .iter()
+        {
+            let mut g = self.field.clone();
+            g.x = self.ux.x.clone();
+            g.vhat.assign(&u.gradient(*deriv, Some(self.scale)));
+            grad.push(g);
+        }
+        let x = &self.ux.x[0];
+        let y = &self.ux.x[1];
+
+        let mut points: Vec<(f64, f64, CriticalKind)> = Vec::new();
+        for i in 1..x.len() - 2 {
+            for j in 1..y.len() - 2 {
+                if let Some((s, t)) = newton_bilinear(&ux, &uy, i, j) {
+                    let guess = [x[i] + s * (x[i + 1] - x[i]), y[j] + t * (y[j + 1] - y[j])];
+                    let dx = (x[i + 1] - x[i]).abs().min((y[j + 1] - y[j]).abs());
+                    let (xp, yp) = newton_spectral(&self.ux, &self.uy, &grad, guess, dx)
+                        .unwrap_or((guess[0], guess[1]));
+                    // remove duplicates (shared cell edges)
+                    if points
+                        .iter()
+                        .any(|p| (p.0 - xp).abs() < 1e-3 * dx && (p.1 - yp).abs() < 1e-3 * dx)
+                    {
+                        continue;
+                    }
+                    points.push((xp, yp, classify(jacobian(&grad, xp, yp))));
+                }
+            }
+        }
+        points
+    }
+}
+
+/// Bilinear interpolation inside cell (i, j), with
+/// local coordinates s, t in \[0, 1\]
+fn bilinear(v: &Array2<f64>, i: usize, j: usize, s: f64, t: f64) -> f64 {
+    (1. - s) * (1. - t) * v[[i, j]]
+        + s * (1. - t) * v[[i + 1, j]]
+        + (1. - s) * t * v[[i, j + 1]]
+        + s * t * v[[i + 1, j + 1]]
+}
+
+/// Find zero of bilinear interpolated (u, v) inside cell (i, j)
+fn newton_bilinear(u: &Array2<f64>, v: &Array2<f64>, i: usize, j: usize) -> Option<(f64, f64)> {
+    let (mut s, mut t) = (0.5, 0.5);
+    for _ in 0..NEWTON_MAX_ITER {
+        let (fu, fv) = (bilinear(u, i, j, s, t), bilinear(v, i, j, s, t));
+        if fu.abs() < NEWTON_TOL && fv.abs() < NEWTON_TOL {
+            let eps = 1e-8;
+            if s > -eps && s < 1. + eps && t > -eps && t < 1. + eps {
+                return Some((s.max(0.).min(1.), t.max(0.).min(1.)));
+            }
+            return None;
+        }
+        // partial derivatives of bilinear function
+        let dus = (1. - t) * (u[[i + 1, j]] - u[[i, j]]) + t * (u[[i + 1, j + 1]] - u[[i, j + 1]]);
+        let dut = (1. - s) * (u[[i, j + 1]] - u[[i, j]]) + s * (u[[i + 1, j + 1]] - u[[i + 1, j]]);
+        let dvs = (1. - t) * (v[[i + 1, j]] - v[[i, j]]) + t * (v[[i + 1, j + 1]] - v[[i, j + 1]]);
+        let dvt = (1. - s) * (v[[i, j + 1]] - v[[i, j]]) + s * (v[[i + 1, j + 1]] - v[[i + 1, j]]);
+        let det = dus * dvt - dut * dvs;
+        if det.abs() < 1e-300 {
+            return None;
+        }
+        s -= (dvt * fu - dut * fv) / det;
+        t -= (-dvs * fu + dus * fv) / det;
+        if !s.is_finite() || !t.is_finite() || s.abs() > 10. || t.abs() > 10. {
+            return None;
+        }
+    }
+    None
+}
+
+/// Velocity gradient tensor \[dudx, dudy, dvdx, dvdy\] at (x, y),
+/// from the gradient fields (spectral interpolation)
+fn jacobian<T, S>(grad: &[Field2<T, S>], x: f64, y: f64) -> [f64; 4]
+where
+    T: Copy + Into<Complex<f64>>,
+    S: BaseSpace<f64, 2, Physical = f64, Spectral = T>,
+{
+    [
+        grad[0].probe(x, y),
+        grad[1].probe(x, y),
+        grad[2].probe(x, y),
+        grad[3].probe(x, y),
+    ]
+}
+
+/// Refine zero of the velocity (ux, uy) by newton iteration on
+/// the spectral interpolant, starting from `p`. Returns None if
+/// the iteration does not converge, or departs more than `h`
+/// (grid spacing) from `p`.
+fn newton_spectral<T, S>(
+    ux: &Field2<T, S>,
+    uy: &Field2<T, S>,
+    grad: &[Field2<T, S>],
+    p: [f64; 2],
+    h: f64,
+) -> Option<(f64, f64)>
+where
+    T: Copy + Into<Complex<f64>>,
+    S: BaseSpace<f64, 2, Physical = f64, Spectral = T>,
+{
+    let (mut x, mut y) = (p[0], p[1]);
+    for _ in 0..NEWTON_MAX_ITER {
+        let (fu, fv) = (ux.probe(x, y), uy.probe(x, y));
+        let [dux, duy, dvx, dvy] = jacobian(grad, x, y);
+        let det = dux * dvy - duy * dvx;
+        if det.abs() < 1e-300 {
+            return None;
+        }
+        let step_x = (dvy * fu - duy * fv) / det;
+        let step_y = (-dvx * fu + dux * fv) / det;
+        x -= step_x;
+        y -= step_y;
+        if !x.is_finite() || !y.is_finite() || (x - p[0]).hypot(y - p[1]) > h {
+            return None;
+        }
+        if step_x.hypot(step_y) < NEWTON_TOL * h {
+            return Some((x, y));
+        }
+    }
+    None
+}
+
+/// Classify critical point from velocity gradient tensor
+/// \[dudx, dudy, dvdx, dvdy\]
+fn classify(jac: [f64; 4]) -> CriticalKind {
+    let trace = jac[0] + jac[3];
+    let det = jac[0] * jac[3] - jac[1] * jac[2];
+    let disc = trace * trace - 4. * det;
+    if det < 0. {
+        CriticalKind::Saddle
+    } else if disc < 0. {
+        if trace.abs() < 1e-6 * det.sqrt().max(1e-12) {
+            CriticalKind::Center
+        } else {
+            CriticalKind::Focus
+        }
+    } else {
+        CriticalKind::Node
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_critical_points_single_vortex() {
+        let (nx, ny) = (33, 33);
+        let mut navier = Navier2D::new(nx, ny, 1e4, 1., 0.01, 1., true);
+        let x = navier.ux.x[0].to_owned();
+        let y = navier.ux.x[1].to_owned();
+        // streamfunction psi = (1-x^2)^2 (1-y^2)^2
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                let (fx, fy) = (1. - xi * xi, 1. - yi * yi);
+                navier.ux.v[[i, j]] = -4. * yi * fy * fx * fx;
+                navier.uy.v[[i, j]] = 4. * xi * fx * fy * fy;
+            }
+        }
+        navier.ux.forward();
+        navier.uy.forward();
+        let points = navier.critical_points();
+        assert_eq!(points.len(), 1, "Found {:?}", points);
+        let (xp, yp, kind) = points[0];
+        assert!(xp.abs() < 1e-6 && yp.abs() < 1e-6);
+        assert_eq!(kind, CriticalKind::Center);
+    }
+
+    #[test]
+    fn test_critical_points_refined() {
+        // Solid body like rotation about (x0, y0), which lies off the grid
+        let (x0, y0) = (0.31, -0.17);
+        let mut navier = Navier2D::new(17, 17, 1e4, 1., 0.01, 1., true);
+        let x = navier.ux.x[0].to_owned();
+        let y = navier.ux.x[1].to_owned();
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                let f = (1. - xi * xi) * (1. - yi * yi);
+                navier.ux.v[[i, j]] = f * (yi - y0);
+                navier.uy.v[[i, j]] = -f * (xi - x0);
+            }
+        }
+        navier.ux.forward();
+        navier.uy.forward();
+        let points = navier.critical_points();
+        assert_eq!(points.len(), 1, "Found {:?}", points);
+        let (xp, yp, kind) = points[0];
+        assert!((xp - x0).abs() < 1e-8 && (yp - y0).abs() < 1e-8);
+        assert_eq!(kind, CriticalKind::Center);
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify([1., 0., 0., -1.]), CriticalKind::Saddle);
+        assert_eq!(classify([0., -1., 1., 0.]), CriticalKind::Center);
+        assert_eq!(classify([0.1, -1., 1., 0.1]), CriticalKind::Focus);
+        assert_eq!(classify([1., 0., 0., 2.]), CriticalKind::Node);
+    }
+}
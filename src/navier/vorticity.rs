@@ -20,6 +20,7 @@
 //!        vorticity_from_file(&fname).unwrap();
 //! }
 //! ```
+use crate::bases::DealiasRule;
 use crate::hdf5::write_to_hdf5;
 use crate::hdf5::write_to_hdf5_complex;
 use crate::hdf5::Result;
@@ -77,9 +78,5 @@ where
     S: BaseSpace<f64, 2, Physical = f64, Spectral = T2>,
     T2: Zero + Clone + Copy,
 {
-    let zero = T2::zero();
-    let n_x: usize = field.vhat.shape()[0] * 2 / 3;
-    let n_y: usize = field.vhat.shape()[1] * 2 / 3;
-    field.vhat.slice_mut(ndarray::s![n_x.., ..]).fill(zero);
-    field.vhat.slice_mut(ndarray::s![.., n_y..]).fill(zero);
+    field.dealias(DealiasRule::TwoThirds);
 }
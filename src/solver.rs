@@ -2,22 +2,28 @@
 //!
 //! Must be updated ...
 #![allow(clippy::module_name_repetitions)]
+pub mod biharmonic;
 pub mod fdma;
 pub mod fdma_tensor;
 pub mod hholtz;
 pub mod hholtz_adi;
+pub mod iterative;
 pub mod matvec;
 pub mod poisson;
 pub mod tdma;
 pub mod utils;
+pub use biharmonic::Biharmonic;
 pub use fdma::Fdma;
 pub use fdma_tensor::FdmaTensor;
 pub use hholtz::Hholtz;
 pub use hholtz_adi::HholtzAdi;
+pub use iterative::{
+    ConjugateGradient, IterativeConfig, IterativeReport, LinearOperator, Residual, SolveIterative,
+};
 pub use matvec::{MatVec, MatVecDot, MatVecFdma};
 use ndarray::{Array, ArrayBase, Data, DataMut};
 use num_complex::Complex;
-pub use poisson::Poisson;
+pub use poisson::{NullspaceMode, Poisson};
 pub use tdma::Tdma;
 use utils::diag;
 //use crate::derive_solve_enum;
@@ -51,6 +57,44 @@ impl<T> SolverScalar for T where
 {
 }
 
+/// Errors which occur during setup of the solvers
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolverError {
+    /// Number of coefficients does not match the dimensionality
+    DimensionMismatch {
+        /// Expected length
+        expected: usize,
+        /// Supplied length
+        got: usize,
+    },
+    /// System is singular, see [`NullspaceMode`]
+    Singular {
+        /// Axis of the vanishing eigenvalue
+        axis: usize,
+        /// Eigenvalue
+        eigenvalue: f64,
+    },
+}
+
+impl std::fmt::Display for SolverError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DimensionMismatch { expected, got } => write!(
+                f,
+                "Dimension mismatch, expected {} coefficients but got {}",
+                expected, got
+            ),
+            Self::Singular { axis, eigenvalue } => write!(
+                f,
+                "Singular system, eigenvalue {:e} along axis {}",
+                eigenvalue, axis
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SolverError {}
+
 /// Solve linear algebraix systems of the form: M x = b.
 #[enum_dispatch]
 pub trait Solve<A, D> {
@@ -153,3 +197,60 @@ derive_solver_enum!(SolverField, f64, f64, ndarray::Ix1, 1);
 derive_solver_enum!(SolverField, f64, f64, ndarray::Ix2, 2);
 derive_solver_enum!(SolverField, Complex<f64>, f64, ndarray::Ix1, 1);
 derive_solver_enum!(SolverField, Complex<f64>, f64, ndarray::Ix2, 2);
+
+impl<A> Residual<A, ndarray::Ix2> for SolverField<f64, 2>
+where
+    A: SolverScalar
+        + std::ops::Div<f64, Output = A>
+        + std::ops::Mul<f64, Output = A>
+        + std::ops::Add<f64, Output = A>
+        + From<f64>
+        + Into<Complex<f64>>,
+{
+    fn residual<S1, S2>(
+        &self,
+        input: &ArrayBase<S1, ndarray::Ix2>,
+        output: &ArrayBase<S2, ndarray::Ix2>,
+    ) -> f64
+    where
+        S1: Data<Elem = A>,
+        S2: Data<Elem = A>,
+    {
+        match self {
+            Self::Hholtz(ref t) => t.residual(input, output),
+            Self::HholtzAdi(ref t) => t.residual(input, output),
+            Self::Poisson(ref t) => t.residual(input, output),
+        }
+    }
+}
+
+/// Direct solvers report a single iteration
+/// and ignore the tolerances.
+impl<A> SolveIterative<A, ndarray::Ix2> for SolverField<f64, 2>
+where
+    A: SolverScalar
+        + std::ops::Div<f64, Output = A>
+        + std::ops::Mul<f64, Output = A>
+        + std::ops::Add<f64, Output = A>
+        + From<f64>
+        + Into<Complex<f64>>,
+    Self: Solve<A, ndarray::Ix2>,
+{
+    fn solve_iterative<S1, S2>(
+        &self,
+        input: &ArrayBase<S1, ndarray::Ix2>,
+        output: &mut ArrayBase<S2, ndarray::Ix2>,
+        axis: usize,
+        _config: &IterativeConfig,
+    ) -> IterativeReport
+    where
+        S1: Data<Elem = A>,
+        S2: Data<Elem = A> + DataMut,
+    {
+        self.solve(input, output, axis);
+        IterativeReport {
+            iterations: 1,
+            residual: self.residual(input, &*output),
+        }
+    }
+}
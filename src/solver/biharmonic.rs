@@ -0,0 +1,231 @@
+//! # Biharmonic Solver
+//! Solve equations of the form:
+//! ..math:
+//!  (a + b*D2 + c*D4) vhat = f
+//!
+//! where D2 and D4 are the second and fourth derivative,
+//! e.g. for the streamfunction in the streamfunction-vorticity
+//! formulation. The outermost dimension must be a chebyshev
+//! biharmonic composite base (`cheb_biharmonic`).
+//!
+//! The equation becomes banded after multiplication with the
+//! pseudoinverse of D4, B4 = B2 @ B2, where B2 is the pseudoinverse
+//! of D2. The banded system is not of four-diagonal type, it is
+//! factorized by a banded LU decomposition.
+//!
+//! 2-D: The first dimension must be a fourier base, i.e. its
+//! laplacian is diagonal with eigenvalues lam_i. The equation for
+//! each row is then
+//! ..math:
+//!  (a + b*(D2 + lam_i) + c*(D4 + 2 lam_i D2 + lam_i^2)) vhat_i = f_i
+use super::SolverScalar;
+use crate::bases::{chebyshev, BandedMatrix, BaseAll, BaseSpace, Basics, LaplacianInverse};
+use crate::field::FieldBase;
+use crate::solver::utils::diag;
+use crate::solver::Solve;
+use funspace::chebyshev::BandedLu;
+
+use ndarray::prelude::*;
+use ndarray::{Data, DataMut, Zip};
+use std::ops::{Add, Div, Mul, Sub};
+
+/// Entries of the preconditioned system, which are smaller than
+/// this threshold relative to its largest entry, are rounding
+/// errors and dropped, to keep the system banded
+const BAND_TOL: f64 = 1e-13;
+
+/// Container for Biharmonic Solver
+#[derive(Clone)]
+pub struct Biharmonic<T, const N: usize> {
+    /// Factorized systems along the outermost dimension,
+    /// one per eigenvalue of the first dimension (2-D)
+    lu: Vec<BandedLu<T>>,
+    /// Preconditioner B4 along the outermost dimension
+    precond: Array2<T>,
+    /// Coefficients \[a, b, c\]
+    coef: [T; 3],
+}
+
+impl<const N: usize> Biharmonic<f64, N> {
+    /// Construct Biharmonic solver from field:
+    ///
+    ///  (a + b*D2 + c*D4) vhat = B4 f
+    ///
+    /// with *coef* = \[a, b, c\]. The input f is expected
+    /// in orthogonal space, see `to_ortho`.
+    ///
+    /// # Panics
+    /// Panics if the outermost base is not a chebyshev biharmonic
+    /// base, if a first dimension (2-D) is not of fourier type,
+    /// or for N > 2.
+    pub fn new<T2, S>(field: &FieldBase<f64, f64, T2, S, N>, coef: [f64; 3]) -> Self
+    where
+        S: BaseSpace<f64, N, Physical = f64, Spectral = T2>,
+    {
+        assert!(N == 1 || N == 2, "Biharmonic supports N = 1, 2, got {}", N);
+        let bases = field.space.base_all();
+        // Eigenvalues of laplacian along first dimension
+        let lam = if N == 2 {
+            match &bases[0] {
+                BaseAll::BaseR2c(_) | BaseAll::BaseC2c(_) => diag(&bases[0].laplace(), 0),
+                BaseAll::BaseR2r(_) => panic!("Biharmonic requires a fourier base along axis 0."),
+            }
+        } else {
+            Array1::zeros(1)
+        };
+        // Outermost dimension
+        let base = &bases[N - 1];
+        let (n, m) = (base.len_phys(), base.len_spec());
+        assert!(
+            matches!(base, BaseAll::BaseR2r(_)) && m + 4 == n,
+            "Biharmonic requires a chebyshev biharmonic base along axis {}.",
+            N - 1
+        );
+        let stencil = base.mass();
+        let d2 = chebyshev::<f64>(n).laplace();
+        let d4 = d2.dot(&d2);
+        let pinv = base.laplace_inv();
+        let precond = pinv.dot(&pinv).slice(s![4.., ..]).to_owned();
+        let eye = Array2::<f64>::eye(n);
+        let [a, b, c] = coef;
+        let lu = lam
+            .iter()
+            .map(|l| {
+                let op = &eye * (a + b * l + c * l * l) + &d2 * (b + 2. * c * l) + &d4 * c;
+                let mut mat = precond.dot(&op).dot(&stencil);
+                let max = mat.iter().fold(0_f64, |m, v| m.max(v.abs()));
+                mat.mapv_inplace(|v| if v.abs() < BAND_TOL * max { 0. } else { v });
+                BandedLu::new(&BandedMatrix::from_dense(&mat))
+            })
+            .collect();
+        Self { lu, precond, coef }
+    }
+
+    /// Construct Biharmonic solver from space,
+    /// see [`Biharmonic::new`].
+    pub fn from_space<T2, S>(space: &S, coef: [f64; 3]) -> Self
+    where
+        S: BaseSpace<f64, N, Physical = f64, Spectral = T2>,
+    {
+        let field = FieldBase::new(space);
+        Self::new(&field, coef)
+    }
+
+    /// Coefficients \[a, b, c\]
+    pub fn coef(&self) -> [f64; 3] {
+        self.coef
+    }
+}
+
+impl<A> Solve<A, Ix1> for Biharmonic<f64, 1>
+where
+    A: SolverScalar
+        + funspace::Scalar
+        + Add<f64, Output = A>
+        + Sub<f64, Output = A>
+        + Mul<f64, Output = A>
+        + Div<f64, Output = A>,
+{
+    /// Solve 1-D problem
+    fn solve<S1, S2>(
+        &self,
+        input: &ArrayBase<S1, Ix1>,
+        output: &mut ArrayBase<S2, Ix1>,
+        _axis: usize,
+    ) where
+        S1: Data<Elem = A>,
+        S2: Data<Elem = A> + DataMut,
+    {
+        let precond: Array2<A> = self.precond.mapv(|x| x.into());
+        output.assign(&precond.dot(input));
+        self.lu[0].solve_inplace(output);
+    }
+}
+
+impl<A> Solve<A, Ix2> for Biharmonic<f64, 2>
+where
+    A: SolverScalar
+        + funspace::Scalar
+        + Add<f64, Output = A>
+        + Sub<f64, Output = A>
+        + Mul<f64, Output = A>
+        + Div<f64, Output = A>,
+{
+    /// Solve 2-D problem, row by row
+    fn solve<S1, S2>(
+        &self,
+        input: &ArrayBase<S1, Ix2>,
+        output: &mut ArrayBase<S2, Ix2>,
+        _axis: usize,
+    ) where
+        S1: Data<Elem = A>,
+        S2: Data<Elem = A> + DataMut,
+    {
+        let precond: Array2<A> = self.precond.mapv(|x| x.into());
+        output.assign(&input.dot(&precond.t()));
+        Zip::from(output.outer_iter_mut())
+            .and(&self.lu)
+            .par_for_each(|mut out, lu| lu.solve_inplace(&mut out));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{Field1, Field2, Space1, Space2};
+    use crate::{cheb_biharmonic, fourier_r2c};
+    use num_complex::Complex;
+
+    #[test]
+    fn test_biharmonic1d_clamped_beam() {
+        // u'''' = 1, u(+-1) = u'(+-1) = 0 => u = (1 - x^2)^2 / 24
+        let n = 12;
+        let space = Space1::new(&cheb_biharmonic(n));
+        let mut field = Field1::new(&space);
+        let solver = Biharmonic::new(&field, [0., 0., 1.]);
+        let mut rhs = Array1::<f64>::zeros(n);
+        rhs[0] = 1.;
+        let mut result = Array1::<f64>::zeros(n - 4);
+        solver.solve(&rhs, &mut result, 0);
+        field.vhat.assign(&result);
+        field.backward();
+        for (v, x) in field.v.iter().zip(field.x[0].iter()) {
+            let expected = (1. - x * x).powi(2) / 24.;
+            assert!(
+                (v - expected).abs() < 1e-10,
+                "got {} expected {}",
+                v,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_biharmonic2d_fo_cb() {
+        // u = cos(2x) (1 - y^2)^2 y
+        let (nx, ny) = (16, 14);
+        let space = Space2::new(&fourier_r2c(nx), &cheb_biharmonic(ny));
+        let mut field = Field2::new(&space);
+        let (x, y) = (field.x[0].to_owned(), field.x[1].to_owned());
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                field.v[[i, j]] = (2. * xi).cos() * (1. - yi * yi).powi(2) * yi;
+            }
+        }
+        field.forward();
+        // f = a u + b lap(u) + c lap(lap(u)) (orthogonal space)
+        let [a, b, c] = [2., -0.5, 0.1];
+        let rhs = field.to_ortho().mapv(|v| v * a)
+            + &(field.gradient([2, 0], None) + &field.gradient([0, 2], None)).mapv(|v| v * b)
+            + &(field.gradient([4, 0], None)
+                + &field.gradient([2, 2], None).mapv(|v| v * 2.)
+                + &field.gradient([0, 4], None))
+                .mapv(|v| v * c);
+        let solver = Biharmonic::from_space(&space, [a, b, c]);
+        let mut result = Array2::<Complex<f64>>::zeros(field.vhat.raw_dim());
+        solver.solve(&rhs, &mut result, 0);
+        for (r, e) in result.iter().zip(field.vhat.iter()) {
+            assert!((r - e).norm() < 1e-8, "got {} expected {}", r, e);
+        }
+    }
+}
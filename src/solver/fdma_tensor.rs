@@ -5,12 +5,14 @@
 //! adds two matrix multiplications per dimension to the solve
 //! step, one before the fdma solver, and one after.
 #![allow(clippy::doc_markdown)]
-use super::utils::{diag, eig, inv};
+use super::iterative::{tensor_residual_2d, Residual};
+use super::utils::{diag, Decompose};
 use super::Fdma;
 use super::Solve;
 use super::SolverScalar;
-use ndarray::{Array1, Array2, ArrayBase, Ix1, Ix2, Zip};
-use ndarray::{Data, DataMut};
+use ndarray::{Array1, Array2, ArrayBase, ArrayView2, Axis, Ix1, Ix2, Ix3, Zip};
+use ndarray::{Data, DataMut, ScalarOperand};
+use num_complex::Complex;
 use std::ops::{Add, Div, Mul};
 
 /// Tensor solver handles non-seperable multidimensional
@@ -68,6 +70,11 @@ use std::ops::{Add, Div, Mul};
 /// .. math::
 ///
 ///    g = Qx ghat = self.q.dot(ghat)
+///
+/// The matrices may be real (`T = f64`) or complex
+/// (`T = Complex<f64>`), e.g. for problems with complex
+/// coefficients. The in- and output of real operators
+/// may still be complex.
 #[derive(Debug, Clone)]
 #[allow(clippy::similar_names)]
 pub struct FdmaTensor<T, const N: usize> {
@@ -81,13 +88,28 @@ pub struct FdmaTensor<T, const N: usize> {
     pub bwd: Vec<Option<Array2<T>>>,
     /// Eigenvalues, of size (N-1)
     pub lam: Vec<Array1<T>>,
-    /// Define wether problem is singular (pure neumann for example)
+    /// Swept fdma solvers a + (lam_i + alpha) c, one per eigenvalue
+    /// of the first dimension (2-D), or a + (lam_i + lam_j + alpha) c,
+    /// one per pair of eigenvalues of the first two dimensions in
+    /// row-major order (3-D). Must be updated with `sweep_lam` after
+    /// modifying `lam` or `alpha`.
+    pub fdma_lam: Vec<Fdma<T>>,
+    /// Define wether problem is singular (pure neumann for example).
+    /// If set, the constant mode of the first eigenvalue, which must
+    /// vanish, is pinned to zero. Must be updated with `sweep_lam`.
     pub singular: bool,
     /// Additional constant for hholtz problems
     pub alpha: T,
+    /// Matrices a (used to evaluate residual)
+    pub mat_a: Vec<Array2<T>>,
+    /// Matrices c (used to evaluate residual)
+    pub mat_c: Vec<Array2<T>>,
 }
 
-impl<const N: usize> FdmaTensor<f64, N> {
+impl<T, const N: usize> FdmaTensor<T, N>
+where
+    T: SolverScalar + ScalarOperand + Decompose,
+{
     /// Supply array of matrices a and c, as defined in the definition of `FdmaTensor`.
     ///
     /// Eigendecompoiton:
@@ -103,15 +125,15 @@ impl<const N: usize> FdmaTensor<f64, N> {
     /// In this case, only a, which must be a banded matrix, is used in solve.
     #[allow(clippy::many_single_char_names, clippy::similar_names)]
     pub fn from_matrix(
-        a: [&Array2<f64>; N],
-        c: [&Array2<f64>; N],
+        a: [&Array2<T>; N],
+        c: [&Array2<T>; N],
         a_is_diag: [&bool; N],
-        alpha: f64,
+        alpha: T,
     ) -> Self {
         //todo!()
-        let mut fwd: Vec<Option<Array2<f64>>> = Vec::new();
-        let mut bwd: Vec<Option<Array2<f64>>> = Vec::new();
-        let mut lam: Vec<Array1<f64>> = Vec::new();
+        let mut fwd: Vec<Option<Array2<T>>> = Vec::new();
+        let mut bwd: Vec<Option<Array2<T>>> = Vec::new();
+        let mut lam: Vec<Array1<T>> = Vec::new();
         // Inner dimensions
         for i in 0..N - 1 {
             if *a_is_diag[i] {
@@ -119,10 +141,11 @@ impl<const N: usize> FdmaTensor<f64, N> {
                 fwd.push(None);
                 bwd.push(None);
             } else {
-                let xmat = inv(c[i]).dot(a[i]);
-                let (l, q, p) = eig(&xmat);
+                let cinv = T::inv(c[i]);
+                let xmat = cinv.dot(a[i]);
+                let (l, q, p) = T::eig(&xmat);
                 lam.push(l);
-                fwd.push(Some(p.dot(&inv(c[i]))));
+                fwd.push(Some(p.dot(&cinv)));
                 bwd.push(Some(q));
             }
         }
@@ -139,8 +162,11 @@ impl<const N: usize> FdmaTensor<f64, N> {
             fwd,
             bwd,
             lam,
+            fdma_lam: Vec::new(),
             singular: false,
             alpha,
+            mat_a: a.iter().map(|x| (*x).to_owned()).collect(),
+            mat_c: c.iter().map(|x| (*x).to_owned()).collect(),
         };
 
         // For 1-D problems, the forward sweep
@@ -148,20 +174,45 @@ impl<const N: usize> FdmaTensor<f64, N> {
         if N == 1 {
             tensor.fdma[0].sweep();
         }
+        // For 2-D and 3-D problems, the forward sweeps
+        // for all eigenvalues
+        if N == 2 || N == 3 {
+            tensor.sweep_lam();
+        }
         // Return
         tensor
     }
+
+    /// Precompute the swept fdma solvers for all eigenvalues
+    /// of the first dimension, see `fdma_lam`.
+    pub fn sweep_lam(&mut self) {
+        let mut lam_sum: Vec<T> = Vec::new();
+        if N == 2 {
+            lam_sum.extend(self.lam[0].iter());
+        } else if N == 3 {
+            for l0 in self.lam[0].iter() {
+                lam_sum.extend(self.lam[1].iter().map(|l1| *l0 + *l1));
+            }
+        }
+        self.fdma_lam = lam_sum
+            .iter()
+            .enumerate()
+            .map(|(i, lam)| {
+                let mut fdma = &self.fdma[0] + &(&self.fdma[1] * (*lam + self.alpha));
+                if self.singular && i == 0 {
+                    fdma.dia[0] = T::one();
+                }
+                fdma.sweep();
+                fdma
+            })
+            .collect();
+    }
 }
 
-impl<S> Solve<S, Ix1> for FdmaTensor<f64, 1>
+impl<T, S> Solve<S, Ix1> for FdmaTensor<T, 1>
 where
-    S: SolverScalar
-        + std::ops::Div<f64>
-        + std::ops::Mul<f64>
-        + std::ops::Add<f64>
-        + Div<f64, Output = S>
-        + Mul<f64, Output = S>
-        + Add<f64, Output = S>,
+    T: SolverScalar,
+    S: SolverScalar + Div<T, Output = S> + Mul<T, Output = S> + Add<T, Output = S> + From<T>,
 {
     /// Solve 1-D
     fn solve<S1: Data<Elem = S>, S2: Data<Elem = S> + DataMut>(
@@ -181,17 +232,12 @@ where
     }
 }
 
-impl<S> Solve<S, Ix2> for FdmaTensor<f64, 2>
+impl<T, S> Solve<S, Ix2> for FdmaTensor<T, 2>
 where
-    S: SolverScalar
-        + std::ops::Div<f64>
-        + std::ops::Mul<f64>
-        + std::ops::Add<f64>
-        + Div<f64, Output = S>
-        + Mul<f64, Output = S>
-        + Add<f64, Output = S>,
+    T: SolverScalar,
+    S: SolverScalar + Div<T, Output = S> + Mul<T, Output = S> + Add<T, Output = S> + From<T>,
 {
-    /// Solve 2-D Problem with real in and output
+    /// Solve 2-D Problem with real or complex operator
     fn solve<S1: Data<Elem = S>, S2: Data<Elem = S> + DataMut>(
         &self,
         input: &ArrayBase<S1, Ix2>,
@@ -218,13 +264,13 @@ where
 
         // Step 2: Solve along y (but iterate over all lanes in x)
         Zip::from(output.outer_iter_mut())
-            .and(self.lam[0].outer_iter())
-            .par_for_each(|mut out, lam| {
-                let l = lam.as_slice().unwrap()[0] + self.alpha;
-                let mut fdma = &self.fdma[0] + &(&self.fdma[1] * l);
-                fdma.sweep();
+            .and(&self.fdma_lam)
+            .par_for_each(|mut out, fdma| {
                 fdma.solve(&out.to_owned(), &mut out, 0);
             });
+        if self.singular {
+            output[[0, 0]] = S::zero();
+        }
 
         // Step 3: Backward Transform solution along x
         if let Some(q) = &self.bwd[0] {
@@ -234,6 +280,92 @@ where
     }
 }
 
+impl<T, S> Solve<S, Ix3> for FdmaTensor<T, 3>
+where
+    T: SolverScalar,
+    S: SolverScalar + Div<T, Output = S> + Mul<T, Output = S> + Add<T, Output = S> + From<T>,
+{
+    /// Solve 3-D Problem with real or complex operator
+    ///
+    /// The first two dimensions are diagonalized, the
+    /// system is banded along the last dimension.
+    fn solve<S1: Data<Elem = S>, S2: Data<Elem = S> + DataMut>(
+        &self,
+        input: &ArrayBase<S1, Ix3>,
+        output: &mut ArrayBase<S2, Ix3>,
+        _axis: usize,
+    ) {
+        let (n0, n1) = (self.lam[0].len(), self.lam[1].len());
+        if input.shape() != [n0, n1, self.n] {
+            panic!(
+                "Dimension mismatch in Tensor! Got {:?} vs. {:?}.",
+                input.shape(),
+                [n0, n1, self.n]
+            );
+        }
+
+        // Step 1: Forward Transform rhs along x and y
+        output.assign(input);
+        for (axis, fwd) in self.fwd.iter().enumerate() {
+            if let Some(p) = fwd {
+                dot_along_3d(p, output, axis);
+            }
+        }
+
+        // Step 2: Solve along z (but iterate over all lanes in x and y)
+        let fdma_lam = ArrayView2::from_shape((n0, n1), &self.fdma_lam).unwrap();
+        Zip::from(output.lanes_mut(Axis(2)))
+            .and(fdma_lam)
+            .par_for_each(|mut out, fdma| {
+                fdma.solve(&out.to_owned(), &mut out, 0);
+            });
+        if self.singular {
+            output[[0, 0, 0]] = S::zero();
+        }
+
+        // Step 3: Backward Transform solution along x and y
+        for (axis, bwd) in self.bwd.iter().enumerate() {
+            if let Some(q) = bwd {
+                dot_along_3d(q, output, axis);
+            }
+        }
+    }
+}
+
+/// Multiply matrix with each lane of a 3-D array along axis
+fn dot_along_3d<T, S, S2>(mat: &Array2<T>, data: &mut ArrayBase<S2, Ix3>, axis: usize)
+where
+    T: SolverScalar,
+    S: SolverScalar + From<T>,
+    S2: Data<Elem = S> + DataMut,
+{
+    let mat_cast: Array2<S> = mat.mapv(|x| x.into());
+    Zip::from(data.lanes_mut(Axis(axis))).par_for_each(|mut lane| {
+        let v = mat_cast.dot(&lane);
+        lane.assign(&v);
+    });
+}
+
+impl<A> Residual<A, Ix2> for FdmaTensor<f64, 2>
+where
+    A: SolverScalar + From<f64> + Into<Complex<f64>>,
+{
+    /// Residual of a0 x c1^T + c0 x a1^T + alpha c0 x c1^T = b
+    fn residual<S1, S2>(&self, input: &ArrayBase<S1, Ix2>, output: &ArrayBase<S2, Ix2>) -> f64
+    where
+        S1: Data<Elem = A>,
+        S2: Data<Elem = A>,
+    {
+        tensor_residual_2d(
+            &input.to_owned(),
+            &output.to_owned(),
+            [&self.mat_a[0], &self.mat_a[1]],
+            [&self.mat_c[0], &self.mat_c[1]],
+            self.alpha,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -355,6 +487,95 @@ mod tests {
         approx_eq(&recover, &data);
     }
 
+    #[test]
+    fn test_tensor2d_cached_sweeps() {
+        let nx = 6;
+        let mut data = Array2::<f64>::zeros((nx, nx));
+        for (i, v) in data.iter_mut().enumerate() {
+            *v = (i as f64).sin();
+        }
+        let a = ndarray::array![
+            [-1.0, 0.0, 1.0, 0.0, 0.0, 0.0],
+            [0.0, -1.0, 0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, -1.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, -1.0, 0.0, 1.0],
+            [0.0, 0.0, 0.0, 0.0, -1.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0, 0.0, -1.0]
+        ];
+        let c = ndarray::array![
+            [0.41666, 0.0, -0.2083, 0.0, 0.041666, 0.0],
+            [0.0, 0.104166, 0.0, -0.0833, 0.0, 0.0208],
+            [-0.0208, 0.0, 0.0542, 0.0, -0.0333, 0.0],
+            [0.0, -0.0125, 0.0, 0.033333, 0.0, -0.020833],
+            [0.0, 0.0, -0.00833, 0.0, 0.00833, 0.0],
+            [0.0, 0.0, 0.0, -0.00595, 0.0, 0.00595]
+        ];
+        let mut solver = FdmaTensor::from_matrix([&a, &a], [&c, &c], [&false, &false], 0.5);
+        let mut result = Array2::<f64>::zeros((nx, nx));
+        solver.solve(&data, &mut result, 0);
+        // Sweep for each eigenvalue on the fly
+        let mut expected = solver.fwd[0].as_ref().unwrap().dot(&data);
+        for (mut out, lam) in expected.outer_iter_mut().zip(solver.lam[0].iter()) {
+            let mut fdma = &solver.fdma[0] + &(&solver.fdma[1] * (lam + solver.alpha));
+            fdma.sweep();
+            fdma.solve(&out.to_owned(), &mut out, 0);
+        }
+        let expected = solver.bwd[0].as_ref().unwrap().dot(&expected);
+        for (r, e) in result.iter().zip(expected.iter()) {
+            assert!((r - e).abs() < 1e-12);
+        }
+        // Modified eigenvalues
+        solver.lam[0] -= 0.1;
+        solver.sweep_lam();
+        let mut shifted = Array2::<f64>::zeros((nx, nx));
+        solver.solve(&data, &mut shifted, 0);
+        let solver = FdmaTensor::from_matrix([&a, &a], [&c, &c], [&false, &false], 0.4);
+        solver.solve(&data, &mut result, 0);
+        for (r, e) in shifted.iter().zip(result.iter()) {
+            assert!((r - e).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_tensor3d() {
+        let n = 6;
+        let mut data = ndarray::Array3::<f64>::zeros((n, n, n));
+        for (i, v) in data.iter_mut().enumerate() {
+            *v = (i as f64).sin();
+        }
+        let a = ndarray::array![
+            [-1.0, 0.0, 1.0, 0.0, 0.0, 0.0],
+            [0.0, -1.0, 0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, -1.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, -1.0, 0.0, 1.0],
+            [0.0, 0.0, 0.0, 0.0, -1.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0, 0.0, -1.0]
+        ];
+        let c = ndarray::array![
+            [0.41666, 0.0, -0.2083, 0.0, 0.041666, 0.0],
+            [0.0, 0.104166, 0.0, -0.0833, 0.0, 0.0208],
+            [-0.0208, 0.0, 0.0542, 0.0, -0.0333, 0.0],
+            [0.0, -0.0125, 0.0, 0.033333, 0.0, -0.020833],
+            [0.0, 0.0, -0.00833, 0.0, 0.00833, 0.0],
+            [0.0, 0.0, 0.0, -0.00595, 0.0, 0.00595]
+        ];
+        let solver =
+            FdmaTensor::from_matrix([&a, &a, &a], [&c, &c, &c], [&false, &false, &false], 0.);
+        let mut result = ndarray::Array3::<f64>::zeros((n, n, n));
+        solver.solve(&data, &mut result, 0);
+
+        // Recover b = (A x C x C + C x A x C + C x C x A) x
+        let kron = |mats: [&Array2<f64>; 3]| {
+            let mut y = result.clone();
+            for (axis, mat) in mats.iter().enumerate() {
+                dot_along_3d(mat, &mut y, axis);
+            }
+            y
+        };
+        let recover = kron([&a, &c, &c]) + kron([&c, &a, &c]) + kron([&c, &c, &a]);
+        approx_eq(&recover, &data);
+    }
+
     #[test]
     fn test_tensor2d_complex() {
         type Ty = Complex<f64>;
@@ -394,4 +615,42 @@ mod tests {
         let recover = ac.dot(&x).dot(&(cc.t())) + cc.dot(&x).dot(&(ac.t()));
         approx_eq_complex(&recover, &data);
     }
+
+    #[test]
+    fn test_tensor2d_complex_vs_split() {
+        let nx = 6;
+        let mut data = Array2::<Complex<f64>>::zeros((nx, nx));
+        for (i, v) in data.iter_mut().enumerate() {
+            v.re = (i as f64).sin();
+            v.im = (i as f64).cos();
+        }
+        let a = ndarray::array![
+            [-1.0, 0.0, 1.0, 0.0, 0.0, 0.0],
+            [0.0, -1.0, 0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, -1.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, -1.0, 0.0, 1.0],
+            [0.0, 0.0, 0.0, 0.0, -1.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0, 0.0, -1.0]
+        ];
+        let c = ndarray::array![
+            [0.41666, 0.0, -0.2083, 0.0, 0.041666, 0.0],
+            [0.0, 0.104166, 0.0, -0.0833, 0.0, 0.0208],
+            [-0.0208, 0.0, 0.0542, 0.0, -0.0333, 0.0],
+            [0.0, -0.0125, 0.0, 0.033333, 0.0, -0.020833],
+            [0.0, 0.0, -0.00833, 0.0, 0.00833, 0.0],
+            [0.0, 0.0, 0.0, -0.00595, 0.0, 0.00595]
+        ];
+        let solver = FdmaTensor::from_matrix([&a, &a], [&c, &c], [&false, &false], 0.);
+        // Direct complex solve
+        let mut result = Array2::<Complex<f64>>::zeros((nx, nx));
+        solver.solve(&data, &mut result, 0);
+        // Split into two real solves
+        let mut result_re = Array2::<f64>::zeros((nx, nx));
+        let mut result_im = Array2::<f64>::zeros((nx, nx));
+        solver.solve(&data.mapv(|v| v.re), &mut result_re, 0);
+        solver.solve(&data.mapv(|v| v.im), &mut result_im, 0);
+        for ((r, re), im) in result.iter().zip(result_re.iter()).zip(result_im.iter()) {
+            assert!((r.re - re).abs() < 1e-10 && (r.im - im).abs() < 1e-10);
+        }
+    }
 }
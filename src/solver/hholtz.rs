@@ -19,9 +19,10 @@
 use super::{MatVec, MatVecFdma, SolverScalar};
 use crate::bases::BaseSpace;
 use crate::field::FieldBase;
-use crate::solver::utils::vec_to_array;
-use crate::solver::{FdmaTensor, Solve, SolveReturn};
+use crate::solver::utils::{slice_to_array, vec_to_array};
+use crate::solver::{FdmaTensor, Residual, Solve, SolveReturn, SolverError};
 use ndarray::prelude::*;
+use num_complex::Complex;
 use std::ops::{Add, Div, Mul};
 
 /// Container for Hholtz
@@ -113,6 +114,21 @@ impl<const N: usize> Hholtz<f64, N> {
             matvec,
         }
     }
+
+    /// Construct Helmholtz solver from space, with coefficients
+    /// supplied as slice. Useful for dimension agnostic code.
+    ///
+    /// # Errors
+    /// Returns `SolverError::DimensionMismatch` if length of `c`
+    /// does not match the dimensionality of the space.
+    pub fn try_from_space<T2, S>(space: &S, c: &[f64]) -> Result<Self, SolverError>
+    where
+        S: BaseSpace<f64, N, Physical = f64, Spectral = T2>,
+    {
+        let c = slice_to_array::<f64, N>(c)?;
+        let field = FieldBase::new(space);
+        Ok(Self::new(&field, c))
+    }
 }
 
 #[allow(unused_variables)]
@@ -181,11 +197,8 @@ where
         }
         // Step 2: Solve along y (but iterate over all lanes in x)
         Zip::from(output.outer_iter_mut())
-            .and(solver.lam[0].outer_iter())
-            .par_for_each(|mut out, lam| {
-                let l = lam.as_slice().unwrap()[0] + solver.alpha;
-                let mut fdma = &solver.fdma[0] + &(&solver.fdma[1] * l);
-                fdma.sweep();
+            .and(&solver.fdma_lam)
+            .par_for_each(|mut out, fdma| {
                 fdma.solve(&out.to_owned(), &mut out, 0);
             });
 
@@ -197,6 +210,31 @@ where
     }
 }
 
+impl<A> Residual<A, Ix2> for Hholtz<f64, 2>
+where
+    A: SolverScalar
+        + Div<f64, Output = A>
+        + Mul<f64, Output = A>
+        + Add<f64, Output = A>
+        + From<f64>
+        + Into<Complex<f64>>,
+{
+    /// Residual of the preconditioned system
+    fn residual<S1, S2>(&self, input: &ArrayBase<S1, Ix2>, output: &ArrayBase<S2, Ix2>) -> f64
+    where
+        S1: ndarray::Data<Elem = A>,
+        S2: ndarray::Data<Elem = A>,
+    {
+        let mut rhs = self.matvec[0]
+            .as_ref()
+            .map_or_else(|| input.to_owned(), |x| x.solve(input, 0));
+        if let Some(x) = &self.matvec[1] {
+            rhs = x.solve(&rhs, 1);
+        };
+        self.solver.residual(&rhs, output)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -277,4 +315,40 @@ mod tests {
         // Compare
         approx_eq(&field.v, &expected);
     }
+
+    #[test]
+    fn test_hholtz_try_from_space() {
+        let (nx, ny) = (16, 7);
+        let space = Space2::new(&fourier_r2c(nx), &cheb_dirichlet(ny));
+        let mut field = Field2::new(&space);
+        let x = &field.x[0];
+        let y = &field.x[1];
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                field.v[[i, j]] = xi.cos() * (std::f64::consts::PI / 2. * yi).cos();
+            }
+        }
+        field.forward();
+        let input = field.to_ortho();
+
+        // Correct length
+        let solver = Hholtz::try_from_space(&space, &[1e-2, 1e-2]).unwrap();
+        let expected_solver = Hholtz::new(&field, [1e-2, 1e-2]);
+        let mut result = field.vhat.clone();
+        let mut expected = field.vhat.clone();
+        solver.solve(&input, &mut result, 0);
+        expected_solver.solve(&input, &mut expected, 0);
+        assert!(result == expected);
+
+        // Incorrect length
+        let err = Hholtz::<f64, 2>::try_from_space(&space, &[1e-2]).err();
+        assert_eq!(
+            err,
+            Some(SolverError::DimensionMismatch {
+                expected: 2,
+                got: 1
+            })
+        );
+        assert!(Hholtz::<f64, 2>::try_from_space(&space, &[1e-2; 3]).is_err());
+    }
 }
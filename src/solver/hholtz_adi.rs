@@ -22,9 +22,11 @@
 use super::{MatVec, MatVecFdma, Solver, SolverScalar};
 use crate::bases::BaseSpace;
 use crate::field::FieldBase;
-use crate::solver::{Fdma, Solve, SolveReturn};
+use crate::solver::iterative::norm_l2_diff;
+use crate::solver::{Fdma, Residual, Solve, SolveReturn};
 use ndarray::prelude::*;
 use ndarray::{Data, DataMut};
+use num_complex::Complex;
 use std::ops::{Add, Div, Mul};
 
 /// Container for `HholtzAdi`
@@ -35,6 +37,8 @@ pub struct HholtzAdi<T, const N: usize>
 {
     solver: Vec<Solver<T>>,
     matvec: Vec<Option<MatVec<T>>>,
+    /// System matrices along each axis (used to evaluate residual)
+    mats: Vec<Array2<T>>,
 }
 
 impl<const N: usize> HholtzAdi<f64, N> {
@@ -48,6 +52,7 @@ impl<const N: usize> HholtzAdi<f64, N> {
         // Gather matrices and preconditioner
         let mut solver: Vec<Solver<f64>> = Vec::new();
         let mut matvec: Vec<Option<MatVec<f64>>> = Vec::new();
+        let mut mats: Vec<Array2<f64>> = Vec::new();
         for (axis, ci) in c.iter().enumerate() {
             // Matrices and preconditioner
             let (mat_a, mat_b, precond) = field.ingredients_for_hholtz(axis);
@@ -57,9 +62,14 @@ impl<const N: usize> HholtzAdi<f64, N> {
 
             solver.push(solver_axis);
             matvec.push(matvec_axis);
+            mats.push(mat);
         }
 
-        Self { solver, matvec }
+        Self {
+            solver,
+            matvec,
+            mats,
+        }
     }
 }
 
@@ -130,6 +140,34 @@ where
     }
 }
 
+impl<A> Residual<A, Ix2> for HholtzAdi<f64, 2>
+where
+    A: SolverScalar
+        + Div<f64, Output = A>
+        + Mul<f64, Output = A>
+        + Add<f64, Output = A>
+        + From<f64>
+        + Into<Complex<f64>>,
+{
+    /// Residual of the preconditioned and factorized system
+    /// M0 x M1^T = b, i.e. without the splitting error of ADI.
+    fn residual<S1, S2>(&self, input: &ArrayBase<S1, Ix2>, output: &ArrayBase<S2, Ix2>) -> f64
+    where
+        S1: Data<Elem = A>,
+        S2: Data<Elem = A>,
+    {
+        let mut rhs = self.matvec[0]
+            .as_ref()
+            .map_or_else(|| input.to_owned(), |x| x.solve(input, 0));
+        if let Some(x) = &self.matvec[1] {
+            rhs = x.solve(&rhs, 1);
+        }
+        let m0: Array2<A> = self.mats[0].mapv(|v| v.into());
+        let m1: Array2<A> = self.mats[1].mapv(|v| v.into());
+        norm_l2_diff(&rhs, &m0.dot(output).dot(&m1.t()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
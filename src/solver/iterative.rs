@@ -0,0 +1,400 @@
+//! # Iterative solver
+//! Tolerances and convergence report, shared by all
+//! iterative solver backends.
+//!
+//! An iterative solve stops, when
+//!
+//!  |b - M x| <= max(atol, rtol * |b|)
+//!
+//! or after *max_iter* iterations. The direct solvers
+//! implement the same interface, reporting a single
+//! iteration and the residual of their linear system.
+use super::{MatVec, MatVecDot, Solve, SolveReturn};
+use ndarray::{Array, Array2, ArrayBase, Data, DataMut, Dimension, Zip};
+use num_complex::Complex;
+
+/// Stopping criteria of iterative solvers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IterativeConfig {
+    /// Relative tolerance (with respect to |b|)
+    pub rtol: f64,
+    /// Absolute tolerance
+    pub atol: f64,
+    /// Maximum number of iterations
+    pub max_iter: usize,
+}
+
+impl Default for IterativeConfig {
+    fn default() -> Self {
+        Self {
+            rtol: 1e-10,
+            atol: 1e-14,
+            max_iter: 1000,
+        }
+    }
+}
+
+impl IterativeConfig {
+    /// Returns true if residual satisfies the tolerances
+    pub fn converged(&self, residual: f64, norm_rhs: f64) -> bool {
+        residual <= self.atol.max(self.rtol * norm_rhs)
+    }
+}
+
+/// Convergence report of a solve
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IterativeReport {
+    /// Number of iterations performed
+    pub iterations: usize,
+    /// L2 norm of final residual |b - M x|
+    pub residual: f64,
+}
+
+/// Residual of linear system M x = b
+pub trait Residual<A, D> {
+    /// Returns L2 norm of b - M x, with b = *input*
+    /// and x = *output*, as supplied to `solve`.
+    fn residual<S1, S2>(&self, input: &ArrayBase<S1, D>, output: &ArrayBase<S2, D>) -> f64
+    where
+        S1: Data<Elem = A>,
+        S2: Data<Elem = A>;
+}
+
+/// Solve linear system and report convergence
+pub trait SolveIterative<A, D> {
+    /// Solves M x = b (see `Solve`) and returns the
+    /// number of iterations and the final residual.
+    fn solve_iterative<S1, S2>(
+        &self,
+        input: &ArrayBase<S1, D>,
+        output: &mut ArrayBase<S2, D>,
+        axis: usize,
+        config: &IterativeConfig,
+    ) -> IterativeReport
+    where
+        S1: Data<Elem = A>,
+        S2: Data<Elem = A> + DataMut;
+}
+
+/// L2 norm of real or complex array
+pub fn norm_l2<A, S, D>(array: &ArrayBase<S, D>) -> f64
+where
+    A: Copy + Into<Complex<f64>>,
+    S: Data<Elem = A>,
+    D: Dimension,
+{
+    array
+        .iter()
+        .map(|x| (*x).into().norm_sqr())
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// L2 norm of the difference of two arrays
+pub fn norm_l2_diff<A, S1, S2, D>(a: &ArrayBase<S1, D>, b: &ArrayBase<S2, D>) -> f64
+where
+    A: Copy + Into<Complex<f64>>,
+    S1: Data<Elem = A>,
+    S2: Data<Elem = A>,
+    D: Dimension,
+{
+    a.iter()
+        .zip(b.iter())
+        .map(|(x, y)| ((*x).into() - (*y).into()).norm_sqr())
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Linear operator defined by a closure, which returns
+/// M x for x. Use it as operator or preconditioner
+/// of [`ConjugateGradient`].
+#[derive(Clone)]
+pub struct LinearOperator<F>(pub F);
+
+impl<F, A, D> SolveReturn<A, D> for LinearOperator<F>
+where
+    F: Fn(&Array<A, D>) -> Array<A, D>,
+    A: Clone,
+    D: Dimension,
+{
+    /// Apply operator (*axis* is ignored)
+    fn solve<S1>(&self, input: &ArrayBase<S1, D>, _axis: usize) -> Array<A, D>
+    where
+        S1: Data<Elem = A>,
+    {
+        (self.0)(&input.to_owned())
+    }
+}
+
+/// Preconditioned conjugate gradient solver for symmetric
+/// positive definite systems, e.g. with spatially varying
+/// coefficients, which the direct solvers cannot handle.
+///
+/// The operator M and the optional preconditioner P (an
+/// approximate inverse of M) are applied via `SolveReturn`,
+/// i.e. they are [`MatVec`] types or a [`LinearOperator`].
+#[derive(Debug, Clone)]
+pub struct ConjugateGradient<M = MatVec<f64>, P = M> {
+    /// System operator
+    operator: M,
+    /// Preconditioner
+    precond: Option<P>,
+    /// Maximum number of iterations of `solve`
+    pub max_iter: usize,
+    /// Relative tolerance of `solve`
+    pub tol: f64,
+}
+
+impl ConjugateGradient {
+    /// Construct from symmetric positive definite matrix
+    pub fn new(mat: &Array2<f64>) -> Self {
+        Self::from_operator(MatVec::MatVecDot(MatVecDot::new(mat)))
+    }
+}
+
+impl<M> ConjugateGradient<M, M> {
+    /// Construct from symmetric positive definite operator
+    pub fn from_operator(operator: M) -> Self {
+        let config = IterativeConfig::default();
+        Self {
+            operator,
+            precond: None,
+            max_iter: config.max_iter,
+            tol: config.rtol,
+        }
+    }
+}
+
+impl<M, P> ConjugateGradient<M, P> {
+    /// Return solver with (symmetric positive definite) preconditioner,
+    /// which approximates the inverse of the operator
+    pub fn with_preconditioner<P2>(self, precond: P2) -> ConjugateGradient<M, P2> {
+        ConjugateGradient {
+            operator: self.operator,
+            precond: Some(precond),
+            max_iter: self.max_iter,
+            tol: self.tol,
+        }
+    }
+}
+
+/// Inner product of real arrays
+fn inner<S1, S2, D>(a: &ArrayBase<S1, D>, b: &ArrayBase<S2, D>) -> f64
+where
+    S1: Data<Elem = f64>,
+    S2: Data<Elem = f64>,
+    D: Dimension,
+{
+    Zip::from(a).and(b).fold(0., |acc, x, y| acc + x * y)
+}
+
+impl<M, P, D> Residual<f64, D> for ConjugateGradient<M, P>
+where
+    M: SolveReturn<f64, D>,
+    D: Dimension,
+{
+    fn residual<S1, S2>(&self, input: &ArrayBase<S1, D>, output: &ArrayBase<S2, D>) -> f64
+    where
+        S1: Data<Elem = f64>,
+        S2: Data<Elem = f64>,
+    {
+        norm_l2_diff(input, &self.operator.solve(output, 0))
+    }
+}
+
+impl<M, P, D> SolveIterative<f64, D> for ConjugateGradient<M, P>
+where
+    M: SolveReturn<f64, D>,
+    P: SolveReturn<f64, D>,
+    D: Dimension,
+{
+    /// Starts from the initial guess supplied in *output*
+    fn solve_iterative<S1, S2>(
+        &self,
+        input: &ArrayBase<S1, D>,
+        output: &mut ArrayBase<S2, D>,
+        axis: usize,
+        config: &IterativeConfig,
+    ) -> IterativeReport
+    where
+        S1: Data<Elem = f64>,
+        S2: Data<Elem = f64> + DataMut,
+    {
+        let precond = |r: &Array<f64, D>| {
+            self.precond
+                .as_ref()
+                .map_or_else(|| r.clone(), |p| p.solve(r, axis))
+        };
+        let norm_rhs = norm_l2(input);
+        let mut r: Array<f64, D> = input - &self.operator.solve(&*output, axis);
+        let mut z = precond(&r);
+        let mut p = z.clone();
+        let mut rz = inner(&r, &z);
+        let mut iterations = 0;
+        while iterations < config.max_iter && !config.converged(norm_l2(&r), norm_rhs) {
+            if rz == 0. {
+                break;
+            }
+            let mp = self.operator.solve(&p, axis);
+            let alpha = rz / inner(&p, &mp);
+            output.scaled_add(alpha, &p);
+            r.scaled_add(-alpha, &mp);
+            z = precond(&r);
+            let rz_new = inner(&r, &z);
+            p = &z + &(p * (rz_new / rz));
+            rz = rz_new;
+            iterations += 1;
+        }
+        IterativeReport {
+            iterations,
+            residual: self.residual(input, &*output),
+        }
+    }
+}
+
+impl<M, P, D> Solve<f64, D> for ConjugateGradient<M, P>
+where
+    M: SolveReturn<f64, D>,
+    P: SolveReturn<f64, D>,
+    D: Dimension,
+{
+    /// Solve with tolerance *tol* and at most *max_iter*
+    /// iterations, starting from zero
+    fn solve<S1, S2>(&self, input: &ArrayBase<S1, D>, output: &mut ArrayBase<S2, D>, axis: usize)
+    where
+        S1: Data<Elem = f64>,
+        S2: Data<Elem = f64> + DataMut,
+    {
+        let config = IterativeConfig {
+            rtol: self.tol,
+            max_iter: self.max_iter,
+            ..IterativeConfig::default()
+        };
+        output.fill(0.);
+        self.solve_iterative(input, output, axis, &config);
+    }
+}
+
+/// Returns b - M x for a 2-D tensor product system,
+/// where M x = a0 x c1^T + c0 x a1^T + alpha c0 x c1^T
+pub(crate) fn tensor_residual_2d<A>(
+    rhs: &Array2<A>,
+    x: &Array2<A>,
+    a: [&Array2<f64>; 2],
+    c: [&Array2<f64>; 2],
+    alpha: f64,
+) -> f64
+where
+    A: super::SolverScalar + From<f64> + Into<Complex<f64>>,
+{
+    let cast = |m: &Array2<f64>| -> Array2<A> { m.mapv(|v| v.into()) };
+    let (a0, a1, c0, c1) = (cast(a[0]), cast(a[1]), cast(c[0]), cast(c[1]));
+    let alpha: A = alpha.into();
+    let mx: Array2<A> = a0.dot(x).dot(&c1.t())
+        + &c0.dot(x).dot(&a1.t())
+        + &(c0.dot(x).dot(&c1.t()).mapv(|v| v * alpha));
+    norm_l2_diff(rhs, &mx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{Field2, Space2};
+    use crate::solver::{Hholtz, Poisson, SolverField};
+    use crate::{cheb_dirichlet, fourier_r2c};
+    use ndarray::Array1;
+
+    /// Symmetric positive definite test matrix
+    fn spd_matrix(n: usize) -> Array2<f64> {
+        let mut mat = Array2::<f64>::zeros((n, n));
+        for i in 0..n {
+            mat[[i, i]] = 4.;
+            if i > 0 {
+                mat[[i, i - 1]] = -1.;
+            }
+            if i < n - 1 {
+                mat[[i, i + 1]] = -1.;
+            }
+        }
+        mat
+    }
+
+    #[test]
+    fn test_cg_report() {
+        let n = 50;
+        let cg = ConjugateGradient::new(&spd_matrix(n));
+        let b = Array1::from_shape_fn(n, |i| (i as f64).sin());
+        let mut last = f64::INFINITY;
+        for max_iter in [2, 5, 10, 15].iter() {
+            let config = IterativeConfig {
+                max_iter: *max_iter,
+                rtol: 0.,
+                atol: 0.,
+            };
+            let mut x = Array1::<f64>::zeros(n);
+            let report = cg.solve_iterative(&b, &mut x, 0, &config);
+            assert_eq!(report.iterations, *max_iter);
+            assert!(report.residual < last);
+            last = report.residual;
+        }
+        // Converged
+        let config = IterativeConfig::default();
+        let mut x = Array1::<f64>::zeros(n);
+        let report = cg.solve_iterative(&b, &mut x, 0, &config);
+        assert!(report.iterations <= n);
+        assert!(config.converged(report.residual, norm_l2(&b)));
+    }
+
+    #[test]
+    fn test_cg_poisson2d() {
+        let (nx, ny) = (8, 7);
+        let space = Space2::new(&cheb_dirichlet(nx), &cheb_dirichlet(ny));
+        let field = Field2::new(&space);
+        let b = Array2::from_shape_fn((nx, ny), |(_, j)| (j + 1) as f64);
+        // Direct solution
+        let poisson = Poisson::new(&field, [1., 1.]);
+        let mut expected = Array2::<f64>::zeros((nx - 2, ny - 2));
+        poisson.solve(&b, &mut expected, 0);
+        // Preconditioned system L0 x C1^T + C0 x L1^T = f is not symmetric,
+        // solve normal equations M^T M x = M^T f
+        let (c0, l0, p0, _) = field.ingredients_for_poisson(0);
+        let (c1, l1, p1, _) = field.ingredients_for_poisson(1);
+        let f = p0.unwrap().dot(&b).dot(&p1.unwrap().t());
+        let (c0_t, l0_t) = (c0.t().to_owned(), l0.t().to_owned());
+        let (c1_t, l1_t) = (c1.t().to_owned(), l1.t().to_owned());
+        let mat_t = move |y: &Array2<f64>| l0_t.dot(y).dot(&c1) + c0_t.dot(y).dot(&l1);
+        let rhs = mat_t(&f);
+        let normal = LinearOperator(move |x: &Array2<f64>| {
+            mat_t(&(l0.dot(x).dot(&c1_t) + c0.dot(x).dot(&l1_t)))
+        });
+        let mut cg = ConjugateGradient::from_operator(normal);
+        cg.tol = 1e-14;
+        let config = IterativeConfig {
+            rtol: cg.tol,
+            ..IterativeConfig::default()
+        };
+        let mut x = Array2::<f64>::zeros((nx - 2, ny - 2));
+        let report = cg.solve_iterative(&rhs, &mut x, 0, &config);
+        assert!(report.iterations > 0 && report.iterations <= 100);
+        assert!(config.converged(report.residual, norm_l2(&rhs)));
+        assert!(norm_l2_diff(&x, &expected) < 1e-8 * norm_l2(&expected));
+        // Solve trait
+        let mut y = Array2::<f64>::zeros((nx - 2, ny - 2));
+        cg.solve(&rhs, &mut y, 0);
+        assert!(norm_l2_diff(&y, &x) < 1e-10 * norm_l2(&x));
+    }
+
+    #[test]
+    fn test_direct_solver_report() {
+        let space = Space2::new(&fourier_r2c(16), &cheb_dirichlet(12));
+        let field = Field2::new(&space);
+        let solver = SolverField::Hholtz(Hholtz::new(&field, [0.1, 0.1]));
+        let b = Array2::from_shape_fn((9, 12), |(i, j)| Complex::new((i + j) as f64, 1.));
+        let mut x = Array2::<Complex<f64>>::zeros((9, 10));
+        let report = solver.solve_iterative(&b, &mut x, 0, &IterativeConfig::default());
+        assert_eq!(report.iterations, 1);
+        // Compare to residual of zero solution
+        let r0 = solver.residual(&b, &Array2::<Complex<f64>>::zeros((9, 10)));
+        assert!(report.residual < 1e-8 * r0);
+    }
+}
@@ -19,19 +19,44 @@
 //! banded after multiplication with the pseudoinverse
 //! of D2 (B2). In this case, the second equation is
 //! solved, with A = B2.
+//!
+//! The coefficients c may be complex, see [`Poisson::new_shifted`].
+use super::utils::Decompose;
 use super::{MatVec, MatVecFdma, SolverScalar};
 use crate::bases::BaseSpace;
 use crate::field::FieldBase;
-use crate::solver::utils::vec_to_array;
-use crate::solver::{FdmaTensor, Solve, SolveReturn};
+use crate::solver::utils::{slice_to_array, vec_to_array};
+use crate::solver::{FdmaTensor, Residual, Solve, SolveReturn, SolverError};
 use ndarray::prelude::*;
+use ndarray::ScalarOperand;
+use num_complex::Complex;
 use std::ops::{Add, Div, Mul};
 
 /// Container for Poisson Solver
 #[derive(Clone)]
 pub struct Poisson<T, const N: usize> {
     solver: Box<FdmaTensor<T, N>>,
-    matvec: Vec<Option<MatVec<T>>>,
+    matvec: Vec<Option<MatVec<f64>>>,
+    c: [T; N],
+}
+
+/// Maximum relative error accepted by [`Poisson::self_check`]
+const SELF_CHECK_TOL: f64 = 1e-8;
+
+/// Eigenvalues below this threshold are considered zero
+const SINGULAR_TOL: f64 = 1e-10;
+
+/// Treatment of singular problems, like pure neumann
+/// problems, whose solution is only defined up to a constant
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NullspaceMode {
+    /// Shift the vanishing eigenvalue by -1e-10. The constant
+    /// mode of the solution becomes large and arbitrary.
+    Nudge,
+    /// Pin the coefficient of the constant mode to zero
+    PinMean,
+    /// Return [`SolverError::Singular`]
+    Error,
 }
 
 impl<const N: usize> Poisson<f64, N> {
@@ -44,23 +69,97 @@ impl<const N: usize> Poisson<f64, N> {
     /// chebyshev bases.
     ///
     /// Bases are diagonal, when there laplacian is a diagonal matrix.
-    /// This is the case for fourier bases. Other bases will be made
+    /// This is the case for fourier bases (including sine and cosine). Other bases will be made
     /// diagonal by an eigendecomposition. This is entirely done in
     /// the `FdmaTensor` solver.
     pub fn new<T2, S>(field: &FieldBase<f64, f64, T2, S, N>, c: [f64; N]) -> Self
+    where
+        S: BaseSpace<f64, N, Physical = f64, Spectral = T2>,
+    {
+        Self::with_nullspace(field, c, NullspaceMode::Nudge).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Construct Poisson solver from field, see [`Poisson::new`].
+    /// Singular problems are treated according to `nullspace`.
+    ///
+    /// # Errors
+    /// Returns `SolverError::Singular` if the problem is singular
+    /// and `nullspace` is `NullspaceMode::Error`.
+    pub fn with_nullspace<T2, S>(
+        field: &FieldBase<f64, f64, T2, S, N>,
+        c: [f64; N],
+        nullspace: NullspaceMode,
+    ) -> Result<Self, SolverError>
+    where
+        S: BaseSpace<f64, N, Physical = f64, Spectral = T2>,
+    {
+        let mut poisson = Self::new_shifted(field, c, 0.);
+        // Handle singularity (2D)
+        let solver = &mut poisson.solver;
+        if N == 2 && solver.lam[0][0].abs() < SINGULAR_TOL {
+            match nullspace {
+                NullspaceMode::Nudge => solver.lam[0] -= 1e-10,
+                NullspaceMode::PinMean => solver.singular = true,
+                NullspaceMode::Error => {
+                    return Err(SolverError::Singular {
+                        axis: 0,
+                        eigenvalue: solver.lam[0][0],
+                    })
+                }
+            }
+            solver.sweep_lam();
+        }
+        Ok(poisson)
+    }
+
+    /// Construct Poisson solver from space, with coefficients
+    /// supplied as slice. Useful for dimension agnostic code.
+    ///
+    /// # Errors
+    /// Returns `SolverError::DimensionMismatch` if length of `c`
+    /// does not match the dimensionality of the space, and
+    /// `SolverError::Singular`, see [`Poisson::with_nullspace`].
+    pub fn try_from_space<T2, S>(
+        space: &S,
+        c: &[f64],
+        nullspace: NullspaceMode,
+    ) -> Result<Self, SolverError>
+    where
+        S: BaseSpace<f64, N, Physical = f64, Spectral = T2>,
+    {
+        let c = slice_to_array::<f64, N>(c)?;
+        let field = FieldBase::new(space);
+        Self::with_nullspace(&field, c, nullspace)
+    }
+}
+
+impl<T, const N: usize> Poisson<T, N>
+where
+    T: SolverScalar + ScalarOperand + Decompose,
+{
+    /// Construct shifted Poisson (Helmholtz) solver from field:
+    ///
+    ///  [c0 (D2x x Iy) + c1 (Ix x D2y) + alpha (Ix x Iy)] vhat = A f
+    ///
+    /// The coefficients *c* and *alpha* may be complex
+    /// (`T = Complex<f64>`), e.g. a helmholtz equation with
+    /// a complex wavenumber k has *alpha* = k^2.
+    /// The matrices are then eigendecomposed in complex
+    /// arithmetic. Singular problems are not handled.
+    pub fn new_shifted<T2, S>(field: &FieldBase<f64, f64, T2, S, N>, c: [T; N], alpha: T) -> Self
     where
         S: BaseSpace<f64, N, Physical = f64, Spectral = T2>,
     {
         // Gather matrices and preconditioner
-        let mut laplacians: Vec<Array2<f64>> = Vec::new();
-        let mut masses: Vec<Array2<f64>> = Vec::new();
+        let mut laplacians: Vec<Array2<T>> = Vec::new();
+        let mut masses: Vec<Array2<T>> = Vec::new();
         let mut is_diags: Vec<bool> = Vec::new();
         let mut matvec: Vec<Option<MatVec<f64>>> = Vec::new();
         for (axis, ci) in c.iter().enumerate() {
             // Matrices and preconditioner
             let (mat_a, mat_b, precond, is_diag) = field.ingredients_for_poisson(axis);
-            let mass = mat_a;
-            let laplacian = mat_b * *ci;
+            let mass = mat_a.mapv(T::from);
+            let laplacian = mat_b.mapv(|x| T::from(x) * *ci);
             let matvec_axis = precond.map(|x| MatVec::MatVecFdma(MatVecFdma::new(&x)));
 
             laplacians.push(laplacian);
@@ -70,34 +169,94 @@ impl<const N: usize> Poisson<f64, N> {
         }
 
         // Vectors -> Arrays
-        let laplacians = vec_to_array::<&Array2<f64>, N>(laplacians.iter().collect());
-        let masses = vec_to_array::<&Array2<f64>, N>(masses.iter().collect());
+        let laplacians = vec_to_array::<&Array2<T>, N>(laplacians.iter().collect());
+        let masses = vec_to_array::<&Array2<T>, N>(masses.iter().collect());
         let is_diag = vec_to_array::<&bool, N>(is_diags.iter().collect());
 
         // Solver
-        let mut solver = FdmaTensor::from_matrix(laplacians, masses, is_diag, 0.);
-        // Handle singularity (2D)
-        if N == 2 && solver.lam[0][0].abs() < 1e-10 {
-            solver.lam[0] -= 1e-10;
-            println!("Poisson seems singular! Eigenvalue 0 is manipulated to help out.");
-        }
+        let solver = FdmaTensor::from_matrix(laplacians, masses, is_diag, alpha);
 
-        // let solver = Box::new(solver);
         Self {
             solver: Box::new(solver),
             matvec,
+            c,
+        }
+    }
+}
+
+impl Poisson<f64, 2> {
+    /// Solve a manufactured problem on *space* and return
+    /// the maximum error relative to the known solution.
+    ///
+    /// The solution u = (cos x + sin 2x)(cos y + sin y) is projected
+    /// onto *space*, such that it satisfies the boundary conditions,
+    /// and the forcing is obtained by spectral differentiation of u.
+    /// A mis-assembled operator results in an O(1) error.
+    ///
+    /// *space* must be the space the solver was constructed from.
+    /// Singular problems (e.g. pure neumann) are not supported,
+    /// their solution is only defined up to a constant.
+    ///
+    /// # Errors
+    /// Returns an error message if the relative error
+    /// exceeds `SELF_CHECK_TOL` (1e-8).
+    pub fn self_check<T2, S>(&self, space: &S) -> Result<f64, String>
+    where
+        S: BaseSpace<f64, 2, Physical = f64, Spectral = T2>,
+        T2: SolverScalar
+            + Div<f64, Output = T2>
+            + Mul<f64, Output = T2>
+            + Add<f64, Output = T2>
+            + From<f64>,
+    {
+        let mut field = FieldBase::new(space);
+        let (x, y) = (field.x[0].to_owned(), field.x[1].to_owned());
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                field.v[[i, j]] = (xi.cos() + (2. * xi).sin()) * (yi.cos() + yi.sin());
+            }
+        }
+        field.forward();
+        field.backward();
+        let expected = field.v.to_owned();
+
+        // Forcing c0 * d2u/dx2 + c1 * d2u/dy2 (orthogonal space)
+        let rhs = field.gradient([2, 0], None).mapv(|v| v * self.c[0])
+            + &field.gradient([0, 2], None).mapv(|v| v * self.c[1]);
+        let mut result = field.vhat.to_owned();
+        self.solve(&rhs, &mut result, 0);
+        field.vhat.assign(&result);
+        field.backward();
+
+        let norm = expected.iter().fold(0_f64, |m, v| m.max(v.abs()));
+        let error = (&field.v - &expected)
+            .iter()
+            .fold(0_f64, |m, v| m.max(v.abs()))
+            / norm;
+        if error.is_finite() && error < SELF_CHECK_TOL {
+            Ok(error)
+        } else {
+            Err(format!(
+                "Poisson self check failed: relative error {:e} exceeds {:e}.",
+                error, SELF_CHECK_TOL
+            ))
         }
     }
 }
 
 #[allow(unused_variables)]
-impl<A> Solve<A, ndarray::Ix1> for Poisson<f64, 1>
+impl<T, A> Solve<A, ndarray::Ix1> for Poisson<T, 1>
 where
+    T: SolverScalar,
     A: SolverScalar
         + Div<f64, Output = A>
         + Mul<f64, Output = A>
         + Add<f64, Output = A>
-        + From<f64>,
+        + From<f64>
+        + Div<T, Output = A>
+        + Mul<T, Output = A>
+        + Add<T, Output = A>
+        + From<T>,
 {
     /// # Example
     fn solve<S1, S2>(
@@ -119,13 +278,18 @@ where
 }
 
 #[allow(unused_variables)]
-impl<A> Solve<A, ndarray::Ix2> for Poisson<f64, 2>
+impl<T, A> Solve<A, ndarray::Ix2> for Poisson<T, 2>
 where
+    T: SolverScalar,
     A: SolverScalar
         + Div<f64, Output = A>
         + Mul<f64, Output = A>
         + Add<f64, Output = A>
-        + From<f64>,
+        + From<f64>
+        + Div<T, Output = A>
+        + Mul<T, Output = A>
+        + Add<T, Output = A>
+        + From<T>,
 {
     /// # Example
     fn solve<S1, S2>(
@@ -149,11 +313,36 @@ where
     }
 }
 
+impl<A> Residual<A, Ix2> for Poisson<f64, 2>
+where
+    A: SolverScalar
+        + Div<f64, Output = A>
+        + Mul<f64, Output = A>
+        + Add<f64, Output = A>
+        + From<f64>
+        + Into<Complex<f64>>,
+{
+    /// Residual of the preconditioned system
+    fn residual<S1, S2>(&self, input: &ArrayBase<S1, Ix2>, output: &ArrayBase<S2, Ix2>) -> f64
+    where
+        S1: ndarray::Data<Elem = A>,
+        S2: ndarray::Data<Elem = A>,
+    {
+        let mut rhs = self.matvec[0]
+            .as_ref()
+            .map_or_else(|| input.to_owned(), |x| x.solve(input, 0));
+        if let Some(x) = &self.matvec[1] {
+            rhs = x.solve(&rhs, 1);
+        };
+        self.solver.residual(&rhs, output)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::field::{Field1, Field2, Space1, Space2};
-    use crate::{cheb_dirichlet, fourier_r2c};
+    use crate::{cheb_dirichlet, cheb_neumann, fourier_r2c};
     use ndarray::array;
     use num_complex::Complex;
     //use std::f64::consts::PI;
@@ -204,6 +393,26 @@ mod tests {
         approx_eq(&x, &y);
     }
 
+    #[test]
+    fn test_poisson1d_sine() {
+        use crate::bases::fourier_sine;
+        let nx = 16;
+        let space = Space1::new(&fourier_sine(nx));
+        let mut field = Field1::new(&space);
+        // Sine and cosine bases are diagonal
+        assert!(field.ingredients_for_poisson(0).3);
+        let x = field.x[0].to_owned();
+        field.v = x.mapv(|x| x.sin() + 0.5 * (4. * x).sin() - 0.1 * (9. * x).sin());
+        field.forward();
+        let rhs = field.gradient([2], None);
+        let mut result = Array1::<f64>::zeros(nx - 2);
+        let poisson = Poisson::new(&field, [1.0]);
+        poisson.solve(&rhs, &mut result, 0);
+        for (a, b) in result.iter().zip(field.vhat.iter()) {
+            assert!((a - b).abs() < 1e-14, "{} != {}", a, b);
+        }
+    }
+
     #[test]
     fn test_poisson2d() {
         let (nx, ny) = (8, 7);
@@ -337,4 +546,120 @@ mod tests {
         // Compare
         approx_eq(&field.v, &expected);
     }
+
+    #[test]
+    fn test_helmholtz2d_complex_wavenumber() {
+        // (D2 + k^2) u = f, with complex k^2
+        let (nx, ny) = (16, 15);
+        let space = Space2::new(&cheb_dirichlet(nx), &cheb_dirichlet(ny));
+        let mut field = Field2::new(&space);
+        let k2 = Complex::new(1.5, 0.8);
+        let one = Complex::new(1., 0.);
+        let solver = Poisson::new_shifted(&field, [one, one], k2);
+        let x = &field.x[0];
+        let y = &field.x[1];
+
+        // Analytical field: D2 f = -2 n^2 f
+        let n = std::f64::consts::PI / 2.;
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                field.v[[i, j]] = (n * xi).cos() * (n * yi).cos();
+            }
+        }
+
+        // Solve
+        field.forward();
+        let input = field.to_ortho().mapv(|v| Complex::new(v, 0.));
+        let mut result = Array2::<Complex<f64>>::zeros(field.vhat.raw_dim());
+        solver.solve(&input, &mut result, 0);
+
+        // Compare
+        let expected = field.vhat.mapv(|v| Complex::new(v, 0.) / (k2 - 2. * n * n));
+        for (r, e) in result.iter().zip(expected.iter()) {
+            assert!((r - e).norm() < 1e-6, "got {} expected {}", r, e);
+        }
+    }
+
+    #[test]
+    fn test_poisson_try_from_space() {
+        let (nx, ny) = (16, 7);
+        let space = Space2::new(&fourier_r2c(nx), &cheb_dirichlet(ny));
+        let mut field = Field2::new(&space);
+        let x = &field.x[0];
+        let y = &field.x[1];
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                field.v[[i, j]] = xi.cos() * (std::f64::consts::PI / 2. * yi).cos();
+            }
+        }
+        field.forward();
+        let input = field.to_ortho();
+
+        // Correct length
+        let solver = Poisson::try_from_space(&space, &[1e-2, 1e-2], NullspaceMode::Nudge).unwrap();
+        let expected_solver = Poisson::new(&field, [1e-2, 1e-2]);
+        let mut result = field.vhat.clone();
+        let mut expected = field.vhat.clone();
+        solver.solve(&input, &mut result, 0);
+        expected_solver.solve(&input, &mut expected, 0);
+        assert!(result == expected);
+
+        // Incorrect length
+        let err = Poisson::<f64, 2>::try_from_space(&space, &[1e-2], NullspaceMode::Nudge).err();
+        assert_eq!(
+            err,
+            Some(SolverError::DimensionMismatch {
+                expected: 2,
+                got: 1
+            })
+        );
+        assert!(
+            Poisson::<f64, 2>::try_from_space(&space, &[1e-2; 3], NullspaceMode::Nudge).is_err()
+        );
+    }
+
+    #[test]
+    fn test_poisson_nullspace() {
+        let (nx, ny) = (16, 12);
+        let space = Space2::new(&fourier_r2c(nx), &cheb_neumann(ny));
+        let err = Poisson::try_from_space(&space, &[1.0, 1.0], NullspaceMode::Error).err();
+        assert!(matches!(err, Some(SolverError::Singular { axis: 0, .. })));
+
+        // u = (1 + cos 2x) cos(pi y)
+        let mut field = Field2::new(&space);
+        let (x, y) = (field.x[0].to_owned(), field.x[1].to_owned());
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yi) in y.iter().enumerate() {
+                let n = std::f64::consts::PI;
+                field.v[[i, j]] = (1. + (2. * xi).cos()) * (n * yi).cos();
+            }
+        }
+        field.forward();
+        let rhs = field.gradient([2, 0], None) + &field.gradient([0, 2], None);
+        let poisson = Poisson::try_from_space(&space, &[1.0, 1.0], NullspaceMode::PinMean).unwrap();
+        let mut result = field.vhat.to_owned();
+        poisson.solve(&rhs, &mut result, 0);
+        // Solution is defined up to the constant mode
+        let mut expected = field.vhat.to_owned();
+        expected[[0, 0]] = Complex::new(0., 0.);
+        for (r, e) in result.iter().zip(expected.iter()) {
+            assert!((r - e).norm() < 1e-8, "got {} expected {}", r, e);
+        }
+    }
+
+    #[test]
+    fn test_poisson_self_check() {
+        let space = Space2::new(&cheb_dirichlet(16), &cheb_dirichlet(14));
+        let poisson = Poisson::try_from_space(&space, &[1.0, 0.5], NullspaceMode::Error).unwrap();
+        assert!(poisson.self_check(&space).unwrap() < 1e-8);
+
+        let space = Space2::new(&fourier_r2c(16), &cheb_dirichlet(14));
+        let poisson = Poisson::try_from_space(&space, &[1.0, 1.0], NullspaceMode::Error).unwrap();
+        assert!(poisson.self_check(&space).unwrap() < 1e-8);
+
+        // Deliberately broken operator
+        let mut broken = poisson.clone();
+        broken.c[0] = 2.0;
+        assert!(broken.self_check(&space).is_err());
+    }
 }
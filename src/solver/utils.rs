@@ -1,6 +1,7 @@
 //! Collection of usefull algebra methods
 use ndarray::LinalgScalar;
 use ndarray::{Array1, Array2};
+use num_complex::Complex;
 
 // use ndarray_02::Array1 as Array1_old;
 // use ndarray_02::Array2 as Array2_old;
@@ -105,6 +106,70 @@ pub fn inv(a: &Array2<f64>) -> Array2<f64> {
     a.inv().unwrap()
 }
 
+/// Returns complex-valued eigendecomposition A = Q lam Qi,
+/// where A is a square matrix.
+/// The output is sorted with respect to the real part
+/// of the eigenvalues, i.e. largest -> smallest.
+///
+/// ## Panics
+/// Panics if eigendecomposition or inverse fails.
+pub fn eig_complex(
+    a: &Array2<Complex<f64>>,
+) -> (
+    Array1<Complex<f64>>,
+    Array2<Complex<f64>>,
+    Array2<Complex<f64>>,
+) {
+    use ndarray::Axis;
+    use ndarray_linalg::Eig;
+    let (eval, evec) = a.eig().unwrap();
+    // Order Eigenvalues, largest first
+    let eval_re: Vec<f64> = eval.iter().map(|e| e.re).collect();
+    let permut: Vec<usize> = argsort(&eval_re).into_iter().rev().collect();
+    let eval = eval.select(Axis(0), &permut).to_owned();
+    let evec = evec.select(Axis(1), &permut).to_owned();
+    // Inverse of evec
+    let evec_inv = inv_complex(&evec);
+    (eval, evec, evec_inv)
+}
+
+/// Return inverse of complex square matrix
+/// ## Panics
+/// Panics when computation of inverse fails.
+pub fn inv_complex(a: &Array2<Complex<f64>>) -> Array2<Complex<f64>> {
+    use ndarray_linalg::Inverse;
+    a.inv().unwrap()
+}
+
+/// Scalars, whose matrices can be inverted and
+/// eigendecomposed (see [`eig`] and [`eig_complex`])
+pub trait Decompose: Sized {
+    /// Eigendecomposition A = Q lam Qi, returns (lam, Q, Qi)
+    fn eig(a: &Array2<Self>) -> (Array1<Self>, Array2<Self>, Array2<Self>);
+    /// Inverse of square matrix
+    fn inv(a: &Array2<Self>) -> Array2<Self>;
+}
+
+impl Decompose for f64 {
+    fn eig(a: &Array2<Self>) -> (Array1<Self>, Array2<Self>, Array2<Self>) {
+        eig(a)
+    }
+
+    fn inv(a: &Array2<Self>) -> Array2<Self> {
+        inv(a)
+    }
+}
+
+impl Decompose for Complex<f64> {
+    fn eig(a: &Array2<Self>) -> (Array1<Self>, Array2<Self>, Array2<Self>) {
+        eig_complex(a)
+    }
+
+    fn inv(a: &Array2<Self>) -> Array2<Self> {
+        inv_complex(a)
+    }
+}
+
 // // Convert 2d to old ndarray
 // fn ndarray_to_old<T: LinalgScalar>(new: &Array2<T>) -> Array2_old<T> {
 //     let (n, m) = (new.shape()[0], new.shape()[1]);
@@ -200,6 +265,35 @@ mod tests {
         let t = evec.dot(&t);
         approx_eq(&t, &test);
     }
+
+    #[test]
+    fn test_eig_complex() {
+        let test = array![
+            [
+                Complex::new(1., 1.),
+                Complex::new(2., 0.),
+                Complex::new(0., -1.)
+            ],
+            [
+                Complex::new(0., 2.),
+                Complex::new(3., 0.),
+                Complex::new(1., 0.)
+            ],
+            [
+                Complex::new(1., 0.),
+                Complex::new(0., 1.),
+                Complex::new(-2., 1.)
+            ]
+        ];
+        let (e, evec, evec_inv) = eig_complex(&test);
+        // sorted by real part
+        assert!(e[0].re >= e[1].re && e[1].re >= e[2].re);
+        let lam = Array2::from_diag(&e);
+        let t = evec.dot(&lam.dot(&evec_inv));
+        for (a, b) in t.iter().zip(test.iter()) {
+            assert!((a - b).norm() < 1e-8);
+        }
+    }
 }
 
 // /// Convert 2d array from ndarray to nalgebra
@@ -234,3 +328,18 @@ pub fn vec_to_array<T, const N: usize>(v: Vec<T>) -> [T; N] {
     v.try_into()
         .unwrap_or_else(|v: Vec<T>| panic!("Expected a Vec of length {} but it was {}", N, v.len()))
 }
+
+/// Convert slice to static array
+///
+/// ## Errors
+/// Returns `SolverError::DimensionMismatch` if slice length
+/// does not match array size
+pub fn slice_to_array<T: Copy, const N: usize>(
+    v: &[T],
+) -> std::result::Result<[T; N], super::SolverError> {
+    v.try_into()
+        .map_err(|_| super::SolverError::DimensionMismatch {
+            expected: N,
+            got: v.len(),
+        })
+}